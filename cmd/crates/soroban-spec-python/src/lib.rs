@@ -0,0 +1,344 @@
+use std::{fs, io};
+
+use sha2::{Digest, Sha256};
+use soroban_spec::read::{from_wasm, FromWasmError};
+use soroban_spec_json::types::{
+    Entry, EnumCase, ErrorEnumCase, FunctionInput, StructField, Type, UnionCase,
+};
+use stellar_xdr::curr::ScSpecEntry;
+
+#[derive(thiserror::Error, Debug)]
+pub enum GenerateFromFileError {
+    #[error("reading file: {0}")]
+    Io(io::Error),
+    #[error("sha256 does not match, expected: {expected}")]
+    VerifySha256 { expected: String },
+    #[error("getting contract spec: {0}")]
+    GetSpec(FromWasmError),
+}
+
+/// Imports of the generated module, and the stubs converting values to and from `ScVal`, left to
+/// be implemented with the SDK of the user's choice
+const HEADER: &str = r#""""Client and types of a Soroban contract, generated from its spec"""
+
+from __future__ import annotations
+
+from dataclasses import dataclass
+from enum import IntEnum
+from typing import Any, Callable, Dict, List, Optional, Tuple
+
+
+def to_scval(value: Any) -> Any:
+    """Convert a value of the contract's types to an `ScVal`, e.g. with the Stellar Python SDK"""
+    raise NotImplementedError
+
+
+def from_scval(scval: Any) -> Any:
+    """Convert an `ScVal` returned by the contract to a value of its types"""
+    raise NotImplementedError"#;
+
+const KEYWORDS: [&str; 35] = [
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue",
+    "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import",
+    "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while",
+    "with", "yield",
+];
+
+/// # Errors
+///
+/// Will return an error if the file cannot be read, or the wasm cannot be parsed.
+pub fn generate_from_file(
+    file: &str,
+    verify_sha256: Option<&str>,
+) -> Result<String, GenerateFromFileError> {
+    // Read file.
+    let wasm = fs::read(file).map_err(GenerateFromFileError::Io)?;
+
+    // Produce hash for file.
+    let sha256 = Sha256::digest(&wasm);
+    let sha256 = format!("{sha256:x}");
+
+    if let Some(verify_sha256) = verify_sha256 {
+        if verify_sha256 != sha256 {
+            return Err(GenerateFromFileError::VerifySha256 { expected: sha256 });
+        }
+    }
+
+    // Generate code.
+    let python = generate_from_wasm(&wasm).map_err(GenerateFromFileError::GetSpec)?;
+    Ok(python)
+}
+
+/// # Errors
+///
+/// Will return an error if the wasm cannot be parsed.
+pub fn generate_from_wasm(wasm: &[u8]) -> Result<String, FromWasmError> {
+    let spec = from_wasm(wasm)?;
+    Ok(generate(&spec))
+}
+
+/// A Python module with a dataclass or `IntEnum` for each of the contract's types, and a `Client`
+/// class with a method for each of its functions
+pub fn generate(spec: &[ScSpecEntry]) -> String {
+    let (fns, types): (Vec<_>, Vec<_>) = spec
+        .iter()
+        .map(Entry::from)
+        .filter(|entry| !matches!(entry, Entry::Function { name, .. } if name.starts_with("__")))
+        .partition(|entry| matches!(entry, Entry::Function { .. }));
+    let mut sections = vec![HEADER.to_string()];
+    sections.extend(types.iter().filter_map(entry_to_python));
+    sections.push(client(&fns));
+    sections.join("\n\n\n") + "\n"
+}
+
+fn client(fns: &[Entry]) -> String {
+    let methods = fns
+        .iter()
+        .filter_map(function_to_method)
+        .map(|method| format!("\n\n{method}"))
+        .collect::<String>();
+    format!(
+        r#"class Client:
+    """Client of the contract, calling `invoke` with its ID, a function name and the `ScVal`
+    arguments of each invocation"""
+
+    def __init__(self, contract_id: str, invoke: Callable[[str, str, List[Any]], Any]):
+        self.contract_id = contract_id
+        self.invoke = invoke{methods}"#
+    )
+}
+
+fn function_to_method(entry: &Entry) -> Option<String> {
+    let Entry::Function {
+        doc,
+        name,
+        inputs,
+        outputs,
+    } = entry
+    else {
+        return None;
+    };
+    let params = inputs
+        .iter()
+        .map(|FunctionInput { name, value, .. }| {
+            format!(", {}: {}", identifier(name), type_to_python(value, false))
+        })
+        .collect::<String>();
+    let args = inputs
+        .iter()
+        .map(|FunctionInput { name, .. }| format!("to_scval({})", identifier(name)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let returns = match outputs.as_slice() {
+        [] => "None".to_string(),
+        [output] => type_to_python(output, false),
+        outputs => format!("Tuple[{}]", types_to_python(outputs, false)),
+    };
+    let doc = docstring(doc, "        ")
+        .map(|doc| format!("        {doc}\n"))
+        .unwrap_or_default();
+    Some(format!(
+        r#"    def {}(self{params}) -> {returns}:
+{doc}        return from_scval(self.invoke(self.contract_id, "{name}", [{args}]))"#,
+        identifier(name)
+    ))
+}
+
+fn entry_to_python(entry: &Entry) -> Option<String> {
+    match entry {
+        Entry::Function { .. } => None,
+        Entry::Struct { doc, name, fields }
+            if fields.first().is_some_and(|field| field.name == "0") =>
+        {
+            let elements = fields
+                .iter()
+                .map(|StructField { value, .. }| value.clone())
+                .collect::<Vec<_>>();
+            Some(format!(
+                "{}{name} = Tuple[{}]",
+                comment(doc, ""),
+                types_to_python(&elements, true)
+            ))
+        }
+        Entry::Struct { doc, name, fields } => {
+            let fields = fields
+                .iter()
+                .map(|StructField { doc, name, value }| {
+                    format!(
+                        "{}    {}: {}",
+                        comment(doc, "    "),
+                        identifier(name),
+                        type_to_python(value, false)
+                    )
+                })
+                .collect::<Vec<_>>();
+            Some(format!(
+                "@dataclass\nclass {name}:\n{}",
+                class_body(doc, &fields)
+            ))
+        }
+        Entry::Union { doc, name, cases } => {
+            let cases = cases
+                .iter()
+                .map(|UnionCase { name, values, .. }| {
+                    if values.is_empty() {
+                        format!("`{name}`")
+                    } else {
+                        format!("`{name}({})`", types_to_python(values, false))
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            let doc = if doc.is_empty() {
+                format!("One of {cases}")
+            } else {
+                format!("{doc}\n\nOne of {cases}")
+            };
+            Some(format!(
+                "@dataclass\nclass {name}:\n{}",
+                class_body(
+                    &doc,
+                    &[
+                        "    tag: str".to_string(),
+                        "    values: Tuple[Any, ...] = ()".to_string()
+                    ]
+                )
+            ))
+        }
+        Entry::Enum { doc, name, cases } => {
+            let cases = cases
+                .iter()
+                .map(|EnumCase { doc, name, value }| enum_case(doc, name, *value))
+                .collect::<Vec<_>>();
+            Some(format!(
+                "class {name}(IntEnum):\n{}",
+                class_body(doc, &cases)
+            ))
+        }
+        Entry::ErrorEnum { doc, name, cases } => {
+            let cases = cases
+                .iter()
+                .map(|ErrorEnumCase { doc, name, value }| enum_case(doc, name, *value))
+                .collect::<Vec<_>>();
+            Some(format!(
+                "class {name}(IntEnum):\n{}",
+                class_body(doc, &cases)
+            ))
+        }
+    }
+}
+
+fn enum_case(doc: &str, name: &str, value: u32) -> String {
+    format!("{}    {} = {value}", comment(doc, "    "), identifier(name))
+}
+
+/// The docstring and members of a class, or `pass` if it has neither
+fn class_body(doc: &str, members: &[String]) -> String {
+    let doc = docstring(doc, "    ").map(|doc| format!("    {doc}"));
+    match (doc, members.is_empty()) {
+        (None, true) => "    pass".to_string(),
+        (None, false) => members.join("\n"),
+        (Some(doc), true) => doc,
+        (Some(doc), false) => format!("{doc}\n\n{}", members.join("\n")),
+    }
+}
+
+fn docstring(doc: &str, indent: &str) -> Option<String> {
+    let doc = doc.trim();
+    if doc.is_empty() {
+        return None;
+    }
+    let mut doc = doc
+        .replace('\\', r"\\")
+        .replace(r#"""""#, r#"\"\"\""#)
+        .replace('\n', &format!("\n{indent}"));
+    // A quote right before the closing ones would end the string early
+    if doc.ends_with('"') {
+        doc.push(' ');
+    }
+    Some(format!(r#""""{doc}""""#))
+}
+
+fn comment(doc: &str, indent: &str) -> String {
+    doc.trim()
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| format!("{indent}# {line}\n"))
+        .collect()
+}
+
+fn identifier(name: &str) -> String {
+    if KEYWORDS.contains(&name) {
+        format!("{name}_")
+    } else {
+        name.to_string()
+    }
+}
+
+fn types_to_python(types: &[Type], forward: bool) -> String {
+    types
+        .iter()
+        .map(|type_| type_to_python(type_, forward))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// The type hint of a spec type. With `forward`, the contract's own types are quoted, for hints
+/// evaluated when the module loads, before those types may be defined.
+fn type_to_python(type_: &Type, forward: bool) -> String {
+    match type_ {
+        Type::U32
+        | Type::I32
+        | Type::U64
+        | Type::I64
+        | Type::U128
+        | Type::I128
+        | Type::U256
+        | Type::I256
+        | Type::Timepoint
+        | Type::Duration
+        // The code of a contract error
+        | Type::Error => "int".to_string(),
+        Type::Bool => "bool".to_string(),
+        Type::Symbol | Type::String | Type::Address => "str".to_string(),
+        Type::Bytes | Type::BytesN { .. } => "bytes".to_string(),
+        Type::Map { key, value } => format!(
+            "Dict[{}, {}]",
+            type_to_python(key, forward),
+            type_to_python(value, forward)
+        ),
+        Type::Option { value } => format!("Optional[{}]", type_to_python(value, forward)),
+        // Errors are raised rather than returned
+        Type::Result { value, .. } => type_to_python(value, forward),
+        Type::Vec { element } => format!("List[{}]", type_to_python(element, forward)),
+        Type::Tuple { elements } if elements.is_empty() => "None".to_string(),
+        Type::Tuple { elements } => format!("Tuple[{}]", types_to_python(elements, forward)),
+        Type::Custom { name } if forward => format!("\"{name}\""),
+        Type::Custom { name } => name.clone(),
+        Type::Val => "Any".to_string(),
+        Type::Void => "None".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLE_WASM: &[u8] = include_bytes!(
+        "../../../../target/wasm32-unknown-unknown/test-wasms/test_custom_types.wasm"
+    );
+
+    #[test]
+    fn custom_types() {
+        let python = generate_from_wasm(EXAMPLE_WASM).unwrap();
+        assert!(python.contains(
+            "@dataclass\nclass Test:\n    \"\"\"This is from the rust doc above the struct Test\"\"\"\n\n    a: int\n    b: bool\n    c: str\n"
+        ));
+        assert!(python.contains("    def strukt(self, strukt: Test) -> Test:\n"));
+        assert!(python.contains(
+            "        return from_scval(self.invoke(self.contract_id, \"strukt\", [to_scval(strukt)]))\n"
+        ));
+        assert!(python.contains("TupleStruct = Tuple[\"Test\", \"SimpleEnum\"]"));
+        assert!(python.contains("class RoyalCard(IntEnum):\n    Jack = 11\n"));
+    }
+}
@@ -2,15 +2,17 @@
 use std::str::FromStr;
 
 use itertools::Itertools;
+use serde::{ser::SerializeMap, Serialize, Serializer};
 use serde_json::{json, Value};
 use stellar_xdr::curr::{
     AccountId, BytesM, ContractExecutable, Error as XdrError, Hash, Int128Parts, Int256Parts,
-    PublicKey, ScAddress, ScBytes, ScContractInstance, ScMap, ScMapEntry, ScNonceKey, ScSpecEntry,
-    ScSpecFunctionV0, ScSpecTypeDef as ScType, ScSpecTypeMap, ScSpecTypeOption, ScSpecTypeResult,
-    ScSpecTypeTuple, ScSpecTypeUdt, ScSpecTypeVec, ScSpecUdtEnumV0, ScSpecUdtErrorEnumCaseV0,
-    ScSpecUdtErrorEnumV0, ScSpecUdtStructV0, ScSpecUdtUnionCaseTupleV0, ScSpecUdtUnionCaseV0,
-    ScSpecUdtUnionCaseVoidV0, ScSpecUdtUnionV0, ScString, ScSymbol, ScVal, ScVec, StringM,
-    UInt128Parts, UInt256Parts, Uint256, VecM,
+    PublicKey, ScAddress, ScBytes, ScContractInstance, ScError, ScErrorType, ScMap, ScMapEntry,
+    ScNonceKey, ScSpecEntry, ScSpecFunctionV0, ScSpecTypeBytesN, ScSpecTypeDef as ScType,
+    ScSpecTypeMap, ScSpecTypeOption, ScSpecTypeResult, ScSpecTypeTuple, ScSpecTypeUdt,
+    ScSpecTypeVec, ScSpecUdtEnumV0, ScSpecUdtErrorEnumCaseV0, ScSpecUdtErrorEnumV0,
+    ScSpecUdtStructV0, ScSpecUdtUnionCaseTupleV0, ScSpecUdtUnionCaseV0, ScSpecUdtUnionCaseVoidV0,
+    ScSpecUdtUnionV0, ScString, ScSymbol, ScVal, ScVec, StringM, UInt128Parts, UInt256Parts,
+    Uint256, VecM,
 };
 
 pub mod contract;
@@ -32,6 +34,8 @@ pub enum Error {
     IllFormedEnum(String),
     #[error("Unknown const case {0}")]
     EnumConst(u32),
+    #[error("Unknown const case name {0}")]
+    EnumConstName(String),
     #[error("Enum const value must be a u32 or smaller")]
     EnumConstTooLarge(u64),
     #[error("Missing Entry {0}")]
@@ -63,6 +67,8 @@ pub enum Error {
     Spec(#[from] soroban_spec::read::FromWasmError),
     #[error(transparent)]
     Base64Spec(#[from] soroban_spec::read::ParseSpecBase64Error),
+    #[error("Unknown type {0}")]
+    UnknownType(String),
 }
 
 #[derive(Default, Clone)]
@@ -250,7 +256,9 @@ impl Spec {
                     ScType::Udt(ScSpecTypeUdt { name })
                         if matches!(
                             self.find(&name.to_utf8_string_lossy())?,
-                            ScSpecEntry::UdtUnionV0(_) | ScSpecEntry::UdtStructV0(_)
+                            ScSpecEntry::UdtUnionV0(_)
+                                | ScSpecEntry::UdtStructV0(_)
+                                | ScSpecEntry::UdtEnumV0(_)
                         ) =>
                     {
                         Ok(Value::String(s.to_owned()))
@@ -343,11 +351,21 @@ impl Spec {
             (ScSpecEntry::UdtStructV0(strukt), Value::Array(arr)) => {
                 self.parse_tuple_strukt(strukt, arr)
             }
+            // A tuple struct with a single field also accepts the bare field value
+            (ScSpecEntry::UdtStructV0(strukt), value)
+                if strukt.fields.len() == 1
+                    && strukt.fields[0].name.to_utf8_string_lossy() == "0" =>
+            {
+                self.parse_tuple_strukt(strukt, std::slice::from_ref(value))
+            }
             (
                 ScSpecEntry::UdtUnionV0(union),
                 val @ (Value::Array(_) | Value::String(_) | Value::Object(_)),
             ) => self.parse_union(union, val),
             (ScSpecEntry::UdtEnumV0(enum_), Value::Number(num)) => parse_const_enum(num, enum_),
+            (ScSpecEntry::UdtEnumV0(enum_), Value::String(name)) => {
+                parse_const_enum_name(name, enum_)
+            }
             (s, v) => todo!("Not implemented for {s:#?} {v:#?}"),
         }
     }
@@ -508,6 +526,24 @@ impl Spec {
     ///
     /// May panic
     pub fn xdr_to_json(&self, val: &ScVal, output: &ScType) -> Result<Value, Error> {
+        self.xdr_to_json_with(val, output, BytesFormat::default())
+    }
+
+    /// Like [`Spec::xdr_to_json`], rendering `Bytes` and `BytesN` values in the given format.
+    ///
+    /// # Errors
+    ///
+    /// Might return `Error::InvalidValue`
+    ///
+    /// # Panics
+    ///
+    /// May panic
+    pub fn xdr_to_json_with(
+        &self,
+        val: &ScVal,
+        output: &ScType,
+        bytes: BytesFormat,
+    ) -> Result<Value, Error> {
         Ok(match (val, output) {
             (ScVal::Void, ScType::Val | ScType::Option(_) | ScType::Tuple(_))
             | (ScVal::Map(None) | ScVal::Vec(None), ScType::Option(_)) => Value::Null,
@@ -532,13 +568,13 @@ impl Spec {
                 _,
             )
             | (ScVal::Address(_), ScType::Address)
-            | (ScVal::Bytes(_), ScType::Bytes | ScType::BytesN(_)) => to_json(val)?,
+            | (ScVal::Bytes(_), ScType::Bytes | ScType::BytesN(_)) => to_json_with(val, bytes)?,
 
-            (val, ScType::Result(inner)) => self.xdr_to_json(val, &inner.ok_type)?,
+            (val, ScType::Result(inner)) => self.xdr_to_json_with(val, &inner.ok_type, bytes)?,
 
-            (val, ScType::Option(inner)) => self.xdr_to_json(val, &inner.value_type)?,
+            (val, ScType::Option(inner)) => self.xdr_to_json_with(val, &inner.value_type, bytes)?,
             (ScVal::Map(Some(_)) | ScVal::Vec(Some(_)) | ScVal::U32(_), type_) => {
-                self.sc_object_to_json(val, type_)?
+                self.sc_object_to_json_with(val, type_, bytes)?
             }
 
             (ScVal::Error(_), ScType::Error) => todo!(),
@@ -546,6 +582,283 @@ impl Spec {
         })
     }
 
+    /// The JSON text of [`Spec::xdr_to_json`], with the entries of maps in the order of their
+    /// `ScMap` instead of sorted by the text of their keys, e.g. `{"1":..,"2":..,"10":..}`
+    ///
+    /// # Errors
+    ///
+    /// Might return `Error::InvalidValue`
+    pub fn xdr_to_json_string(&self, val: &ScVal, output: &ScType) -> Result<String, Error> {
+        self.xdr_to_json_string_with(val, output, BytesFormat::default())
+    }
+
+    /// Like [`Spec::xdr_to_json_string`], rendering `Bytes` and `BytesN` values in the given
+    /// format.
+    ///
+    /// # Errors
+    ///
+    /// Might return `Error::InvalidValue`
+    pub fn xdr_to_json_string_with(
+        &self,
+        val: &ScVal,
+        output: &ScType,
+        bytes: BytesFormat,
+    ) -> Result<String, Error> {
+        let json = self.xdr_to_json_with(val, output, bytes)?;
+        Ok(serde_json::to_string(&self.json_in_order(
+            val,
+            Some(output),
+            &json,
+            bytes,
+        )?)?)
+    }
+
+    /// `json`, rendered from `val` of type `type_`, or with [`to_json`] if `None`, with the order
+    /// of map entries taken from `val`. Parts whose shape isn't recognised are kept as they are.
+    fn json_in_order(
+        &self,
+        val: &ScVal,
+        type_: Option<&ScType>,
+        json: &Value,
+        bytes: BytesFormat,
+    ) -> Result<JsonInOrder, Error> {
+        let type_ = type_.filter(|t| !matches!(t, ScType::Val));
+        let entries = match (val, type_, json) {
+            (_, Some(ScType::Option(inner)), _) if !json.is_null() => {
+                return self.json_in_order(val, Some(&inner.value_type), json, bytes);
+            }
+            (_, Some(ScType::Result(inner)), _) => {
+                return self.json_in_order(val, Some(&inner.ok_type), json, bytes);
+            }
+            (ScVal::Vec(Some(vec_)), None, Value::Array(array)) => {
+                let types = vec![None; vec_.len()];
+                return self.json_array_in_order(vec_, &types, array, bytes);
+            }
+            (ScVal::Vec(Some(vec_)), Some(ScType::Vec(vec_type)), Value::Array(array)) => {
+                let types = vec![Some(vec_type.element_type.as_ref()); vec_.len()];
+                return self.json_array_in_order(vec_, &types, array, bytes);
+            }
+            (ScVal::Vec(Some(vec_)), Some(ScType::Tuple(tuple)), Value::Array(array)) => {
+                let types = tuple.value_types.iter().map(Some).collect::<Vec<_>>();
+                return self.json_array_in_order(vec_, &types, array, bytes);
+            }
+            (ScVal::Map(Some(map)), None, Value::Object(_)) => map
+                .iter()
+                .map(|entry| Ok((to_string_with(&entry.key, bytes)?, &entry.val, None)))
+                .collect::<Result<Vec<_>, Error>>()?,
+            (ScVal::Map(Some(map)), Some(ScType::Map(map_type)), Value::Object(_)) => map
+                .iter()
+                .map(|entry| {
+                    let key = self.xdr_to_json_with(&entry.key, &map_type.key_type, bytes)?;
+                    Ok((
+                        key.to_string(),
+                        &entry.val,
+                        Some(map_type.value_type.as_ref()),
+                    ))
+                })
+                .collect::<Result<Vec<_>, Error>>()?,
+            (_, Some(ScType::Udt(ScSpecTypeUdt { name })), _) => {
+                return self.udt_json_in_order(val, &name.to_utf8_string_lossy(), json, bytes);
+            }
+            _ => Vec::new(),
+        };
+        self.json_object_in_order(&entries, json, bytes)
+    }
+
+    /// [`Spec::json_in_order`] for a value of a user-defined type, rendered by
+    /// [`Spec::udt_to_json`]
+    fn udt_json_in_order(
+        &self,
+        val: &ScVal,
+        name: &str,
+        json: &Value,
+        bytes: BytesFormat,
+    ) -> Result<JsonInOrder, Error> {
+        match (val, self.find(name)?, json) {
+            (ScVal::Map(Some(map)), ScSpecEntry::UdtStructV0(strukt), Value::Object(_)) => {
+                let entries = strukt
+                    .fields
+                    .iter()
+                    .zip(map.iter())
+                    .map(|(field, entry)| {
+                        (
+                            field.name.to_utf8_string_lossy(),
+                            &entry.val,
+                            Some(&field.type_),
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                self.json_object_in_order(&entries, json, bytes)
+            }
+            (ScVal::Vec(Some(vec_)), ScSpecEntry::UdtStructV0(strukt), Value::Array(array)) => {
+                let types = strukt
+                    .fields
+                    .iter()
+                    .map(|f| Some(&f.type_))
+                    .collect::<Vec<_>>();
+                self.json_array_in_order(vec_, &types, array, bytes)
+            }
+            (ScVal::Vec(Some(vec_)), ScSpecEntry::UdtUnionV0(union), Value::Object(object)) => {
+                let (Some((ScVal::Symbol(case_name), rest)), Some((key, case_json))) =
+                    (vec_.split_first(), object.iter().next())
+                else {
+                    return Ok(JsonInOrder::Value(json.clone()));
+                };
+                let case = union.cases.iter().find_map(|case| match case {
+                    ScSpecUdtUnionCaseV0::TupleV0(v) if v.name.as_vec() == case_name.as_vec() => {
+                        Some(v)
+                    }
+                    _ => None,
+                });
+                let case_json = match (case, rest) {
+                    (Some(case), [val]) if case.type_.len() == 1 && object.len() == 1 => {
+                        self.json_in_order(val, Some(&case.type_[0]), case_json, bytes)?
+                    }
+                    (Some(case), rest) if object.len() == 1 => {
+                        let Value::Array(array) = case_json else {
+                            return Ok(JsonInOrder::Value(json.clone()));
+                        };
+                        let types = case.type_.iter().map(Some).collect::<Vec<_>>();
+                        self.json_array_in_order(rest, &types, array, bytes)?
+                    }
+                    _ => return Ok(JsonInOrder::Value(json.clone())),
+                };
+                Ok(JsonInOrder::Object(OrderedMap(vec![(
+                    key.clone(),
+                    case_json,
+                )])))
+            }
+            _ => Ok(JsonInOrder::Value(json.clone())),
+        }
+    }
+
+    /// The `entries` of the object `json` in their order, with their values' types, or `json` as
+    /// it is if they aren't its keys
+    fn json_object_in_order(
+        &self,
+        entries: &[(String, &ScVal, Option<&ScType>)],
+        json: &Value,
+        bytes: BytesFormat,
+    ) -> Result<JsonInOrder, Error> {
+        let Value::Object(object) = json else {
+            return Ok(JsonInOrder::Value(json.clone()));
+        };
+        if entries.len() != object.len() || entries.iter().any(|(k, ..)| !object.contains_key(k)) {
+            return Ok(JsonInOrder::Value(json.clone()));
+        }
+        Ok(JsonInOrder::Object(OrderedMap(
+            entries
+                .iter()
+                .map(|(key, val, type_)| {
+                    Ok((
+                        key.clone(),
+                        self.json_in_order(val, *type_, &object[key], bytes)?,
+                    ))
+                })
+                .collect::<Result<_, Error>>()?,
+        )))
+    }
+
+    /// The array `json` of `vals` with their `types`, or as it is if their lengths differ
+    fn json_array_in_order(
+        &self,
+        vals: &[ScVal],
+        types: &[Option<&ScType>],
+        array: &[Value],
+        bytes: BytesFormat,
+    ) -> Result<JsonInOrder, Error> {
+        if vals.len() != array.len() || types.len() != array.len() {
+            return Ok(JsonInOrder::Value(Value::Array(array.to_vec())));
+        }
+        Ok(JsonInOrder::Array(
+            vals.iter()
+                .zip(types)
+                .zip(array)
+                .map(|((val, type_), json)| self.json_in_order(val, *type_, json, bytes))
+                .collect::<Result<_, _>>()?,
+        ))
+    }
+
+    /// Guess the spec type of a value that has no declared type, e.g. a contract storage entry,
+    /// so it can be passed to [`Spec::xdr_to_json`]. Maps and vectors are matched against the
+    /// spec's structs and unions by their field and case names. Returns `None` if nothing
+    /// matches.
+    pub fn infer_type(&self, val: &ScVal) -> Option<ScType> {
+        Some(match val {
+            ScVal::Bool(_) => ScType::Bool,
+            ScVal::Void => ScType::Void,
+            ScVal::U32(_) => ScType::U32,
+            ScVal::I32(_) => ScType::I32,
+            ScVal::U64(_) => ScType::U64,
+            ScVal::I64(_) => ScType::I64,
+            ScVal::Timepoint(_) => ScType::Timepoint,
+            ScVal::Duration(_) => ScType::Duration,
+            ScVal::U128(_) => ScType::U128,
+            ScVal::I128(_) => ScType::I128,
+            ScVal::U256(_) => ScType::U256,
+            ScVal::I256(_) => ScType::I256,
+            ScVal::Bytes(_) => ScType::Bytes,
+            ScVal::String(_) => ScType::String,
+            ScVal::Symbol(_) => ScType::Symbol,
+            ScVal::Address(_) => ScType::Address,
+            ScVal::Map(Some(map)) => self.infer_struct(map)?,
+            ScVal::Vec(Some(vec_)) => self.infer_union(vec_)?,
+            _ => return None,
+        })
+    }
+
+    fn infer_struct(&self, map: &ScMap) -> Option<ScType> {
+        let keys = map
+            .iter()
+            .map(|entry| match &entry.key {
+                ScVal::Symbol(name) => Some(name.as_vec()),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()?;
+        self.0.as_ref()?.iter().find_map(|entry| match entry {
+            ScSpecEntry::UdtStructV0(strukt)
+                if strukt.fields.len() == keys.len()
+                    && strukt
+                        .fields
+                        .iter()
+                        .zip(&keys)
+                        .all(|(field, key)| field.name.as_vec() == *key) =>
+            {
+                Some(ScType::Udt(ScSpecTypeUdt {
+                    name: strukt.name.clone(),
+                }))
+            }
+            _ => None,
+        })
+    }
+
+    fn infer_union(&self, vec_: &ScVec) -> Option<ScType> {
+        let (ScVal::Symbol(case_name), rest) = vec_.split_first()? else {
+            return None;
+        };
+        self.0.as_ref()?.iter().find_map(|entry| {
+            let ScSpecEntry::UdtUnionV0(union) = entry else {
+                return None;
+            };
+            union
+                .cases
+                .iter()
+                .any(|case| match case {
+                    ScSpecUdtUnionCaseV0::VoidV0(v) => {
+                        v.name.as_vec() == case_name.as_vec() && rest.is_empty()
+                    }
+                    ScSpecUdtUnionCaseV0::TupleV0(v) => {
+                        v.name.as_vec() == case_name.as_vec() && v.type_.len() == rest.len()
+                    }
+                })
+                .then(|| {
+                    ScType::Udt(ScSpecTypeUdt {
+                        name: union.name.clone(),
+                    })
+                })
+        })
+    }
+
     /// # Errors
     ///
     /// Might return an error
@@ -553,12 +866,26 @@ impl Spec {
         &self,
         vec_m: &VecM<ScVal, MAX>,
         type_: &ScType,
+    ) -> Result<Value, Error> {
+        self.vec_m_to_json_with(vec_m, type_, BytesFormat::default())
+    }
+
+    /// Like [`Spec::vec_m_to_json`], rendering `Bytes` and `BytesN` values in the given format.
+    ///
+    /// # Errors
+    ///
+    /// Might return an error
+    pub fn vec_m_to_json_with<const MAX: u32>(
+        &self,
+        vec_m: &VecM<ScVal, MAX>,
+        type_: &ScType,
+        bytes: BytesFormat,
     ) -> Result<Value, Error> {
         Ok(Value::Array(
             vec_m
                 .to_vec()
                 .iter()
-                .map(|sc_val| self.xdr_to_json(sc_val, type_))
+                .map(|sc_val| self.xdr_to_json_with(sc_val, type_, bytes))
                 .collect::<Result<Vec<_>, Error>>()?,
         ))
     }
@@ -567,17 +894,36 @@ impl Spec {
     ///
     /// Might return an error
     pub fn sc_map_to_json(&self, sc_map: &ScMap, type_: &ScSpecTypeMap) -> Result<Value, Error> {
+        self.sc_map_to_json_with(sc_map, type_, BytesFormat::default())
+    }
+
+    /// Like [`Spec::sc_map_to_json`], rendering `Bytes` and `BytesN` values in the given format.
+    ///
+    /// # Errors
+    ///
+    /// Might return an error
+    pub fn sc_map_to_json_with(
+        &self,
+        sc_map: &ScMap,
+        type_: &ScSpecTypeMap,
+        bytes: BytesFormat,
+    ) -> Result<Value, Error> {
         let v = sc_map
             .iter()
             .map(|ScMapEntry { key, val }| {
-                let key_s = self.xdr_to_json(key, &type_.key_type)?.to_string();
-                let val_value = self.xdr_to_json(val, &type_.value_type)?;
+                let key_s = self
+                    .xdr_to_json_with(key, &type_.key_type, bytes)?
+                    .to_string();
+                let val_value = self.xdr_to_json_with(val, &type_.value_type, bytes)?;
                 Ok((key_s, val_value))
             })
             .collect::<Result<serde_json::Map<String, Value>, Error>>()?;
         Ok(Value::Object(v))
     }
 
+    /// Tuple structs are rendered as arrays, also when they have a single field, so their output
+    /// always has the canonical shape `from_json` accepts.
+    ///
     /// # Errors
     ///
     /// Might return an error
@@ -586,6 +932,24 @@ impl Spec {
     ///
     /// May panic
     pub fn udt_to_json(&self, name: &StringM<60>, sc_obj: &ScVal) -> Result<Value, Error> {
+        self.udt_to_json_with(name, sc_obj, BytesFormat::default())
+    }
+
+    /// Like [`Spec::udt_to_json`], rendering `Bytes` and `BytesN` values in the given format.
+    ///
+    /// # Errors
+    ///
+    /// Might return an error
+    ///
+    /// # Panics
+    ///
+    /// May panic
+    pub fn udt_to_json_with(
+        &self,
+        name: &StringM<60>,
+        sc_obj: &ScVal,
+        bytes: BytesFormat,
+    ) -> Result<Value, Error> {
         let name = &name.to_utf8_string_lossy();
         let udt = self.find(name)?;
         Ok(match (sc_obj, udt) {
@@ -595,7 +959,7 @@ impl Spec {
                     .iter()
                     .zip(map.iter())
                     .map(|(field, entry)| {
-                        let val = self.xdr_to_json(&entry.val, &field.type_)?;
+                        let val = self.xdr_to_json_with(&entry.val, &field.type_, bytes)?;
                         Ok((field.name.to_utf8_string_lossy(), val))
                     })
                     .collect::<Result<serde_json::Map<String, _>, Error>>()?,
@@ -605,7 +969,7 @@ impl Spec {
                     .fields
                     .iter()
                     .zip(vec_.iter())
-                    .map(|(field, entry)| self.xdr_to_json(entry, &field.type_))
+                    .map(|(field, entry)| self.xdr_to_json_with(entry, &field.type_, bytes))
                     .collect::<Result<Vec<_>, Error>>()?,
             ),
             (ScVal::Vec(Some(vec_)), ScSpecEntry::UdtUnionV0(union)) => {
@@ -642,13 +1006,13 @@ impl Spec {
                             )
                         })?;
                         let val = if v.type_.len() == 1 {
-                            self.xdr_to_json(&rest[0], &v.type_[0])?
+                            self.xdr_to_json_with(&rest[0], &v.type_[0], bytes)?
                         } else {
                             Value::Array(
                                 v.type_
                                     .iter()
                                     .zip(rest.iter())
-                                    .map(|(type_, val)| self.xdr_to_json(val, type_))
+                                    .map(|(type_, val)| self.xdr_to_json_with(val, type_, bytes))
                                     .collect::<Result<Vec<_>, Error>>()?,
                             )
                         };
@@ -673,23 +1037,44 @@ impl Spec {
     ///
     /// Some types are not yet supported and will cause a panic if supplied
     pub fn sc_object_to_json(&self, val: &ScVal, spec_type: &ScType) -> Result<Value, Error> {
+        self.sc_object_to_json_with(val, spec_type, BytesFormat::default())
+    }
+
+    /// Like [`Spec::sc_object_to_json`], rendering `Bytes` and `BytesN` values in the given
+    /// format.
+    ///
+    /// # Errors
+    ///
+    /// Might return an error
+    ///
+    /// # Panics
+    ///
+    /// Some types are not yet supported and will cause a panic if supplied
+    pub fn sc_object_to_json_with(
+        &self,
+        val: &ScVal,
+        spec_type: &ScType,
+        bytes: BytesFormat,
+    ) -> Result<Value, Error> {
         Ok(match (val, spec_type) {
             (ScVal::Vec(Some(ScVec(vec_m))), ScType::Vec(type_)) => {
-                self.vec_m_to_json(vec_m, &type_.element_type)?
+                self.vec_m_to_json_with(vec_m, &type_.element_type, bytes)?
             }
             (ScVal::Vec(Some(ScVec(vec_m))), ScType::Tuple(tuple_type)) => Value::Array(
                 vec_m
                     .iter()
                     .zip(tuple_type.value_types.iter())
-                    .map(|(v, t)| self.xdr_to_json(v, t))
+                    .map(|(v, t)| self.xdr_to_json_with(v, t, bytes))
                     .collect::<Result<Vec<_>, _>>()?,
             ),
             (
                 sc_obj @ (ScVal::Vec(_) | ScVal::Map(_) | ScVal::U32(_)),
                 ScType::Udt(ScSpecTypeUdt { name }),
-            ) => self.udt_to_json(name, sc_obj)?,
+            ) => self.udt_to_json_with(name, sc_obj, bytes)?,
 
-            (ScVal::Map(Some(map)), ScType::Map(map_type)) => self.sc_map_to_json(map, map_type)?,
+            (ScVal::Map(Some(map)), ScType::Map(map_type)) => {
+                self.sc_map_to_json_with(map, map_type, bytes)?
+            }
 
             (ScVal::U64(u64_), ScType::U64) => Value::Number(serde_json::Number::from(*u64_)),
 
@@ -713,7 +1098,7 @@ impl Spec {
             }
 
             (ScVal::Bytes(v), ScType::Bytes | ScType::BytesN(_)) => {
-                Value::String(to_lower_hex(v.as_slice()))
+                Value::String(bytes.format(v.as_slice()))
             }
 
             (ScVal::Bytes(_), ScType::Udt(_)) => todo!(),
@@ -724,7 +1109,7 @@ impl Spec {
 
             (ok_val, ScType::Result(result_type)) => {
                 let ScSpecTypeResult { ok_type, .. } = result_type.as_ref();
-                self.xdr_to_json(ok_val, ok_type)?
+                self.xdr_to_json_with(ok_val, ok_type, bytes)?
             }
 
             (x, y) => return Err(Error::InvalidPair(x.clone(), y.clone())),
@@ -739,6 +1124,96 @@ pub fn from_string_primitive(s: &str, t: &ScType) -> Result<ScVal, Error> {
     Spec::from_string_primitive(s, t)
 }
 
+/// Parses a type name as [`Spec::arg_value_name`] renders it, e.g. `u32`, `Option<u32>` or
+/// `Map<Symbol, i128>`. User-defined types are not supported, as there is no spec to look them up
+/// in.
+///
+/// # Errors
+///
+/// If the name is not a known type
+pub fn type_from_name(name: &str) -> Result<ScType, Error> {
+    let name = name.trim();
+    let unknown = || Error::UnknownType(name.to_string());
+    if let Some((outer, inner)) = name.strip_suffix('>').and_then(|n| n.split_once('<')) {
+        let args = split_type_args(inner)
+            .into_iter()
+            .map(type_from_name)
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut args = args.into_iter();
+        let mut arg = || args.next().map(Box::new).ok_or_else(unknown);
+        let t = match outer.trim() {
+            "Option" => ScType::Option(Box::new(ScSpecTypeOption { value_type: arg()? })),
+            "Array" | "Vec" => ScType::Vec(Box::new(ScSpecTypeVec {
+                element_type: arg()?,
+            })),
+            "Map" => ScType::Map(Box::new(ScSpecTypeMap {
+                key_type: arg()?,
+                value_type: arg()?,
+            })),
+            "Result" => ScType::Result(Box::new(ScSpecTypeResult {
+                ok_type: arg()?,
+                error_type: arg()?,
+            })),
+            "Tuple" => {
+                let value_types = std::iter::from_fn(|| arg().ok().map(|t| *t)).collect::<Vec<_>>();
+                ScType::Tuple(Box::new(ScSpecTypeTuple {
+                    value_types: value_types.try_into().map_err(|_| unknown())?,
+                }))
+            }
+            _ => return Err(unknown()),
+        };
+        return if arg().is_ok() { Err(unknown()) } else { Ok(t) };
+    }
+    Ok(match name {
+        "u32" => ScType::U32,
+        "i32" => ScType::I32,
+        "u64" => ScType::U64,
+        "i64" => ScType::I64,
+        "u128" => ScType::U128,
+        "i128" => ScType::I128,
+        "u256" => ScType::U256,
+        "i256" => ScType::I256,
+        "bool" => ScType::Bool,
+        "Symbol" => ScType::Symbol,
+        "String" => ScType::String,
+        "Address" => ScType::Address,
+        "Error" => ScType::Error,
+        "Timepoint" => ScType::Timepoint,
+        "Duration" => ScType::Duration,
+        "Null" => ScType::Void,
+        "hex_bytes" | "Bytes" => ScType::Bytes,
+        "Val" => ScType::Val,
+        _ => {
+            let n = name
+                .strip_suffix("_hex_bytes")
+                .and_then(|n| n.parse().ok())
+                .ok_or_else(unknown)?;
+            ScType::BytesN(ScSpecTypeBytesN { n })
+        }
+    })
+}
+
+/// Splits the comma-separated arguments of a generic type, leaving nested ones whole
+fn split_type_args(s: &str) -> Vec<&str> {
+    let mut args = vec![];
+    let (mut depth, mut start) = (0, 0);
+    for (i, c) in s.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' => depth -= 1,
+            ',' if depth == 0 => {
+                args.push(&s[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if !s[start..].trim().is_empty() {
+        args.push(&s[start..]);
+    }
+    args
+}
+
 fn parse_const_enum(num: &serde_json::Number, enum_: &ScSpecUdtEnumV0) -> Result<ScVal, Error> {
     let num = num
         .as_u64()
@@ -752,6 +1227,16 @@ fn parse_const_enum(num: &serde_json::Number, enum_: &ScSpecUdtEnumV0) -> Result
         .map(|c| ScVal::U32(c.value))
 }
 
+/// The value of the const enum case named `name`
+fn parse_const_enum_name(name: &str, enum_: &ScSpecUdtEnumV0) -> Result<ScVal, Error> {
+    enum_
+        .cases
+        .iter()
+        .find(|c| c.name.to_utf8_string_lossy() == name)
+        .ok_or_else(|| Error::EnumConstName(name.to_string()))
+        .map(|c| ScVal::U32(c.value))
+}
+
 /// # Errors
 ///
 /// Might return an error
@@ -859,7 +1344,7 @@ pub fn from_json_primitives(v: &Value, t: &ScType) -> Result<ScVal, Error> {
                 }
             }
             // Bytes are not an address, just parse as a hex string
-            utils::padded_hex_from_str(s, bytes.n as usize)
+            utils::padded_hex_from_str(strip_hex_prefix(s), bytes.n as usize)
                 .map_err(|_| Error::InvalidValue(Some(t.clone())))?
                 .try_into()
                 .map_err(|_| Error::InvalidValue(Some(t.clone())))?
@@ -868,7 +1353,7 @@ pub fn from_json_primitives(v: &Value, t: &ScType) -> Result<ScVal, Error> {
             from_json_primitives(&Value::String(format!("{n}")), &ScType::Bytes)?
         }
         (ScType::Bytes, Value::String(s)) => ScVal::Bytes(
-            hex::decode(s)
+            hex::decode(strip_hex_prefix(s))
                 .map_err(|_| Error::InvalidValue(Some(t.clone())))?
                 .try_into()
                 .map_err(|_| Error::InvalidValue(Some(t.clone())))?,
@@ -901,6 +1386,15 @@ pub fn from_json_primitives(v: &Value, t: &ScType) -> Result<ScVal, Error> {
 ///
 /// Might return an error
 pub fn to_string(v: &ScVal) -> Result<String, Error> {
+    to_string_with(v, BytesFormat::default())
+}
+
+/// Like [`to_string`], rendering `Bytes` values in the given format.
+///
+/// # Errors
+///
+/// Might return an error
+pub fn to_string_with(v: &ScVal, bytes: BytesFormat) -> Result<String, Error> {
     #[allow(clippy::match_same_arms)]
     Ok(match v {
         // If symbols are a top-level thing we omit the wrapping quotes
@@ -909,15 +1403,108 @@ pub fn to_string(v: &ScVal) -> Result<String, Error> {
             .map_err(|_| Error::InvalidValue(Some(ScType::Symbol)))?
             .to_string(),
         ScVal::LedgerKeyContractInstance => "LedgerKeyContractInstance".to_string(),
-        _ => serde_json::to_string(&to_json(v)?)?,
+        _ => serde_json::to_string(&to_json_with(v, bytes)?)?,
     })
 }
 
+/// An error value labelled with its type and code, e.g.
+/// `{ "error": { "type": "contract", "code": 1 } }`. Host error codes are given as the number of
+/// their `ScErrorCode`.
+fn error_to_json(e: &ScError) -> Value {
+    let type_ = match e.discriminant() {
+        ScErrorType::Contract => "contract",
+        ScErrorType::WasmVm => "wasm_vm",
+        ScErrorType::Context => "context",
+        ScErrorType::Storage => "storage",
+        ScErrorType::Object => "object",
+        ScErrorType::Crypto => "crypto",
+        ScErrorType::Events => "events",
+        ScErrorType::Budget => "budget",
+        ScErrorType::Value => "value",
+        ScErrorType::Auth => "auth",
+    };
+    let code = match e {
+        ScError::Contract(code) => Value::from(*code),
+        ScError::WasmVm(code)
+        | ScError::Context(code)
+        | ScError::Storage(code)
+        | ScError::Object(code)
+        | ScError::Crypto(code)
+        | ScError::Events(code)
+        | ScError::Budget(code)
+        | ScError::Value(code)
+        | ScError::Auth(code) => Value::from(i32::from(*code)),
+    };
+    json!({ "error": { "type": type_, "code": code } })
+}
+
 /// # Errors
 ///
 /// Might return an error
-#[allow(clippy::too_many_lines)]
 pub fn to_json(v: &ScVal) -> Result<Value, Error> {
+    to_json_with(v, BytesFormat::default())
+}
+
+/// The JSON text of [`to_json`], with the entries of maps in the order of their `ScMap` instead
+/// of sorted by the text of their keys
+///
+/// # Errors
+///
+/// Might return an error
+pub fn to_json_string(v: &ScVal) -> Result<String, Error> {
+    to_json_string_with(v, BytesFormat::default())
+}
+
+/// Like [`to_json_string`], rendering `Bytes` values in the given format.
+///
+/// # Errors
+///
+/// Might return an error
+pub fn to_json_string_with(v: &ScVal, bytes: BytesFormat) -> Result<String, Error> {
+    let json = to_json_with(v, bytes)?;
+    Ok(serde_json::to_string(
+        &Spec(None).json_in_order(v, None, &json, bytes)?,
+    )?)
+}
+
+/// JSON whose objects keep their entries in order, as [`Spec::xdr_to_json_string`] and
+/// [`to_json_string`] write it
+enum JsonInOrder {
+    Value(Value),
+    Array(Vec<JsonInOrder>),
+    Object(OrderedMap),
+}
+
+/// The entries of a JSON object, serialized in their order instead of sorted by key
+struct OrderedMap(Vec<(String, JsonInOrder)>);
+
+impl Serialize for JsonInOrder {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            JsonInOrder::Value(value) => value.serialize(serializer),
+            JsonInOrder::Array(items) => items.serialize(serializer),
+            JsonInOrder::Object(map) => map.serialize(serializer),
+        }
+    }
+}
+
+impl Serialize for OrderedMap {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for (key, value) in &self.0 {
+            map.serialize_entry(key, value)?;
+        }
+        map.end()
+    }
+}
+
+/// Like [`to_json`], rendering `Bytes` values in the given format.
+///
+/// # Errors
+///
+/// Might return an error
+#[allow(clippy::too_many_lines)]
+pub fn to_json_with(v: &ScVal, bytes: BytesFormat) -> Result<Value, Error> {
     #[allow(clippy::match_same_arms)]
     let val: Value = match v {
         ScVal::Bool(b) => Value::Bool(*b),
@@ -944,7 +1531,7 @@ pub fn to_json(v: &ScVal) -> Result<Value, Error> {
                 || Ok(vec![]),
                 |v| {
                     v.iter()
-                        .map(|item| -> Result<Value, Error> { to_json(item) })
+                        .map(|item| -> Result<Value, Error> { to_json_with(item, bytes) })
                         .collect()
                 },
             );
@@ -955,13 +1542,13 @@ pub fn to_json(v: &ScVal) -> Result<Value, Error> {
             // TODO: What do we do if the key is not a string?
             let mut m = serde_json::Map::<String, Value>::with_capacity(v.len());
             for ScMapEntry { key, val } in v.iter() {
-                let k: String = to_string(key)?;
-                let v: Value = to_json(val).map_err(|_| Error::InvalidValue(None))?;
+                let k: String = to_string_with(key, bytes)?;
+                let v: Value = to_json_with(val, bytes).map_err(|_| Error::InvalidValue(None))?;
                 m.insert(k, v);
             }
             Value::Object(m)
         }
-        ScVal::Bytes(v) => Value::String(to_lower_hex(v.as_slice())),
+        ScVal::Bytes(v) => Value::String(bytes.format(v.as_slice())),
         ScVal::Address(v) => sc_address_to_json(v),
         ScVal::U128(n) => {
             let hi: [u8; 8] = n.hi.to_be_bytes();
@@ -1034,11 +1621,17 @@ pub fn to_json(v: &ScVal) -> Result<Value, Error> {
         ScVal::LedgerKeyNonce(ScNonceKey { nonce }) => {
             Value::Number(serde_json::Number::from(*nonce))
         }
-        ScVal::Error(e) => serde_json::to_value(e)?,
+        ScVal::Error(e) => error_to_json(e),
     };
     Ok(val)
 }
 
+fn strip_hex_prefix(s: &str) -> &str {
+    s.strip_prefix("0x")
+        .or_else(|| s.strip_prefix("0X"))
+        .unwrap_or(s)
+}
+
 fn sc_address_to_json(v: &ScAddress) -> Value {
     match v {
         ScAddress::Account(AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(k)))) => {
@@ -1063,12 +1656,28 @@ fn sc_address_from_json(s: &str) -> Result<ScVal, Error> {
         .ok_or(Error::InvalidValue(Some(ScType::Address)))
 }
 
-fn to_lower_hex(bytes: &[u8]) -> String {
-    let mut res = String::with_capacity(bytes.len());
-    for b in bytes {
-        res.push_str(&format!("{b:02x}"));
+/// How `Bytes` and `BytesN` values are rendered as JSON strings
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum BytesFormat {
+    /// Bare lowercase hex, e.g. `beefface`
+    #[default]
+    Hex,
+    /// Lowercase hex with a `0x` prefix, e.g. `0xbeefface`
+    PrefixedHex,
+}
+
+impl BytesFormat {
+    #[must_use]
+    pub fn format(self, bytes: &[u8]) -> String {
+        let mut res = String::with_capacity(bytes.len() * 2 + 2);
+        if self == BytesFormat::PrefixedHex {
+            res.push_str("0x");
+        }
+        for b in bytes {
+            res.push_str(&format!("{b:02x}"));
+        }
+        res
     }
-    res
 }
 
 impl Spec {
@@ -1287,8 +1896,8 @@ impl Spec {
             ScType::Udt(ScSpecTypeUdt { name }) => {
                 self.example_udts(name.to_utf8_string_lossy().as_ref())
             }
-            // No specific value name for these yet.
-            ScType::Val => None,
+            // Any value is accepted, given as the JSON form of an `ScVal`.
+            ScType::Val => Some(r#"{ "u32": 1 }"#.to_string()),
         }
     }
 
@@ -1361,7 +1970,7 @@ impl Spec {
 mod tests {
     use super::*;
 
-    use stellar_xdr::curr::ScSpecTypeBytesN;
+    use stellar_xdr::curr::{ScErrorCode, ScSpecUdtStructFieldV0};
 
     #[test]
     fn from_json_primitives_bytesn() {
@@ -1410,6 +2019,218 @@ mod tests {
         );
     }
 
+    #[test]
+    fn bytes_round_trip_hex_prefix() {
+        let spec = Spec(None);
+        let bytes = ScVal::Bytes(ScBytes(vec![0xbe, 0xef, 0xfa, 0xce].try_into().unwrap()));
+        for t in [ScType::Bytes, ScType::BytesN(ScSpecTypeBytesN { n: 4 })] {
+            for (format, expected) in [
+                (BytesFormat::Hex, "beefface"),
+                (BytesFormat::PrefixedHex, "0xbeefface"),
+            ] {
+                let json = spec.xdr_to_json_with(&bytes, &t, format).unwrap();
+                assert_eq!(json, Value::String(expected.to_string()));
+                assert_eq!(from_json_primitives(&json, &t).unwrap(), bytes);
+            }
+        }
+        assert_eq!(
+            to_string_with(&bytes, BytesFormat::PrefixedHex).unwrap(),
+            r#""0xbeefface""#
+        );
+        assert_eq!(
+            from_json_primitives(&Value::String("0XBEEFFACE".to_string()), &ScType::Bytes).unwrap(),
+            bytes
+        );
+    }
+
+    #[test]
+    fn error_to_json_labels_type_and_code() {
+        assert_eq!(
+            to_json(&ScVal::Error(ScError::Contract(1))).unwrap(),
+            json!({ "error": { "type": "contract", "code": 1 } })
+        );
+        assert_eq!(
+            to_json(&ScVal::Error(ScError::Budget(ScErrorCode::ExceededLimit))).unwrap(),
+            json!({ "error": { "type": "budget", "code": 5 } })
+        );
+    }
+
+    #[test]
+    fn map_to_json_keeps_sc_map_order() {
+        let map = ScVal::Map(Some(
+            ScMap::sorted_from([
+                (ScVal::U32(10), ScVal::Bool(false)),
+                (ScVal::U32(2), ScVal::Bool(true)),
+                (ScVal::U32(1), ScVal::Bool(true)),
+            ])
+            .unwrap(),
+        ));
+        let expected = r#"{"1":true,"2":true,"10":false}"#;
+        assert_eq!(to_json_string(&map).unwrap(), expected);
+        assert_eq!(
+            serde_json::from_str::<Value>(expected).unwrap(),
+            to_json(&map).unwrap()
+        );
+        let map_type = ScType::Map(Box::new(ScSpecTypeMap {
+            key_type: Box::new(ScType::U32),
+            value_type: Box::new(ScType::Bool),
+        }));
+        assert_eq!(
+            Spec(None).xdr_to_json_string(&map, &map_type).unwrap(),
+            expected
+        );
+
+        // Also inside other values
+        let maps = ScVal::Vec(Some(vec![map.clone(), map].try_into().unwrap()));
+        let maps_type = ScType::Option(Box::new(ScSpecTypeOption {
+            value_type: Box::new(ScType::Vec(Box::new(ScSpecTypeVec {
+                element_type: Box::new(map_type),
+            }))),
+        }));
+        assert_eq!(
+            Spec(None).xdr_to_json_string(&maps, &maps_type).unwrap(),
+            format!("[{expected},{expected}]")
+        );
+        assert_eq!(
+            to_json_string(&maps).unwrap(),
+            format!("[{expected},{expected}]")
+        );
+    }
+
+    #[test]
+    fn type_names_round_trip() {
+        let spec = Spec(None);
+        for name in [
+            "u32",
+            "i128",
+            "Symbol",
+            "32_hex_bytes",
+            "Option<u32>",
+            "Array<Option<Address>>",
+            "Map<Symbol, i128>",
+            "Result<u64, Error>",
+            "Tuple<u32, Map<u32, bool>, String>",
+        ] {
+            let t = type_from_name(name).unwrap();
+            assert_eq!(spec.arg_value_name(&t, 0).unwrap(), name);
+        }
+        assert_eq!(
+            type_from_name("Map<Symbol,i128>").unwrap(),
+            type_from_name("Map<Symbol, i128>").unwrap()
+        );
+        for name in ["u33", "Option<u32, u32>", "Map<u32>", "Foo<u32>"] {
+            assert!(type_from_name(name).is_err(), "{name}");
+        }
+    }
+
+    #[test]
+    fn example_val() {
+        let spec = Spec(None);
+        let example = spec.example(&ScType::Val).unwrap();
+        let parsed = spec
+            .from_json(&serde_json::from_str(&example).unwrap(), &ScType::Val)
+            .unwrap();
+        assert_eq!(parsed, ScVal::U32(1));
+    }
+
+    #[test]
+    fn single_field_tuple_strukt_round_trip() {
+        let spec = Spec(Some(vec![ScSpecEntry::UdtStructV0(ScSpecUdtStructV0 {
+            doc: StringM::default(),
+            lib: StringM::default(),
+            name: "Wrapper".try_into().unwrap(),
+            fields: vec![ScSpecUdtStructFieldV0 {
+                doc: StringM::default(),
+                name: "0".try_into().unwrap(),
+                type_: ScType::U32,
+            }]
+            .try_into()
+            .unwrap(),
+        })]));
+        let t = ScType::Udt(ScSpecTypeUdt {
+            name: "Wrapper".try_into().unwrap(),
+        });
+        let expected = ScVal::Vec(Some(vec![ScVal::U32(5)].try_into().unwrap()));
+        for input in [json!([5]), json!(5), json!({ "0": 5 })] {
+            assert_eq!(spec.from_json(&input, &t).unwrap(), expected);
+        }
+        let output = spec.xdr_to_json(&expected, &t).unwrap();
+        assert_eq!(output, json!([5]));
+        assert_eq!(spec.from_json(&output, &t).unwrap(), expected);
+    }
+
+    #[test]
+    fn infer_storage_types() {
+        let spec = Spec(Some(vec![
+            ScSpecEntry::UdtStructV0(ScSpecUdtStructV0 {
+                doc: StringM::default(),
+                lib: StringM::default(),
+                name: "Point".try_into().unwrap(),
+                fields: vec![
+                    ScSpecUdtStructFieldV0 {
+                        doc: StringM::default(),
+                        name: "x".try_into().unwrap(),
+                        type_: ScType::U32,
+                    },
+                    ScSpecUdtStructFieldV0 {
+                        doc: StringM::default(),
+                        name: "y".try_into().unwrap(),
+                        type_: ScType::U32,
+                    },
+                ]
+                .try_into()
+                .unwrap(),
+            }),
+            ScSpecEntry::UdtUnionV0(ScSpecUdtUnionV0 {
+                doc: StringM::default(),
+                lib: StringM::default(),
+                name: "DataKey".try_into().unwrap(),
+                cases: vec![
+                    ScSpecUdtUnionCaseV0::VoidV0(ScSpecUdtUnionCaseVoidV0 {
+                        doc: StringM::default(),
+                        name: "Counter".try_into().unwrap(),
+                    }),
+                    ScSpecUdtUnionCaseV0::TupleV0(ScSpecUdtUnionCaseTupleV0 {
+                        doc: StringM::default(),
+                        name: "Balance".try_into().unwrap(),
+                        type_: vec![ScType::U32].try_into().unwrap(),
+                    }),
+                ]
+                .try_into()
+                .unwrap(),
+            }),
+        ]));
+        let symbol = |s: &str| ScVal::Symbol(ScSymbol(s.try_into().unwrap()));
+        let point = ScVal::Map(Some(
+            vec![
+                ScMapEntry {
+                    key: symbol("x"),
+                    val: ScVal::U32(1),
+                },
+                ScMapEntry {
+                    key: symbol("y"),
+                    val: ScVal::U32(2),
+                },
+            ]
+            .try_into()
+            .unwrap(),
+        ));
+        let key = ScVal::Vec(Some(
+            vec![symbol("Balance"), ScVal::U32(3)].try_into().unwrap(),
+        ));
+        let unknown = ScVal::Vec(Some(vec![symbol("Balance")].try_into().unwrap()));
+
+        let t = spec.infer_type(&point).unwrap();
+        assert_eq!(
+            spec.xdr_to_json(&point, &t).unwrap(),
+            json!({ "x": 1, "y": 2 })
+        );
+        let t = spec.infer_type(&key).unwrap();
+        assert_eq!(spec.xdr_to_json(&key, &t).unwrap(), json!({ "Balance": 3 }));
+        assert_eq!(spec.infer_type(&ScVal::U32(7)), Some(ScType::U32));
+        assert_eq!(spec.infer_type(&unknown), None);
+    }
+
     #[test]
     fn test_sc_address_from_json_strkey() {
         // All zero contract address
@@ -16,6 +16,10 @@ const NETWORK_PASSPHRASE_TESTNET: &str = "Test SDF Network ; September 2015";
 const NETWORK_PASSPHRASE_FUTURENET: &str = "Test SDF Future Network ; October 2022";
 const NETWORK_PASSPHRASE_STANDALONE: &str = "Standalone Network ; February 2017";
 
+/// Template files of a package with only the contract's types and client, without the README and
+/// the network constants of a project
+const PACKAGE_FILES: [&str; 3] = ["package.json", "tsconfig.json", "src/index.ts"];
+
 pub struct Project(PathBuf);
 
 impl TryInto<Project> for PathBuf {
@@ -122,6 +126,30 @@ impl Project {
     }
 }
 
+/// Write a package named `package_name` to `out_dir`, with the contract's types and client
+/// appended to `src/index.ts` of the template
+pub fn write_package(
+    out_dir: &Path,
+    package_name: &str,
+    spec: &[ScSpecEntry],
+) -> std::io::Result<()> {
+    PACKAGE_FILES.into_iter().try_for_each(|file_name| {
+        let template = PROJECT_DIR
+            .get_file(file_name)
+            .and_then(|file| file.contents_utf8())
+            .expect("package files are in the project template");
+        let mut contents = template.replace("INSERT_CONTRACT_NAME_HERE", package_name);
+        if file_name == "src/index.ts" {
+            contents = format!("{contents}\n\n{}", generate(spec));
+        }
+        let file = out_dir.join(file_name);
+        if let Some(dir) = file.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(file, contents)
+    })
+}
+
 #[cfg(test)]
 mod test {
     use temp_dir::TempDir;
@@ -4,7 +4,7 @@
     clippy::missing_panics_doc
 )]
 
-use std::{fs, io};
+use std::{fs, io, path::Path};
 
 use crate::types::Type;
 use itertools::Itertools;
@@ -53,6 +53,25 @@ pub fn generate_from_file(
     Ok(json)
 }
 
+#[derive(thiserror::Error, Debug)]
+pub enum GeneratePackageError {
+    #[error("writing package: {0}")]
+    Io(io::Error),
+    #[error("getting contract spec: {0}")]
+    GetSpec(FromWasmError),
+}
+
+/// Generate a package for the contract in `out_dir`: `src/index.ts` with its types and a client
+/// embedding its spec, and a minimal `package.json` and `tsconfig.json` to build it with `tsc`
+pub fn generate_package(
+    wasm: &[u8],
+    out_dir: &Path,
+    package_name: &str,
+) -> Result<(), GeneratePackageError> {
+    let spec = from_wasm(wasm).map_err(GeneratePackageError::GetSpec)?;
+    boilerplate::write_package(out_dir, package_name, &spec).map_err(GeneratePackageError::Io)
+}
+
 pub fn generate_from_wasm(wasm: &[u8]) -> Result<String, FromWasmError> {
     let spec = from_wasm(wasm)?;
     let json = generate(&spec);
@@ -333,3 +352,28 @@ pub fn type_to_ts(value: &types::Type) -> String {
         types::Type::Duration => "Duration".to_string(),
     }
 }
+
+#[cfg(test)]
+mod test {
+    use temp_dir::TempDir;
+
+    use super::*;
+
+    const EXAMPLE_WASM: &[u8] = include_bytes!(
+        "../../../../target/wasm32-unknown-unknown/test-wasms/test_custom_types.wasm"
+    );
+
+    #[test]
+    fn package_has_client_and_manifests() {
+        let out_dir = TempDir::new().unwrap();
+        generate_package(EXAMPLE_WASM, out_dir.path(), "custom-types").unwrap();
+
+        let index = fs::read_to_string(out_dir.path().join("src/index.ts")).unwrap();
+        assert!(index.contains("export class Client extends ContractClient"));
+        assert!(index.contains("new ContractSpec(["));
+        let package = fs::read_to_string(out_dir.path().join("package.json")).unwrap();
+        assert!(package.contains(r#""name": "custom-types""#));
+        assert!(out_dir.path().join("tsconfig.json").is_file());
+        assert!(!out_dir.path().join("README.md").exists());
+    }
+}
@@ -17,17 +17,20 @@
 //! - `TestEnv::cmd_arr` is a generic function which takes an array of `&str` which is passed directly to clap.
 //!    This is the preferred way since it ensures no string parsing footguns.
 //! - `TestEnv::invoke` a convenience function for using the invoke command.
+//! - `TestEnv::invoke_typed` invokes and deserializes the result into a Rust type.
 //!
 #![allow(
     clippy::missing_errors_doc,
     clippy::must_use_candidate,
     clippy::missing_panics_doc
 )]
-use std::{ffi::OsString, fmt::Display, path::Path};
+use std::{ffi::OsString, fmt::Display, ops::Range, path::Path};
 
 use assert_cmd::{assert::Assert, Command};
 use assert_fs::{fixture::FixtureError, prelude::PathChild, TempDir};
 use fs_extra::dir::CopyOptions;
+use serde::de::DeserializeOwned;
+use sha2::{Digest, Sha256};
 
 use soroban_cli::{
     commands::{config, contract::invoke, global, keys, network, NetworkRunnable},
@@ -51,6 +54,9 @@ pub enum Error {
 
     #[error(transparent)]
     Invoke(#[from] invoke::Error),
+
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
 }
 
 /// A `TestEnv` is a contained process for a specific test, with its own ENV and
@@ -58,6 +64,9 @@ pub enum Error {
 pub struct TestEnv {
     pub temp_dir: TempDir,
     pub rpc_url: String,
+    /// Seed that accounts generated without an explicit seed are derived from, see
+    /// `TestEnv::deterministic`
+    pub seed: Option<u64>,
 }
 
 impl Default for TestEnv {
@@ -66,6 +75,7 @@ impl Default for TestEnv {
         Self {
             temp_dir,
             rpc_url: "http://localhost:8889/soroban/rpc".to_string(),
+            seed: None,
         }
     }
 }
@@ -108,16 +118,39 @@ impl TestEnv {
     }
 
     pub fn new() -> TestEnv {
+        Self::with_rpc_url(&Self::rpc_url_from_env())
+    }
+
+    /// Like `TestEnv::new`, but every account generated without an explicit seed, including
+    /// the `test` account, is derived from `seed` and the account's name, so the same seed
+    /// always gives the same addresses. The `config` and `data` directories are created up
+    /// front, so the layout of the temp dir doesn't depend on which commands have run. The RPC
+    /// URL is still read from `SOROBAN_RPC_URL` or `SOROBAN_PORT`.
+    pub fn deterministic(seed: u64) -> TestEnv {
+        let env = TestEnv {
+            rpc_url: Self::rpc_url_from_env(),
+            seed: Some(seed),
+            ..Default::default()
+        };
+        for dir in ["config", "data"] {
+            std::fs::create_dir_all(env.temp_dir.join(dir)).unwrap();
+        }
+        env.generate_account(TEST_ACCOUNT, None).assert().success();
+        env
+    }
+
+    fn rpc_url_from_env() -> String {
         if let Ok(rpc_url) = std::env::var("SOROBAN_RPC_URL") {
-            return Self::with_rpc_url(&rpc_url);
+            return rpc_url;
         }
         let host_port = std::env::var("SOROBAN_PORT")
             .as_deref()
             .ok()
             .and_then(|n| n.parse().ok())
             .unwrap_or(8000);
-        Self::with_port(host_port)
+        format!("http://localhost:{host_port}/soroban/rpc")
     }
+
     /// Create a new `assert_cmd::Command` for a given subcommand and set's the current directory
     /// to be the internal `temp_dir`.
     pub fn new_assert_cmd(&self, subcommand: &str) -> Command {
@@ -139,6 +172,7 @@ impl TestEnv {
     pub fn generate_account(&self, account: &str, seed: Option<String>) -> Command {
         let mut cmd = self.new_assert_cmd("keys");
         cmd.arg("generate").arg(account);
+        let seed = seed.or_else(|| self.seed.map(|seed| account_seed(seed, account)));
         if let Some(seed) = seed {
             cmd.arg(format!("--seed={seed}"));
         }
@@ -186,6 +220,27 @@ impl TestEnv {
         self.invoke_with(command_str, "test").await
     }
 
+    /// Invoke with the `test` account and deserialize the JSON result into `T`. Integers the CLI
+    /// renders as strings, like `"42"`, deserialize into integer types too.
+    pub async fn invoke_typed<T: DeserializeOwned>(
+        &self,
+        command_str: &[impl AsRef<str>],
+    ) -> Result<T, Error> {
+        let output = self.invoke_with_test(command_str).await?;
+        let value = if output.trim().is_empty() {
+            serde_json::Value::Null
+        } else {
+            serde_json::from_str(&output)?
+        };
+        match serde_json::from_value(value) {
+            Ok(typed) => Ok(typed),
+            Err(e) => match serde_json::from_str(&output) {
+                Ok(serde_json::Value::String(s)) => serde_json::from_str(&s).map_err(|_| e.into()),
+                _ => Err(e.into()),
+            },
+        }
+    }
+
     /// A convenience method for using the invoke command.
     pub async fn invoke_with<I: AsRef<str>>(
         &self,
@@ -225,6 +280,7 @@ impl TestEnv {
                 rpc_url: Some(self.rpc_url.clone()),
                 network_passphrase: Some(LOCAL_NETWORK_PASSPHRASE.to_string()),
                 network: None,
+                rpc_timeout: None,
             },
             source_account: account.to_string(),
             locator: config::locator::Args {
@@ -232,6 +288,7 @@ impl TestEnv {
                 config_dir,
             },
             hd_path: None,
+            sign_with_ledger: false,
         }
     }
 
@@ -245,12 +302,7 @@ impl TestEnv {
         cmd.run_against_rpc_server(
             Some(&global::Args {
                 locator: config.locator.clone(),
-                filter_logs: Vec::default(),
-                quiet: false,
-                verbose: false,
-                very_verbose: false,
-                list: false,
-                no_cache: false,
+                ..Default::default()
             }),
             Some(&config),
         )
@@ -264,15 +316,27 @@ impl TestEnv {
 
     /// Returns the public key corresponding to the test keys's `hd_path`
     pub fn test_address(&self, hd_path: usize) -> String {
-        self.cmd::<keys::address::Cmd>(&format!("--hd-path={hd_path}"))
+        self.cmd::<keys::address::Cmd>(&format!("{TEST_ACCOUNT} --hd-path={hd_path}"))
             .public_key()
             .unwrap()
             .to_string()
     }
 
+    /// Returns the public keys corresponding to the test keys's `hd_paths`, reading the identity
+    /// once for all of them
+    pub fn test_addresses(&self, hd_paths: Range<usize>) -> Vec<String> {
+        let mut cmd = self.cmd::<keys::address::Cmd>(TEST_ACCOUNT);
+        hd_paths
+            .map(|hd_path| {
+                cmd.hd_path = Some(hd_path);
+                cmd.public_key().unwrap().to_string()
+            })
+            .collect()
+    }
+
     /// Returns the private key corresponding to the test keys's `hd_path`
     pub fn test_show(&self, hd_path: usize) -> String {
-        self.cmd::<keys::show::Cmd>(&format!("--hd-path={hd_path}"))
+        self.cmd::<keys::show::Cmd>(&format!("{TEST_ACCOUNT} --hd-path={hd_path}"))
             .private_key()
             .unwrap()
             .to_string()
@@ -296,6 +360,16 @@ impl TestEnv {
     }
 }
 
+/// Seed for `keys generate --seed` derived from a `TestEnv` seed and the account's name. It is
+/// 16 hex characters, as the seed is used as the 16 bytes of entropy of the seed phrase.
+fn account_seed(seed: u64, account: &str) -> String {
+    let hash = Sha256::new()
+        .chain_update(seed.to_be_bytes())
+        .chain_update(account.as_bytes())
+        .finalize();
+    format!("{:016x}", u64::from_be_bytes(hash[..8].try_into().unwrap()))
+}
+
 pub fn temp_ledger_file() -> OsString {
     TempDir::new()
         .unwrap()
@@ -0,0 +1,14 @@
+#![no_std]
+use soroban_sdk::{contract, contractimpl, contractmeta};
+
+contractmeta!(key = "Description", val = "A test contract with meta entries");
+
+#[contract]
+pub struct Contract;
+
+#[contractimpl]
+impl Contract {
+    pub fn hello() -> u32 {
+        1
+    }
+}
@@ -209,6 +209,24 @@ fn parse_enum_const() {
     println!("{:#?}", entries.from_json(&json!(11), type_));
 }
 
+#[test]
+fn parse_enum_const_by_value_or_name() {
+    let entries = get_spec();
+    let func = entries.find_function("card").unwrap();
+    let type_ = &func.inputs.as_slice()[0].type_;
+    assert_eq!(
+        entries.from_json(&json!(11), type_).unwrap(),
+        ScVal::U32(11)
+    );
+    assert_eq!(
+        entries.from_json(&json!("Jack"), type_).unwrap(),
+        ScVal::U32(11)
+    );
+    assert_eq!(entries.from_string("11", type_).unwrap(), ScVal::U32(11));
+    assert_eq!(entries.from_string("Jack", type_).unwrap(), ScVal::U32(11));
+    assert!(entries.from_json(&json!("Ace"), type_).is_err());
+}
+
 fn get_spec() -> Spec {
     let res = soroban_spec::read::from_wasm(&CUSTOM_TYPES.bytes()).unwrap();
     Spec(Some(res))
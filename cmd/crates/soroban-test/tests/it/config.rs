@@ -180,6 +180,75 @@ fn generate_key() {
     );
 }
 
+#[test]
+fn deterministic_test_env() {
+    let a = TestEnv::deterministic(7);
+    let b = TestEnv::deterministic(7);
+    assert_eq!(a.test_address(0), b.test_address(0));
+    assert_ne!(a.test_address(0), TestEnv::deterministic(8).test_address(0));
+}
+
+#[test]
+fn test_addresses_are_distinct() {
+    let sandbox = TestEnv::deterministic(7);
+    let addresses = sandbox.test_addresses(0..3);
+    assert_eq!(addresses.len(), 3);
+    assert_eq!(addresses[2], sandbox.test_address(2));
+    assert_ne!(addresses[0], addresses[1]);
+    assert_ne!(addresses[1], addresses[2]);
+    assert_ne!(addresses[0], addresses[2]);
+}
+
+#[test]
+fn ls_keys_as_json() {
+    let sandbox = TestEnv::default();
+    add_test_id(sandbox.dir().as_ref());
+    sandbox
+        .new_assert_cmd("keys")
+        .arg("generate")
+        .arg("--no-fund")
+        .arg("--seed")
+        .arg("0000000000000000")
+        .arg("test_2")
+        .assert()
+        .success();
+    let address = |name: &str| {
+        sandbox
+            .new_assert_cmd("keys")
+            .arg("address")
+            .arg(name)
+            .assert()
+            .stdout_as_str()
+    };
+
+    let output = sandbox
+        .new_assert_cmd("keys")
+        .arg("ls")
+        .arg("--output")
+        .arg("json")
+        .assert()
+        .success()
+        .stdout_as_str();
+    let mut entries: Vec<serde_json::Value> = serde_json::from_str(&output).unwrap();
+    entries.sort_by_key(|entry| entry["name"].as_str().unwrap().to_string());
+    assert_eq!(
+        entries,
+        vec![
+            serde_json::json!({
+                "name": "test_2",
+                "kind": "seed_phrase",
+                "public_key": address("test_2"),
+                "hd_path": 0,
+            }),
+            serde_json::json!({
+                "name": "test_id",
+                "kind": "secret_key",
+                "public_key": address("test_id"),
+            }),
+        ]
+    );
+}
+
 #[test]
 fn generate_key_on_testnet() {
     if std::env::var("CI_TEST").is_err() {
@@ -257,3 +326,223 @@ fn use_env() {
         .success()
         .stdout("SDIY6AQQ75WMD4W46EYB7O6UYMHOCGQHLAQGQTKHDX4J2DYQCHVCQYFD\n");
 }
+
+#[cfg(unix)]
+#[test]
+fn add_from_secret_file() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let sandbox = TestEnv::default();
+    let secret_file = sandbox.dir().join("secret");
+    fs::write(
+        &secret_file,
+        "SDIY6AQQ75WMD4W46EYB7O6UYMHOCGQHLAQGQTKHDX4J2DYQCHVCQYFD\n",
+    )
+    .unwrap();
+    let add = |mode: u32| {
+        fs::set_permissions(&secret_file, fs::Permissions::from_mode(mode)).unwrap();
+        sandbox
+            .new_assert_cmd("keys")
+            .arg("add")
+            .arg("bob")
+            .arg("--secret-file")
+            .arg(&secret_file)
+            .assert()
+    };
+
+    add(0o644)
+        .failure()
+        .stderr(predicates::str::contains("permissions 0644"));
+    add(0o600).success();
+    sandbox
+        .new_assert_cmd("keys")
+        .arg("show")
+        .arg("bob")
+        .assert()
+        .success()
+        .stdout("SDIY6AQQ75WMD4W46EYB7O6UYMHOCGQHLAQGQTKHDX4J2DYQCHVCQYFD\n");
+}
+
+#[test]
+fn sign_payload() {
+    let sandbox = TestEnv::default();
+    sandbox
+        .new_assert_cmd("keys")
+        .env(
+            "SOROBAN_SECRET_KEY",
+            "SDIY6AQQ75WMD4W46EYB7O6UYMHOCGQHLAQGQTKHDX4J2DYQCHVCQYFD",
+        )
+        .arg("add")
+        .arg("bob")
+        .assert()
+        .success();
+    let public_key = sandbox
+        .new_assert_cmd("keys")
+        .arg("address")
+        .arg("bob")
+        .assert()
+        .success()
+        .stdout_as_str();
+    let public_key = stellar_strkey::ed25519::PublicKey::from_string(&public_key).unwrap();
+    let verifying_key = ed25519_dalek::VerifyingKey::from_bytes(&public_key.0).unwrap();
+
+    let payload = [7; 32];
+    let signature = sandbox
+        .new_assert_cmd("keys")
+        .arg("sign")
+        .arg("--sign-with-key=bob")
+        .arg(format!("--payload={}", hex::encode(payload)))
+        .assert()
+        .success()
+        .stdout_as_str();
+    let signature: [u8; 64] = hex::decode(signature).unwrap().try_into().unwrap();
+    verifying_key
+        .verify_strict(&payload, &ed25519_dalek::Signature::from_bytes(&signature))
+        .unwrap();
+
+    sandbox
+        .new_assert_cmd("keys")
+        .arg("sign")
+        .arg("--sign-with-key=bob")
+        .arg("--payload=0102")
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("pass --raw"));
+    sandbox
+        .new_assert_cmd("keys")
+        .arg("sign")
+        .arg("--sign-with-key=bob")
+        .arg("--payload=0102")
+        .arg("--raw")
+        .assert()
+        .success();
+}
+
+#[test]
+fn contract_alias_add_ls_rm() {
+    let sandbox = TestEnv::default();
+    let id = "CAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAABSC4";
+    sandbox
+        .new_assert_cmd("contract")
+        .args(["alias", "add", "hello", id])
+        .assert()
+        .success();
+    let ls = sandbox
+        .new_assert_cmd("contract")
+        .args(["alias", "ls"])
+        .assert()
+        .success()
+        .stdout_as_str();
+    assert_eq!(ls, format!("hello: {id} ({LOCAL_NETWORK_PASSPHRASE})"));
+    sandbox
+        .new_assert_cmd("contract")
+        .args(["alias", "add", "not an alias", id])
+        .assert()
+        .failure();
+    sandbox
+        .new_assert_cmd("contract")
+        .args(["alias", "rm", "hello"])
+        .assert()
+        .success();
+    sandbox
+        .new_assert_cmd("contract")
+        .args(["alias", "ls"])
+        .assert()
+        .success()
+        .stdout("");
+    sandbox
+        .new_assert_cmd("contract")
+        .args(["alias", "rm", "hello"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn contract_id_wasm_from_deployer() {
+    let sandbox = TestEnv::default();
+    sandbox
+        .new_assert_cmd("contract")
+        .args([
+            "id",
+            "wasm",
+            "--deployer",
+            "GAAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQDZ7H",
+            "--salt",
+            "01",
+            "--network-passphrase",
+            "Standalone Network ; February 2017",
+        ])
+        .assert()
+        .success()
+        .stdout("CAYBUMZKCSEK63YHMDUF23LIJDDFFU7VFP4U7T3TIMICQCDLQJJB6BSA\n");
+}
+
+#[test]
+fn network_from_project_config() {
+    let sandbox = TestEnv::default();
+    fs::write(
+        sandbox.dir().join("stellar.toml"),
+        r#"
+[networks.project]
+rpc_url = "https://soroban-testnet.stellar.org"
+network_passphrase = "Test SDF Network ; September 2015"
+"#,
+    )
+    .unwrap();
+    sandbox
+        .new_assert_cmd("contract")
+        .args(["id", "asset", "--asset", "native", "--network", "project"])
+        .assert()
+        .success()
+        .stdout("CDLZFC3SYJYDZT7K67VZ75HPJVIEUVNIXF47ZG2FB2RMQQVU2HHGCYSC\n");
+}
+
+#[test]
+fn default_source_account() {
+    let sandbox = TestEnv::default();
+    add_test_id(sandbox.dir());
+    sandbox
+        .new_assert_cmd("keys")
+        .args(["use", "test_id"])
+        .assert()
+        .success();
+    sandbox
+        .new_assert_cmd("keys")
+        .arg("default")
+        .assert()
+        .success()
+        .stdout("test_id\n");
+    let contract_id = |source: &[&str]| {
+        sandbox
+            .new_assert_cmd("contract")
+            .env_remove("SOROBAN_ACCOUNT")
+            .args([
+                "id",
+                "wasm",
+                "--salt",
+                "01",
+                "--network-passphrase",
+                "Standalone Network ; February 2017",
+            ])
+            .args(source)
+            .assert()
+            .success()
+            .stdout_as_str()
+    };
+    assert_eq!(
+        contract_id(&[]),
+        contract_id(&["--source-account", "test_id"])
+    );
+}
+
+#[test]
+fn json_fails_on_unsupported_commands() {
+    let sandbox = TestEnv::default();
+    sandbox
+        .new_assert_cmd("keys")
+        .env("STELLAR_JSON", "true")
+        .arg("ls")
+        .assert()
+        .failure()
+        .stderr("error: --json is not supported by this command\n");
+}
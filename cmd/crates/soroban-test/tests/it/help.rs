@@ -1,7 +1,9 @@
 use soroban_cli::commands::contract;
-use soroban_test::TestEnv;
+use soroban_test::{AssertExt, TestEnv};
 
-use crate::util::{invoke_custom as invoke, CUSTOM_TYPES, DEFAULT_CONTRACT_ID};
+use crate::util::{
+    invoke_custom as invoke, CONTRACT_META, CUSTOM_TYPES, DEFAULT_CONTRACT_ID, HELLO_WORLD,
+};
 
 async fn invoke_custom(func: &str, args: &str) -> Result<String, contract::invoke::Error> {
     let e = &TestEnv::default();
@@ -95,3 +97,113 @@ fn build() {
         .assert()
         .success();
 }
+
+#[test]
+fn info_functions() {
+    let sandbox = TestEnv::default();
+    sandbox
+        .new_assert_cmd("contract")
+        .arg("info")
+        .arg("functions")
+        .arg("--wasm")
+        .arg(CUSTOM_TYPES.path())
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "strukt(strukt: { a: u32, b: bool, c: Symbol }) -> { a: u32, b: bool, c: Symbol }",
+        ));
+    let output = sandbox
+        .new_assert_cmd("contract")
+        .arg("info")
+        .arg("functions")
+        .arg("--wasm")
+        .arg(CUSTOM_TYPES.path())
+        .arg("--output")
+        .arg("json")
+        .assert()
+        .success()
+        .stdout_as_str();
+    let functions: Vec<serde_json::Value> = serde_json::from_str(&output).unwrap();
+    assert!(functions.iter().any(|f| f["name"] == "strukt"));
+}
+
+#[test]
+fn complete_function_and_arg_names() {
+    let sandbox = TestEnv::default();
+    let complete = |words: &[&str]| {
+        sandbox
+            .new_assert_cmd("contract")
+            .arg("complete")
+            .arg("--wasm")
+            .arg(HELLO_WORLD.path())
+            .args(words)
+            .assert()
+            .success()
+            .stdout_as_str()
+    };
+    assert_eq!(complete(&["he"]), "hello");
+    assert_eq!(complete(&["hello", "--w"]), "--world");
+    assert_eq!(complete(&["hello", "--world=there", "--w"]), "");
+}
+
+#[test]
+fn info_meta() {
+    let sandbox = TestEnv::default();
+    sandbox
+        .new_assert_cmd("contract")
+        .arg("info")
+        .arg("meta")
+        .arg("--wasm")
+        .arg(CONTRACT_META.path())
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "Description: A test contract with meta entries\n",
+        ))
+        .stdout(predicates::str::contains("rssdkver: "));
+    let output = sandbox
+        .new_assert_cmd("contract")
+        .arg("info")
+        .arg("meta")
+        .arg("--wasm")
+        .arg(CONTRACT_META.path())
+        .arg("--output")
+        .arg("json")
+        .assert()
+        .success()
+        .stdout_as_str();
+    let meta: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(meta["Description"], "A test contract with meta entries");
+}
+
+#[test]
+fn info_types() {
+    let sandbox = TestEnv::default();
+    sandbox
+        .new_assert_cmd("contract")
+        .arg("info")
+        .arg("types")
+        .arg("--wasm")
+        .arg(CUSTOM_TYPES.path())
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "struct Test { a: u32, b: bool, c: Symbol }",
+        ));
+    let output = sandbox
+        .new_assert_cmd("contract")
+        .arg("info")
+        .arg("types")
+        .arg("--wasm")
+        .arg(CUSTOM_TYPES.path())
+        .arg("--output")
+        .arg("json")
+        .assert()
+        .success()
+        .stdout_as_str();
+    let types: Vec<serde_json::Value> = serde_json::from_str(&output).unwrap();
+    let test = types.iter().find(|t| t["name"] == "Test").unwrap();
+    assert_eq!(test["type"], "struct");
+    assert_eq!(test["fields"][0]["name"], "a");
+    assert_eq!(test["fields"][0]["value"]["type"], "u32");
+}
@@ -1,4 +1,5 @@
 mod bindings;
+mod custom_account;
 mod custom_types;
 mod dotenv;
 mod hello_world;
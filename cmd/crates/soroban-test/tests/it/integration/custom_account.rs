@@ -0,0 +1,74 @@
+use soroban_test::{AssertExt, TestEnv};
+
+use super::util::{deploy_custom_account, HELLO_WORLD};
+
+#[tokio::test]
+async fn simulate_auth_with_dummy_signature() {
+    let sandbox = &TestEnv::new();
+    let account = deploy_custom_account(sandbox).await;
+    let signer_id = "01".repeat(32);
+    let factory = sandbox.test_address(0);
+    sandbox
+        .new_assert_cmd("contract")
+        .arg("invoke")
+        .arg("--id")
+        .arg(&account)
+        .arg("--")
+        .arg("init")
+        .arg(format!("--id={signer_id}"))
+        .arg(format!("--pk={}", "02".repeat(65)))
+        .arg(format!("--factory={factory}"))
+        .assert()
+        .success();
+    let hello = sandbox
+        .new_assert_cmd("contract")
+        .arg("deploy")
+        .arg("--wasm")
+        .arg(HELLO_WORLD.path())
+        .arg("--ignore-checks")
+        .assert()
+        .success()
+        .stdout_as_str();
+
+    // `hello_world::auth` requires the account's auth, which runs its `__check_auth`
+    let auth_context = serde_json::json!({
+        "contract": hello,
+        "fn_name": "auth",
+        "args": [account, "world"],
+    });
+    let signature = |id: &str| {
+        serde_json::json!({
+            "id": id,
+            "authenticator_data": "",
+            "client_data_json": "",
+            "signature": "00".repeat(64),
+        })
+        .to_string()
+    };
+    sandbox
+        .new_assert_cmd("contract")
+        .arg("simulate-auth")
+        .arg("--id")
+        .arg(&account)
+        .arg("--auth-context")
+        .arg(auth_context.to_string())
+        .arg("--signature")
+        .arg(signature(&signer_id))
+        .assert()
+        .success()
+        .stdout("__check_auth passed\n");
+
+    // A signer the account does not know fails with the contract's `NotFound` error
+    sandbox
+        .new_assert_cmd("contract")
+        .arg("simulate-auth")
+        .arg("--id")
+        .arg(&account)
+        .arg("--auth-context")
+        .arg(auth_context.to_string())
+        .arg("--signature")
+        .arg(signature(&"03".repeat(32)))
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("__check_auth failed"));
+}
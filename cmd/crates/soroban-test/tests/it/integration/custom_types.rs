@@ -1,7 +1,9 @@
+use base64::Engine as _;
 use serde_json::json;
 
 use soroban_cli::commands;
-use soroban_test::TestEnv;
+use soroban_env_host::xdr::{Limits, ReadXdr, ScVal, WriteXdr};
+use soroban_test::{AssertExt, TestEnv};
 
 use crate::integration::util::{deploy_custom, extend_contract};
 
@@ -34,6 +36,7 @@ async fn parse() {
     e_2_s_tuple(sandbox, id).await;
     e_2_s_strukt(sandbox, id).await;
     number_arg(sandbox, id).await;
+    number_arg_typed(sandbox, id).await;
     number_arg_return_err(sandbox, id).await;
     i32(sandbox, id).await;
     i64(sandbox, id).await;
@@ -44,6 +47,9 @@ async fn parse() {
     bytes(sandbox, id).await;
     const_enum(sandbox, id).await;
     number_arg_return_ok(sandbox, id);
+    xdr_output(sandbox, id);
+    xdr_arg(sandbox, id);
+    msgpack_output(sandbox, id);
     void(sandbox, id);
     val(sandbox, id);
     parse_u128(sandbox, id);
@@ -176,6 +182,14 @@ async fn number_arg(sandbox: &TestEnv, id: &str) {
     invoke_with_roundtrip(sandbox, id, "u32_", 42).await;
 }
 
+async fn number_arg_typed(sandbox: &TestEnv, id: &str) {
+    let n: u32 = sandbox
+        .invoke_typed(&["--id", id, "--", "u32_", "--u32_=42"])
+        .await
+        .unwrap();
+    assert_eq!(n, 42);
+}
+
 fn number_arg_return_ok(sandbox: &TestEnv, id: &str) {
     invoke_custom(sandbox, id, "u32_fail_on_even")
         .arg("--u32_")
@@ -190,11 +204,115 @@ async fn number_arg_return_err(sandbox: &TestEnv, id: &str) {
         .invoke_with_test(&["--id", id, "--", "u32_fail_on_even", "--u32_=2"])
         .await
         .unwrap_err();
-    if let commands::contract::invoke::Error::ContractInvoke(name, doc) = &res {
-        assert_eq!(name, "NumberMustBeOdd");
-        assert_eq!(doc, "Please provide an odd number");
-    };
-    println!("{res:#?}");
+    assert!(
+        matches!(
+            &res,
+            commands::contract::invoke::Error::ContractInvoke { code: 1, name, doc }
+                if name == "NumberMustBeOdd" && doc == "Please provide an odd number"
+        ),
+        "{res:#?}"
+    );
+    // The exit status is 100 plus the contract error's code
+    invoke_custom(sandbox, id, "u32_fail_on_even")
+        .arg("--u32_=2")
+        .assert()
+        .code(101)
+        .stderr(predicates::str::contains(
+            "error: Error 1: NumberMustBeOdd\nPlease provide an odd number",
+        ));
+}
+
+fn xdr_output(sandbox: &TestEnv, id: &str) {
+    for view in [false, true] {
+        let mut cmd = sandbox.new_assert_cmd("contract");
+        cmd.arg("invoke")
+            .arg("--id")
+            .arg(id)
+            .arg("--output")
+            .arg("xdr");
+        if view {
+            cmd.arg("--is-view");
+        }
+        let out = cmd
+            .arg("--")
+            .arg("u32_")
+            .arg("--u32_")
+            .arg("42")
+            .assert()
+            .success()
+            .stdout_as_str();
+        assert_eq!(
+            ScVal::from_xdr_base64(out, Limits::none()).unwrap(),
+            ScVal::U32(42)
+        );
+    }
+}
+
+fn xdr_arg(sandbox: &TestEnv, id: &str) {
+    let xdr = |val: ScVal| val.to_xdr_base64(Limits::none()).unwrap();
+    invoke_custom(sandbox, id, "u32_")
+        .arg("--u32_-xdr")
+        .arg(xdr(ScVal::U32(42)))
+        .assert()
+        .success()
+        .stdout("42\n");
+    invoke_custom(sandbox, id, "u32_")
+        .arg("--u32_-xdr")
+        .arg(xdr(ScVal::I32(42)))
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("is not a value of its type"));
+    invoke_custom(sandbox, id, "u32_")
+        .arg("--u32_-xdr")
+        .arg(xdr(ScVal::U32(42)))
+        .arg("--u32_")
+        .arg("42")
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("cannot be used with"));
+}
+
+fn msgpack_output(sandbox: &TestEnv, id: &str) {
+    let big = "170000000000000000000000000000000000000";
+    let strukt = rmpv::Value::Map(vec![(
+        "Struct".into(),
+        rmpv::Value::Map(vec![
+            ("a".into(), 42.into()),
+            ("b".into(), true.into()),
+            ("c".into(), "world".into()),
+        ]),
+    )]);
+    for (function, arg, expected) in [
+        ("i128", "-42", rmpv::Value::from(-42)),
+        ("i128", big, rmpv::Value::from(big)),
+        (
+            "complex",
+            r#"{"Struct":{"a":42,"b":true,"c":"world"}}"#,
+            strukt,
+        ),
+    ] {
+        let out = sandbox
+            .new_assert_cmd("contract")
+            .arg("invoke")
+            .arg("--id")
+            .arg(id)
+            .arg("--output")
+            .arg("msgpack")
+            .arg("--")
+            .arg(function)
+            .arg(format!("--{function}"))
+            .arg(arg)
+            .assert()
+            .success()
+            .stdout_as_str();
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(out)
+            .unwrap();
+        assert_eq!(
+            rmpv::decode::read_value(&mut bytes.as_slice()).unwrap(),
+            expected
+        );
+    }
 }
 
 fn void(sandbox: &TestEnv, id: &str) {
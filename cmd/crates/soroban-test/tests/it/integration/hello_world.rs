@@ -2,14 +2,19 @@ use predicates::boolean::PredicateBooleanExt;
 use soroban_cli::commands::{
     config::{locator, secret},
     contract::{self, fetch},
+    global,
     txn_result::TxnResult,
 };
+use soroban_env_host::xdr::{
+    ContractDataDurability, Hash, LedgerKey, LedgerKeyContractCode, LedgerKeyContractData, Limits,
+    ScAddress, ScVal, WriteXdr,
+};
 use soroban_rpc::GetLatestLedgerResponse;
 use soroban_test::{AssertExt, TestEnv, LOCAL_NETWORK_PASSPHRASE};
 
 use crate::integration::util::extend_contract;
 
-use super::util::{deploy_hello, extend, HELLO_WORLD};
+use super::util::{deploy_custom, deploy_hello, extend, CUSTOM_TYPES, HELLO_WORLD};
 
 #[allow(clippy::too_many_lines)]
 #[tokio::test]
@@ -274,6 +279,50 @@ async fn contract_data_read() {
         .success()
         .stdout(predicates::str::starts_with("COUNTER,1"));
 
+    sandbox
+        .new_assert_cmd("contract")
+        .arg("read")
+        .arg("--id")
+        .arg(id)
+        .arg("--all")
+        .arg("--key")
+        .arg(KEY)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("COUNTER,1,persistent,"));
+
+    let json = sandbox
+        .new_assert_cmd("contract")
+        .arg("read")
+        .arg("--id")
+        .arg(id)
+        .arg("--key")
+        .arg(KEY)
+        .arg("--durability=persistent")
+        .arg("--format=json")
+        .assert()
+        .success()
+        .stdout_as_str();
+    let entries: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(entries[0]["key"], KEY);
+    assert_eq!(entries[0]["value"], 1);
+    assert_eq!(entries[0]["durability"], "persistent");
+
+    sandbox
+        .new_assert_cmd("contract")
+        .arg("read")
+        .arg("--id")
+        .arg(id)
+        .arg("--key")
+        .arg(KEY)
+        .arg("--durability=persistent")
+        .arg("--format=pretty")
+        .assert()
+        .success()
+        .stdout(predicates::str::starts_with(
+            "COUNTER = 1\n  persistent, live until ledger ",
+        ));
+
     sandbox
         .new_assert_cmd("contract")
         .arg("invoke")
@@ -297,6 +346,470 @@ async fn contract_data_read() {
         .stdout(predicates::str::starts_with("COUNTER,2"));
 }
 
+#[tokio::test]
+async fn invoke_by_alias() {
+    let sandbox = &TestEnv::new();
+    let id = &deploy_hello(sandbox).await;
+    sandbox
+        .new_assert_cmd("contract")
+        .args(["alias", "add", "hello", id])
+        .assert()
+        .success();
+    sandbox
+        .new_assert_cmd("contract")
+        .arg("invoke")
+        .arg("--id=hello")
+        .arg("--")
+        .arg("hello")
+        .arg("--world=world")
+        .assert()
+        .success()
+        .stdout("[\"Hello\",\"world\"]\n");
+}
+
+#[tokio::test]
+async fn invoke_show_auth_tree() {
+    let sandbox = &TestEnv::new();
+    let id = &deploy_hello(sandbox).await;
+    let signer = &sandbox.test_addresses(1..2)[0];
+    sandbox
+        .new_assert_cmd("contract")
+        .arg("invoke")
+        .arg("--id")
+        .arg(id)
+        .arg("--sim-only")
+        .arg("--show-auth-tree")
+        .arg("--")
+        .arg("auth")
+        .arg(format!("--addr={signer}"))
+        .arg("--world=world")
+        .assert()
+        .success()
+        .stderr(predicates::str::contains(format!(
+            "{signer} signs:\n  {id}::auth(\"{signer}\", world)\n"
+        )));
+}
+
+#[tokio::test]
+async fn invoke_show_changes() {
+    let sandbox = &TestEnv::new();
+    let id = &deploy_hello(sandbox).await;
+    let show_changes = || {
+        sandbox
+            .new_assert_cmd("contract")
+            .arg("invoke")
+            .arg("--id")
+            .arg(id)
+            .arg("--sim-only")
+            .arg("--show-changes")
+            .arg("--")
+            .arg("inc")
+            .assert()
+            .success()
+    };
+    show_changes().stderr(predicates::str::contains(format!(
+        "contract data {id} persistent COUNTER (new)"
+    )));
+    sandbox
+        .invoke_with_test(&["--id", id, "--", "inc"])
+        .await
+        .unwrap();
+    show_changes().stderr(predicates::str::contains(format!(
+        "contract data {id} persistent COUNTER (was 1)"
+    )));
+}
+
+#[tokio::test]
+async fn deploy_with_alias() {
+    let sandbox = &TestEnv::new();
+    let id = sandbox
+        .new_assert_cmd("contract")
+        .arg("deploy")
+        .arg("--wasm")
+        .arg(HELLO_WORLD.path())
+        .arg("--alias=hello")
+        .arg("--ignore-checks")
+        .assert()
+        .success()
+        .stdout_as_str();
+    // The id is saved for the network it was deployed to
+    let ls = sandbox
+        .new_assert_cmd("contract")
+        .args(["alias", "ls"])
+        .assert()
+        .success()
+        .stdout_as_str();
+    assert_eq!(ls, format!("hello: {id} ({LOCAL_NETWORK_PASSPHRASE})"));
+    sandbox
+        .new_assert_cmd("contract")
+        .arg("invoke")
+        .arg("--id=hello")
+        .arg("--")
+        .arg("hello")
+        .arg("--world=world")
+        .assert()
+        .success()
+        .stdout("[\"Hello\",\"world\"]\n");
+}
+
+#[tokio::test]
+async fn install_verify() {
+    let sandbox = &TestEnv::new();
+    sandbox
+        .new_assert_cmd("contract")
+        .arg("install")
+        .arg("--wasm")
+        .arg(HELLO_WORLD.path())
+        .arg("--verify")
+        .assert()
+        .success()
+        .stdout(format!("{}\n", HELLO_WORLD.hash().unwrap()))
+        .stderr(predicates::str::contains("Verified the Wasm installed"));
+}
+
+#[tokio::test]
+async fn deploy_from_wasm_hash() {
+    let sandbox = &TestEnv::new();
+    let wasm_hash = sandbox
+        .new_assert_cmd("contract")
+        .arg("install")
+        .arg("--wasm")
+        .arg(HELLO_WORLD.path())
+        .assert()
+        .success()
+        .stdout_as_str();
+    let id = sandbox
+        .new_assert_cmd("contract")
+        .arg("deploy")
+        .arg("--wasm-hash")
+        .arg(&wasm_hash)
+        .assert()
+        .success()
+        .stdout_as_str();
+    sandbox
+        .new_assert_cmd("contract")
+        .arg("invoke")
+        .arg("--id")
+        .arg(&id)
+        .arg("--")
+        .arg("hello")
+        .arg("--world=world")
+        .assert()
+        .success()
+        .stdout("[\"Hello\",\"world\"]\n");
+    sandbox
+        .new_assert_cmd("contract")
+        .arg("deploy")
+        .arg("--wasm-hash")
+        .arg("00".repeat(32))
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("install it first"));
+}
+
+#[tokio::test]
+async fn verify_deployed_wasm() {
+    let sandbox = &TestEnv::new();
+    let id = &deploy_hello(sandbox).await;
+    sandbox
+        .new_assert_cmd("contract")
+        .arg("verify")
+        .arg("--wasm")
+        .arg(HELLO_WORLD.path())
+        .arg("--id")
+        .arg(id)
+        .assert()
+        .success()
+        .stdout(predicates::str::starts_with("wasm matches: "));
+    sandbox
+        .new_assert_cmd("contract")
+        .arg("verify")
+        .arg("--wasm")
+        .arg(CUSTOM_TYPES.path())
+        .arg("--id")
+        .arg(id)
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("wasm does not match"));
+    sandbox
+        .new_assert_cmd("contract")
+        .arg("verify")
+        .arg("--wasm")
+        .arg(HELLO_WORLD.path())
+        .arg("--against-hash")
+        .arg("00".repeat(32))
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("wasm does not match"));
+}
+
+#[tokio::test]
+async fn extend_keys_from_file() {
+    let sandbox = &TestEnv::new();
+    let id = &deploy_hello(sandbox).await;
+    let contract = stellar_strkey::Contract::from_string(id).unwrap();
+    let keys = [
+        LedgerKey::ContractData(LedgerKeyContractData {
+            contract: ScAddress::Contract(Hash(contract.0)),
+            key: ScVal::LedgerKeyContractInstance,
+            durability: ContractDataDurability::Persistent,
+        }),
+        LedgerKey::ContractCode(LedgerKeyContractCode {
+            hash: HELLO_WORLD.hash().unwrap(),
+        }),
+    ]
+    .iter()
+    .map(|key| key.to_xdr_base64(Limits::none()).unwrap())
+    .collect::<Vec<_>>();
+    let key_file = sandbox.dir().join("keys.json");
+    std::fs::write(&key_file, serde_json::to_string(&keys).unwrap()).unwrap();
+
+    let output = sandbox
+        .new_assert_cmd("contract")
+        .arg("extend")
+        .arg("--key-file")
+        .arg(&key_file)
+        .arg("--ledgers-to-extend")
+        .arg("100001")
+        .arg("--ttl-ledger-only")
+        .assert()
+        .success()
+        .stdout_as_str();
+    let ttl_ledgers = output
+        .lines()
+        .map(|line| line.parse::<u32>().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(ttl_ledgers.len(), 2);
+    assert_eq!(ttl_ledgers[0], ttl_ledgers[1]);
+
+    // Restoring live entries leaves them as they are, and prints each one's TTL
+    let output = sandbox
+        .new_assert_cmd("contract")
+        .arg("restore")
+        .arg("--key-file")
+        .arg(&key_file)
+        .arg("--ttl-ledger-only")
+        .assert()
+        .success()
+        .stdout_as_str();
+    let restored = output
+        .lines()
+        .map(|line| line.parse::<u32>().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(restored, ttl_ledgers);
+}
+
+#[tokio::test]
+async fn restore_auto_skips_live_entries() {
+    // A freshly deployed contract has nothing archived, so nothing is sent
+    let sandbox = &TestEnv::new();
+    let id = &deploy_hello(sandbox).await;
+    sandbox
+        .new_assert_cmd("contract")
+        .arg("restore")
+        .arg("--id")
+        .arg(id)
+        .arg("--auto")
+        .assert()
+        .success()
+        .stdout("")
+        .stderr(predicates::str::contains("No archived entries to restore"));
+}
+
+#[tokio::test]
+async fn invoke_show_diagnostics() {
+    let sandbox = &TestEnv::new();
+    let id = &deploy_hello(sandbox).await;
+    sandbox
+        .new_assert_cmd("contract")
+        .arg("invoke")
+        .arg("--show-diagnostics")
+        .arg("--id")
+        .arg(id)
+        .arg("--")
+        .arg("log")
+        .arg("--str=world")
+        .assert()
+        .success()
+        .stderr(predicates::str::contains(r#""data":["hello {}","world"]"#));
+}
+
+#[tokio::test]
+async fn invoke_instruction_warning() {
+    let sandbox = &TestEnv::new();
+    let id = &deploy_hello(sandbox).await;
+    let invoke = |threshold: &str| {
+        sandbox
+            .new_assert_cmd("contract")
+            .arg("invoke")
+            .arg("--id")
+            .arg(id)
+            .arg(format!("--instruction-warning-threshold={threshold}"))
+            .arg("--")
+            .arg("hello")
+            .arg("--world=world")
+            .assert()
+            .success()
+    };
+    invoke("0").stderr(predicates::str::contains("of the network's limit of"));
+    invoke("1").stderr(predicates::str::contains("of the network's limit of").not());
+}
+
+#[tokio::test]
+async fn invoke_json_output() {
+    let sandbox = &TestEnv::new();
+    let id = &deploy_hello(sandbox).await;
+    let stdout = sandbox
+        .new_assert_cmd("contract")
+        .env("STELLAR_JSON", "true")
+        .arg("invoke")
+        .arg("--id")
+        .arg(id)
+        .arg("--output")
+        .arg("json")
+        .arg("--")
+        .arg("hello")
+        .arg("--world=world")
+        .assert()
+        .success()
+        .stdout_as_str();
+    let output: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(output["result"], serde_json::json!(["Hello", "world"]));
+    // Sent, but on a local network stellar.expert doesn't index
+    assert_eq!(output["tx_hash"].as_str().unwrap().len(), 64);
+    assert!(output.get("explorer_url").is_none());
+}
+
+#[tokio::test]
+async fn snapshot() {
+    let sandbox = &TestEnv::new();
+    let id = &deploy_hello(sandbox).await;
+    let out = sandbox.dir().join("snapshot.json");
+    sandbox
+        .new_assert_cmd("contract")
+        .arg("snapshot")
+        .arg("--id")
+        .arg(id)
+        .arg("--out")
+        .arg(&out)
+        .assert()
+        .success();
+    let snapshot: contract::snapshot::Snapshot =
+        serde_json::from_str(&std::fs::read_to_string(&out).unwrap()).unwrap();
+    assert_eq!(&snapshot.contract_id, id);
+    let keys = snapshot
+        .entries
+        .iter()
+        .map(|entry| entry.decode().unwrap().0)
+        .collect::<Vec<_>>();
+    let instance_key = LedgerKey::ContractData(LedgerKeyContractData {
+        contract: ScAddress::Contract(Hash(stellar_strkey::Contract::from_string(id).unwrap().0)),
+        key: ScVal::LedgerKeyContractInstance,
+        durability: ContractDataDurability::Persistent,
+    });
+    assert!(keys.contains(&instance_key));
+    assert!(keys
+        .iter()
+        .any(|key| matches!(key, LedgerKey::ContractCode(_))));
+}
+
+#[tokio::test]
+async fn invoke_batch() {
+    let sandbox = &TestEnv::new();
+    let id = &deploy_hello(sandbox).await;
+    let calls = sandbox.dir().join("calls.jsonl");
+    std::fs::write(
+        &calls,
+        "{ \"function\": \"inc\", \"args\": {} }\n{ \"function\": \"inc\" }\n",
+    )
+    .unwrap();
+    sandbox
+        .new_assert_cmd("contract")
+        .arg("invoke-batch")
+        .arg("--id")
+        .arg(id)
+        .arg("--file")
+        .arg(&calls)
+        .assert()
+        .success()
+        .stdout("{\"function\":\"inc\",\"result\":1}\n{\"function\":\"inc\",\"result\":2}\n");
+}
+
+#[tokio::test]
+async fn contract_repl() {
+    let sandbox = &TestEnv::new();
+    let id = &deploy_hello(sandbox).await;
+    sandbox
+        .new_assert_cmd("contract")
+        .arg("repl")
+        .arg("--id")
+        .arg(id)
+        .write_stdin("hello world=there\nnot_a_function\ninc\ninc\n")
+        .assert()
+        .success()
+        .stdout(
+            predicates::str::contains("[\"Hello\",\"there\"]\n")
+                .and(predicates::str::contains("1\n"))
+                .and(predicates::str::contains("2\n")),
+        )
+        .stderr(predicates::str::contains("not_a_function"));
+}
+
+#[tokio::test]
+async fn invoke_restore_flags() {
+    // Nothing is archived yet, so both modes should submit the invocation directly
+    // without issuing a restore first.
+    let sandbox = &TestEnv::new();
+    let id = &deploy_hello(sandbox).await;
+    for (flag, count) in [("--restore", "1"), ("--no-restore", "2")] {
+        sandbox
+            .new_assert_cmd("contract")
+            .arg("invoke")
+            .arg("--id")
+            .arg(id)
+            .arg(flag)
+            .arg("--")
+            .arg("inc")
+            .assert()
+            .success()
+            .stdout(format!("{count}\n"))
+            .stderr(predicates::str::contains("Restored").not());
+    }
+}
+
+#[tokio::test]
+async fn diff_cost() {
+    let sandbox = &TestEnv::new();
+    // Both contracts have a `not` function, but different Wasms, so running it costs differently
+    let old = &deploy_hello(sandbox).await;
+    let new = &deploy_custom(sandbox).await;
+    let out = sandbox
+        .new_assert_cmd("contract")
+        .arg("diff-cost")
+        .arg("--old")
+        .arg(old)
+        .arg("--new")
+        .arg(new)
+        .arg("--")
+        .arg("not")
+        .arg("--boolean")
+        .assert()
+        .success()
+        .stdout_as_str();
+    for metric in ["cpu_insns", "mem_bytes", "resource_fee"] {
+        assert!(
+            out.lines()
+                .any(|line| line.starts_with(&format!("{metric}: ")) && line.contains(" -> ")),
+            "missing {metric} in {out}"
+        );
+    }
+    let cpu_insns = out
+        .lines()
+        .find(|line| line.starts_with("cpu_insns: "))
+        .unwrap();
+    assert!(!cpu_insns.ends_with("(+0)"), "{cpu_insns}");
+}
+
 #[tokio::test]
 #[ignore]
 async fn half_max_instructions() {
@@ -356,7 +869,7 @@ async fn fetch(sandbox: &TestEnv, id: &str) {
         "--out-file",
         f.to_str().unwrap(),
     ]);
-    cmd.run().await.unwrap();
+    cmd.run(&global::Args::default()).await.unwrap();
     assert!(f.exists());
 }
 
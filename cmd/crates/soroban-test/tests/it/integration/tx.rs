@@ -1,7 +1,10 @@
-use soroban_sdk::xdr::{Limits, ReadXdr, TransactionEnvelope, WriteXdr};
+use soroban_sdk::xdr::{
+    Limits, OperationBody, ReadXdr, SorobanAuthorizationEntry, SorobanTransactionData,
+    TransactionEnvelope, TransactionExt, WriteXdr,
+};
 use soroban_test::{AssertExt, TestEnv};
 
-use crate::integration::util::{deploy_contract, DeployKind, HELLO_WORLD};
+use crate::integration::util::{deploy_contract, deploy_hello, DeployKind, HELLO_WORLD};
 
 #[tokio::test]
 async fn txn_simulate() {
@@ -30,3 +33,190 @@ async fn txn_simulate() {
         assembled_str
     );
 }
+
+fn soroban_data(tx_env_base64: &str) -> (u32, SorobanTransactionData) {
+    let tx_env = TransactionEnvelope::from_xdr_base64(tx_env_base64, Limits::none()).unwrap();
+    let tx = soroban_cli::commands::tx::xdr::unwrap_envelope_v1(tx_env).unwrap();
+    let TransactionExt::V1(data) = tx.ext else {
+        panic!("expected soroban transaction data");
+    };
+    (tx.fee, data)
+}
+
+#[tokio::test]
+async fn txn_simulate_resource_overrides() {
+    let sandbox = &TestEnv::new();
+    let xdr_base64_build_only = deploy_contract(sandbox, HELLO_WORLD, DeployKind::BuildOnly).await;
+    let tx_env =
+        TransactionEnvelope::from_xdr_base64(&xdr_base64_build_only, Limits::none()).unwrap();
+    let tx = soroban_cli::commands::tx::xdr::unwrap_envelope_v1(tx_env).unwrap();
+    let assembled = sandbox
+        .client()
+        .simulate_and_assemble_transaction(&tx)
+        .await
+        .unwrap();
+    let simulated = assembled.sim_response().transaction_data().unwrap();
+    let resource_fee = simulated.resource_fee + 1000;
+
+    let overridden = sandbox
+        .new_assert_cmd("tx")
+        .arg("simulate")
+        .arg("--fee=200")
+        .arg(format!("--resource-fee={resource_fee}"))
+        .arg("--instruction-leeway=5000")
+        .write_stdin(xdr_base64_build_only.as_bytes())
+        .assert()
+        .success()
+        .stdout_as_str();
+    let (fee, data) = soroban_data(&overridden);
+    assert_eq!(data.resource_fee, resource_fee);
+    assert_eq!(
+        data.resources.instructions,
+        simulated.resources.instructions + 5000
+    );
+    assert_eq!(i64::from(fee), 200 + resource_fee);
+
+    // Without a --resource-fee, the leeway's instructions are paid for on top of the simulated fee
+    let padded = sandbox
+        .new_assert_cmd("tx")
+        .arg("simulate")
+        .arg("--fee=200")
+        .arg("--instruction-leeway=1000000")
+        .write_stdin(xdr_base64_build_only.as_bytes())
+        .assert()
+        .success()
+        .stdout_as_str();
+    let (fee, data) = soroban_data(&padded);
+    assert!(data.resource_fee > simulated.resource_fee);
+    assert_eq!(i64::from(fee), 200 + data.resource_fee);
+
+    sandbox
+        .new_assert_cmd("tx")
+        .arg("simulate")
+        .arg("--resource-fee=1")
+        .write_stdin(xdr_base64_build_only.as_bytes())
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("below the simulated minimum"));
+
+    let forced = sandbox
+        .new_assert_cmd("tx")
+        .arg("simulate")
+        .arg("--resource-fee=1")
+        .arg("--force")
+        .write_stdin(xdr_base64_build_only.as_bytes())
+        .assert()
+        .success()
+        .stdout_as_str();
+    assert_eq!(soroban_data(&forced).1.resource_fee, 1);
+}
+
+#[tokio::test]
+async fn invoke_build_only_is_assembled() {
+    let sandbox = &TestEnv::new();
+    let id = &deploy_hello(sandbox).await;
+    let build_only = sandbox
+        .new_assert_cmd("contract")
+        .arg("invoke")
+        .arg("--build-only")
+        .arg("--id")
+        .arg(id)
+        .arg("--")
+        .arg("hello")
+        .arg("--world=world")
+        .assert()
+        .success()
+        .stdout_as_str();
+    let tx_env = TransactionEnvelope::from_xdr_base64(&build_only, Limits::none()).unwrap();
+    let TransactionEnvelope::Tx(env) = &tx_env else {
+        panic!("expected a v1 envelope");
+    };
+    assert!(env.signatures.is_empty());
+    assert!(matches!(
+        env.tx.operations[0].body,
+        OperationBody::InvokeHostFunction(_)
+    ));
+    let (_, data) = soroban_data(&build_only);
+    assert!(!data.resources.footprint.read_only.is_empty());
+}
+
+#[tokio::test]
+async fn txn_save_and_attach_auth() {
+    let sandbox = &TestEnv::new();
+    let id = &deploy_hello(sandbox).await;
+    let build_only = sandbox
+        .new_assert_cmd("contract")
+        .arg("invoke")
+        .arg("--build-only")
+        .arg("--id")
+        .arg(id)
+        .arg("--")
+        .arg("auth")
+        .arg("--addr=test")
+        .arg("--world=world")
+        .assert()
+        .success()
+        .stdout_as_str();
+    let auth_file = sandbox.dir().join("auth.json");
+    let simulated = sandbox
+        .new_assert_cmd("tx")
+        .arg("simulate")
+        .arg("--save-auth")
+        .arg(&auth_file)
+        .write_stdin(build_only.as_bytes())
+        .assert()
+        .success()
+        .stdout_as_str();
+    let entries: Vec<String> =
+        serde_json::from_str(&std::fs::read_to_string(&auth_file).unwrap()).unwrap();
+    assert_eq!(entries.len(), 1);
+    let mut entry =
+        SorobanAuthorizationEntry::from_xdr_base64(&entries[0], Limits::none()).unwrap();
+
+    let attached = sandbox
+        .new_assert_cmd("tx")
+        .arg("attach-auth")
+        .arg("--auth")
+        .arg(&auth_file)
+        .write_stdin(simulated.as_bytes())
+        .assert()
+        .success()
+        .stdout_as_str();
+    assert_eq!(attached, simulated);
+
+    // An entry changed offline replaces the simulated one
+    entry.root_invocation.sub_invocations = vec![entry.root_invocation.clone()].try_into().unwrap();
+    let changed = entry.to_xdr_base64(Limits::none()).unwrap();
+    std::fs::write(&auth_file, serde_json::to_string(&[changed]).unwrap()).unwrap();
+    let attached = sandbox
+        .new_assert_cmd("tx")
+        .arg("attach-auth")
+        .arg("--auth")
+        .arg(&auth_file)
+        .write_stdin(simulated.as_bytes())
+        .assert()
+        .success()
+        .stdout_as_str();
+    let TransactionEnvelope::Tx(attached) =
+        TransactionEnvelope::from_xdr_base64(attached, Limits::none()).unwrap()
+    else {
+        panic!("expected a v1 transaction envelope")
+    };
+    let OperationBody::InvokeHostFunction(invoke) = &attached.tx.operations[0].body else {
+        panic!("expected an invoke host function operation")
+    };
+    assert_eq!(invoke.auth.as_slice(), [entry]);
+
+    std::fs::write(&auth_file, "[]").unwrap();
+    sandbox
+        .new_assert_cmd("tx")
+        .arg("attach-auth")
+        .arg("--auth")
+        .arg(&auth_file)
+        .write_stdin(simulated.as_bytes())
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "has 1 auth entries, but the file has 0",
+        ));
+}
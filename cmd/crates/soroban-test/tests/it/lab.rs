@@ -0,0 +1,53 @@
+use soroban_test::TestEnv;
+
+#[test]
+fn scval_encode() {
+    let sandbox = TestEnv::default();
+    for (type_, value, xdr) in [
+        ("u32", "42", "AAAAAwAAACo="),
+        ("u128", r#""42""#, "AAAACQAAAAAAAAAAAAAAAAAAACo="),
+        ("Symbol", "hello", "AAAADwAAAAVoZWxsbwAAAA=="),
+        (
+            "Array<u32>",
+            "[1,2]",
+            "AAAAEAAAAAEAAAACAAAAAwAAAAEAAAADAAAAAg==",
+        ),
+        ("bool", "true", "AAAAAAAAAAE="),
+    ] {
+        sandbox
+            .new_assert_cmd("lab")
+            .args(["scval", "encode", "--type", type_, "--value", value])
+            .assert()
+            .success()
+            .stdout(format!("{xdr}\n"));
+    }
+}
+
+#[test]
+fn scval_decode() {
+    let sandbox = TestEnv::default();
+    for (xdr, json) in [
+        ("AAAAAwAAACo=", "42"),
+        ("AAAACQAAAAAAAAAAAAAAAAAAACo=", r#""42""#),
+        ("AAAADwAAAAVoZWxsbwAAAA==", r#""hello""#),
+        ("AAAAEAAAAAEAAAACAAAAAwAAAAEAAAADAAAAAg==", "[1,2]"),
+    ] {
+        sandbox
+            .new_assert_cmd("lab")
+            .args(["scval", "decode", "--xdr", xdr])
+            .assert()
+            .success()
+            .stdout(format!("{json}\n"));
+    }
+}
+
+#[test]
+fn scval_encode_unknown_type() {
+    let sandbox = TestEnv::default();
+    sandbox
+        .new_assert_cmd("lab")
+        .args(["scval", "encode", "--type", "u33", "--value", "1"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Unknown type u33"));
+}
@@ -1,8 +1,10 @@
 mod arg_parsing;
 mod config;
 mod help;
+mod lab;
 #[cfg(feature = "it")]
 mod integration;
 mod plugin;
+mod tx;
 mod util;
 mod version;
@@ -0,0 +1,355 @@
+use soroban_cli::signer::sign_soroban_authorizations;
+use soroban_sdk::xdr::{
+    AccountId, Asset, BumpSequenceOp, Hash, HostFunction, InvokeContractArgs, InvokeHostFunctionOp,
+    InvokeHostFunctionResult, Limits, Memo, MuxedAccount, Operation, OperationBody,
+    OperationResult, OperationResultTr, PaymentOp, Preconditions, PublicKey, ReadXdr, ScAddress,
+    ScSymbol, ScVal, SequenceNumber, SorobanAddressCredentials, SorobanAuthorizationEntry,
+    SorobanAuthorizedFunction, SorobanAuthorizedInvocation, SorobanCredentials, Transaction,
+    TransactionEnvelope, TransactionExt, TransactionResult, TransactionResultExt,
+    TransactionResultResult, TransactionV1Envelope, Uint256, VecM, WriteXdr,
+};
+use soroban_test::{AssertExt, TestEnv, LOCAL_NETWORK_PASSPHRASE};
+
+fn unsigned_envelope() -> TransactionEnvelope {
+    TransactionEnvelope::Tx(TransactionV1Envelope {
+        tx: Transaction {
+            source_account: MuxedAccount::Ed25519(Uint256([1; 32])),
+            fee: 100,
+            seq_num: SequenceNumber(1),
+            cond: Preconditions::None,
+            memo: Memo::None,
+            operations: vec![Operation {
+                source_account: None,
+                body: OperationBody::BumpSequence(BumpSequenceOp {
+                    bump_to: SequenceNumber(2),
+                }),
+            }]
+            .try_into()
+            .unwrap(),
+            ext: TransactionExt::V0,
+        },
+        signatures: [].try_into().unwrap(),
+    })
+}
+
+fn generate(sandbox: &TestEnv, name: &str, seed: &str) -> [u8; 32] {
+    sandbox
+        .new_assert_cmd("keys")
+        .arg("generate")
+        .arg("--no-fund")
+        .arg("--seed")
+        .arg(seed)
+        .arg(name)
+        .assert()
+        .success();
+    let address = sandbox
+        .new_assert_cmd("keys")
+        .arg("address")
+        .arg(name)
+        .assert()
+        .stdout_as_str();
+    stellar_strkey::ed25519::PublicKey::from_string(&address)
+        .unwrap()
+        .0
+}
+
+#[test]
+fn sign_with_multiple_keys() {
+    let sandbox = &TestEnv::default();
+    let alice = generate(sandbox, "alice", "0000000000000000");
+    let bob = generate(sandbox, "bob", "1111111111111111");
+    // Signatures are appended in order of public key, regardless of argument order
+    let mut keys = [alice, bob];
+    keys.sort_unstable();
+    let expected_hints = keys.iter().map(|k| k[28..].to_vec()).collect::<Vec<_>>();
+    let tx_env = unsigned_envelope().to_xdr_base64(Limits::none()).unwrap();
+
+    for args in [
+        vec!["--sign-with-key=alice", "--sign-with-key=bob"],
+        vec!["--sign-with-key=bob,alice"],
+    ] {
+        let signed = sandbox
+            .new_assert_cmd("tx")
+            .arg("sign")
+            .args(args)
+            .write_stdin(tx_env.as_bytes())
+            .assert()
+            .success()
+            .stdout_as_str();
+        let TransactionEnvelope::Tx(TransactionV1Envelope { signatures, .. }) =
+            TransactionEnvelope::from_xdr_base64(&signed, Limits::none()).unwrap()
+        else {
+            panic!("expected a v1 envelope");
+        };
+        let hints = signatures
+            .iter()
+            .map(|s| s.hint.0.to_vec())
+            .collect::<Vec<_>>();
+        assert_eq!(hints, expected_hints);
+    }
+}
+
+#[test]
+fn hash() {
+    let sandbox = &TestEnv::default();
+    // Fee bump of the unsigned envelope, from fee source `[2; 32]` with a fee of 400
+    let fee_bump = "AAAABQAAAAACAgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgAAAAAAAAGQAAAAAgAAAAABAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQAAAGQAAAAAAAAAAQAAAAAAAAAAAAAAAQAAAAAAAAALAAAAAAAAAAIAAAAAAAAAAAAAAAAAAAAA";
+    for (tx_env, expected) in [
+        (
+            unsigned_envelope().to_xdr_base64(Limits::none()).unwrap(),
+            "fc8c3a75843e5bc7e1d19d89a4b5dc82cc077741f400a742ef16c69087c992bf",
+        ),
+        (
+            fee_bump.to_string(),
+            "f976ef4a3c94f83b0d50307e91d573639d968e70a6b61af9394a260bec430435",
+        ),
+    ] {
+        sandbox
+            .new_assert_cmd("tx")
+            .arg("hash")
+            .arg("--network-passphrase")
+            .arg(LOCAL_NETWORK_PASSPHRASE)
+            .write_stdin(tx_env.as_bytes())
+            .assert()
+            .success()
+            .stdout(format!("{expected}\n"));
+    }
+}
+
+fn payment_envelope() -> TransactionEnvelope {
+    TransactionEnvelope::Tx(TransactionV1Envelope {
+        tx: Transaction {
+            source_account: MuxedAccount::Ed25519(Uint256([1; 32])),
+            fee: 100,
+            seq_num: SequenceNumber(1),
+            cond: Preconditions::None,
+            memo: Memo::None,
+            operations: vec![Operation {
+                source_account: None,
+                body: OperationBody::Payment(PaymentOp {
+                    destination: MuxedAccount::Ed25519(Uint256([2; 32])),
+                    asset: Asset::Native,
+                    amount: 10_000_000,
+                }),
+            }]
+            .try_into()
+            .unwrap(),
+            ext: TransactionExt::V0,
+        },
+        signatures: [].try_into().unwrap(),
+    })
+}
+
+#[test]
+fn uri_round_trip() {
+    let sandbox = &TestEnv::default();
+    let tx_env = payment_envelope().to_xdr_base64(Limits::none()).unwrap();
+    let uri = sandbox
+        .new_assert_cmd("tx")
+        .arg("to-uri")
+        .arg("--network-passphrase")
+        .arg(LOCAL_NETWORK_PASSPHRASE)
+        .arg("--callback=https://example.com/sign")
+        .arg("--origin-domain=example.com")
+        .write_stdin(tx_env.as_bytes())
+        .assert()
+        .success()
+        .stdout_as_str();
+    // The XDR is standard base64, percent-encoded
+    let standard = tx_env
+        .replace('+', "%2B")
+        .replace('/', "%2F")
+        .replace('=', "%3D");
+    assert!(
+        uri.starts_with(&format!("web+stellar:tx?xdr={standard}&")),
+        "{uri}"
+    );
+    for param in [
+        "callback=url%3Ahttps%3A%2F%2Fexample%2Ecom%2Fsign",
+        "network_passphrase=Standalone%20Network%20%3B%20February%202017",
+        "origin_domain=example%2Ecom",
+    ] {
+        assert!(uri.contains(param), "{uri} should contain {param}");
+    }
+
+    // With --base64url the XDR is in the URL-safe alphabet, without padding
+    let url_safe = tx_env
+        .trim_end_matches('=')
+        .replace('+', "-")
+        .replace('/', "_");
+    let url_safe_uri = sandbox
+        .new_assert_cmd("tx")
+        .arg("to-uri")
+        .arg("--network-passphrase")
+        .arg(LOCAL_NETWORK_PASSPHRASE)
+        .arg("--base64url")
+        .write_stdin(tx_env.as_bytes())
+        .assert()
+        .success()
+        .stdout_as_str();
+    assert!(
+        url_safe_uri.starts_with(&format!("web+stellar:tx?xdr={url_safe}&")),
+        "{url_safe_uri}"
+    );
+
+    for uri in [uri, url_safe_uri] {
+        sandbox
+            .new_assert_cmd("tx")
+            .arg("from-uri")
+            .arg(&uri)
+            .assert()
+            .success()
+            .stdout(format!("{tx_env}\n"));
+    }
+}
+
+#[test]
+fn from_uri_rejects_other_operations() {
+    let sandbox = &TestEnv::default();
+    sandbox
+        .new_assert_cmd("tx")
+        .arg("from-uri")
+        .arg("web+stellar:pay?destination=GAAA")
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("not a SEP-0007 transaction URI"));
+}
+
+fn invoke_tx(auth_address: &ed25519_dalek::SigningKey) -> Transaction {
+    let invoke = InvokeContractArgs {
+        contract_address: ScAddress::Contract(Hash([0; 32])),
+        function_name: ScSymbol("hello".try_into().unwrap()),
+        args: VecM::default(),
+    };
+    let auth = SorobanAuthorizationEntry {
+        credentials: SorobanCredentials::Address(SorobanAddressCredentials {
+            address: ScAddress::Account(AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(
+                auth_address.verifying_key().to_bytes(),
+            )))),
+            nonce: 1,
+            signature_expiration_ledger: 0,
+            signature: ScVal::Void,
+        }),
+        root_invocation: SorobanAuthorizedInvocation {
+            function: SorobanAuthorizedFunction::ContractFn(invoke.clone()),
+            sub_invocations: VecM::default(),
+        },
+    };
+    Transaction {
+        source_account: MuxedAccount::Ed25519(Uint256([1; 32])),
+        fee: 100,
+        seq_num: SequenceNumber(1),
+        cond: Preconditions::None,
+        memo: Memo::None,
+        operations: vec![Operation {
+            source_account: None,
+            body: OperationBody::InvokeHostFunction(InvokeHostFunctionOp {
+                host_function: HostFunction::InvokeContract(invoke),
+                auth: vec![auth].try_into().unwrap(),
+            }),
+        }]
+        .try_into()
+        .unwrap(),
+        ext: TransactionExt::V0,
+    }
+}
+
+/// Flip a bit of the first signature of the transaction's auth entry
+fn tamper_auth_signature(tx: &mut Transaction) {
+    let mut ops = tx.operations.to_vec();
+    let OperationBody::InvokeHostFunction(invoke) = &mut ops[0].body else {
+        panic!("expected an invoke host function operation");
+    };
+    let mut auth = invoke.auth.to_vec();
+    let SorobanCredentials::Address(credentials) = &mut auth[0].credentials else {
+        panic!("expected address credentials");
+    };
+    let ScVal::Vec(Some(signatures)) = &credentials.signature else {
+        panic!("expected signatures");
+    };
+    let mut signatures = signatures.to_vec();
+    let ScVal::Map(Some(map)) = &signatures[0] else {
+        panic!("expected a signature map");
+    };
+    let mut map = map.to_vec();
+    let ScVal::Bytes(signature) = &map[1].val else {
+        panic!("expected signature bytes");
+    };
+    let mut signature = signature.to_vec();
+    signature[0] ^= 1;
+    map[1].val = ScVal::Bytes(signature.try_into().unwrap());
+    signatures[0] = ScVal::Map(Some(map.try_into().unwrap()));
+    credentials.signature = ScVal::Vec(Some(signatures.try_into().unwrap()));
+    invoke.auth = auth.try_into().unwrap();
+    tx.operations = ops.try_into().unwrap();
+}
+
+#[test]
+fn verify_auth_flags_tampered_signature() {
+    let sandbox = &TestEnv::default();
+    let alice = ed25519_dalek::SigningKey::from_bytes(&[1; 32]);
+    let alice_address =
+        stellar_strkey::ed25519::PublicKey(alice.verifying_key().to_bytes()).to_string();
+    let mut tx = sign_soroban_authorizations(
+        &invoke_tx(&alice),
+        &alice,
+        &[],
+        &[],
+        100,
+        LOCAL_NETWORK_PASSPHRASE,
+    )
+    .unwrap()
+    .unwrap();
+
+    for (tampered, status) in [(false, "valid"), (true, "invalid")] {
+        if tampered {
+            tamper_auth_signature(&mut tx);
+        }
+        let tx_env = TransactionEnvelope::Tx(TransactionV1Envelope {
+            tx: tx.clone(),
+            signatures: [].try_into().unwrap(),
+        });
+        let assert = sandbox
+            .new_assert_cmd("tx")
+            .arg("verify-auth")
+            .arg("--network-passphrase")
+            .arg(LOCAL_NETWORK_PASSPHRASE)
+            .write_stdin(tx_env.to_xdr_base64(Limits::none()).unwrap())
+            .assert()
+            .stdout(format!("0 {alice_address} {status}\n"));
+        if tampered {
+            assert.failure();
+        } else {
+            assert.success();
+        }
+    }
+}
+
+#[test]
+fn decode_failed_result() {
+    let sandbox = &TestEnv::default();
+    let result = TransactionResult {
+        fee_charged: 12345,
+        result: TransactionResultResult::TxFailed(
+            vec![OperationResult::OpInner(
+                OperationResultTr::InvokeHostFunction(InvokeHostFunctionResult::Trapped),
+            )]
+            .try_into()
+            .unwrap(),
+        ),
+        ext: TransactionResultExt::V0,
+    };
+    sandbox
+        .new_assert_cmd("tx")
+        .arg("decode-result")
+        .arg("--xdr")
+        .arg(result.to_xdr_base64(Limits::none()).unwrap())
+        .assert()
+        .success()
+        .stdout(
+            "txFAILED: one of the operations failed, so none were applied\n\
+             Fee charged: 12345 stroops\n\
+             Operation 0: InvokeHostFunction Trapped: the contract function failed, its \
+             diagnostic events have the contract's error\n",
+        );
+}
@@ -6,7 +6,9 @@ use soroban_cli::commands::{
 };
 use soroban_test::{TestEnv, Wasm, TEST_ACCOUNT};
 
+pub const HELLO_WORLD: &Wasm = &Wasm::Custom("test-wasms", "test_hello_world");
 pub const CUSTOM_TYPES: &Wasm = &Wasm::Custom("test-wasms", "test_custom_types");
+pub const CONTRACT_META: &Wasm = &Wasm::Custom("test-wasms", "test_contract_meta");
 
 #[derive(Clone)]
 pub enum SecretKind {
@@ -6,11 +6,11 @@ use ledger_transport_hid::{
 };
 
 use soroban_env_host::xdr::{Hash, Transaction};
-use std::vec;
+use std::{time::Duration, vec};
 use stellar_strkey::DecodeError;
 use stellar_xdr::curr::{
-    self as xdr, Limits, TransactionSignaturePayload, TransactionSignaturePayloadTaggedTransaction,
-    WriteXdr,
+    self as xdr, DecoratedSignature, Limits, Signature, SignatureHint, TransactionSignaturePayload,
+    TransactionSignaturePayloadTaggedTransaction, WriteXdr,
 };
 
 pub use crate::signer::Blob;
@@ -18,10 +18,12 @@ pub mod hd_path;
 mod signer;
 
 // this is from https://github.com/LedgerHQ/ledger-live/blob/36cfbf3fa3300fd99bcee2ab72e1fd8f280e6280/libs/ledgerjs/packages/hw-app-str/src/Str.ts#L181
+// it is used for the Nano S, and for devices whose model is unknown
 const APDU_MAX_SIZE: u8 = 150;
+// the largest APDU data length, accepted by devices with a larger buffer than the Nano S
+const APDU_MAX_SIZE_LARGE: u8 = 255;
 const HD_PATH_ELEMENTS_COUNT: u8 = 3;
 const BUFFER_SIZE: u8 = 1 + HD_PATH_ELEMENTS_COUNT * 4;
-const CHUNK_SIZE: u8 = APDU_MAX_SIZE - BUFFER_SIZE;
 
 // These constant values are from https://github.com/LedgerHQ/app-stellar/blob/develop/docs/COMMANDS.md
 const SIGN_TX_RESPONSE_SIZE: usize = 64;
@@ -49,6 +51,29 @@ const P2_SIGN_TX_HASH: u8 = 0x00;
 
 const RETURN_CODE_OK: u16 = 36864; // APDUAnswer.retcode which means success from Ledger
 
+/// A description of common error return codes, from the Stellar app
+/// (<https://github.com/LedgerHQ/app-stellar/blob/develop/docs/COMMANDS.md>) and the device itself
+fn retcode_description(retcode: u16) -> Option<&'static str> {
+    Some(match retcode {
+        0x6C66 => "Hash signing is not enabled on the device; enable it in the Stellar app settings.",
+        0x6985 => "The request was rejected on the device.",
+        0x5515 => "The device is locked; unlock it and try again.",
+        0x6511 | 0x6E01 => "The Stellar app is not open on the device; open it and try again.",
+        0x6D00 | 0x6E00 => {
+            "The app open on the device does not support this request; make sure it is an up to date Stellar app."
+        }
+        _ => return None,
+    })
+}
+
+/// The error message for a return code, which keeps the code itself for support
+fn retcode_message(retcode: u16) -> String {
+    match retcode_description(retcode) {
+        Some(description) => format!("{description} (Ledger APDU retcode: 0x{retcode:X})"),
+        None => format!("Ledger APDU retcode: 0x{retcode:X}"),
+    }
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     #[error("Error occurred while initializing HIDAPI: {0}")]
@@ -71,19 +96,57 @@ pub enum Error {
 
     #[error(transparent)]
     DecodeError(#[from] DecodeError),
+
+    #[error("Timed out after {0:?} waiting for the address to be confirmed on the Ledger device")]
+    ConfirmationTimeout(Duration),
+}
+
+/// Ledger device models, which differ in how much data they accept per APDU
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Model {
+    NanoS,
+    NanoSPlus,
+    NanoX,
+    Stax,
+}
+
+impl Model {
+    /// The model of a device with the given USB product ID, which Ledger keeps in its high byte
+    #[must_use]
+    pub fn from_product_id(product_id: u16) -> Option<Self> {
+        match product_id {
+            // Nano S firmware older than 1.6 reports a fixed product ID
+            0x0001 => Some(Model::NanoS),
+            _ => match product_id >> 8 {
+                0x10 => Some(Model::NanoS),
+                0x40 => Some(Model::NanoX),
+                0x50 => Some(Model::NanoSPlus),
+                0x60 => Some(Model::Stax),
+                _ => None,
+            },
+        }
+    }
+
+    /// The largest APDU data length the model accepts
+    #[must_use]
+    pub fn apdu_max_size(self) -> u8 {
+        match self {
+            Model::NanoS => APDU_MAX_SIZE,
+            Model::NanoSPlus | Model::NanoX | Model::Stax => APDU_MAX_SIZE_LARGE,
+        }
+    }
 }
 
 pub struct LedgerSigner<T: Exchange> {
     transport: T,
+    model: Option<Model>,
 }
 
 unsafe impl<T> Send for LedgerSigner<T> where T: Exchange {}
 unsafe impl<T> Sync for LedgerSigner<T> where T: Exchange {}
 
 pub fn native() -> Result<LedgerSigner<TransportNativeHID>, Error> {
-    Ok(LedgerSigner {
-        transport: get_transport()?,
-    })
+    LedgerSigner::<TransportNativeHID>::native()
 }
 
 impl<T> LedgerSigner<T>
@@ -91,12 +154,32 @@ where
     T: Exchange,
 {
     pub fn new(transport: T) -> Self {
-        Self { transport }
+        Self {
+            transport,
+            model: None,
+        }
     }
+    /// Connect to the first Ledger device over HID, detecting its model from its product ID
     pub fn native() -> Result<LedgerSigner<TransportNativeHID>, Error> {
-        Ok(LedgerSigner {
-            transport: get_transport()?,
-        })
+        let (transport, model) = get_transport()?;
+        tracing::debug!("Ledger device model: {model:?}");
+        Ok(LedgerSigner { transport, model })
+    }
+    /// Use the APDU size of the given model, instead of the size that is safe for every model
+    #[must_use]
+    pub fn with_model(mut self, model: Model) -> Self {
+        self.model = Some(model);
+        self
+    }
+    /// The device model, if known
+    #[must_use]
+    pub fn model(&self) -> Option<Model> {
+        self.model
+    }
+    /// The number of bytes of the data to sign sent in each APDU
+    fn chunk_size(&self) -> usize {
+        let apdu_max_size = self.model.map_or(APDU_MAX_SIZE, Model::apdu_max_size);
+        usize::from(apdu_max_size - BUFFER_SIZE)
     }
     /// Get the device app's configuration
     /// # Errors
@@ -150,7 +233,7 @@ where
         data.append(&mut hd_path_to_bytes);
         data.append(&mut signature_payload_as_bytes);
 
-        let chunks = data.chunks(CHUNK_SIZE as usize);
+        let chunks = data.chunks(self.chunk_size());
         let chunks_count = chunks.len();
 
         let mut result = Vec::with_capacity(SIGN_TX_RESPONSE_SIZE);
@@ -181,6 +264,43 @@ where
         Ok(result)
     }
 
+    /// Sign a Stellar transaction with the account on the Ledger device, as a signature hinted
+    /// with the account's public key, ready to add to the transaction's envelope
+    /// # Errors
+    /// Returns an error if there is an issue with connecting with the device or signing the given tx on the device
+    pub async fn sign_transaction_decorated(
+        &self,
+        hd_path: impl Into<HdPath>,
+        transaction: Transaction,
+        network_id: Hash,
+    ) -> Result<DecoratedSignature, Error> {
+        let hd_path = hd_path.into();
+        let [.., a, b, c, d] = self.get_public_key(&hd_path).await?.0;
+        let signature = self
+            .sign_transaction(hd_path, transaction, network_id)
+            .await?;
+        Ok(DecoratedSignature {
+            hint: SignatureHint([a, b, c, d]),
+            signature: Signature(signature.try_into()?),
+        })
+    }
+
+    /// Get the public key after showing it on the device's screen for the user to confirm
+    /// # Errors
+    /// Returns an error if there is an issue with connecting with the device, if the user rejects the address, or if the user doesn't respond within `timeout`
+    pub async fn get_confirmed_public_key(
+        &self,
+        hd_path: impl Into<HdPath>,
+        timeout: Duration,
+    ) -> Result<stellar_strkey::ed25519::PublicKey, Error> {
+        tokio::time::timeout(
+            timeout,
+            self.get_public_key_with_display_flag(hd_path, true),
+        )
+        .await
+        .map_err(|_| Error::ConfirmationTimeout(timeout))?
+    }
+
     /// The `display_and_confirm` bool determines if the Ledger will display the public key on its screen and requires user approval to share
     async fn get_public_key_with_display_flag(
         &self,
@@ -232,9 +352,9 @@ where
                     return Ok(response.data().to_vec());
                 }
 
-                let retcode = response.retcode();
-                let error_string = format!("Ledger APDU retcode: 0x{retcode:X}");
-                Err(Error::APDUExchangeError(error_string))
+                Err(Error::APDUExchangeError(retcode_message(
+                    response.retcode(),
+                )))
             }
             Err(_err) => Err(Error::LedgerConnectionError(
                 "Error connecting to ledger device".to_string(),
@@ -286,10 +406,15 @@ where
     }
 }
 
-fn get_transport() -> Result<TransportNativeHID, Error> {
+fn get_transport() -> Result<(TransportNativeHID, Option<Model>), Error> {
     // instantiate the connection to Ledger, this will return an error if Ledger is not connected
     let hidapi = HidApi::new().map_err(Error::HidApiError)?;
-    TransportNativeHID::new(&hidapi).map_err(Error::LedgerHidError)
+    // the transport connects to the first device listed, so that is the one to get the model of
+    let model = TransportNativeHID::list_ledgers(&hidapi)
+        .next()
+        .and_then(|device| Model::from_product_id(device.product_id()));
+    let transport = TransportNativeHID::new(&hidapi).map_err(Error::LedgerHidError)?;
+    Ok((transport, model))
 }
 
 pub const TEST_NETWORK_PASSPHRASE: &[u8] = b"Test SDF Network ; September 2015";
@@ -451,7 +576,10 @@ mod test {
 
         let err = ledger.sign_blob(&path.into(), test_hash).await.unwrap_err();
         if let Error::APDUExchangeError(msg) = err {
-            assert_eq!(msg, "Ledger APDU retcode: 0x6C66");
+            assert_eq!(
+                msg,
+                "Hash signing is not enabled on the device; enable it in the Stellar app settings. (Ledger APDU retcode: 0x6C66)"
+            );
         } else {
             panic!("Unexpected error: {err:?}");
         }
@@ -492,4 +620,20 @@ mod test {
 
         mock_server.assert();
     }
+
+    #[test]
+    fn test_retcode_messages() {
+        assert_eq!(
+            crate::retcode_message(0x6C66),
+            "Hash signing is not enabled on the device; enable it in the Stellar app settings. (Ledger APDU retcode: 0x6C66)"
+        );
+        assert_eq!(
+            crate::retcode_message(0x6985),
+            "The request was rejected on the device. (Ledger APDU retcode: 0x6985)"
+        );
+        assert_eq!(
+            crate::retcode_message(0x1234),
+            "Ledger APDU retcode: 0x1234"
+        );
+    }
 }
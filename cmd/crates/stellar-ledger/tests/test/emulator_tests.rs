@@ -1,12 +1,14 @@
-use ledger_transport::Exchange;
+use ledger_transport::{APDUAnswer, APDUCommand, Exchange};
 use serde::Deserialize;
 use soroban_env_host::xdr::{self, Operation, OperationBody, Uint256};
 use soroban_env_host::xdr::{Hash, Transaction};
 use std::vec;
 
 use stellar_ledger::hd_path::HdPath;
-use stellar_ledger::{Blob, Error, LedgerSigner};
+use stellar_ledger::{Blob, Error, LedgerSigner, Model};
 
+use std::ops::Deref;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::{collections::HashMap, time::Duration};
 
@@ -77,7 +79,7 @@ async fn test_get_public_key(ledger_device_model: String) {
 #[test_case("nanox".to_string() ; "when the device is NanoX")]
 #[test_case("nanosp".to_string() ; "when the device is NanoS Plus")]
 #[tokio::test]
-async fn test_get_app_configuration(ledger_device_model: String) {
+async fn test_get_confirmed_public_key(ledger_device_model: String) {
     let args = Args {
         ledger_device_model,
     };
@@ -87,18 +89,40 @@ async fn test_get_app_configuration(ledger_device_model: String) {
     let ui_host_port: u16 = node.get_host_port_ipv4(5000);
     wait_for_emulator_start_text(ui_host_port).await;
 
-    let ledger = ledger(host_port);
+    let ledger = Arc::new(ledger(host_port));
 
-    match ledger.get_app_configuration().await {
-        Ok(config) => {
-            assert_eq!(config, vec![0, 5, 0, 3]);
+    let get_public_key = tokio::task::spawn({
+        let ledger = Arc::clone(&ledger);
+        async move {
+            ledger
+                .get_confirmed_public_key(0, Duration::from_secs(60))
+                .await
+        }
+    });
+    let approve = tokio::task::spawn(approve_address(ui_host_port));
+
+    let result = get_public_key.await.unwrap();
+    approve.await.unwrap();
+
+    match result {
+        Ok(public_key) => {
+            let expected_public_key = "GDUTHCF37UX32EMANXIL2WOOVEDZ47GHBTT3DYKU6EKM37SOIZXM2FN7";
+            assert_eq!(public_key.to_string(), expected_public_key);
         }
         Err(e) => {
             node.stop();
-            println!("{e}");
-            assert!(false);
+            panic!("Unexpected result: {e}");
         }
-    };
+    }
+
+    // Nobody confirms this time
+    let result = ledger
+        .get_confirmed_public_key(0, Duration::from_secs(1))
+        .await;
+    if !matches!(result, Err(Error::ConfirmationTimeout(_))) {
+        node.stop();
+        panic!("Unexpected result: {result:?}");
+    }
 
     node.stop();
 }
@@ -107,52 +131,45 @@ async fn test_get_app_configuration(ledger_device_model: String) {
 #[test_case("nanox".to_string() ; "when the device is NanoX")]
 #[test_case("nanosp".to_string() ; "when the device is NanoS Plus")]
 #[tokio::test]
-async fn test_sign_tx(ledger_device_model: String) {
+async fn test_get_app_configuration(ledger_device_model: String) {
     let args = Args {
         ledger_device_model,
     };
     let docker = clients::Cli::default();
-    let node = docker.run((Speculos::new(), args.clone()));
+    let node = docker.run((Speculos::new(), args));
     let host_port = node.get_host_port_ipv4(9998);
     let ui_host_port: u16 = node.get_host_port_ipv4(5000);
     wait_for_emulator_start_text(ui_host_port).await;
 
-    let ledger = Arc::new(ledger(host_port));
-
-    let path = HdPath(0);
+    let ledger = ledger(host_port);
 
-    let source_account_str = "GAQNVGMLOXSCWH37QXIHLQJH6WZENXYSVWLPAEF4673W64VRNZLRHMFM";
-    let source_account_bytes = match stellar_strkey::Strkey::from_string(source_account_str) {
-        Ok(key) => match key {
-            stellar_strkey::Strkey::PublicKeyEd25519(p) => p.0,
-            _ => {
-                eprintln!("Error decoding public key: {:?}", key);
-                return;
-            }
-        },
-        Err(err) => {
-            eprintln!("Error decoding public key: {}", err);
-            return;
+    match ledger.get_app_configuration().await {
+        Ok(config) => {
+            assert_eq!(config, vec![0, 5, 0, 3]);
+        }
+        Err(e) => {
+            node.stop();
+            println!("{e}");
+            assert!(false);
         }
     };
 
-    let destination_account_str = "GCKUD4BHIYSAYHU7HBB5FDSW6CSYH3GSOUBPWD2KE7KNBERP4BSKEJDV";
-    let destination_account_bytes =
-        match stellar_strkey::Strkey::from_string(destination_account_str) {
-            Ok(key) => match key {
-                stellar_strkey::Strkey::PublicKeyEd25519(p) => p.0,
-                _ => {
-                    eprintln!("Error decoding public key: {:?}", key);
-                    return;
-                }
-            },
-            Err(err) => {
-                eprintln!("Error decoding public key: {}", err);
-                return;
-            }
-        };
-
-    let tx = Transaction {
+    node.stop();
+}
+
+/// A payment transaction that is signed in two APDUs at the Nano S size, but one at larger sizes
+fn payment_tx() -> Transaction {
+    let source_account_bytes = stellar_strkey::ed25519::PublicKey::from_string(
+        "GAQNVGMLOXSCWH37QXIHLQJH6WZENXYSVWLPAEF4673W64VRNZLRHMFM",
+    )
+    .unwrap()
+    .0;
+    let destination_account_bytes = stellar_strkey::ed25519::PublicKey::from_string(
+        "GCKUD4BHIYSAYHU7HBB5FDSW6CSYH3GSOUBPWD2KE7KNBERP4BSKEJDV",
+    )
+    .unwrap()
+    .0;
+    Transaction {
         source_account: MuxedAccount::Ed25519(Uint256(source_account_bytes)),
         fee: 100,
         seq_num: SequenceNumber(1),
@@ -169,7 +186,28 @@ async fn test_sign_tx(ledger_device_model: String) {
         }]
         .try_into()
         .unwrap(),
+    }
+}
+
+#[test_case("nanos".to_string() ; "when the device is NanoS")]
+#[test_case("nanox".to_string() ; "when the device is NanoX")]
+#[test_case("nanosp".to_string() ; "when the device is NanoS Plus")]
+#[tokio::test]
+async fn test_sign_tx(ledger_device_model: String) {
+    let args = Args {
+        ledger_device_model,
     };
+    let docker = clients::Cli::default();
+    let node = docker.run((Speculos::new(), args.clone()));
+    let host_port = node.get_host_port_ipv4(9998);
+    let ui_host_port: u16 = node.get_host_port_ipv4(5000);
+    wait_for_emulator_start_text(ui_host_port).await;
+
+    let ledger = Arc::new(ledger(host_port));
+
+    let path = HdPath(0);
+
+    let tx = payment_tx();
 
     let sign = tokio::task::spawn({
         let ledger = Arc::clone(&ledger);
@@ -194,6 +232,128 @@ async fn test_sign_tx(ledger_device_model: String) {
     node.stop();
 }
 
+#[test_case("nanos".to_string() ; "when the device is NanoS")]
+#[test_case("nanox".to_string() ; "when the device is NanoX")]
+#[test_case("nanosp".to_string() ; "when the device is NanoS Plus")]
+#[tokio::test]
+async fn test_sign_tx_decorated(ledger_device_model: String) {
+    let args = Args {
+        ledger_device_model,
+    };
+    let docker = clients::Cli::default();
+    let node = docker.run((Speculos::new(), args.clone()));
+    let host_port = node.get_host_port_ipv4(9998);
+    let ui_host_port: u16 = node.get_host_port_ipv4(5000);
+    wait_for_emulator_start_text(ui_host_port).await;
+
+    let ledger = Arc::new(ledger(host_port));
+
+    let sign = tokio::task::spawn({
+        let ledger = Arc::clone(&ledger);
+        async move {
+            ledger
+                .sign_transaction_decorated(HdPath(0), payment_tx(), test_network_hash())
+                .await
+        }
+    });
+    let approve = tokio::task::spawn(approve_tx_signature(ui_host_port, args.ledger_device_model));
+
+    let result = sign.await.unwrap();
+    let _ = approve.await.unwrap();
+    node.stop();
+
+    let signature = result.unwrap();
+    // The account at hd path 0 of the emulator's seed phrase
+    let public_key = stellar_strkey::ed25519::PublicKey::from_string(
+        "GDUTHCF37UX32EMANXIL2WOOVEDZ47GHBTT3DYKU6EKM37SOIZXM2FN7",
+    )
+    .unwrap();
+    assert_eq!(signature.hint.0, public_key.0[28..]);
+    assert_eq!(
+        hex::encode(signature.signature.0),
+        "5c2f8eb41e11ab922800071990a25cf9713cc6e7c43e50e0780ddc4c0c6da50c784609ef14c528a12f520d8ea9343b49083f59c51e3f28af8c62b3edeaade60e"
+    );
+}
+
+/// Counts the APDUs exchanged with the device
+struct CountingTransport<T> {
+    inner: T,
+    count: Arc<AtomicUsize>,
+}
+
+#[ledger_transport::async_trait]
+impl<T> Exchange for CountingTransport<T>
+where
+    T: Exchange + Send + Sync,
+{
+    type Error = T::Error;
+    type AnswerType = T::AnswerType;
+
+    async fn exchange<I>(
+        &self,
+        command: &APDUCommand<I>,
+    ) -> Result<APDUAnswer<Self::AnswerType>, Self::Error>
+    where
+        I: Deref<Target = [u8]> + Send + Sync,
+    {
+        self.count.fetch_add(1, Ordering::SeqCst);
+        self.inner.exchange(command).await
+    }
+}
+
+#[test_case("nanos".to_string(), Model::NanoS, 2 ; "when the device is NanoS")]
+#[test_case("nanox".to_string(), Model::NanoX, 1 ; "when the device is NanoX")]
+#[test_case("nanosp".to_string(), Model::NanoSPlus, 1 ; "when the device is NanoS Plus")]
+#[tokio::test]
+async fn test_sign_tx_chunks_per_model(
+    ledger_device_model: String,
+    model: Model,
+    expected_apdus: usize,
+) {
+    let args = Args {
+        ledger_device_model,
+    };
+    let docker = clients::Cli::default();
+    let node = docker.run((Speculos::new(), args.clone()));
+    let host_port = node.get_host_port_ipv4(9998);
+    let ui_host_port: u16 = node.get_host_port_ipv4(5000);
+    wait_for_emulator_start_text(ui_host_port).await;
+
+    let count = Arc::new(AtomicUsize::new(0));
+    let transport = CountingTransport {
+        inner: EmulatorHttpTransport::new("127.0.0.1", host_port),
+        count: Arc::clone(&count),
+    };
+    let ledger = Arc::new(LedgerSigner::new(transport).with_model(model));
+
+    let sign = tokio::task::spawn({
+        let ledger = Arc::clone(&ledger);
+        async move {
+            ledger
+                .sign_transaction(HdPath(0), payment_tx(), test_network_hash())
+                .await
+        }
+    });
+    let approve = tokio::task::spawn(approve_tx_signature(ui_host_port, args.ledger_device_model));
+
+    let result = sign.await.unwrap();
+    approve.await.unwrap();
+
+    match result {
+        Ok(response) => {
+            // The signature does not depend on how the transaction was split up
+            assert_eq!( hex::encode(response), "5c2f8eb41e11ab922800071990a25cf9713cc6e7c43e50e0780ddc4c0c6da50c784609ef14c528a12f520d8ea9343b49083f59c51e3f28af8c62b3edeaade60e");
+            assert_eq!(count.load(Ordering::SeqCst), expected_apdus);
+        }
+        Err(e) => {
+            node.stop();
+            panic!("Unexpected result: {e}");
+        }
+    }
+
+    node.stop();
+}
+
 #[test_case("nanos".to_string() ; "when the device is NanoS")]
 #[test_case("nanox".to_string() ; "when the device is NanoX")]
 #[test_case("nanosp".to_string() ; "when the device is NanoS Plus")]
@@ -216,7 +376,7 @@ async fn test_sign_tx_hash_when_hash_signing_is_not_enabled(ledger_device_model:
 
     let result = ledger.sign_transaction_hash(path, test_hash).await;
     if let Err(Error::APDUExchangeError(msg)) = result {
-        assert_eq!(msg, "Ledger APDU retcode: 0x6C66");
+        assert_eq!(msg, "Hash signing is not enabled on the device; enable it in the Stellar app settings. (Ledger APDU retcode: 0x6C66)");
         // this error code is SW_TX_HASH_SIGNING_MODE_NOT_ENABLED https://github.com/LedgerHQ/app-stellar/blob/develop/docs/COMMANDS.md
     } else {
         node.stop();
@@ -378,6 +538,19 @@ async fn approve_tx_hash_signature(ui_host_port: u16, device_model: String) {
     click(ui_host_port, "button/both").await;
 }
 
+async fn approve_address(ui_host_port: u16) {
+    // The address takes a different number of screens on each device, page through it until the
+    // approve screen shows up
+    for _ in 0..10 {
+        let events = get_emulator_events(ui_host_port).await;
+        if events.iter().any(|event| event.text == "Approve") {
+            break;
+        }
+        click(ui_host_port, "button/right").await;
+    }
+    click(ui_host_port, "button/both").await;
+}
+
 async fn approve_tx_signature(ui_host_port: u16, device_model: String) {
     let number_of_right_clicks = if device_model == "nanos" { 17 } else { 11 };
     for _ in 0..number_of_right_clicks {
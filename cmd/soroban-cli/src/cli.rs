@@ -72,6 +72,6 @@ pub async fn main() {
 
     if let Err(e) = root.run().await {
         eprintln!("error: {e}");
-        std::process::exit(1);
+        std::process::exit(e.exit_code());
     }
 }
@@ -2,7 +2,12 @@ use crate::rpc::{GetTransactionResponse, GetTransactionResponseRaw, SimulateTran
 use directories::ProjectDirs;
 use http::Uri;
 use serde::{Deserialize, Serialize};
-use std::str::FromStr;
+use sha2::{Digest, Sha256};
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 use crate::xdr::{self, WriteXdr};
 
@@ -24,6 +29,10 @@ pub enum Error {
 
 pub const XDG_DATA_HOME: &str = "XDG_DATA_HOME";
 
+/// How long a contract id's Wasm hash is trusted before it is looked up again, since the
+/// contract's code can be upgraded.
+pub const CONTRACT_HASH_TTL: Duration = Duration::from_secs(60);
+
 pub fn project_dir() -> Result<directories::ProjectDirs, Error> {
     std::env::var(XDG_DATA_HOME)
         .map_or_else(
@@ -44,8 +53,23 @@ pub fn actions_dir() -> Result<std::path::PathBuf, Error> {
     Ok(dir)
 }
 
-pub fn spec_dir() -> Result<std::path::PathBuf, Error> {
-    let dir = data_local_dir()?.join("spec");
+pub fn spec_dir(data_dir: &Path) -> Result<PathBuf, Error> {
+    let dir = data_dir.join("spec");
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+pub fn wasm_dir(data_dir: &Path) -> Result<PathBuf, Error> {
+    let dir = data_dir.join("wasm");
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// Where the Wasm hashes of contracts on the network with `network_passphrase` are cached, as the
+/// same contract id can be deployed to several networks
+pub fn contracts_dir(data_dir: &Path, network_passphrase: &str) -> Result<PathBuf, Error> {
+    let network = hex::encode(Sha256::digest(network_passphrase));
+    let dir = data_dir.join("contracts").join(network);
     std::fs::create_dir_all(&dir)?;
     Ok(dir)
 }
@@ -67,8 +91,12 @@ pub fn read(id: &ulid::Ulid) -> Result<(Action, Uri), Error> {
     Ok((data.action, http::Uri::from_str(&data.rpc_url)?))
 }
 
-pub fn write_spec(hash: &str, spec_entries: &[xdr::ScSpecEntry]) -> Result<(), Error> {
-    let file = spec_dir()?.join(hash);
+pub fn write_spec(
+    data_dir: &Path,
+    hash: &str,
+    spec_entries: &[xdr::ScSpecEntry],
+) -> Result<(), Error> {
+    let file = spec_dir(data_dir)?.join(hash);
     tracing::trace!("writing spec to {:?}", file);
     let mut contents: Vec<u8> = Vec::new();
     for entry in spec_entries {
@@ -78,12 +106,72 @@ pub fn write_spec(hash: &str, spec_entries: &[xdr::ScSpecEntry]) -> Result<(), E
     Ok(())
 }
 
-pub fn read_spec(hash: &str) -> Result<Vec<xdr::ScSpecEntry>, Error> {
-    let file = spec_dir()?.join(hash);
+pub fn read_spec(data_dir: &Path, hash: &str) -> Result<Vec<xdr::ScSpecEntry>, Error> {
+    let file = spec_dir(data_dir)?.join(hash);
     tracing::trace!("reading spec from {:?}", file);
     Ok(soroban_spec::read::parse_raw(&std::fs::read(file)?)?)
 }
 
+pub fn write_wasm(data_dir: &Path, hash: &str, wasm: &[u8]) -> Result<(), Error> {
+    let file = wasm_dir(data_dir)?.join(hash);
+    tracing::trace!("writing wasm to {:?}", file);
+    std::fs::write(file, wasm)?;
+    Ok(())
+}
+
+pub fn read_wasm(data_dir: &Path, hash: &str) -> Result<Vec<u8>, Error> {
+    let file = wasm_dir(data_dir)?.join(hash);
+    tracing::trace!("reading wasm from {:?}", file);
+    Ok(std::fs::read(file)?)
+}
+
+pub fn write_contract_hash(
+    data_dir: &Path,
+    network_passphrase: &str,
+    contract_id: &str,
+    hash: &str,
+) -> Result<(), Error> {
+    let file = contracts_dir(data_dir, network_passphrase)?
+        .join(contract_id)
+        .with_extension("json");
+    let cached_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let entry = ContractHash {
+        hash: hash.to_string(),
+        cached_at,
+    };
+    std::fs::write(file, serde_json::to_string(&entry)?)?;
+    Ok(())
+}
+
+/// Read the cached Wasm hash of a contract, if it was cached within [`CONTRACT_HASH_TTL`]. A cache
+/// file that can't be parsed is a miss, and is overwritten when the hash is next cached
+pub fn read_contract_hash(
+    data_dir: &Path,
+    network_passphrase: &str,
+    contract_id: &str,
+) -> Result<Option<String>, Error> {
+    let file = contracts_dir(data_dir, network_passphrase)?
+        .join(contract_id)
+        .with_extension("json");
+    if !file.exists() {
+        return Ok(None);
+    }
+    let entry: ContractHash = match serde_json::from_str(&std::fs::read_to_string(&file)?) {
+        Ok(entry) => entry,
+        Err(e) => {
+            tracing::debug!("ignoring unreadable cache file {file:?}: {e}");
+            return Ok(None);
+        }
+    };
+    let age = SystemTime::now()
+        .duration_since(UNIX_EPOCH + Duration::from_secs(entry.cached_at))
+        .unwrap_or_default();
+    Ok((age < CONTRACT_HASH_TTL).then_some(entry.hash))
+}
+
 pub fn list_ulids() -> Result<Vec<ulid::Ulid>, Error> {
     let dir = actions_dir()?;
     let mut list = std::fs::read_dir(dir)?
@@ -134,6 +222,12 @@ fn to_datatime(id: &ulid::Ulid) -> chrono::DateTime<chrono::Utc> {
     chrono::DateTime::from_timestamp_millis(id.timestamp_ms().try_into().unwrap()).unwrap()
 }
 
+#[derive(Serialize, Deserialize)]
+struct ContractHash {
+    hash: String,
+    cached_at: u64,
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 struct Data {
@@ -208,4 +302,43 @@ mod test {
             _ => panic!("Action mismatch"),
         }
     }
+
+    #[test]
+    fn contract_hash_per_network() {
+        let t = assert_fs::TempDir::new().unwrap();
+        let dir = t.path();
+        let contract_id = "CAAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQC526";
+        let testnet = "Test SDF Network ; September 2015";
+        let futurenet = "Test SDF Future Network ; October 2022";
+
+        write_contract_hash(dir, testnet, contract_id, "aa").unwrap();
+        write_contract_hash(dir, futurenet, contract_id, "bb").unwrap();
+        assert_eq!(
+            read_contract_hash(dir, testnet, contract_id)
+                .unwrap()
+                .as_deref(),
+            Some("aa")
+        );
+        assert_eq!(
+            read_contract_hash(dir, futurenet, contract_id)
+                .unwrap()
+                .as_deref(),
+            Some("bb")
+        );
+
+        // A corrupt cache file is a miss, then replaced
+        let file = contracts_dir(dir, testnet)
+            .unwrap()
+            .join(contract_id)
+            .with_extension("json");
+        std::fs::write(&file, "{ not json").unwrap();
+        assert_eq!(read_contract_hash(dir, testnet, contract_id).unwrap(), None);
+        write_contract_hash(dir, testnet, contract_id, "cc").unwrap();
+        assert_eq!(
+            read_contract_hash(dir, testnet, contract_id)
+                .unwrap()
+                .as_deref(),
+            Some("cc")
+        );
+    }
 }
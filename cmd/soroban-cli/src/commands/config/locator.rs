@@ -1,6 +1,7 @@
 use clap::arg;
-use serde::de::DeserializeOwned;
+use serde::{de::DeserializeOwned, Deserialize};
 use std::{
+    collections::BTreeMap,
     ffi::OsStr,
     fmt::Display,
     fs::{self, create_dir_all, OpenOptions},
@@ -13,7 +14,7 @@ use stellar_strkey::{Contract, DecodeError};
 
 use crate::{utils::find_config_dir, Pwd};
 
-use super::{alias, network::Network, secret::Secret};
+use super::{alias, network::Network, secret::Secret, Config};
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
@@ -79,6 +80,23 @@ pub struct Args {
     pub config_dir: Option<PathBuf>,
 }
 
+/// Files, relative to the project directory or one of its parents, that project settings are
+/// read from. The first one found is used.
+pub const PROJECT_CONFIG_FILES: [&str; 2] = ["stellar.toml", ".stellar/config.toml"];
+
+/// File in the config directory with the defaults set by commands such as `keys use`
+pub const DEFAULTS_FILE: &str = "defaults.toml";
+
+/// Settings committed alongside a project, read from one of [`PROJECT_CONFIG_FILES`]
+#[derive(Debug, Default, Deserialize)]
+pub struct ProjectConfig {
+    /// Networks by name, used when no network of the same name was added with `network add`
+    #[serde(default)]
+    pub networks: BTreeMap<String, Network>,
+    /// Source account used when neither `--source-account` nor `STELLAR_ACCOUNT` is set
+    pub source_account: Option<String>,
+}
+
 pub enum Location {
     Local(PathBuf),
     Global(PathBuf),
@@ -144,6 +162,20 @@ impl Args {
         )
     }
 
+    /// The project settings file closest to the current directory, if any
+    pub fn project_config(&self) -> Result<Option<ProjectConfig>, Error> {
+        let pwd = self.current_dir()?;
+        for dir in pwd.ancestors() {
+            for file in PROJECT_CONFIG_FILES {
+                let path = dir.join(file);
+                if path.is_file() {
+                    return Ok(Some(KeyType::read_from_path(&path)?));
+                }
+            }
+        }
+        Ok(None)
+    }
+
     pub fn write_identity(&self, name: &str, secret: &Secret) -> Result<(), Error> {
         KeyType::Identity.write(name, secret, &self.config_dir()?)
     }
@@ -201,9 +233,24 @@ impl Args {
         KeyType::Identity.read_with_global(name, &self.local_config()?)
     }
 
+    /// The identity named `key`, or else `key` itself as a secret key or seed phrase
+    pub fn read_key(&self, key: &str) -> Result<Secret, Error> {
+        if let Ok(secret) = self.read_identity(key) {
+            Ok(secret)
+        } else {
+            Ok(key.parse::<Secret>()?)
+        }
+    }
+
     pub fn read_network(&self, name: &str) -> Result<Network, Error> {
         let res = KeyType::Network.read_with_global(name, &self.local_config()?);
         if let Err(Error::ConfigMissing(_, _)) = &res {
+            if let Some(network) = self
+                .project_config()?
+                .and_then(|mut config| config.networks.remove(name))
+            {
+                return Ok(network);
+            }
             if name == "futurenet" {
                 let network = Network::futurenet();
                 self.write_network(name, &network)?;
@@ -213,6 +260,33 @@ impl Args {
         res
     }
 
+    /// The identity set with `keys use`, from the local config directory or else the global one
+    pub fn default_identity(&self) -> Result<Option<String>, Error> {
+        for dir in [self.local_config()?, global_config_path()?] {
+            let path = dir.join(DEFAULTS_FILE);
+            if path.is_file() {
+                let config: Config = KeyType::read_from_path(&path)?;
+                if config.default_identity.is_some() {
+                    return Ok(config.default_identity);
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Set the identity used as the source account when none is given, in the config directory
+    pub fn write_default_identity(&self, name: &str) -> Result<(), Error> {
+        let filepath = ensure_directory(self.config_dir()?.join(DEFAULTS_FILE))?;
+        let mut config: Config = if filepath.is_file() {
+            KeyType::read_from_path(&filepath)?
+        } else {
+            Config::default()
+        };
+        config.default_identity = Some(name.to_string());
+        let data = toml::to_string(&config).map_err(|_| Error::ConfigSerialization)?;
+        fs::write(&filepath, data).map_err(|error| Error::IdCreationFailed { filepath, error })
+    }
+
     pub fn remove_identity(&self, name: &str) -> Result<(), Error> {
         KeyType::Identity.remove(name, &self.config_dir()?)
     }
@@ -268,6 +342,46 @@ impl Args {
         Ok(to_file.write_all(content.as_bytes())?)
     }
 
+    /// Remove the contract id saved under `alias` for a network, deleting the alias once it has no
+    /// ids left. Returns whether there was an id to remove.
+    pub fn remove_contract_id(&self, network_passphrase: &str, alias: &str) -> Result<bool, Error> {
+        let path = self.alias_path(alias)?;
+        let Some(mut data) = self.load_contract_from_alias(alias)? else {
+            return Ok(false);
+        };
+        if data.ids.remove(network_passphrase).is_none() {
+            return Ok(false);
+        }
+        if data.ids.is_empty() {
+            fs::remove_file(path)?;
+        } else {
+            fs::write(path, serde_json::to_string(&data)?)?;
+        }
+        Ok(true)
+    }
+
+    /// All saved contract aliases, sorted by name
+    pub fn list_contract_aliases(&self) -> Result<Vec<(String, alias::Data)>, Error> {
+        let dir = self.config_dir()?.join("contract-ids");
+        if !dir.exists() {
+            return Ok(vec![]);
+        }
+        let mut res = vec![];
+        for entry in fs::read_dir(dir)?.filter_map(Result::ok) {
+            let path = entry.path();
+            if let (Some("json"), Some(name)) = (
+                path.extension().and_then(OsStr::to_str),
+                path.file_stem().and_then(OsStr::to_str),
+            ) {
+                if let Some(data) = self.load_contract_from_alias(name)? {
+                    res.push((name.to_string(), data));
+                }
+            }
+        }
+        res.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(res)
+    }
+
     pub fn get_contract_id(
         &self,
         alias: &str,
@@ -2,18 +2,25 @@ use std::path::PathBuf;
 
 use clap::{arg, command};
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "ledger")]
+use sha2::{Digest, Sha256};
 
-use soroban_rpc::Client;
+use soroban_rpc::GetTransactionResponse;
+#[cfg(feature = "ledger")]
+use stellar_ledger::{hd_path::HdPath, Blob};
 
+#[cfg(feature = "ledger")]
+use crate::xdr::{Hash, TransactionV1Envelope};
 use crate::{
+    rpc_ext::Client,
     signer,
-    xdr::{Transaction, TransactionEnvelope},
+    xdr::{MuxedAccount, SequenceNumber, Transaction, TransactionEnvelope},
     Pwd,
 };
 
 use self::{network::Network, secret::Secret};
 
-use super::{keys, network};
+use super::{global, keys, network};
 
 pub mod alias;
 pub mod data;
@@ -31,9 +38,36 @@ pub enum Error {
     #[error(transparent)]
     Config(#[from] locator::Error),
     #[error(transparent)]
-    Rpc(#[from] soroban_rpc::Error),
+    Rpc(soroban_rpc::Error),
+    #[error(transparent)]
+    RpcError(#[from] crate::rpc_ext::RpcError),
     #[error(transparent)]
     Signer(#[from] signer::Error),
+    #[error("No source account provided, use --source-account, STELLAR_ACCOUNT, set source_account in stellar.toml or set a default with `stellar keys use`")]
+    NoSourceAccount,
+    #[cfg(feature = "ledger")]
+    #[error(transparent)]
+    Ledger(#[from] stellar_ledger::Error),
+    #[cfg(feature = "ledger")]
+    #[error("hd path {0} is out of range for a Ledger device")]
+    LedgerHdPathOutOfRange(usize),
+    #[cfg(feature = "ledger")]
+    #[error("the Ledger device returned a signature that isn't 64 bytes")]
+    LedgerSignatureLength,
+    #[error(transparent)]
+    Xdr(#[from] crate::xdr::Error),
+    #[error("transaction fee of {fee} stroops is above --max-fee {max_fee}, the transaction was not submitted")]
+    FeeAboveMax { fee: u32, max_fee: u32 },
+    #[error(
+        "cannot parse secp256r1 signer {0:?}, expected <CONTRACT_ID>:<KEY_ID_HEX>:<SECRET_KEY_HEX>"
+    )]
+    CannotParseSecp256r1Signer(String),
+}
+
+impl From<soroban_rpc::Error> for Error {
+    fn from(e: soroban_rpc::Error) -> Self {
+        crate::rpc_ext::RpcError::from_rpc(e).map_or_else(Error::Rpc, Error::RpcError)
+    }
 }
 
 #[derive(Debug, clap::Args, Clone, Default)]
@@ -42,66 +76,237 @@ pub struct Args {
     #[command(flatten)]
     pub network: network::Args,
 
-    #[arg(long, visible_alias = "source", env = "STELLAR_ACCOUNT")]
-    /// Account that signs the final transaction. Alias `source`. Can be an identity (--source alice), a secret key (--source SC36…), or a seed phrase (--source "kite urban…").
+    #[arg(
+        long,
+        visible_alias = "source",
+        env = "STELLAR_ACCOUNT",
+        default_value = "",
+        hide_default_value = true
+    )]
+    /// Account that signs the final transaction. Alias `source`. Can be an identity (--source alice), a secret key (--source SC36…), or a seed phrase (--source "kite urban…"). Defaults to the `source_account` of the project's stellar.toml, or else the identity set with `keys use`.
     pub source_account: String,
 
     #[arg(long)]
     /// If using a seed phrase, which hierarchical deterministic path to use, e.g. `m/44'/148'/{hd_path}`. Example: `--hd-path 1`. Default: `0`
     pub hd_path: Option<usize>,
 
+    #[cfg_attr(feature = "ledger", arg(long))]
+    #[cfg_attr(not(feature = "ledger"), arg(skip))]
+    /// Sign with the account at `--hd-path` on a Ledger device instead of a local key. The device
+    /// shows each transaction and authorization to confirm before signing it
+    pub sign_with_ledger: bool,
+
     #[command(flatten)]
     pub locator: locator::Args,
 }
 
 impl Args {
     pub fn key_pair(&self) -> Result<ed25519_dalek::SigningKey, Error> {
-        let key = self.account(&self.source_account)?;
+        let key = self.account(&self.resolve_source_account()?)?;
         Ok(key.key_pair(self.hd_path)?)
     }
 
-    pub async fn sign_with_local_key(&self, tx: Transaction) -> Result<TransactionEnvelope, Error> {
-        self.sign(tx).await
+    /// `--source-account`, or else the `source_account` of the project's stellar.toml, or else
+    /// the identity set with `keys use`
+    pub fn resolve_source_account(&self) -> Result<String, Error> {
+        if !self.source_account.is_empty() {
+            return Ok(self.source_account.clone());
+        }
+        if let Some(source_account) = self
+            .locator
+            .project_config()?
+            .and_then(|config| config.source_account)
+        {
+            return Ok(source_account);
+        }
+        self.locator
+            .default_identity()?
+            .ok_or(Error::NoSourceAccount)
+    }
+
+    /// The public key of the account that signs, asking the Ledger device for it with
+    /// `--sign-with-ledger`
+    pub async fn public_key(&self) -> Result<stellar_strkey::ed25519::PublicKey, Error> {
+        #[cfg(feature = "ledger")]
+        if self.sign_with_ledger {
+            return Ok(stellar_ledger::native()?
+                .get_public_key(&self.ledger_hd_path()?)
+                .await?);
+        }
+        Ok(stellar_strkey::ed25519::PublicKey(
+            self.key_pair()?.verifying_key().to_bytes(),
+        ))
+    }
+
+    pub async fn sign_with_local_key(
+        &self,
+        tx: Transaction,
+        network: &Network,
+    ) -> Result<TransactionEnvelope, Error> {
+        self.sign(tx, network).await
     }
 
-    #[allow(clippy::unused_async)]
-    pub async fn sign(&self, tx: Transaction) -> Result<TransactionEnvelope, Error> {
+    /// Sign `tx` for `network`, the network resolved with [`Args::get_network`]
+    pub async fn sign(
+        &self,
+        tx: Transaction,
+        network: &Network,
+    ) -> Result<TransactionEnvelope, Error> {
+        let network_passphrase = &network.network_passphrase;
+        #[cfg(feature = "ledger")]
+        if self.sign_with_ledger {
+            eprintln!("Confirm the transaction on your Ledger device");
+            let signature = stellar_ledger::native()?
+                .sign_transaction_decorated(
+                    self.ledger_hd_path()?,
+                    tx.clone(),
+                    Hash(Sha256::digest(network_passphrase.as_bytes()).into()),
+                )
+                .await?;
+            return Ok(TransactionEnvelope::Tx(TransactionV1Envelope {
+                tx,
+                signatures: vec![signature].try_into()?,
+            }));
+        }
         let key = self.key_pair()?;
-        let Network {
-            network_passphrase, ..
-        } = &self.get_network()?;
         Ok(signer::sign_tx(&key, &tx, network_passphrase)?)
     }
 
+    /// Sign and submit `tx`. If `seq_retry` is set and it fails with `txBAD_SEQ`, because the
+    /// sequence number it was built with is stale, the source account's current sequence number
+    /// is fetched and the transaction re-signed and submitted once more. Fails without submitting
+    /// if the fee of `tx` is above `--max-fee`.
+    pub async fn sign_and_send(
+        &self,
+        client: &Client,
+        network: &Network,
+        tx: Transaction,
+        seq_retry: bool,
+        global_args: Option<&global::Args>,
+    ) -> Result<GetTransactionResponse, Error> {
+        if let Some(max_fee) = global_args.and_then(|a| a.max_fee) {
+            if tx.fee > max_fee {
+                return Err(Error::FeeAboveMax {
+                    fee: tx.fee,
+                    max_fee,
+                });
+            }
+        }
+        match client
+            .send_transaction_polling(&self.sign(tx.clone(), network).await?)
+            .await
+        {
+            Err(e) if seq_retry && is_bad_seq(&e) => {
+                let tx = with_current_seq(client, tx).await?;
+                tracing::warn!(
+                    "transaction sequence number was stale, resubmitting with {}",
+                    tx.seq_num.0
+                );
+                Ok(client
+                    .send_transaction_polling(&self.sign(tx, network).await?)
+                    .await?)
+            }
+            res => Ok(res?),
+        }
+    }
+
     pub async fn sign_soroban_authorizations(
         &self,
         tx: &Transaction,
+        network: &Network,
         signers: &[ed25519_dalek::SigningKey],
+        contract_signers: &[signer::Secp256r1Signer],
+        expiration: &SignatureExpirationArgs,
     ) -> Result<Option<Transaction>, Error> {
-        let network = self.get_network()?;
-        let source_key = self.key_pair()?;
-        let client = Client::new(&network.rpc_url)?;
-        let latest_ledger = client.get_latest_ledger().await?.sequence;
-        let seq_num = latest_ledger + 60; // ~ 5 min
+        let client = network.rpc_client()?;
+        let seq_num = expiration.ledger(&client).await?;
+        let source_key = self
+            .auth_signer(tx, seq_num, &network.network_passphrase)
+            .await?;
         Ok(signer::sign_soroban_authorizations(
             tx,
-            &source_key,
+            source_key.as_ref(),
             signers,
+            contract_signers,
             seq_num,
             &network.network_passphrase,
         )?)
     }
 
-    pub fn account(&self, account_str: &str) -> Result<Secret, Error> {
-        if let Ok(secret) = self.locator.read_identity(account_str) {
-            Ok(secret)
-        } else {
-            Ok(account_str.parse::<Secret>()?)
+    /// Signer of the source account's auth entries in `tx`: the local key, or with
+    /// `--sign-with-ledger` the signatures made on the device ahead of time
+    #[cfg_attr(not(feature = "ledger"), allow(unused_variables))]
+    async fn auth_signer(
+        &self,
+        tx: &Transaction,
+        signature_expiration_ledger: u32,
+        network_passphrase: &str,
+    ) -> Result<Box<dyn signer::AuthSigner>, Error> {
+        #[cfg(feature = "ledger")]
+        if self.sign_with_ledger {
+            return Ok(Box::new(
+                self.presign_authorizations_with_ledger(
+                    tx,
+                    signature_expiration_ledger,
+                    network_passphrase,
+                )
+                .await?,
+            ));
         }
+        Ok(Box::new(self.key_pair()?))
+    }
+
+    /// Sign the payloads of the Ledger account's auth entries on the device. The device signs
+    /// them as hashes, so hash signing must be enabled in the Stellar app's settings.
+    #[cfg(feature = "ledger")]
+    async fn presign_authorizations_with_ledger(
+        &self,
+        tx: &Transaction,
+        signature_expiration_ledger: u32,
+        network_passphrase: &str,
+    ) -> Result<signer::PresignedAuth, Error> {
+        let ledger = stellar_ledger::native()?;
+        let hd_path = self.ledger_hd_path()?;
+        let public_key = ledger.get_public_key(&hd_path).await?.0;
+        let mut signatures = Vec::new();
+        for payload in signer::auth_payloads_of(
+            tx,
+            &public_key,
+            signature_expiration_ledger,
+            network_passphrase,
+        )? {
+            eprintln!(
+                "Confirm signing the authorization with hash {} on your Ledger device",
+                hex::encode(payload)
+            );
+            let signature = ledger.sign_transaction_hash(hd_path, &payload).await?;
+            signatures.push((
+                payload,
+                signature
+                    .try_into()
+                    .map_err(|_| Error::LedgerSignatureLength)?,
+            ));
+        }
+        Ok(signer::PresignedAuth {
+            public_key,
+            signatures,
+        })
+    }
+
+    #[cfg(feature = "ledger")]
+    fn ledger_hd_path(&self) -> Result<HdPath, Error> {
+        let index = self.hd_path.unwrap_or_default();
+        Ok(HdPath(
+            u32::try_from(index).map_err(|_| Error::LedgerHdPathOutOfRange(index))?,
+        ))
+    }
+
+    pub fn account(&self, account_str: &str) -> Result<Secret, Error> {
+        Ok(self.locator.read_key(account_str)?)
     }
 
-    pub fn get_network(&self) -> Result<Network, Error> {
-        Ok(self.network.get(&self.locator)?)
+    pub async fn get_network(&self) -> Result<Network, Error> {
+        Ok(self.network.get(&self.locator).await?)
     }
 
     pub fn config_dir(&self) -> Result<PathBuf, Error> {
@@ -115,5 +320,407 @@ impl Pwd for Args {
     }
 }
 
+/// Ledger until which the signatures of auth entries are valid
+#[derive(Debug, clap::Args, Clone)]
+#[group(skip)]
+pub struct SignatureExpirationArgs {
+    /// Last ledger in which the signatures of auth entries are valid
+    #[arg(long)]
+    pub signature_expiration_ledger: Option<u32>,
+    /// Number of ledgers after the latest one that the signatures of auth entries are valid for,
+    /// unless `--signature-expiration-ledger` is given. The default is about 5 minutes
+    #[arg(
+        long,
+        default_value = "60",
+        conflicts_with = "signature_expiration_ledger"
+    )]
+    pub signature_expiration_ledgers_from_now: u32,
+}
+
+impl Default for SignatureExpirationArgs {
+    fn default() -> Self {
+        Self {
+            signature_expiration_ledger: None,
+            signature_expiration_ledgers_from_now: 60,
+        }
+    }
+}
+
+impl SignatureExpirationArgs {
+    /// `--signature-expiration-ledger`, or else the latest ledger plus
+    /// `--signature-expiration-ledgers-from-now`
+    pub async fn ledger(&self, client: &Client) -> Result<u32, soroban_rpc::Error> {
+        if let Some(ledger) = self.signature_expiration_ledger {
+            return Ok(ledger);
+        }
+        let latest_ledger = client.get_latest_ledger().await?.sequence;
+        Ok(latest_ledger.saturating_add(self.signature_expiration_ledgers_from_now))
+    }
+}
+
+/// Keys registered with smart wallet contracts, to sign the wallets' auth entries with
+#[derive(Debug, clap::Args, Clone)]
+#[group(skip)]
+pub struct ContractSignerArgs {
+    /// Secp256r1 (passkey) key of a smart wallet contract, which signs the wallet's auth entries
+    /// the way a WebAuthn authenticator does, as `<CONTRACT_ID>:<KEY_ID_HEX>:<SECRET_KEY_HEX>`,
+    /// where the key ID is the 32 bytes the wallet stores the public key under. Can be repeated
+    #[arg(long = "secp256r1-signer")]
+    pub secp256r1_signers: Vec<String>,
+    /// Relying party ID that the secp256r1 signatures are made for
+    #[arg(long, default_value = "localhost")]
+    pub webauthn_rp_id: String,
+}
+
+impl Default for ContractSignerArgs {
+    fn default() -> Self {
+        Self {
+            secp256r1_signers: Vec::new(),
+            webauthn_rp_id: "localhost".to_string(),
+        }
+    }
+}
+
+impl ContractSignerArgs {
+    pub fn secp256r1_signers(&self) -> Result<Vec<signer::Secp256r1Signer>, Error> {
+        self.secp256r1_signers
+            .iter()
+            .map(|s| {
+                self.parse_secp256r1_signer(s)
+                    .ok_or_else(|| Error::CannotParseSecp256r1Signer(s.clone()))
+            })
+            .collect()
+    }
+
+    fn parse_secp256r1_signer(&self, s: &str) -> Option<signer::Secp256r1Signer> {
+        let [contract, id, key] = s.split(':').collect::<Vec<_>>()[..] else {
+            return None;
+        };
+        Some(signer::Secp256r1Signer {
+            contract: stellar_strkey::Contract::from_string(contract).ok()?.0,
+            id: hex::decode(id).ok()?.try_into().ok()?,
+            key: p256::ecdsa::SigningKey::from_slice(&hex::decode(key).ok()?).ok()?,
+            rp_id: self.webauthn_rp_id.clone(),
+        })
+    }
+}
+
+/// Settings of the config directory, stored in [`locator::DEFAULTS_FILE`]
 #[derive(Default, Serialize, Deserialize)]
-pub struct Config {}
+pub struct Config {
+    /// Identity set with `keys use`, the source account when no other is given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_identity: Option<String>,
+}
+
+fn is_bad_seq(e: &soroban_rpc::Error) -> bool {
+    // The client only hands back the failed transaction result in its debug rendering.
+    matches!(e, soroban_rpc::Error::TransactionSubmissionFailed(result) if result.contains("TxBadSeq"))
+}
+
+/// `tx` with the sequence number following its source account's current one
+async fn with_current_seq(client: &Client, mut tx: Transaction) -> Result<Transaction, Error> {
+    let source = match &tx.source_account {
+        MuxedAccount::Ed25519(key) => key.0,
+        MuxedAccount::MuxedEd25519(muxed) => muxed.ed25519.0,
+    };
+    let account = client
+        .get_account(&stellar_strkey::ed25519::PublicKey(source).to_string())
+        .await?;
+    tx.seq_num = SequenceNumber(account.seq_num.0 + 1);
+    Ok(tx)
+}
+
+#[cfg(test)]
+mod test {
+    use httpmock::prelude::*;
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        test_utils::rpc_response,
+        xdr::{
+            AccountEntry, AccountEntryExt, AccountId, BumpSequenceOp, LedgerEntryData, Limits,
+            Memo, Operation, OperationBody, Preconditions, PublicKey, String32, Thresholds,
+            TransactionExt, TransactionResult, TransactionResultExt, TransactionResultResult,
+            TransactionV1Envelope, Uint256, VecM, WriteXdr,
+        },
+    };
+
+    const PASSPHRASE: &str = "Standalone Network ; February 2017";
+    const SECRET: &str = "SBFGFF27Y64ZUGFAIG5AMJGQODZZKV2YQKAVUUN4HNE24XZXD2OEUVUP";
+
+    fn bump_tx(source: &Uint256, seq: i64) -> Transaction {
+        Transaction {
+            source_account: MuxedAccount::Ed25519(source.clone()),
+            fee: 100,
+            seq_num: SequenceNumber(seq),
+            cond: Preconditions::None,
+            memo: Memo::None,
+            operations: vec![Operation {
+                source_account: None,
+                body: OperationBody::BumpSequence(BumpSequenceOp {
+                    bump_to: SequenceNumber(0),
+                }),
+            }]
+            .try_into()
+            .unwrap(),
+            ext: TransactionExt::V0,
+        }
+    }
+
+    #[tokio::test]
+    async fn signature_expiration_is_relative_to_the_latest_ledger() {
+        let server = MockServer::start();
+        let get_latest_ledger = server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .json_body_partial(r#"{ "method": "getLatestLedger" }"#);
+            then.status(200).json_body(rpc_response(json!({
+                "id": "0",
+                "protocolVersion": 21,
+                "sequence": 10000
+            })));
+        });
+        let client = Client::new(&server.url("/"), network::DEFAULT_RPC_TIMEOUT).unwrap();
+        let relative = SignatureExpirationArgs {
+            signature_expiration_ledgers_from_now: 100,
+            ..Default::default()
+        };
+        assert_eq!(relative.ledger(&client).await.unwrap(), 10100);
+        let absolute = SignatureExpirationArgs {
+            signature_expiration_ledger: Some(20000),
+            ..Default::default()
+        };
+        assert_eq!(absolute.ledger(&client).await.unwrap(), 20000);
+        get_latest_ledger.assert_hits(1);
+    }
+
+    #[tokio::test]
+    async fn auto_passphrase_is_fetched_once_and_signed_with() {
+        let server = MockServer::start();
+        let get_network = server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .json_body_partial(r#"{ "method": "getNetwork" }"#);
+            then.status(200).json_body(rpc_response(json!({
+                "passphrase": PASSPHRASE,
+                "protocolVersion": 21
+            })));
+        });
+        let config = Args {
+            network: network::Args {
+                rpc_url: Some(server.url("/")),
+                network_passphrase: Some(network::AUTO_NETWORK_PASSPHRASE.to_string()),
+                ..Default::default()
+            },
+            source_account: SECRET.to_string(),
+            ..Default::default()
+        };
+        let network = config.get_network().await.unwrap();
+        assert_eq!(network.network_passphrase, PASSPHRASE);
+
+        let key = config.key_pair().unwrap();
+        let tx = bump_tx(&Uint256(key.verifying_key().to_bytes()), 1);
+        let TransactionEnvelope::Tx(TransactionV1Envelope { signatures, .. }) =
+            config.sign(tx.clone(), &network).await.unwrap()
+        else {
+            panic!("expected a v1 envelope");
+        };
+        let signature = ed25519_dalek::Signature::from_slice(&signatures[0].signature.0).unwrap();
+        key.verifying_key()
+            .verify_strict(&signer::hash(&tx, PASSPHRASE).unwrap(), &signature)
+            .unwrap();
+        get_network.assert_hits(1);
+    }
+
+    #[tokio::test]
+    async fn bad_seq_is_retried_with_the_current_sequence() {
+        let server = MockServer::start();
+        let config = Args {
+            network: network::Args {
+                rpc_url: Some(server.url("/")),
+                network_passphrase: Some(PASSPHRASE.to_string()),
+                ..Default::default()
+            },
+            source_account: SECRET.to_string(),
+            ..Default::default()
+        };
+        let key = config.key_pair().unwrap();
+        let source = Uint256(key.verifying_key().to_bytes());
+        let tx = |seq| bump_tx(&source, seq);
+        let envelope = |seq| {
+            signer::sign_tx(&key, &tx(seq), PASSPHRASE)
+                .unwrap()
+                .to_xdr_base64(Limits::none())
+                .unwrap()
+        };
+        let result = |result| {
+            TransactionResult {
+                fee_charged: 100,
+                result,
+                ext: TransactionResultExt::V0,
+            }
+            .to_xdr_base64(Limits::none())
+            .unwrap()
+        };
+        let account = LedgerEntryData::Account(AccountEntry {
+            account_id: AccountId(PublicKey::PublicKeyTypeEd25519(source.clone())),
+            balance: 10_000_000,
+            seq_num: SequenceNumber(41),
+            num_sub_entries: 0,
+            inflation_dest: None,
+            flags: 0,
+            home_domain: String32::default(),
+            thresholds: Thresholds([1, 0, 0, 0]),
+            signers: VecM::default(),
+            ext: AccountEntryExt::V0,
+        });
+
+        let stale = server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .json_body_partial(r#"{ "method": "sendTransaction" }"#)
+                .body_contains(envelope(7));
+            then.status(200).json_body(rpc_response(json!({
+                "status": "ERROR",
+                "hash": "00".repeat(32),
+                "errorResultXdr": result(TransactionResultResult::TxBadSeq),
+                "latestLedger": 10,
+                "latestLedgerCloseTime": "0"
+            })));
+        });
+        let get_account = server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .json_body_partial(r#"{ "method": "getLedgerEntries" }"#);
+            then.status(200).json_body(rpc_response(json!({
+                "entries": [{
+                    "key": "",
+                    "xdr": account.to_xdr_base64(Limits::none()).unwrap(),
+                    "lastModifiedLedgerSeq": 10
+                }],
+                "latestLedger": 10
+            })));
+        });
+        let fresh = server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .json_body_partial(r#"{ "method": "sendTransaction" }"#)
+                .body_contains(envelope(42));
+            then.status(200).json_body(rpc_response(json!({
+                "status": "PENDING",
+                "hash": "00".repeat(32),
+                "latestLedger": 10,
+                "latestLedgerCloseTime": "0"
+            })));
+        });
+        let get_transaction = server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .json_body_partial(r#"{ "method": "getTransaction" }"#);
+            then.status(200).json_body(rpc_response(json!({
+                "status": "SUCCESS",
+                "resultXdr": result(TransactionResultResult::TxSuccess(VecM::default())),
+                "latestLedger": 11
+            })));
+        });
+
+        let network = config.get_network().await.unwrap();
+        let client = network.rpc_client().unwrap();
+        assert!(config
+            .sign_and_send(&client, &network, tx(7), false, None)
+            .await
+            .is_err());
+        config
+            .sign_and_send(&client, &network, tx(7), true, None)
+            .await
+            .unwrap();
+        stale.assert_hits(2);
+        get_account.assert();
+        fresh.assert();
+        get_transaction.assert();
+    }
+
+    #[tokio::test]
+    async fn fee_above_max_fee_is_not_submitted() {
+        let server = MockServer::start();
+        let config = Args {
+            network: network::Args {
+                rpc_url: Some(server.url("/")),
+                network_passphrase: Some(PASSPHRASE.to_string()),
+                ..Default::default()
+            },
+            source_account: SECRET.to_string(),
+            ..Default::default()
+        };
+        let source = Uint256(config.key_pair().unwrap().verifying_key().to_bytes());
+        let send = server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .json_body_partial(r#"{ "method": "sendTransaction" }"#);
+            then.status(200).json_body(rpc_response(json!({
+                "status": "PENDING",
+                "hash": "00".repeat(32),
+                "latestLedger": 10,
+                "latestLedgerCloseTime": "0"
+            })));
+        });
+        let get_transaction = server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .json_body_partial(r#"{ "method": "getTransaction" }"#);
+            then.status(200).json_body(rpc_response(json!({
+                "status": "SUCCESS",
+                "resultXdr": TransactionResult {
+                    fee_charged: 100,
+                    result: TransactionResultResult::TxSuccess(VecM::default()),
+                    ext: TransactionResultExt::V0,
+                }
+                .to_xdr_base64(Limits::none())
+                .unwrap(),
+                "latestLedger": 11
+            })));
+        });
+        let network = config.get_network().await.unwrap();
+        let client = network.rpc_client().unwrap();
+        let max_fee = |max_fee| global::Args {
+            max_fee: Some(max_fee),
+            ..Default::default()
+        };
+
+        let res = config
+            .sign_and_send(
+                &client,
+                &network,
+                bump_tx(&source, 1),
+                false,
+                Some(&max_fee(99)),
+            )
+            .await;
+        assert!(
+            matches!(
+                res,
+                Err(Error::FeeAboveMax {
+                    fee: 100,
+                    max_fee: 99
+                })
+            ),
+            "{res:?}"
+        );
+        send.assert_hits(0);
+
+        config
+            .sign_and_send(
+                &client,
+                &network,
+                bump_tx(&source, 1),
+                false,
+                Some(&max_fee(100)),
+            )
+            .await
+            .unwrap();
+        send.assert();
+        get_transaction.assert();
+    }
+}
@@ -1,6 +1,10 @@
 use clap::arg;
 use serde::{Deserialize, Serialize};
-use std::{io::Write, str::FromStr};
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 use stellar_strkey::ed25519::{PrivateKey, PublicKey};
 
 use crate::utils;
@@ -21,6 +25,10 @@ pub enum Error {
     Ed25519(#[from] ed25519_dalek::SignatureError),
     #[error("Invalid address {0}")]
     InvalidAddress(String),
+    #[error("reading secret key file {0:?}: {1}")]
+    CannotReadSecretFile(PathBuf, std::io::Error),
+    #[error("permissions {mode:04o} for {path:?} are too open, other users can read the secret key. Restrict them with `chmod 600`, or pass --insecure")]
+    SecretFileTooOpen { path: PathBuf, mode: u32 },
 }
 
 #[derive(Debug, clap::Args, Clone)]
@@ -28,16 +36,27 @@ pub enum Error {
 pub struct Args {
     /// Add using secret_key
     /// Can provide with SOROBAN_SECRET_KEY
-    #[arg(long, conflicts_with = "seed_phrase")]
+    #[arg(long, conflicts_with_all = ["seed_phrase", "secret_file"])]
     pub secret_key: bool,
     /// Add using 12 word seed phrase to generate secret_key
-    #[arg(long, conflicts_with = "secret_key")]
+    #[arg(long, conflicts_with_all = ["secret_key", "secret_file"])]
     pub seed_phrase: bool,
+    /// Add using the secret key (S...) in a file. On Unix the file must not be readable by other
+    /// users, like an ssh private key
+    #[arg(long)]
+    pub secret_file: Option<PathBuf>,
+    /// Read the --secret-file even if other users can read it
+    #[arg(long, requires = "secret_file")]
+    pub insecure: bool,
 }
 
 impl Args {
     pub fn read_secret(&self) -> Result<Secret, Error> {
-        if let Ok(secret_key) = std::env::var("SOROBAN_SECRET_KEY") {
+        if let Some(path) = &self.secret_file {
+            Ok(Secret::SecretKey {
+                secret_key: read_secret_file(path, self.insecure)?,
+            })
+        } else if let Ok(secret_key) = std::env::var("SOROBAN_SECRET_KEY") {
             Ok(Secret::SecretKey { secret_key })
         } else if self.secret_key {
             println!("Type a secret key: ");
@@ -140,6 +159,32 @@ impl Secret {
     }
 }
 
+/// The secret key in the file at `path`, refusing a file other users can access unless `insecure`
+fn read_secret_file(path: &Path, insecure: bool) -> Result<String, Error> {
+    #[cfg(unix)]
+    if !insecure {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(path)
+            .map_err(|e| Error::CannotReadSecretFile(path.to_path_buf(), e))?
+            .permissions()
+            .mode()
+            & 0o777;
+        if mode & 0o077 != 0 {
+            return Err(Error::SecretFileTooOpen {
+                path: path.to_path_buf(),
+                mode,
+            });
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = insecure;
+    let secret_key = std::fs::read_to_string(path)
+        .map_err(|e| Error::CannotReadSecretFile(path.to_path_buf(), e))?;
+    Ok(PrivateKey::from_string(secret_key.trim())
+        .map_err(|_| Error::InvalidSecretKey)?
+        .to_string())
+}
+
 fn read_password() -> Result<String, Error> {
     std::io::stdout().flush().map_err(|_| Error::PasswordRead)?;
     rpassword::read_password().map_err(|_| Error::PasswordRead)
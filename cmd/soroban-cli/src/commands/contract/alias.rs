@@ -0,0 +1,34 @@
+pub mod add;
+pub mod ls;
+pub mod rm;
+
+#[derive(Debug, clap::Subcommand)]
+pub enum Cmd {
+    /// Save a contract id under an alias for the selected network
+    Add(add::Cmd),
+    /// List saved contract aliases and the ids they resolve to on each network
+    Ls(ls::Cmd),
+    /// Remove a contract alias for the selected network
+    Rm(rm::Cmd),
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Add(#[from] add::Error),
+    #[error(transparent)]
+    Ls(#[from] ls::Error),
+    #[error(transparent)]
+    Rm(#[from] rm::Error),
+}
+
+impl Cmd {
+    pub async fn run(&self) -> Result<(), Error> {
+        match &self {
+            Cmd::Add(cmd) => cmd.run().await?,
+            Cmd::Ls(cmd) => cmd.run()?,
+            Cmd::Rm(cmd) => cmd.run().await?,
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,50 @@
+use clap::command;
+
+use crate::commands::{config::locator, contract::deploy::wasm::alias_validator, network};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Locator(#[from] locator::Error),
+    #[error(transparent)]
+    Network(#[from] network::Error),
+    #[error("cannot parse contract ID {contract_id}: {error}")]
+    CannotParseContractId {
+        contract_id: String,
+        error: stellar_strkey::DecodeError,
+    },
+}
+
+#[derive(Debug, clap::Parser, Clone)]
+#[group(skip)]
+pub struct Cmd {
+    /// Name of the alias. Can be passed as `--id` to other contract commands
+    #[arg(value_parser = clap::builder::ValueParser::new(alias_validator))]
+    pub alias: String,
+    /// Contract ID the alias resolves to
+    pub contract_id: String,
+    #[command(flatten)]
+    pub network: network::Args,
+    #[command(flatten)]
+    pub config_locator: locator::Args,
+}
+
+impl Cmd {
+    pub async fn run(&self) -> Result<(), Error> {
+        let contract_id = stellar_strkey::Contract(
+            soroban_spec_tools::utils::contract_id_from_str(&self.contract_id).map_err(
+                |error| Error::CannotParseContractId {
+                    contract_id: self.contract_id.clone(),
+                    error,
+                },
+            )?,
+        );
+        let network = self.network.get(&self.config_locator).await?;
+        self.config_locator.save_contract_id(
+            &network.network_passphrase,
+            &contract_id.to_string(),
+            &self.alias,
+        )?;
+        Ok(())
+    }
+}
@@ -0,0 +1,29 @@
+use clap::command;
+
+use crate::commands::config::locator;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Locator(#[from] locator::Error),
+}
+
+#[derive(Debug, clap::Parser, Clone)]
+#[group(skip)]
+pub struct Cmd {
+    #[command(flatten)]
+    pub config_locator: locator::Args,
+}
+
+impl Cmd {
+    pub fn run(&self) -> Result<(), Error> {
+        for (alias, data) in self.config_locator.list_contract_aliases()? {
+            let mut ids = data.ids.into_iter().collect::<Vec<_>>();
+            ids.sort();
+            for (network_passphrase, contract_id) in ids {
+                println!("{alias}: {contract_id} ({network_passphrase})");
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,43 @@
+use clap::command;
+
+use crate::commands::{config::locator, network};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Locator(#[from] locator::Error),
+    #[error(transparent)]
+    Network(#[from] network::Error),
+    #[error("no contract alias {alias:?} found for network {network_passphrase:?}")]
+    NotFound {
+        alias: String,
+        network_passphrase: String,
+    },
+}
+
+#[derive(Debug, clap::Parser, Clone)]
+#[group(skip)]
+pub struct Cmd {
+    /// Name of the alias to remove
+    pub alias: String,
+    #[command(flatten)]
+    pub network: network::Args,
+    #[command(flatten)]
+    pub config_locator: locator::Args,
+}
+
+impl Cmd {
+    pub async fn run(&self) -> Result<(), Error> {
+        let network = self.network.get(&self.config_locator).await?;
+        if !self
+            .config_locator
+            .remove_contract_id(&network.network_passphrase, &self.alias)?
+        {
+            return Err(Error::NotFound {
+                alias: self.alias.clone(),
+                network_passphrase: network.network_passphrase,
+            });
+        }
+        Ok(())
+    }
+}
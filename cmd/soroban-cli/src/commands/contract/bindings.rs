@@ -1,4 +1,5 @@
 pub mod json;
+pub mod python;
 pub mod rust;
 pub mod typescript;
 
@@ -7,6 +8,9 @@ pub enum Cmd {
     /// Generate Json Bindings
     Json(json::Cmd),
 
+    /// Generate a Python module with a client and dataclasses
+    Python(python::Cmd),
+
     /// Generate Rust bindings
     Rust(rust::Cmd),
 
@@ -19,6 +23,9 @@ pub enum Error {
     #[error(transparent)]
     Json(#[from] json::Error),
 
+    #[error(transparent)]
+    Python(#[from] python::Error),
+
     #[error(transparent)]
     Rust(#[from] rust::Error),
 
@@ -30,6 +37,7 @@ impl Cmd {
     pub async fn run(&self) -> Result<(), Error> {
         match &self {
             Cmd::Json(json) => json.run()?,
+            Cmd::Python(python) => python.run()?,
             Cmd::Rust(rust) => rust.run()?,
             Cmd::Typescript(ts) => ts.run().await?,
         }
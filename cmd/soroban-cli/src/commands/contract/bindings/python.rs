@@ -0,0 +1,28 @@
+use std::fmt::Debug;
+
+use clap::{command, Parser};
+
+use crate::wasm;
+
+#[derive(Parser, Debug, Clone)]
+#[group(skip)]
+pub struct Cmd {
+    #[command(flatten)]
+    wasm: wasm::Args,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("generate python from file: {0}")]
+    GeneratePythonFromFile(soroban_spec_python::GenerateFromFileError),
+}
+
+impl Cmd {
+    pub fn run(&self) -> Result<(), Error> {
+        let wasm_path_str = self.wasm.wasm.to_string_lossy();
+        let python = soroban_spec_python::generate_from_file(&wasm_path_str, None)
+            .map_err(Error::GeneratePythonFromFile)?;
+        print!("{python}");
+        Ok(())
+    }
+}
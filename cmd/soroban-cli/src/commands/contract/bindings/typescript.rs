@@ -89,10 +89,10 @@ impl NetworkRunnable for Cmd {
             let wasm: wasm::Args = wasm.into();
             wasm.parse()?.spec
         } else {
-            let network = config.map_or_else(
-                || self.network.get(&self.locator).map_err(Error::from),
-                |c| c.get_network().map_err(Error::from),
-            )?;
+            let network = match config {
+                Some(c) => c.get_network().await?,
+                None => self.network.get(&self.locator).await?,
+            };
 
             let contract_id = self
                 .locator
@@ -128,6 +128,7 @@ impl NetworkRunnable for Cmd {
         } = self
             .network
             .get(&self.locator)
+            .await
             .ok()
             .unwrap_or_else(Network::futurenet);
         let absolute_path = self.output_dir.canonicalize()?;
@@ -0,0 +1,94 @@
+use std::path::PathBuf;
+
+use clap::{arg, command, Parser};
+use heck::ToKebabCase;
+
+use crate::commands::{config::locator, global, network};
+
+use super::info::shared;
+
+#[derive(Parser, Debug, Clone)]
+#[command(group(
+    clap::ArgGroup::new("source")
+        .required(true)
+        .args(&["wasm", "contract_id"]),
+))]
+#[group(skip)]
+pub struct Cmd {
+    /// Local Wasm file to read the spec from
+    #[arg(long)]
+    pub wasm: Option<PathBuf>,
+    /// Contract ID to fetch the spec of
+    #[arg(long = "id", env = "STELLAR_CONTRACT_ID")]
+    pub contract_id: Option<String>,
+    /// Words typed so far after `contract invoke ... --`, the last one being completed. Empty
+    /// or missing to list every function
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+    pub words: Vec<String>,
+    #[command(flatten)]
+    pub locator: locator::Args,
+    #[command(flatten)]
+    pub network: network::Args,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Shared(#[from] shared::Error),
+    #[error(transparent)]
+    Spec(#[from] soroban_spec_tools::Error),
+}
+
+impl Cmd {
+    pub async fn run(&self, global_args: &global::Args) -> Result<(), Error> {
+        for candidate in self.candidates(global_args).await? {
+            println!("{candidate}");
+        }
+        Ok(())
+    }
+
+    /// Function names while the first word is typed, then the `--arg-name` flags of that
+    /// function that aren't used yet, each starting with the last word
+    pub async fn candidates(&self, global_args: &global::Args) -> Result<Vec<String>, Error> {
+        let spec = shared::Args {
+            wasm: self.wasm.clone(),
+            contract_id: self.contract_id.clone(),
+            output: shared::Output::default(),
+            locator: self.locator.clone(),
+            network: self.network.clone(),
+        }
+        .spec(global_args)
+        .await?;
+        let (partial, typed) = self
+            .words
+            .split_last()
+            .map_or(("", &[][..]), |(last, rest)| (last.as_str(), rest));
+        let mut functions = spec.find_functions()?;
+        let mut candidates = match typed {
+            [] => functions
+                .map(|function| function.name.to_utf8_string_lossy())
+                .collect::<Vec<_>>(),
+            [typed_function, used @ ..] => functions
+                .find(|function| {
+                    let name = function.name.to_utf8_string_lossy();
+                    *typed_function == name || *typed_function == name.to_kebab_case()
+                })
+                .map(|function| {
+                    function
+                        .inputs
+                        .iter()
+                        .map(|input| format!("--{}", input.name.to_utf8_string_lossy()))
+                        .filter(|flag| {
+                            !used
+                                .iter()
+                                .any(|word| word == flag || word.starts_with(&format!("{flag}=")))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+        };
+        candidates.retain(|candidate| candidate.starts_with(partial));
+        candidates.sort();
+        Ok(candidates)
+    }
+}
@@ -18,7 +18,9 @@ use crate::{
         txn_result::{TxnEnvelopeResult, TxnResult},
         NetworkRunnable,
     },
-    rpc::{Client, Error as SorobanRpcError},
+    print::Print,
+    rpc::Error as SorobanRpcError,
+    rpc_ext::{self, ClientExt},
     utils::{contract_id_hash_from_asset, parsing::parse_asset},
 };
 
@@ -31,7 +33,9 @@ pub enum Error {
     #[error("error parsing int: {0}")]
     ParseIntError(#[from] ParseIntError),
     #[error(transparent)]
-    Client(#[from] SorobanRpcError),
+    Client(SorobanRpcError),
+    #[error(transparent)]
+    RpcError(#[from] crate::rpc_ext::RpcError),
     #[error("internal conversion error: {0}")]
     TryFromSliceError(#[from] TryFromSliceError),
     #[error("xdr processing error: {0}")]
@@ -44,6 +48,14 @@ pub enum Error {
     Data(#[from] data::Error),
     #[error(transparent)]
     Network(#[from] network::Error),
+    #[error(transparent)]
+    RpcExt(#[from] rpc_ext::Error),
+}
+
+impl From<SorobanRpcError> for Error {
+    fn from(e: SorobanRpcError) -> Self {
+        crate::rpc_ext::RpcError::from_rpc(e).map_or_else(Error::Client, Error::RpcError)
+    }
 }
 
 impl From<Infallible> for Error {
@@ -66,13 +78,17 @@ pub struct Cmd {
 }
 
 impl Cmd {
-    pub async fn run(&self) -> Result<(), Error> {
-        let res = self.run_against_rpc_server(None, None).await?.to_envelope();
+    pub async fn run(&self, global_args: &global::Args) -> Result<(), Error> {
+        let print = Print::new(Some(global_args));
+        let res = self
+            .run_against_rpc_server(Some(global_args), None)
+            .await?
+            .to_envelope();
         match res {
-            TxnEnvelopeResult::TxnEnvelope(tx) => println!("{}", tx.to_xdr_base64(Limits::none())?),
-            TxnEnvelopeResult::Res(contract) => {
-                println!("{contract}");
+            TxnEnvelopeResult::TxnEnvelope(tx) => {
+                print.result("xdr", tx.to_xdr_base64(Limits::none())?);
             }
+            TxnEnvelopeResult::Res(contract) => print.result("contract_id", contract.to_string()),
         }
         Ok(())
     }
@@ -92,16 +108,15 @@ impl NetworkRunnable for Cmd {
         // Parse asset
         let asset = parse_asset(&self.asset)?;
 
-        let network = config.get_network()?;
-        let client = Client::new(&network.rpc_url)?;
+        let network = config.get_network().await?;
+        let client = network.rpc_client()?;
         client
-            .verify_network_passphrase(Some(&network.network_passphrase))
+            .verify_network(&network.network_passphrase, args)
             .await?;
-        let key = config.key_pair()?;
+        let key = config.public_key().await?;
 
         // Get the account sequence number
-        let public_strkey =
-            stellar_strkey::ed25519::PublicKey(key.verifying_key().to_bytes()).to_string();
+        let public_strkey = key.to_string();
         // TODO: use symbols for the method names (both here and in serve)
         let account_details = client.get_account(&public_strkey).await?;
         let sequence: i64 = account_details.seq_num.into();
@@ -123,8 +138,9 @@ impl NetworkRunnable for Cmd {
         if self.fee.sim_only {
             return Ok(TxnResult::Txn(txn));
         }
-        let get_txn_resp = client
-            .send_transaction_polling(&self.config.sign_with_local_key(txn).await?)
+        let get_txn_resp = self
+            .config
+            .sign_and_send(&client, &network, txn, !self.fee.no_seq_retry, args)
             .await?
             .try_into()?;
         if args.map_or(true, |a| !a.no_cache) {
@@ -141,7 +157,7 @@ fn build_wrap_token_tx(
     sequence: i64,
     fee: u32,
     _network_passphrase: &str,
-    key: &ed25519_dalek::SigningKey,
+    key: &stellar_strkey::ed25519::PublicKey,
 ) -> Result<Transaction, Error> {
     let contract = ScAddress::Contract(contract_id.clone());
     let mut read_write = vec![
@@ -180,7 +196,7 @@ fn build_wrap_token_tx(
     };
 
     Ok(Transaction {
-        source_account: MuxedAccount::Ed25519(Uint256(key.verifying_key().to_bytes())),
+        source_account: MuxedAccount::Ed25519(Uint256(key.0)),
         fee,
         seq_num: SequenceNumber(sequence),
         cond: Preconditions::None,
@@ -24,8 +24,10 @@ use crate::commands::{
 };
 use crate::{
     commands::{config, contract::install, HEADING_RPC},
-    rpc::{self, Client},
-    utils, wasm,
+    print::Print,
+    rpc,
+    rpc_ext::{self, Client, ClientExt},
+    wasm,
 };
 
 #[derive(Parser, Debug, Clone)]
@@ -83,15 +85,19 @@ pub enum Error {
         contract_id: String,
         error: stellar_strkey::DecodeError,
     },
-    #[error("cannot parse WASM hash {wasm_hash}: {error}")]
-    CannotParseWasmHash {
+    #[error("cannot parse WASM hash {wasm_hash}, expected 32 bytes of hex")]
+    CannotParseWasmHash { wasm_hash: String },
+    #[error("cannot find WASM with hash {wasm_hash} on the network, install it first: {error}")]
+    WasmNotInstalled {
         wasm_hash: String,
-        error: stellar_strkey::DecodeError,
+        error: rpc::Error,
     },
     #[error("Must provide either --wasm or --wash-hash")]
     WasmNotProvided,
     #[error(transparent)]
-    Rpc(#[from] rpc::Error),
+    Rpc(rpc::Error),
+    #[error(transparent)]
+    RpcError(#[from] crate::rpc_ext::RpcError),
     #[error(transparent)]
     Config(#[from] config::Error),
     #[error(transparent)]
@@ -112,15 +118,29 @@ pub enum Error {
     InvalidAliasFormat { alias: String },
     #[error(transparent)]
     Locator(#[from] locator::Error),
+    #[error(transparent)]
+    RpcExt(#[from] rpc_ext::Error),
+}
+
+impl From<rpc::Error> for Error {
+    fn from(e: rpc::Error) -> Self {
+        crate::rpc_ext::RpcError::from_rpc(e).map_or_else(Error::Rpc, Error::RpcError)
+    }
 }
 
 impl Cmd {
-    pub async fn run(&self) -> Result<(), Error> {
-        let res = self.run_against_rpc_server(None, None).await?.to_envelope();
+    pub async fn run(&self, global_args: &global::Args) -> Result<(), Error> {
+        let print = Print::new(Some(global_args));
+        let res = self
+            .run_against_rpc_server(Some(global_args), None)
+            .await?
+            .to_envelope();
         match res {
-            TxnEnvelopeResult::TxnEnvelope(tx) => println!("{}", tx.to_xdr_base64(Limits::none())?),
+            TxnEnvelopeResult::TxnEnvelope(tx) => {
+                print.result("xdr", tx.to_xdr_base64(Limits::none())?);
+            }
             TxnEnvelopeResult::Res(contract) => {
-                let network = self.config.get_network()?;
+                let network = self.config.get_network().await?;
 
                 if let Some(alias) = self.alias.clone() {
                     self.config.locator.save_contract_id(
@@ -130,14 +150,24 @@ impl Cmd {
                     )?;
                 }
 
-                println!("{contract}");
+                print.result("contract_id", contract.to_string());
             }
         }
         Ok(())
     }
 }
 
-fn alias_validator(alias: &str) -> Result<String, Error> {
+pub(crate) fn parse_wasm_hash(wasm_hash: &str) -> Result<Hash, Error> {
+    hex::decode(wasm_hash)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(Hash)
+        .ok_or_else(|| Error::CannotParseWasmHash {
+            wasm_hash: wasm_hash.to_string(),
+        })
+}
+
+pub(crate) fn alias_validator(alias: &str) -> Result<String, Error> {
     let regex = Regex::new(r"^[a-zA-Z0-9_-]{1,30}$").unwrap();
 
     if regex.is_match(alias) {
@@ -169,6 +199,7 @@ impl NetworkRunnable for Cmd {
                     config: config.clone(),
                     fee: self.fee.clone(),
                     ignore_checks: self.ignore_checks,
+                    verify: false,
                 }
                 .run_against_rpc_server(global_args, Some(config))
                 .await?
@@ -183,13 +214,8 @@ impl NetworkRunnable for Cmd {
                 .to_string()
         };
 
-        let wasm_hash = Hash(utils::contract_id_from_str(&wasm_hash).map_err(|e| {
-            Error::CannotParseWasmHash {
-                wasm_hash: wasm_hash.clone(),
-                error: e,
-            }
-        })?);
-        let network = config.get_network()?;
+        let wasm_hash = parse_wasm_hash(&wasm_hash)?;
+        let network = config.get_network().await?;
         let salt: [u8; 32] = match &self.salt {
             Some(h) => soroban_spec_tools::utils::padded_hex_from_str(h, 32)
                 .map_err(|_| Error::CannotParseSalt { salt: h.clone() })?
@@ -198,15 +224,24 @@ impl NetworkRunnable for Cmd {
             None => rand::thread_rng().gen::<[u8; 32]>(),
         };
 
-        let client = Client::new(&network.rpc_url)?;
+        let client = network.rpc_client()?;
         client
-            .verify_network_passphrase(Some(&network.network_passphrase))
+            .verify_network(&network.network_passphrase, global_args)
             .await?;
-        let key = config.key_pair()?;
+        if self.wasm.is_none() && !self.ignore_checks {
+            // Nothing gets installed when deploying from a hash, so make sure it already was
+            client
+                .get_remote_wasm_from_hash(wasm_hash.clone())
+                .await
+                .map_err(|error| Error::WasmNotInstalled {
+                    wasm_hash: wasm_hash.to_string(),
+                    error,
+                })?;
+        }
+        let key = config.public_key().await?;
 
         // Get the account sequence number
-        let public_strkey =
-            stellar_strkey::ed25519::PublicKey(key.verifying_key().to_bytes()).to_string();
+        let public_strkey = key.to_string();
 
         let account_details = client.get_account(&public_strkey).await?;
         let sequence: i64 = account_details.seq_num.into();
@@ -227,8 +262,8 @@ impl NetworkRunnable for Cmd {
         if self.fee.sim_only {
             return Ok(TxnResult::Txn(txn));
         }
-        let get_txn_resp = client
-            .send_transaction_polling(&config.sign_with_local_key(txn).await?)
+        let get_txn_resp = config
+            .sign_and_send(&client, &network, txn, !self.fee.no_seq_retry, global_args)
             .await?
             .try_into()?;
         if global_args.map_or(true, |a| !a.no_cache) {
@@ -246,11 +281,9 @@ fn build_create_contract_tx(
     fee: u32,
     network_passphrase: &str,
     salt: [u8; 32],
-    key: &ed25519_dalek::SigningKey,
+    key: &stellar_strkey::ed25519::PublicKey,
 ) -> Result<(Transaction, Hash), Error> {
-    let source_account = AccountId(PublicKey::PublicKeyTypeEd25519(
-        key.verifying_key().to_bytes().into(),
-    ));
+    let source_account = AccountId(PublicKey::PublicKeyTypeEd25519(key.0.into()));
 
     let contract_id_preimage = ContractIdPreimage::Address(ContractIdPreimageFromAddress {
         address: ScAddress::Account(source_account),
@@ -269,7 +302,7 @@ fn build_create_contract_tx(
         }),
     };
     let tx = Transaction {
-        source_account: MuxedAccount::Ed25519(Uint256(key.verifying_key().to_bytes())),
+        source_account: MuxedAccount::Ed25519(Uint256(key.0)),
         fee,
         seq_num: SequenceNumber(sequence),
         cond: Preconditions::None,
@@ -297,8 +330,14 @@ mod tests {
             1,
             "Public Global Stellar Network ; September 2015",
             [0u8; 32],
-            &utils::parse_secret_key("SBFGFF27Y64ZUGFAIG5AMJGQODZZKV2YQKAVUUN4HNE24XZXD2OEUVUP")
-                .unwrap(),
+            &stellar_strkey::ed25519::PublicKey(
+                crate::utils::parse_secret_key(
+                    "SBFGFF27Y64ZUGFAIG5AMJGQODZZKV2YQKAVUUN4HNE24XZXD2OEUVUP",
+                )
+                .unwrap()
+                .verifying_key()
+                .to_bytes(),
+            ),
         );
 
         assert!(result.is_ok());
@@ -333,4 +372,13 @@ mod tests {
             assert!(result.is_err());
         }
     }
+
+    #[test]
+    fn test_parse_wasm_hash() {
+        let hash = "f55ff16f66f43360266b95db6f8fec01d76031054306ae4a4b380598f6cfd114";
+        assert_eq!(parse_wasm_hash(hash).unwrap().to_string(), hash);
+        assert!(parse_wasm_hash("f55ff16f").is_err());
+        assert!(parse_wasm_hash(&format!("{hash}00")).is_err());
+        assert!(parse_wasm_hash("not hex").is_err());
+    }
 }
@@ -0,0 +1,66 @@
+use std::ffi::OsString;
+
+use clap::{arg, command, Parser};
+
+use crate::commands::{config, global};
+use crate::rpc;
+
+use super::invoke;
+
+#[derive(Parser, Debug, Clone)]
+#[group(skip)]
+pub struct Cmd {
+    /// Contract ID or alias of the deployed version to compare against
+    #[arg(long)]
+    pub old: String,
+    /// Contract ID or alias of the deployed new version, e.g. a copy of the old one deployed from
+    /// the new Wasm
+    #[arg(long)]
+    pub new: String,
+    /// Function name, then arguments for that function as `--arg-name value`, as for `contract
+    /// invoke`
+    #[arg(last = true, id = "CONTRACT_FN_AND_ARGS")]
+    pub slop: Vec<OsString>,
+    #[command(flatten)]
+    pub config: config::Args,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Config(#[from] config::Error),
+    #[error(transparent)]
+    Invoke(#[from] invoke::Error),
+}
+
+impl Cmd {
+    pub async fn run(&self, global_args: &global::Args) -> Result<(), Error> {
+        let old = self.simulate(&self.old, global_args).await?;
+        let new = self.simulate(&self.new, global_args).await?;
+
+        print_diff("cpu_insns", old.cost.cpu_insns, new.cost.cpu_insns);
+        print_diff("mem_bytes", old.cost.mem_bytes, new.cost.mem_bytes);
+        print_diff("resource_fee", old.min_resource_fee, new.min_resource_fee);
+        Ok(())
+    }
+
+    async fn simulate(
+        &self,
+        contract_id: &str,
+        global_args: &global::Args,
+    ) -> Result<rpc::SimulateTransactionResponse, Error> {
+        Ok(invoke::Cmd {
+            contract_id: contract_id.to_string(),
+            slop: self.slop.clone(),
+            config: self.config.clone(),
+            ..Default::default()
+        }
+        .simulate(global_args)
+        .await?)
+    }
+}
+
+fn print_diff(name: &str, old: u64, new: u64) {
+    let delta = i128::from(new) - i128::from(old);
+    println!("{name}: {old} -> {new} ({delta:+})");
+}
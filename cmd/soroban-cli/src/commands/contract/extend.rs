@@ -1,6 +1,7 @@
-use std::{fmt::Debug, path::Path, str::FromStr};
+use std::{collections::HashMap, fmt::Debug, path::Path, str::FromStr};
 
 use clap::{command, Parser};
+use sha2::{Digest, Sha256};
 use soroban_env_host::xdr::{
     Error as XdrError, ExtendFootprintTtlOp, ExtensionPoint, LedgerEntry, LedgerEntryChange,
     LedgerEntryData, LedgerFootprint, Limits, Memo, MuxedAccount, Operation, OperationBody,
@@ -15,8 +16,8 @@ use crate::{
         txn_result::{TxnEnvelopeResult, TxnResult},
         NetworkRunnable,
     },
-    key,
-    rpc::{self, Client},
+    key, rpc,
+    rpc_ext::{self, ClientExt},
     wasm, Pwd,
 };
 
@@ -72,10 +73,14 @@ pub enum Error {
     Xdr(#[from] XdrError),
     #[error("Ledger entry not found")]
     LedgerEntryNotFound,
+    #[error("ledger entry {0} was not extended")]
+    EntryNotExtended(String),
     #[error("missing operation result")]
     MissingOperationResult,
     #[error(transparent)]
-    Rpc(#[from] rpc::Error),
+    Rpc(rpc::Error),
+    #[error(transparent)]
+    RpcError(#[from] crate::rpc_ext::RpcError),
     #[error(transparent)]
     Wasm(#[from] wasm::Error),
     #[error(transparent)]
@@ -86,19 +91,49 @@ pub enum Error {
     Network(#[from] network::Error),
     #[error(transparent)]
     Locator(#[from] locator::Error),
+    #[error(transparent)]
+    RpcExt(#[from] rpc_ext::Error),
+}
+
+impl From<rpc::Error> for Error {
+    fn from(e: rpc::Error) -> Self {
+        crate::rpc_ext::RpcError::from_rpc(e).map_or_else(Error::Rpc, Error::RpcError)
+    }
 }
 
 impl Cmd {
     #[allow(clippy::too_many_lines)]
-    pub async fn run(&self) -> Result<(), Error> {
-        let res = self.run_against_rpc_server(None, None).await?.to_envelope();
+    pub async fn run(&self, global_args: &global::Args) -> Result<(), Error> {
+        let res = self
+            .run_against_rpc_server(Some(global_args), None)
+            .await?
+            .to_envelope();
         match res {
             TxnEnvelopeResult::TxnEnvelope(tx) => println!("{}", tx.to_xdr_base64(Limits::none())?),
-            TxnEnvelopeResult::Res(ttl_ledger) => {
-                if self.ttl_ledger_only {
-                    println!("{ttl_ledger}");
-                } else {
-                    println!("New ttl ledger: {ttl_ledger}");
+            TxnEnvelopeResult::Res(ttl_ledgers) => {
+                if self.key.key_file.is_some() {
+                    for (key, ttl_ledger) in self
+                        .key
+                        .file_keys()?
+                        .unwrap_or_default()
+                        .iter()
+                        .zip(ttl_ledgers)
+                    {
+                        if self.ttl_ledger_only {
+                            println!("{ttl_ledger}");
+                        } else {
+                            println!(
+                                "New ttl ledger: {ttl_ledger} ({})",
+                                key.to_xdr_base64(Limits::none())?
+                            );
+                        }
+                    }
+                } else if let Some(ttl_ledger) = ttl_ledgers.first() {
+                    if self.ttl_ledger_only {
+                        println!("{ttl_ledger}");
+                    } else {
+                        println!("New ttl ledger: {ttl_ledger}");
+                    }
                 }
             }
         }
@@ -120,34 +155,39 @@ impl Cmd {
 #[async_trait::async_trait]
 impl NetworkRunnable for Cmd {
     type Error = Error;
-    type Result = TxnResult<u32>;
+    type Result = TxnResult<Vec<u32>>;
 
     async fn run_against_rpc_server(
         &self,
         args: Option<&global::Args>,
         config: Option<&config::Args>,
-    ) -> Result<TxnResult<u32>, Self::Error> {
+    ) -> Result<TxnResult<Vec<u32>>, Self::Error> {
         let config = config.unwrap_or(&self.config);
-        let network = config.get_network()?;
+        let network = &config.get_network().await?;
         tracing::trace!(?network);
-        let contract = config.locator.resolve_contract_id(
-            self.key.contract_id.as_ref().unwrap(),
-            &network.network_passphrase,
-        )?;
-        let keys = self.key.parse_keys(contract)?;
-        let network = &config.get_network()?;
-        let client = Client::new(&network.rpc_url)?;
-        let key = config.key_pair()?;
+        let keys = if let Some(keys) = self.key.file_keys()? {
+            keys
+        } else {
+            let contract = config.locator.resolve_contract_id(
+                self.key.contract_id.as_ref().unwrap(),
+                &network.network_passphrase,
+            )?;
+            self.key.parse_keys(contract)?
+        };
+        let client = network.rpc_client()?;
+        client
+            .verify_network_if_strict(&network.network_passphrase, args)
+            .await?;
+        let key = config.public_key().await?;
         let extend_to = self.ledgers_to_extend();
 
         // Get the account sequence number
-        let public_strkey =
-            stellar_strkey::ed25519::PublicKey(key.verifying_key().to_bytes()).to_string();
+        let public_strkey = key.to_string();
         let account_details = client.get_account(&public_strkey).await?;
         let sequence: i64 = account_details.seq_num.into();
 
         let tx = Transaction {
-            source_account: MuxedAccount::Ed25519(Uint256(key.verifying_key().to_bytes())),
+            source_account: MuxedAccount::Ed25519(Uint256(key.0)),
             fee: self.fee.fee,
             seq_num: SequenceNumber(sequence + 1),
             cond: Preconditions::None,
@@ -182,8 +222,8 @@ impl NetworkRunnable for Cmd {
             .await?
             .transaction()
             .clone();
-        let res = client
-            .send_transaction_polling(&config.sign_with_local_key(tx).await?)
+        let res = config
+            .sign_and_send(&client, network, tx, !self.fee.no_seq_retry, args)
             .await?;
         if args.map_or(true, |a| !a.no_cache) {
             data::write(res.clone().try_into()?, &network.rpc_uri()?)?;
@@ -204,33 +244,49 @@ impl NetworkRunnable for Cmd {
             return Err(Error::LedgerEntryNotFound);
         };
 
-        // Simply check if there is exactly one entry here. We only support extending a single
-        // entry via this command (which we should fix separately, but).
         if operations.len() == 0 {
             return Err(Error::LedgerEntryNotFound);
         }
 
-        if operations[0].changes.is_empty() {
-            let entry = client.get_full_ledger_entries(&keys).await?;
-            let extension = entry.entries[0].live_until_ledger_seq;
-            if entry.latest_ledger + i64::from(extend_to) < i64::from(extension) {
-                return Ok(TxnResult::Res(extension));
-            }
-        }
-
-        match (&operations[0].changes[0], &operations[0].changes[1]) {
-            (
-                LedgerEntryChange::State(_),
+        // TTL entries are keyed by the hash of the ledger key they belong to.
+        let extended = operations[0]
+            .changes
+            .iter()
+            .filter_map(|change| match change {
                 LedgerEntryChange::Updated(LedgerEntry {
                     data:
                         LedgerEntryData::Ttl(TtlEntry {
+                            key_hash,
                             live_until_ledger_seq,
-                            ..
                         }),
                     ..
-                }),
-            ) => Ok(TxnResult::Res(*live_until_ledger_seq)),
-            _ => Err(Error::LedgerEntryNotFound),
+                }) => Some((key_hash.0, *live_until_ledger_seq)),
+                _ => None,
+            })
+            .collect::<HashMap<_, _>>();
+        let mut ttl_ledgers = Vec::with_capacity(keys.len());
+        for key in &keys {
+            let key_hash: [u8; 32] = Sha256::digest(key.to_xdr(Limits::none())?).into();
+            if let Some(live_until_ledger_seq) = extended.get(&key_hash) {
+                ttl_ledgers.push(*live_until_ledger_seq);
+                continue;
+            }
+            // Entries that already live long enough are left untouched, so there is no change
+            // to read the TTL from.
+            let entry = client
+                .get_full_ledger_entries(std::slice::from_ref(key))
+                .await?;
+            let extension = entry
+                .entries
+                .first()
+                .ok_or(Error::LedgerEntryNotFound)?
+                .live_until_ledger_seq;
+            if entry.latest_ledger + i64::from(extend_to) < i64::from(extension) {
+                ttl_ledgers.push(extension);
+            } else {
+                return Err(Error::EntryNotExtended(key.to_xdr_base64(Limits::none())?));
+            }
         }
+        Ok(TxnResult::Res(ttl_ledgers))
     }
 }
@@ -23,7 +23,8 @@ use super::super::config::{self, locator};
 use crate::commands::network::{self, Network};
 use crate::commands::{global, NetworkRunnable};
 use crate::{
-    rpc::{self, Client},
+    get_spec, rpc,
+    rpc_ext::{self, ClientExt},
     Pwd,
 };
 
@@ -61,7 +62,9 @@ impl Pwd for Cmd {
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     #[error(transparent)]
-    Rpc(#[from] rpc::Error),
+    Rpc(rpc::Error),
+    #[error(transparent)]
+    RpcError(#[from] crate::rpc_ext::RpcError),
     #[error(transparent)]
     Config(#[from] config::Error),
     #[error(transparent)]
@@ -86,6 +89,16 @@ pub enum Error {
     Network(#[from] network::Error),
     #[error("cannot create contract directory for {0:?}")]
     CannotCreateContractDir(PathBuf),
+    #[error(transparent)]
+    GetSpec(#[from] get_spec::Error),
+    #[error(transparent)]
+    RpcExt(#[from] rpc_ext::Error),
+}
+
+impl From<rpc::Error> for Error {
+    fn from(e: rpc::Error) -> Self {
+        crate::rpc_ext::RpcError::from_rpc(e).map_or_else(Error::Rpc, Error::RpcError)
+    }
 }
 
 impl From<Infallible> for Error {
@@ -95,8 +108,8 @@ impl From<Infallible> for Error {
 }
 
 impl Cmd {
-    pub async fn run(&self) -> Result<(), Error> {
-        let bytes = self.get_bytes().await?;
+    pub async fn run(&self, global_args: &global::Args) -> Result<(), Error> {
+        let bytes = self.get_bytes(Some(global_args)).await?;
         if let Some(out_file) = &self.out_file {
             if let Some(parent) = out_file.parent() {
                 if !parent.exists() {
@@ -115,16 +128,15 @@ impl Cmd {
         }
     }
 
-    pub async fn get_bytes(&self) -> Result<Vec<u8>, Error> {
-        self.run_against_rpc_server(None, None).await
+    pub async fn get_bytes(&self, global_args: Option<&global::Args>) -> Result<Vec<u8>, Error> {
+        self.run_against_rpc_server(global_args, None).await
     }
 
-    pub fn network(&self) -> Result<Network, Error> {
-        Ok(self.network.get(&self.locator)?)
+    pub async fn network(&self) -> Result<Network, Error> {
+        Ok(self.network.get(&self.locator).await?)
     }
 
-    fn contract_id(&self) -> Result<[u8; 32], Error> {
-        let network = self.network()?;
+    fn contract_id(&self, network: &Network) -> Result<[u8; 32], Error> {
         Ok(self
             .locator
             .resolve_contract_id(&self.contract_id, &network.network_passphrase)?
@@ -138,17 +150,24 @@ impl NetworkRunnable for Cmd {
     type Result = Vec<u8>;
     async fn run_against_rpc_server(
         &self,
-        _args: Option<&global::Args>,
+        args: Option<&global::Args>,
         config: Option<&config::Args>,
     ) -> Result<Vec<u8>, Error> {
-        let network = config.map_or_else(|| self.network(), |c| Ok(c.get_network()?))?;
+        let network = match config {
+            Some(c) => c.get_network().await?,
+            None => self.network().await?,
+        };
         tracing::trace!(?network);
-        let contract_id = self.contract_id()?;
-        let client = Client::new(&network.rpc_url)?;
+        let contract_id = self.contract_id(&network)?;
+        let client = network.rpc_client()?;
         client
-            .verify_network_passphrase(Some(&network.network_passphrase))
+            .verify_network(&network.network_passphrase, args)
             .await?;
-        Ok(client.get_remote_wasm(&contract_id).await?)
+        let no_cache = args.map_or(false, |a| a.no_cache);
+        Ok(
+            get_spec::get_remote_wasm(&client, &network.network_passphrase, &contract_id, no_cache)
+                .await?,
+        )
     }
 }
 pub fn get_contract_wasm_from_storage(
@@ -18,9 +18,9 @@ pub enum Error {
 }
 
 impl Cmd {
-    pub fn run(&self) -> Result<(), Error> {
+    pub async fn run(&self) -> Result<(), Error> {
         match &self {
-            Cmd::Asset(asset) => asset.run()?,
+            Cmd::Asset(asset) => asset.run().await?,
             Cmd::Wasm(wasm) => wasm.run()?,
         }
         Ok(())
@@ -25,14 +25,14 @@ pub enum Error {
     Xdr(#[from] soroban_env_host::xdr::Error),
 }
 impl Cmd {
-    pub fn run(&self) -> Result<(), Error> {
-        println!("{}", self.contract_address()?);
+    pub async fn run(&self) -> Result<(), Error> {
+        println!("{}", self.contract_address().await?);
         Ok(())
     }
 
-    pub fn contract_address(&self) -> Result<stellar_strkey::Contract, Error> {
+    pub async fn contract_address(&self) -> Result<stellar_strkey::Contract, Error> {
         let asset = parse_asset(&self.asset)?;
-        let network = self.config.get_network()?;
+        let network = self.config.get_network().await?;
         let contract_id = contract_id_hash_from_asset(&asset, &network.network_passphrase)?;
         Ok(stellar_strkey::Contract(contract_id.0))
     }
@@ -5,7 +5,10 @@ use soroban_env_host::xdr::{
     HashIdPreimageContractId, Limits, PublicKey, ScAddress, Uint256, WriteXdr,
 };
 
-use crate::commands::config;
+use crate::commands::{
+    config::{self, locator},
+    network, HEADING_RPC,
+};
 
 #[derive(Parser, Debug, Clone)]
 #[group(skip)]
@@ -13,9 +16,33 @@ pub struct Cmd {
     /// ID of the Soroban contract
     #[arg(long)]
     pub salt: String,
-
+    /// Account that will deploy the contract, e.g. `G...`. Takes precedence over
+    /// `--source-account`, and unlike it needs no secret key
+    #[arg(long)]
+    pub deployer: Option<String>,
+    /// Account that will deploy the contract. Alias `source`. Can be an identity (--source alice), a secret key (--source SC36…), or a seed phrase (--source "kite urban…"). Defaults to the `source_account` of the project's stellar.toml, or else the identity set with `keys use`.
+    #[arg(long, visible_alias = "source", env = "STELLAR_ACCOUNT")]
+    pub source_account: Option<String>,
+    /// If using a seed phrase, which hierarchical deterministic path to use, e.g. `m/44'/148'/{hd_path}`. Example: `--hd-path 1`. Default: `0`
+    #[arg(long)]
+    pub hd_path: Option<usize>,
+    /// Network passphrase the contract will be deployed with
+    #[arg(
+        long = "network-passphrase",
+        required_unless_present = "network",
+        env = "STELLAR_NETWORK_PASSPHRASE",
+        help_heading = HEADING_RPC,
+    )]
+    pub network_passphrase: Option<String>,
+    /// Name of network to use from config
+    #[arg(long, env = "STELLAR_NETWORK", help_heading = HEADING_RPC)]
+    pub network: Option<String>,
+    /// Unused, the contract id is computed without contacting the network. Kept so existing
+    /// invocations keep working
+    #[arg(long = "rpc-url", hide = true)]
+    pub rpc_url: Option<String>,
     #[command(flatten)]
-    pub config: config::Args,
+    pub locator: locator::Args,
 }
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
@@ -24,30 +51,57 @@ pub enum Error {
     #[error(transparent)]
     ConfigError(#[from] crate::commands::config::Error),
     #[error(transparent)]
+    Locator(#[from] locator::Error),
+    #[error(transparent)]
     Xdr(#[from] xdr::Error),
     #[error("cannot parse salt {0}")]
     CannotParseSalt(String),
+    #[error("cannot parse deployer {0}, expected a `G...` account")]
+    CannotParseDeployer(String),
 }
 impl Cmd {
     pub fn run(&self) -> Result<(), Error> {
+        println!("{}", self.contract_address()?);
+        Ok(())
+    }
+
+    pub fn contract_address(&self) -> Result<stellar_strkey::Contract, Error> {
         let salt: [u8; 32] = soroban_spec_tools::utils::padded_hex_from_str(&self.salt, 32)
             .map_err(|_| Error::CannotParseSalt(self.salt.clone()))?
             .try_into()
             .map_err(|_| Error::CannotParseSalt(self.salt.clone()))?;
-        let contract_id_preimage =
-            contract_preimage(&self.config.key_pair()?.verifying_key(), salt);
-        let contract_id = get_contract_id(
-            contract_id_preimage.clone(),
-            &self.config.get_network()?.network_passphrase,
-        )?;
-        let strkey_contract_id = stellar_strkey::Contract(contract_id.0).to_string();
-        println!("{strkey_contract_id}");
-        Ok(())
+        let contract_id_preimage = contract_preimage(&self.deployer()?, salt);
+        let contract_id = get_contract_id(contract_id_preimage, &self.network_passphrase()?)?;
+        Ok(stellar_strkey::Contract(contract_id.0))
+    }
+
+    fn deployer(&self) -> Result<[u8; 32], Error> {
+        if let Some(deployer) = &self.deployer {
+            return Ok(stellar_strkey::ed25519::PublicKey::from_string(deployer)
+                .map_err(|_| Error::CannotParseDeployer(deployer.clone()))?
+                .0);
+        }
+        let config = config::Args {
+            network: network::Args::default(),
+            source_account: self.source_account.clone().unwrap_or_default(),
+            hd_path: self.hd_path,
+            sign_with_ledger: false,
+            locator: self.locator.clone(),
+        };
+        Ok(config.key_pair()?.verifying_key().to_bytes())
+    }
+
+    fn network_passphrase(&self) -> Result<String, Error> {
+        if let Some(passphrase) = &self.network_passphrase {
+            return Ok(passphrase.clone());
+        }
+        let name = self.network.as_deref().unwrap_or_default();
+        Ok(self.locator.read_network(name)?.network_passphrase)
     }
 }
 
-pub fn contract_preimage(key: &ed25519_dalek::VerifyingKey, salt: [u8; 32]) -> ContractIdPreimage {
-    let source_account = AccountId(PublicKey::PublicKeyTypeEd25519(key.to_bytes().into()));
+pub fn contract_preimage(account: &[u8; 32], salt: [u8; 32]) -> ContractIdPreimage {
+    let source_account = AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(*account)));
     ContractIdPreimage::Address(ContractIdPreimageFromAddress {
         address: ScAddress::Account(source_account),
         salt: Uint256(salt),
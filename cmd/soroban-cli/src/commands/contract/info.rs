@@ -0,0 +1,38 @@
+pub mod functions;
+pub mod meta;
+pub mod shared;
+pub mod types;
+
+use crate::commands::global;
+
+#[derive(Debug, clap::Subcommand)]
+pub enum Cmd {
+    /// List the functions of a contract with their arguments and return types
+    Functions(functions::Cmd),
+    /// List the meta entries of a contract, such as those set with `contractmeta!` and the
+    /// versions of the SDK and rustc it was built with
+    Meta(meta::Cmd),
+    /// List the user-defined types (structs, unions, enums and errors) of a contract
+    Types(types::Cmd),
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Functions(#[from] functions::Error),
+    #[error(transparent)]
+    Meta(#[from] meta::Error),
+    #[error(transparent)]
+    Types(#[from] types::Error),
+}
+
+impl Cmd {
+    pub async fn run(&self, global_args: &global::Args) -> Result<(), Error> {
+        match &self {
+            Cmd::Functions(functions) => functions.run(global_args).await?,
+            Cmd::Meta(meta) => meta.run(global_args).await?,
+            Cmd::Types(types) => types.run(global_args).await?,
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,82 @@
+use clap::{command, Parser};
+use serde_json::json;
+use soroban_spec_tools::Spec;
+
+use super::shared::{self, type_name, Output};
+use crate::commands::global;
+use crate::xdr::ScSpecFunctionV0;
+
+#[derive(Parser, Debug, Clone)]
+#[group(skip)]
+pub struct Cmd {
+    #[command(flatten)]
+    pub args: shared::Args,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Shared(#[from] shared::Error),
+    #[error(transparent)]
+    Spec(#[from] soroban_spec_tools::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+impl Cmd {
+    pub async fn run(&self, global_args: &global::Args) -> Result<(), Error> {
+        let spec = self.args.spec(global_args).await?;
+        let functions = spec.find_functions()?;
+        match self.args.output {
+            Output::Text => {
+                for function in functions {
+                    println!("{}", signature(&spec, function));
+                }
+            }
+            Output::Json => {
+                let functions = functions
+                    .map(|function| to_json(&spec, function))
+                    .collect::<Vec<_>>();
+                println!("{}", serde_json::to_string_pretty(&functions)?);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `name(arg: Type, ..) -> Type`, or without the arrow when nothing is returned
+fn signature(spec: &Spec, function: &ScSpecFunctionV0) -> String {
+    let inputs = function
+        .inputs
+        .iter()
+        .map(|input| {
+            format!(
+                "{}: {}",
+                input.name.to_utf8_string_lossy(),
+                type_name(spec, &input.type_)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    let name = function.name.to_utf8_string_lossy();
+    match function.outputs.first() {
+        Some(output) => format!("{name}({inputs}) -> {}", type_name(spec, output)),
+        None => format!("{name}({inputs})"),
+    }
+}
+
+fn to_json(spec: &Spec, function: &ScSpecFunctionV0) -> serde_json::Value {
+    json!({
+        "name": function.name.to_utf8_string_lossy(),
+        "doc": function.doc.to_utf8_string_lossy(),
+        "inputs": function
+            .inputs
+            .iter()
+            .map(|input| json!({
+                "name": input.name.to_utf8_string_lossy(),
+                "type": type_name(spec, &input.type_),
+            }))
+            .collect::<Vec<_>>(),
+        "output": function.outputs.first().map(|output| type_name(spec, output)),
+    })
+}
@@ -0,0 +1,57 @@
+use clap::{command, Parser};
+use serde_json::{Map, Value};
+use soroban_spec_tools::contract;
+
+use super::shared::{self, Output};
+use crate::commands::global;
+use crate::xdr::{ScMetaEntry, ScMetaV0};
+
+#[derive(Parser, Debug, Clone)]
+#[group(skip)]
+pub struct Cmd {
+    #[command(flatten)]
+    pub args: shared::Args,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Shared(#[from] shared::Error),
+    #[error(transparent)]
+    Spec(#[from] contract::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+impl Cmd {
+    pub async fn run(&self, global_args: &global::Args) -> Result<(), Error> {
+        let wasm = self.args.wasm(global_args).await?;
+        let meta = entries(&contract::Spec::new(&wasm)?.meta);
+        match self.args.output {
+            Output::Text => {
+                for (key, val) in meta {
+                    println!("{key}: {val}");
+                }
+            }
+            Output::Json => {
+                let meta = meta
+                    .into_iter()
+                    .map(|(key, val)| (key, Value::String(val)))
+                    .collect::<Map<_, _>>();
+                println!("{}", serde_json::to_string_pretty(&meta)?);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The key and value of each entry of the `contractmetav0` section, in order. Besides the
+/// contract's own `contractmeta!` entries, the SDK adds its version as `rssdkver` and the version
+/// of rustc that built the contract as `rsver`.
+pub fn entries(meta: &[ScMetaEntry]) -> Vec<(String, String)> {
+    meta.iter()
+        .map(|ScMetaEntry::ScMetaV0(ScMetaV0 { key, val })| {
+            (key.to_utf8_string_lossy(), val.to_utf8_string_lossy())
+        })
+        .collect()
+}
@@ -0,0 +1,117 @@
+use std::path::PathBuf;
+
+use clap::arg;
+use soroban_spec_tools::Spec;
+
+use crate::commands::{config::locator, global, network};
+use crate::get_spec::{self, get_remote_contract_spec, get_remote_wasm};
+use crate::xdr::{ScSpecTypeDef, ScSpecTypeUdt};
+use crate::{rpc, wasm};
+
+#[derive(Debug, clap::Args, Clone)]
+#[command(group(
+    clap::ArgGroup::new("source")
+        .required(true)
+        .args(&["wasm", "contract_id"]),
+))]
+#[group(skip)]
+pub struct Args {
+    /// Local Wasm file of the contract
+    #[arg(long)]
+    pub wasm: Option<PathBuf>,
+    /// Contract ID to fetch the spec or Wasm of
+    #[arg(long = "id", env = "STELLAR_CONTRACT_ID")]
+    pub contract_id: Option<String>,
+    /// Format of the output
+    #[arg(long, value_enum, default_value_t)]
+    pub output: Output,
+    #[command(flatten)]
+    pub locator: locator::Args,
+    #[command(flatten)]
+    pub network: network::Args,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq, clap::ValueEnum)]
+pub enum Output {
+    /// One entry per line, in Rust-like notation
+    #[default]
+    Text,
+    /// Entries as JSON
+    Json,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Wasm(#[from] wasm::Error),
+    #[error(transparent)]
+    Network(#[from] network::Error),
+    #[error(transparent)]
+    Locator(#[from] locator::Error),
+    #[error(transparent)]
+    GetSpec(#[from] get_spec::Error),
+    #[error(transparent)]
+    Rpc(rpc::Error),
+    #[error(transparent)]
+    RpcError(#[from] crate::rpc_ext::RpcError),
+}
+
+impl From<rpc::Error> for Error {
+    fn from(e: rpc::Error) -> Self {
+        crate::rpc_ext::RpcError::from_rpc(e).map_or_else(Error::Rpc, Error::RpcError)
+    }
+}
+
+impl Args {
+    /// The spec of the `--wasm` file, or else of the contract deployed at `--id`
+    pub async fn spec(&self, global_args: &global::Args) -> Result<Spec, Error> {
+        if let Some(wasm) = &self.wasm {
+            let spec = wasm::Args { wasm: wasm.clone() }.parse()?.spec;
+            return Ok(Spec(Some(spec)));
+        }
+        let network = self.network.get(&self.locator).await?;
+        let contract_id = self.locator.resolve_contract_id(
+            self.contract_id.as_deref().unwrap_or_default(),
+            &network.network_passphrase,
+        )?;
+        let spec = get_remote_contract_spec(
+            &contract_id.0,
+            &self.locator,
+            &self.network,
+            Some(global_args),
+            None,
+        )
+        .await?;
+        Ok(Spec(Some(spec)))
+    }
+
+    /// The bytes of the `--wasm` file, or else the Wasm of the contract deployed at `--id`
+    pub async fn wasm(&self, global_args: &global::Args) -> Result<Vec<u8>, Error> {
+        if let Some(wasm) = &self.wasm {
+            return Ok(wasm::Args { wasm: wasm.clone() }.read()?);
+        }
+        let network = self.network.get(&self.locator).await?;
+        let contract_id = self.locator.resolve_contract_id(
+            self.contract_id.as_deref().unwrap_or_default(),
+            &network.network_passphrase,
+        )?;
+        let client = network.rpc_client()?;
+        Ok(get_remote_wasm(
+            &client,
+            &network.network_passphrase,
+            &contract_id.0,
+            global_args.no_cache,
+        )
+        .await?)
+    }
+}
+
+/// The name of a type as shown in `invoke` help, falling back to the name of user-defined types
+/// that have no value notation, such as errors.
+pub fn type_name(spec: &Spec, type_: &ScSpecTypeDef) -> String {
+    spec.arg_value_name(type_, 0)
+        .unwrap_or_else(|| match type_ {
+            ScSpecTypeDef::Udt(ScSpecTypeUdt { name }) => name.to_utf8_string_lossy(),
+            _ => "Val".to_string(),
+        })
+}
@@ -0,0 +1,108 @@
+use clap::{command, Parser};
+use soroban_spec_json::types::Entry;
+use soroban_spec_tools::Spec;
+
+use super::shared::{self, type_name, Output};
+use crate::commands::global;
+use crate::xdr::{ScSpecEntry, ScSpecUdtUnionCaseV0};
+
+#[derive(Parser, Debug, Clone)]
+#[group(skip)]
+pub struct Cmd {
+    #[command(flatten)]
+    pub args: shared::Args,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Shared(#[from] shared::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+impl Cmd {
+    pub async fn run(&self, global_args: &global::Args) -> Result<(), Error> {
+        let spec = self.args.spec(global_args).await?;
+        let types = spec
+            .0
+            .iter()
+            .flatten()
+            .filter(|entry| !matches!(entry, ScSpecEntry::FunctionV0(_)));
+        match self.args.output {
+            Output::Text => {
+                for entry in types {
+                    println!("{}", definition(&spec, entry));
+                }
+            }
+            Output::Json => {
+                let types = types.map(Entry::from).collect::<Vec<_>>();
+                println!("{}", serde_json::to_string_pretty(&types)?);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A one line, Rust-like definition of a user-defined type
+fn definition(spec: &Spec, entry: &ScSpecEntry) -> String {
+    match entry {
+        ScSpecEntry::UdtStructV0(strukt) => {
+            let fields = strukt
+                .fields
+                .iter()
+                .map(|f| {
+                    format!(
+                        "{}: {}",
+                        f.name.to_utf8_string_lossy(),
+                        type_name(spec, &f.type_)
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!(
+                "struct {} {{ {fields} }}",
+                strukt.name.to_utf8_string_lossy()
+            )
+        }
+        ScSpecEntry::UdtUnionV0(union) => {
+            let cases = union
+                .cases
+                .iter()
+                .map(|case| match case {
+                    ScSpecUdtUnionCaseV0::VoidV0(v) => v.name.to_utf8_string_lossy(),
+                    ScSpecUdtUnionCaseV0::TupleV0(t) => {
+                        let types = t
+                            .type_
+                            .iter()
+                            .map(|t| type_name(spec, t))
+                            .collect::<Vec<_>>()
+                            .join(", ");
+                        format!("{}({types})", t.name.to_utf8_string_lossy())
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("union {} {{ {cases} }}", union.name.to_utf8_string_lossy())
+        }
+        ScSpecEntry::UdtEnumV0(enum_) => {
+            let cases = enum_
+                .cases
+                .iter()
+                .map(|c| format!("{} = {}", c.name.to_utf8_string_lossy(), c.value))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("enum {} {{ {cases} }}", enum_.name.to_utf8_string_lossy())
+        }
+        ScSpecEntry::UdtErrorEnumV0(error) => {
+            let cases = error
+                .cases
+                .iter()
+                .map(|c| format!("{} = {}", c.name.to_utf8_string_lossy(), c.value))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("error {} {{ {cases} }}", error.name.to_utf8_string_lossy())
+        }
+        ScSpecEntry::FunctionV0(function) => format!("fn {}", function.name.to_utf8_string_lossy()),
+    }
+}
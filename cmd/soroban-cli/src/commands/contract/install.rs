@@ -15,7 +15,9 @@ use crate::commands::network;
 use crate::commands::txn_result::{TxnEnvelopeResult, TxnResult};
 use crate::commands::{config::data, global, NetworkRunnable};
 use crate::key;
-use crate::rpc::{self, Client};
+use crate::print::Print;
+use crate::rpc;
+use crate::rpc_ext::{self, Client, ClientExt};
 use crate::{commands::config, utils, wasm};
 
 const CONTRACT_META_SDK_KEY: &str = "rssdkver";
@@ -33,6 +35,10 @@ pub struct Cmd {
     #[arg(long, short = 'i', default_value = "false")]
     /// Whether to ignore safety checks when deploying contracts
     pub ignore_checks: bool,
+    /// Fetch the installed Wasm back from the network and fail if it differs from the uploaded
+    /// one
+    #[arg(long, conflicts_with_all = ["build_only", "sim_only"])]
+    pub verify: bool,
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -46,7 +52,9 @@ pub enum Error {
     #[error("jsonrpc error: {0}")]
     JsonRpc(#[from] jsonrpsee_core::Error),
     #[error(transparent)]
-    Rpc(#[from] rpc::Error),
+    Rpc(rpc::Error),
+    #[error(transparent)]
+    RpcError(#[from] crate::rpc_ext::RpcError),
     #[error(transparent)]
     Config(#[from] config::Error),
     #[error(transparent)]
@@ -69,14 +77,33 @@ pub enum Error {
     Network(#[from] network::Error),
     #[error(transparent)]
     Data(#[from] data::Error),
+    #[error(transparent)]
+    RpcExt(#[from] rpc_ext::Error),
+    #[error("the Wasm installed on the network under hash {hash} does not match {wasm}")]
+    InstalledWasmMismatch {
+        hash: String,
+        wasm: std::path::PathBuf,
+    },
+}
+
+impl From<rpc::Error> for Error {
+    fn from(e: rpc::Error) -> Self {
+        crate::rpc_ext::RpcError::from_rpc(e).map_or_else(Error::Rpc, Error::RpcError)
+    }
 }
 
 impl Cmd {
-    pub async fn run(&self) -> Result<(), Error> {
-        let res = self.run_against_rpc_server(None, None).await?.to_envelope();
+    pub async fn run(&self, global_args: &global::Args) -> Result<(), Error> {
+        let print = Print::new(Some(global_args));
+        let res = self
+            .run_against_rpc_server(Some(global_args), None)
+            .await?
+            .to_envelope();
         match res {
-            TxnEnvelopeResult::TxnEnvelope(tx) => println!("{}", tx.to_xdr_base64(Limits::none())?),
-            TxnEnvelopeResult::Res(hash) => println!("{}", hex::encode(hash)),
+            TxnEnvelopeResult::TxnEnvelope(tx) => {
+                print.result("xdr", tx.to_xdr_base64(Limits::none())?);
+            }
+            TxnEnvelopeResult::Res(hash) => print.result("wasm_hash", hex::encode(hash)),
         };
         Ok(())
     }
@@ -93,10 +120,10 @@ impl NetworkRunnable for Cmd {
     ) -> Result<TxnResult<Hash>, Error> {
         let config = config.unwrap_or(&self.config);
         let contract = self.wasm.read()?;
-        let network = config.get_network()?;
-        let client = Client::new(&network.rpc_url)?;
+        let network = config.get_network().await?;
+        let client = network.rpc_client()?;
         client
-            .verify_network_passphrase(Some(&network.network_passphrase))
+            .verify_network(&network.network_passphrase, args)
             .await?;
         let wasm_spec = &self.wasm.parse().map_err(|e| Error::CannotParseWasm {
             wasm: self.wasm.wasm.clone(),
@@ -118,11 +145,10 @@ impl NetworkRunnable for Cmd {
                 tracing::warn!("the deployed smart contract {path} was built with Soroban Rust SDK v{rs_sdk_ver}, a release candidate version not intended for use with the Stellar Public Network", path = self.wasm.wasm.display());
             }
         }
-        let key = config.key_pair()?;
+        let key = config.public_key().await?;
 
         // Get the account sequence number
-        let public_strkey =
-            stellar_strkey::ed25519::PublicKey(key.verifying_key().to_bytes()).to_string();
+        let public_strkey = key.to_string();
         let account_details = client.get_account(&public_strkey).await?;
         let sequence: i64 = account_details.seq_num.into();
 
@@ -153,6 +179,7 @@ impl NetworkRunnable for Cmd {
                             // Skip reupload if this isn't V0 because V1 extension already
                             // exists.
                             if code.ext.ne(&ContractCodeEntryExt::V0) {
+                                self.verify_installed(&client, &hash, &contract).await?;
                                 return Ok(TxnResult::Res(hash));
                             }
                         }
@@ -170,8 +197,9 @@ impl NetworkRunnable for Cmd {
         if self.fee.sim_only {
             return Ok(TxnResult::Txn(txn));
         }
-        let txn_resp = client
-            .send_transaction_polling(&self.config.sign_with_local_key(txn).await?)
+        let txn_resp = self
+            .config
+            .sign_and_send(&client, &network, txn, !self.fee.no_seq_retry, args)
             .await?;
         if args.map_or(true, |a| !a.no_cache) {
             data::write(txn_resp.clone().try_into().unwrap(), &network.rpc_uri()?)?;
@@ -190,23 +218,49 @@ impl NetworkRunnable for Cmd {
                     key_xdr: None,
                     wasm: Some(self.wasm.wasm.clone()),
                     wasm_hash: None,
+                    key_file: None,
                     durability: super::Durability::Persistent,
                 },
                 config: config.clone(),
                 fee: self.fee.clone(),
                 ledgers_to_extend: None,
                 ttl_ledger_only: true,
+                auto: false,
             }
             .run_against_rpc_server(args, None)
             .await?;
         }
         if args.map_or(true, |a| !a.no_cache) {
-            data::write_spec(&hash.to_string(), &wasm_spec.spec)?;
+            data::write_spec(&data::data_local_dir()?, &hash.to_string(), &wasm_spec.spec)?;
         }
+        self.verify_installed(&client, &hash, &contract).await?;
         Ok(TxnResult::Res(hash))
     }
 }
 
+impl Cmd {
+    /// With `--verify`, check that the code installed under `hash` is `contract` byte for byte
+    async fn verify_installed(
+        &self,
+        client: &Client,
+        hash: &Hash,
+        contract: &[u8],
+    ) -> Result<(), Error> {
+        if !self.verify {
+            return Ok(());
+        }
+        let installed = client.get_remote_wasm_from_hash(hash.clone()).await?;
+        if installed != contract {
+            return Err(Error::InstalledWasmMismatch {
+                hash: hash.to_string(),
+                wasm: self.wasm.wasm.clone(),
+            });
+        }
+        eprintln!("Verified the Wasm installed under hash {hash}");
+        Ok(())
+    }
+}
+
 fn get_contract_meta_sdk_version(wasm_spec: &soroban_spec_tools::contract::Spec) -> Option<String> {
     let rs_sdk_version_option = if let Some(_meta) = &wasm_spec.meta_base64 {
         wasm_spec.meta.iter().find(|entry| match entry {
@@ -231,14 +285,12 @@ pub(crate) fn build_install_contract_code_tx(
     source_code: &[u8],
     sequence: i64,
     fee: u32,
-    key: &ed25519_dalek::SigningKey,
+    key: &stellar_strkey::ed25519::PublicKey,
 ) -> Result<(Transaction, Hash), XdrError> {
     let hash = utils::contract_hash(source_code)?;
 
     let op = Operation {
-        source_account: Some(MuxedAccount::Ed25519(Uint256(
-            key.verifying_key().to_bytes(),
-        ))),
+        source_account: Some(MuxedAccount::Ed25519(Uint256(key.0))),
         body: OperationBody::InvokeHostFunction(InvokeHostFunctionOp {
             host_function: HostFunction::UploadContractWasm(source_code.try_into()?),
             auth: VecM::default(),
@@ -246,7 +298,7 @@ pub(crate) fn build_install_contract_code_tx(
     };
 
     let tx = Transaction {
-        source_account: MuxedAccount::Ed25519(Uint256(key.verifying_key().to_bytes())),
+        source_account: MuxedAccount::Ed25519(Uint256(key.0)),
         fee,
         seq_num: SequenceNumber(sequence),
         cond: Preconditions::None,
@@ -268,8 +320,12 @@ mod tests {
             b"foo",
             300,
             1,
-            &utils::parse_secret_key("SBFGFF27Y64ZUGFAIG5AMJGQODZZKV2YQKAVUUN4HNE24XZXD2OEUVUP")
-                .unwrap(),
+            &stellar_strkey::ed25519::PublicKey(
+                utils::parse_secret_key("SBFGFF27Y64ZUGFAIG5AMJGQODZZKV2YQKAVUUN4HNE24XZXD2OEUVUP")
+                    .unwrap()
+                    .verifying_key()
+                    .to_bytes(),
+            ),
         );
 
         assert!(result.is_ok());
@@ -12,11 +12,15 @@ use heck::ToKebabCase;
 
 use soroban_env_host::{
     xdr::{
-        self, AccountEntry, AccountEntryExt, AccountId, Hash, HostFunction, InvokeContractArgs,
-        InvokeHostFunctionOp, LedgerEntryData, Limits, Memo, MuxedAccount, Operation,
-        OperationBody, Preconditions, PublicKey, ScAddress, ScSpecEntry, ScSpecFunctionV0,
-        ScSpecTypeDef, ScVal, ScVec, SequenceNumber, String32, StringM, Thresholds, Transaction,
-        TransactionExt, Uint256, VecM, WriteXdr,
+        self, AccountEntry, AccountEntryExt, AccountId, ContractDataEntry, ContractEvent,
+        ContractEventBody, ContractEventType, ContractEventV0, DiagnosticEvent, ExtensionPoint,
+        Hash, HostFunction, InvokeContractArgs, InvokeHostFunctionOp, LedgerEntryData, LedgerKey,
+        Limits, Memo, MuxedAccount, Operation, OperationBody, Preconditions, PublicKey, ReadXdr,
+        RestoreFootprintOp, ScAddress, ScError, ScSpecEntry, ScSpecFunctionV0, ScSpecTypeDef,
+        ScVal, ScVec, SequenceNumber, SorobanAddressCredentials, SorobanAuthorizedFunction,
+        SorobanAuthorizedInvocation, SorobanCredentials, SorobanTransactionData, String32, StringM,
+        Thresholds, Transaction, TransactionEnvelope, TransactionExt, TransactionV1Envelope,
+        Uint256, VecM, WriteXdr,
     },
     HostError,
 };
@@ -27,14 +31,19 @@ use super::super::{
     config::{self, locator},
     events,
 };
+use crate::commands::tx::simulate::{describe_address, describe_ledger_key};
 use crate::commands::txn_result::{TxnEnvelopeResult, TxnResult};
 use crate::commands::NetworkRunnable;
 use crate::get_spec::{self, get_remote_contract_spec};
 use crate::{
     commands::{config::data, global, network},
-    rpc, Pwd,
+    msgpack,
+    print::Print,
+    rpc,
+    rpc_ext::{self, ClientExt},
+    utils, Pwd,
 };
-use soroban_spec_tools::{contract, Spec};
+use soroban_spec_tools::{contract, BytesFormat, Spec};
 
 #[derive(Parser, Debug, Default, Clone)]
 #[allow(clippy::struct_excessive_bools)]
@@ -46,9 +55,42 @@ pub struct Cmd {
     // For testing only
     #[arg(skip)]
     pub wasm: Option<std::path::PathBuf>,
+    /// Spec of the contract, fetched from the network if not set
+    #[arg(skip)]
+    pub spec: Option<Vec<ScSpecEntry>>,
     /// View the result simulating and do not sign and submit transaction
     #[arg(long, env = "STELLAR_INVOKE_VIEW")]
     pub is_view: bool,
+    /// Restore any archived ledger entries the invocation needs before submitting it (default).
+    /// With `--build-only`, both transactions are written, as a JSON array of the restore's
+    /// envelope then the invocation's, to sign offline together
+    #[arg(long, overrides_with = "no_restore")]
+    pub restore: bool,
+    /// Do not restore archived ledger entries; fail and list them instead
+    #[arg(long, overrides_with = "restore")]
+    pub no_restore: bool,
+    /// Prefix hex-encoded `Bytes` and `BytesN` values in the output with `0x`
+    #[arg(long)]
+    pub hex_prefix: bool,
+    /// Format of the function's return value
+    #[arg(long, value_enum, default_value("json"))]
+    pub output: Output,
+    /// Base URL of the block explorer linked to from the `--json` output of sent invocations.
+    /// Defaults to stellar.expert on mainnet, testnet and futurenet
+    #[arg(long, env = "STELLAR_EXPLORER_URL")]
+    pub explorer_url: Option<String>,
+    /// Print the diagnostic events of the invocation, such as contract logs, to stderr as JSON,
+    /// one per line. Shown even when the invocation succeeds
+    #[arg(long)]
+    pub show_diagnostics: bool,
+    /// Print the authorizations the simulated invocation needs to stderr, as a tree of the
+    /// invocations each address must sign
+    #[arg(long)]
+    pub show_auth_tree: bool,
+    /// Print the ledger entries the simulated invocation writes to stderr, each with its current
+    /// value, or `new` if it doesn't exist yet
+    #[arg(long)]
+    pub show_changes: bool,
     /// Function name as subcommand, then arguments for that function as `--arg-name value`
     #[arg(last = true, id = "CONTRACT_FN_AND_ARGS")]
     pub slop: Vec<OsString>,
@@ -56,6 +98,24 @@ pub struct Cmd {
     pub config: config::Args,
     #[command(flatten)]
     pub fee: crate::fee::Args,
+    #[command(flatten)]
+    pub resources: crate::fee::ResourceArgs,
+    #[command(flatten)]
+    pub expiration: config::SignatureExpirationArgs,
+    #[command(flatten)]
+    pub contract_signers: config::ContractSignerArgs,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq, clap::ValueEnum)]
+pub enum Output {
+    /// JSON rendering of the return value, using the contract's spec
+    #[default]
+    Json,
+    /// Base64-encoded XDR of the return value's `ScVal`
+    Xdr,
+    /// Base64-encoded MessagePack of the return value, keeping integers that fit in 64 bits as
+    /// integers
+    Msgpack,
 }
 
 impl FromStr for Cmd {
@@ -80,6 +140,10 @@ pub enum Error {
         arg: String,
         error: soroban_spec_tools::Error,
     },
+    #[error("parsing XDR of argument {arg}: {error}")]
+    CannotParseXdrArg { arg: String, error: xdr::Error },
+    #[error("XDR of argument {arg} is not a value of its type {expected}")]
+    XdrArgTypeMismatch { arg: String, expected: String },
     #[error("cannot add contract to ledger entries: {0}")]
     CannotAddContractToLedgerEntries(xdr::Error),
     #[error(transparent)]
@@ -111,7 +175,9 @@ pub enum Error {
     #[error("error parsing int: {0}")]
     ParseIntError(#[from] ParseIntError),
     #[error(transparent)]
-    Rpc(#[from] rpc::Error),
+    Rpc(rpc::Error),
+    #[error(transparent)]
+    RpcError(#[from] crate::rpc_ext::RpcError),
     #[error("unexpected contract code data type: {0:?}")]
     UnexpectedContractCodeDataType(LedgerEntryData),
     #[error("missing operation result")]
@@ -130,8 +196,12 @@ pub enum Error {
     Clap(#[from] clap::Error),
     #[error(transparent)]
     Locator(#[from] locator::Error),
-    #[error("Contract Error\n{0}: {1}")]
-    ContractInvoke(String, String),
+    #[error("Error {code}: {name}{}", doc_line(.doc))]
+    ContractInvoke {
+        code: u32,
+        name: String,
+        doc: String,
+    },
     #[error(transparent)]
     StrKey(#[from] stellar_strkey::DecodeError),
     #[error(transparent)]
@@ -146,6 +216,20 @@ pub enum Error {
     Network(#[from] network::Error),
     #[error(transparent)]
     GetSpecError(#[from] get_spec::Error),
+    #[error(transparent)]
+    Fee(#[from] crate::fee::Error),
+    #[error("the invocation needs the following archived ledger entries restored, rerun without --no-restore to restore them:\n{}", .0.join("\n"))]
+    ArchivedEntries(Vec<String>),
+    #[error(transparent)]
+    RpcExt(#[from] rpc_ext::Error),
+    #[error(transparent)]
+    Msgpack(#[from] msgpack::Error),
+}
+
+impl From<rpc::Error> for Error {
+    fn from(e: rpc::Error) -> Self {
+        crate::rpc_ext::RpcError::from_rpc(e).map_or_else(Error::Rpc, Error::RpcError)
+    }
 }
 
 impl From<Infallible> for Error {
@@ -154,6 +238,80 @@ impl From<Infallible> for Error {
     }
 }
 
+/// Exit status of an invocation that failed with a contract error is this plus the error's code,
+/// up to 255
+const CONTRACT_ERROR_EXIT_CODE_BASE: u32 = 100;
+
+impl Error {
+    /// The process exit status for the error. A contract error exits with
+    /// [`CONTRACT_ERROR_EXIT_CODE_BASE`] plus its code, so scripts can tell contract errors
+    /// apart, any other error with 1.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::ContractInvoke { code, .. } => {
+                i32::try_from(code.saturating_add(CONTRACT_ERROR_EXIT_CODE_BASE).min(255))
+                    .unwrap_or(255)
+            }
+            _ => 1,
+        }
+    }
+}
+
+fn doc_line(doc: &str) -> String {
+    if doc.is_empty() {
+        String::new()
+    } else {
+        format!("\n{doc}")
+    }
+}
+
+/// Simulate and assemble `tx`. A simulation that failed with a contract error is reported as the
+/// case of the contract's error enum with that code
+async fn simulate_and_assemble(
+    client: &rpc_ext::Client,
+    tx: &Transaction,
+    spec: &Spec,
+) -> Result<rpc::Assembled, Error> {
+    match client.simulate_and_assemble_transaction(tx).await {
+        Err(error @ rpc::Error::TransactionSimulationFailed(_)) => {
+            // The error only carries the simulation's message, the error value is in its
+            // diagnostic events
+            let sim_res = client
+                .simulate_transaction_envelope(&TransactionEnvelope::Tx(TransactionV1Envelope {
+                    tx: tx.clone(),
+                    signatures: VecM::default(),
+                }))
+                .await?;
+            match contract_error_code(&sim_res.events()?)
+                .and_then(|code| spec.find_error_type(code).ok())
+            {
+                Some(case) => Err(Error::ContractInvoke {
+                    code: case.value,
+                    name: case.name.to_utf8_string_lossy(),
+                    doc: case.doc.to_utf8_string_lossy(),
+                }),
+                None => Err(error.into()),
+            }
+        }
+        res => Ok(res?),
+    }
+}
+
+/// The code of the last `ScError::Contract` in the topics of `events`, which is the contract
+/// error the invocation failed with
+fn contract_error_code(events: &[DiagnosticEvent]) -> Option<u32> {
+    events
+        .iter()
+        .rev()
+        .find_map(|DiagnosticEvent { event, .. }| {
+            let ContractEventBody::V0(ContractEventV0 { topics, .. }) = &event.body;
+            topics.iter().find_map(|topic| match topic {
+                ScVal::Error(ScError::Contract(code)) => Some(*code),
+                _ => None,
+            })
+        })
+}
+
 impl Cmd {
     fn is_view(&self) -> bool {
         self.is_view ||
@@ -163,6 +321,14 @@ impl Cmd {
             std::env::var("SYSTEM_TEST_VERBOSE_OUTPUT").as_deref() == Ok("true")
     }
 
+    fn bytes_format(&self) -> BytesFormat {
+        if self.hex_prefix {
+            BytesFormat::PrefixedHex
+        } else {
+            BytesFormat::Hex
+        }
+    }
+
     fn build_host_function_parameters(
         &self,
         contract_id: [u8; 32],
@@ -170,15 +336,7 @@ impl Cmd {
         config: &config::Args,
     ) -> Result<(String, Spec, InvokeContractArgs, Vec<SigningKey>), Error> {
         let spec = Spec(Some(spec_entries.to_vec()));
-        let mut cmd = clap::Command::new(self.contract_id.clone())
-            .no_binary_name(true)
-            .term_width(300)
-            .max_term_width(300);
-
-        for ScSpecFunctionV0 { name, .. } in spec.find_functions()? {
-            cmd = cmd.subcommand(build_custom_cmd(&name.to_utf8_string_lossy(), &spec)?);
-        }
-        cmd.build();
+        let mut cmd = build_contract_cmd(&self.contract_id, &spec)?;
         let long_help = cmd.render_long_help();
         let mut matches_ = cmd.get_matches_from(&self.slop);
         let Some((function, matches_)) = &matches_.remove_subcommand() else {
@@ -194,7 +352,21 @@ impl Cmd {
             .iter()
             .map(|i| {
                 let name = i.name.to_utf8_string()?;
-                if let Some(mut val) = matches_.get_raw(&name) {
+                if let Some(xdr) = matches_.get_one::<String>(&fmt_arg_xdr_name(&name)) {
+                    let val = ScVal::from_xdr_base64(xdr, Limits::none()).map_err(|error| {
+                        Error::CannotParseXdrArg {
+                            arg: name.clone(),
+                            error,
+                        }
+                    })?;
+                    if !is_value_of_type(&val, &i.type_) {
+                        return Err(Error::XdrArgTypeMismatch {
+                            arg: name,
+                            expected: spec.arg_value_name(&i.type_, 0).unwrap_or_default(),
+                        });
+                    }
+                    Ok(val)
+                } else if let Some(mut val) = matches_.get_raw(&name) {
                     let mut s = val.next().unwrap().to_string_lossy().to_string();
                     if matches!(i.type_, ScSpecTypeDef::Address) {
                         let cmd = crate::commands::keys::address::Cmd {
@@ -266,12 +438,67 @@ impl Cmd {
     }
 
     pub async fn run(&self, global_args: &global::Args) -> Result<(), Error> {
-        let res = self.invoke(global_args).await?.to_envelope();
-        match res {
-            TxnEnvelopeResult::TxnEnvelope(tx) => println!("{}", tx.to_xdr_base64(Limits::none())?),
-            TxnEnvelopeResult::Res(output) => {
-                println!("{output}");
+        let print = Print::new(Some(global_args));
+        let (res, sent_hash, restore_tx) =
+            self.invoke_and_send(Some(global_args), None, true).await?;
+        match res.to_envelope() {
+            TxnEnvelopeResult::TxnEnvelope(tx) => {
+                if let Some(restore_tx) = restore_tx {
+                    eprintln!("Archived ledger entries need restoring first, send the first transaction before the second");
+                    print.result("xdr", restore_bundle(restore_tx, tx)?);
+                } else {
+                    print.result("xdr", tx.to_xdr_base64(Limits::none())?);
+                }
+            }
+            TxnEnvelopeResult::Res(output) if print.json() => {
+                let value = if self.output == Output::Json {
+                    // Nest the JSON output as is, functions that return nothing have empty output
+                    serde_json::from_str(&output).unwrap_or(serde_json::Value::Null)
+                } else {
+                    serde_json::Value::String(output)
+                };
+                let explorer_url = match &self.explorer_url {
+                    Some(url) => Some(url.clone()),
+                    None => self
+                        .config
+                        .get_network()
+                        .await
+                        .ok()
+                        .and_then(|network| Some(network.explorer_url()?.to_string())),
+                };
+                println!("{}", json_result(value, sent_hash, explorer_url.as_deref()));
             }
+            TxnEnvelopeResult::Res(output) => print.result("result", output),
+        }
+        Ok(())
+    }
+
+    /// Submit a footprint restore built from a simulation's restore preamble
+    async fn restore(
+        &self,
+        client: &rpc_ext::Client,
+        config: &config::Args,
+        network: &network::Network,
+        global_args: Option<&global::Args>,
+        tx: Transaction,
+    ) -> Result<(), Error> {
+        let fee = tx.fee;
+        let keys: Vec<LedgerKey> = match &tx.ext {
+            TransactionExt::V1(data) => data.resources.footprint.read_write.to_vec(),
+            TransactionExt::V0 => vec![],
+        };
+        let res = config
+            .sign_and_send(client, network, tx, !self.fee.no_seq_retry, global_args)
+            .await?;
+        if global_args.map_or(true, |a| !a.no_cache) {
+            data::write(res.try_into()?, &network.rpc_uri()?)?;
+        }
+        eprintln!(
+            "Restored {} archived ledger entries for a fee of {fee} stroops:",
+            keys.len()
+        );
+        for key in keys {
+            eprintln!("  {}", key.to_xdr_base64(Limits::none())?);
         }
         Ok(())
     }
@@ -280,6 +507,39 @@ impl Cmd {
         self.run_against_rpc_server(Some(global_args), None).await
     }
 
+    /// Simulate the invocation from a placeholder account, without signing or sending it, for
+    /// its cost and resources
+    pub async fn simulate(
+        &self,
+        global_args: &global::Args,
+    ) -> Result<rpc::SimulateTransactionResponse, Error> {
+        let config = &self.config;
+        let network = config.get_network().await?;
+        let contract_id = config
+            .locator
+            .resolve_contract_id(&self.contract_id, &network.network_passphrase)?
+            .0;
+        let spec_entries = get_remote_contract_spec(
+            &contract_id,
+            &config.locator,
+            &config.network,
+            Some(global_args),
+            Some(config),
+        )
+        .await
+        .map_err(Error::from)?;
+        let (_, spec, host_function_params, _) =
+            self.build_host_function_parameters(contract_id, &spec_entries, config)?;
+        let AccountId(PublicKey::PublicKeyTypeEd25519(account_id)) =
+            default_account_entry().account_id;
+        let tx = build_invoke_contract_tx(host_function_params, 1, self.fee.fee, account_id)?;
+        let client = network.rpc_client()?;
+        Ok(simulate_and_assemble(&client, &tx, &spec)
+            .await?
+            .sim_response()
+            .clone())
+    }
+
     pub fn read_wasm(&self) -> Result<Option<Vec<u8>>, Error> {
         Ok(if let Some(wasm) = self.wasm.as_ref() {
             Some(fs::read(wasm).map_err(|e| Error::CannotReadContractFile(wasm.clone(), e))?)
@@ -307,8 +567,24 @@ impl NetworkRunnable for Cmd {
         global_args: Option<&global::Args>,
         config: Option<&config::Args>,
     ) -> Result<TxnResult<String>, Error> {
+        // Callers only get the invocation back, so there's nothing to bundle the restore into
+        Ok(self.invoke_and_send(global_args, config, false).await?.0)
+    }
+}
+
+impl Cmd {
+    /// Invoke the contract, and if the invocation was signed and sent rather than only
+    /// simulated, return the hash of its transaction with the result. With `--build-only` and
+    /// `bundle_restore`, also return the transaction restoring the archived entries the invocation
+    /// needs, if any, the invocation then taking the sequence number after it
+    async fn invoke_and_send(
+        &self,
+        global_args: Option<&global::Args>,
+        config: Option<&config::Args>,
+        bundle_restore: bool,
+    ) -> Result<(TxnResult<String>, Option<[u8; 32]>, Option<Transaction>), Error> {
         let config = config.unwrap_or(&self.config);
-        let network = config.get_network()?;
+        let network = config.get_network().await?;
         tracing::trace!(?network);
         let contract_id = self
             .config
@@ -320,32 +596,35 @@ impl NetworkRunnable for Cmd {
             // For testing wasm arg parsing
             let _ = self.build_host_function_parameters(contract_id, spec_entries, config)?;
         }
-        let client = rpc::Client::new(&network.rpc_url)?;
-        let account_details = if self.is_view {
-            default_account_entry()
+        let client = network.rpc_client()?;
+        // A view isn't sent, so it has no account, nor any use for the instruction warning
+        let (account_details, compute) = if self.is_view {
+            (default_account_entry(), None)
         } else {
             client
-                .verify_network_passphrase(Some(&network.network_passphrase))
+                .verify_network(&network.network_passphrase, global_args)
                 .await?;
-            let key = config.key_pair()?;
+            let key = config.public_key().await?;
 
-            // Get the account sequence number
-            let public_strkey =
-                stellar_strkey::ed25519::PublicKey(key.verifying_key().to_bytes()).to_string();
-            client.get_account(&public_strkey).await?
+            // Get the account sequence number, and the instruction limit in the same request
+            client.get_account_and_contract_compute(&key).await?
         };
         let sequence: i64 = account_details.seq_num.into();
         let AccountId(PublicKey::PublicKeyTypeEd25519(account_id)) = account_details.account_id;
 
-        let spec_entries = get_remote_contract_spec(
-            &contract_id,
-            &config.locator,
-            &config.network,
-            global_args,
-            Some(config),
-        )
-        .await
-        .map_err(Error::from)?;
+        let spec_entries = if let Some(spec) = &self.spec {
+            spec.clone()
+        } else {
+            get_remote_contract_spec(
+                &contract_id,
+                &config.locator,
+                &config.network,
+                global_args,
+                Some(config),
+            )
+            .await
+            .map_err(Error::from)?
+        };
 
         // Get the ledger footprint
         let (function, spec, host_function_params, signers) =
@@ -354,48 +633,298 @@ impl NetworkRunnable for Cmd {
             host_function_params.clone(),
             sequence + 1,
             self.fee.fee,
-            account_id,
+            account_id.clone(),
         )?;
-        if self.fee.build_only {
-            return Ok(TxnResult::Txn(tx));
+        // `--build-only` still simulates, so the unsigned transaction it prints carries the
+        // footprint and resources needed to sign and send it as is
+        let tx_only = self.fee.build_only || self.fee.sim_only;
+        let mut txn = simulate_and_assemble(&client, &tx, &spec).await?;
+        let mut restore_tx = None;
+        if let Some(preamble) = txn.sim_response().restore_preamble.clone() {
+            if !self.is_view() && (!tx_only || (self.fee.build_only && bundle_restore)) {
+                let transaction_data = SorobanTransactionData::from_xdr_base64(
+                    &preamble.transaction_data,
+                    Limits::none(),
+                )?;
+                let keys = &transaction_data.resources.footprint.read_write;
+                if self.no_restore {
+                    return Err(Error::ArchivedEntries(
+                        keys.iter()
+                            .map(|k| k.to_xdr_base64(Limits::none()))
+                            .collect::<Result<_, _>>()?,
+                    ));
+                }
+                let restore = build_restore_tx(
+                    transaction_data,
+                    preamble.min_resource_fee,
+                    sequence + 1,
+                    self.fee.fee,
+                    account_id.clone(),
+                )?;
+                if self.fee.build_only {
+                    // Signed and sent by the caller, before the invocation
+                    restore_tx = Some(restore);
+                } else {
+                    self.restore(&client, config, &network, global_args, restore)
+                        .await?;
+                    // The restore consumed a sequence number, maybe more than one if it was
+                    // resubmitted, so rebuild from the account's current one and resimulate
+                    let sequence: i64 = client
+                        .get_account(&stellar_strkey::ed25519::PublicKey(account_id.0).to_string())
+                        .await?
+                        .seq_num
+                        .into();
+                    let tx = build_invoke_contract_tx(
+                        host_function_params.clone(),
+                        sequence + 1,
+                        self.fee.fee,
+                        account_id,
+                    )?;
+                    txn = simulate_and_assemble(&client, &tx, &spec).await?;
+                }
+            }
         }
-        let txn = client.simulate_and_assemble_transaction(&tx).await?;
         let txn = self.fee.apply_to_assembled_txn(txn);
-        if self.fee.sim_only {
-            return Ok(TxnResult::Txn(txn.transaction().clone()));
-        }
         let sim_res = txn.sim_response();
+        self.resources
+            .warn_on_instructions(compute.as_ref(), sim_res);
+        let mut tx = self
+            .resources
+            .apply(&client, txn.transaction().clone(), sim_res)
+            .await?;
+        if let Some(restore) = &restore_tx {
+            // Simulation assumes the archived entries are restored, so the footprint is already
+            // right and only the sequence number changes, to the one after the restore's
+            tx.seq_num = SequenceNumber(restore.seq_num.0 + 1);
+        }
+        if self.show_auth_tree {
+            for line in auth_tree_lines(&tx)? {
+                eprintln!("{line}");
+            }
+        }
+        if self.show_changes {
+            for line in change_lines(&client, &tx).await? {
+                eprintln!("{line}");
+            }
+        }
+        if tx_only {
+            return Ok((TxnResult::Txn(tx), None, restore_tx));
+        }
         if global_args.map_or(true, |a| !a.no_cache) {
             data::write(sim_res.clone().into(), &network.rpc_uri()?)?;
         }
-        let (return_value, events) = if self.is_view() {
+        let (return_value, events, sent_hash) = if self.is_view() {
             // log_auth_cost_and_footprint(Some(&sim_res.transaction_data()?.resources));
-            (sim_res.results()?[0].xdr.clone(), sim_res.events()?)
+            (sim_res.results()?[0].xdr.clone(), sim_res.events()?, None)
         } else {
             let global::Args { no_cache, .. } = global_args.cloned().unwrap_or_default();
             // Need to sign all auth entries
-            let mut txn = txn.transaction().clone();
+            let mut txn = tx;
             // let auth = auth_entries(&txn);
             // crate::log::auth(&[auth]);
 
-            if let Some(tx) = config.sign_soroban_authorizations(&txn, &signers).await? {
+            if let Some(tx) = config
+                .sign_soroban_authorizations(
+                    &txn,
+                    &network,
+                    &signers,
+                    &self.contract_signers.secp256r1_signers()?,
+                    &self.expiration,
+                )
+                .await?
+            {
                 txn = tx;
             }
             // log_auth_cost_and_footprint(resources(&txn));
-            let res = client
-                .send_transaction_polling(&config.sign_with_local_key(txn).await?)
+            let res = config
+                .sign_and_send(
+                    &client,
+                    &network,
+                    txn.clone(),
+                    !self.fee.no_seq_retry,
+                    global_args,
+                )
                 .await?;
+            let hash = sent_hash(&res, &txn, &network.network_passphrase)?;
             if !no_cache {
                 data::write(res.clone().try_into()?, &network.rpc_uri()?)?;
             }
-            (res.return_value()?, res.contract_events()?)
+            (res.return_value()?, res.contract_events()?, Some(hash))
         };
 
         crate::log::diagnostic_events(&events, tracing::Level::INFO);
-        output_to_string(&spec, &return_value, &function)
+        if self.show_diagnostics {
+            print_diagnostic_events(&sim_res.events()?)?;
+        }
+        let res = match self.output {
+            Output::Json => output_to_string(&spec, &return_value, &function, self.bytes_format())?,
+            Output::Xdr => TxnResult::Res(return_value.to_xdr_base64(Limits::none())?),
+            Output::Msgpack => {
+                TxnResult::Res(match spec.find_function(&function)?.outputs.first() {
+                    Some(output) => msgpack::to_base64_typed(&spec, &return_value, output)?,
+                    None => msgpack::to_base64(&return_value)?,
+                })
+            }
+        };
+        Ok((res, sent_hash, None))
+    }
+}
+
+/// The restore transaction and the invocation following it, as a JSON array of base64 envelopes
+/// to sign offline together
+fn restore_bundle(
+    restore_tx: Transaction,
+    invoke_tx: TransactionEnvelope,
+) -> Result<serde_json::Value, xdr::Error> {
+    let restore_tx: TransactionEnvelope = restore_tx.into();
+    Ok(serde_json::json!([
+        restore_tx.to_xdr_base64(Limits::none())?,
+        invoke_tx.to_xdr_base64(Limits::none())?,
+    ]))
+}
+
+/// Hash of the transaction the network applied, which differs from `tx` if it was resubmitted
+/// with the account's current sequence number
+fn sent_hash(
+    res: &rpc::GetTransactionResponse,
+    tx: &Transaction,
+    network_passphrase: &str,
+) -> Result<[u8; 32], xdr::Error> {
+    match &res.envelope {
+        Some(TransactionEnvelope::Tx(TransactionV1Envelope { tx, .. })) => {
+            utils::transaction_hash(tx, network_passphrase)
+        }
+        _ => utils::transaction_hash(tx, network_passphrase),
     }
 }
 
+/// The `--json` output of an invocation: its `result`, and if it was sent, the `tx_hash` of its
+/// transaction and, with an explorer, the `explorer_url` of the transaction's page
+fn json_result(
+    result: serde_json::Value,
+    sent_hash: Option<[u8; 32]>,
+    explorer_url: Option<&str>,
+) -> serde_json::Value {
+    let mut json = serde_json::json!({ "result": result });
+    if let Some(hash) = sent_hash.map(hex::encode) {
+        if let Some(explorer_url) = explorer_url {
+            json["explorer_url"] =
+                format!("{}/tx/{hash}", explorer_url.trim_end_matches('/')).into();
+        }
+        json["tx_hash"] = hash.into();
+    }
+    json
+}
+
+/// The auth entries of a transaction, each as the address that must sign it, then its root
+/// invocation and sub-invocations, one per line and indented by depth
+fn auth_tree_lines(tx: &Transaction) -> Result<Vec<String>, Error> {
+    let mut lines = Vec::new();
+    for op in tx.operations.iter() {
+        let OperationBody::InvokeHostFunction(InvokeHostFunctionOp { auth, .. }) = &op.body else {
+            continue;
+        };
+        for entry in auth.iter() {
+            lines.push(match &entry.credentials {
+                SorobanCredentials::SourceAccount => "Source account signs:".to_string(),
+                SorobanCredentials::Address(SorobanAddressCredentials { address, .. }) => {
+                    format!("{} signs:", describe_address(address))
+                }
+            });
+            invocation_lines(&entry.root_invocation, 1, &mut lines)?;
+        }
+    }
+    Ok(lines)
+}
+
+/// The ledger entries in the read-write footprint of a simulated transaction, each with its value
+/// before the transaction is applied. The values it writes are only known once it's sent.
+async fn change_lines(client: &rpc_ext::Client, tx: &Transaction) -> Result<Vec<String>, Error> {
+    let TransactionExt::V1(data) = &tx.ext else {
+        return Ok(Vec::new());
+    };
+    let keys = data.resources.footprint.read_write.as_slice();
+    if keys.is_empty() {
+        return Ok(Vec::new());
+    }
+    let entries = client.get_full_ledger_entries(keys).await?.entries;
+    let mut lines = vec!["Writes:".to_string()];
+    for key in keys {
+        let before = match entries.iter().find(|entry| entry.key == *key) {
+            None => "new".to_string(),
+            Some(rpc::FullLedgerEntry {
+                val: LedgerEntryData::ContractData(ContractDataEntry { val, .. }),
+                ..
+            }) => soroban_spec_tools::to_string(val)
+                .map_or_else(|_| "exists".to_string(), |val| format!("was {val}")),
+            Some(_) => "exists".to_string(),
+        };
+        lines.push(format!("  {} ({before})", describe_ledger_key(key)?));
+    }
+    Ok(lines)
+}
+
+fn invocation_lines(
+    invocation: &SorobanAuthorizedInvocation,
+    depth: usize,
+    lines: &mut Vec<String>,
+) -> Result<(), Error> {
+    let call = match &invocation.function {
+        SorobanAuthorizedFunction::ContractFn(InvokeContractArgs {
+            contract_address,
+            function_name,
+            args,
+        }) => {
+            let args = args
+                .iter()
+                .map(soroban_spec_tools::to_string)
+                .collect::<Result<Vec<_>, _>>()?
+                .join(", ");
+            format!(
+                "{}::{}({args})",
+                describe_address(contract_address),
+                function_name.to_utf8_string_lossy()
+            )
+        }
+        SorobanAuthorizedFunction::CreateContractHostFn(_) => "create contract".to_string(),
+    };
+    lines.push(format!("{}{call}", "  ".repeat(depth)));
+    for sub_invocation in invocation.sub_invocations.iter() {
+        invocation_lines(sub_invocation, depth + 1, lines)?;
+    }
+    Ok(())
+}
+
+/// Print the diagnostic events among `events` to stderr, decoded to JSON. Contract events are
+/// skipped, they are logged separately.
+fn print_diagnostic_events(events: &[DiagnosticEvent]) -> Result<(), Error> {
+    for DiagnosticEvent {
+        event:
+            ContractEvent {
+                contract_id,
+                type_,
+                body: ContractEventBody::V0(ContractEventV0 { topics, data }),
+                ..
+            },
+        ..
+    } in events
+    {
+        if *type_ != ContractEventType::Diagnostic {
+            continue;
+        }
+        let event = serde_json::json!({
+            "contract_id": contract_id.as_ref().map(|id| stellar_strkey::Contract(id.0).to_string()),
+            "topics": topics
+                .iter()
+                .map(soroban_spec_tools::to_json)
+                .collect::<Result<Vec<_>, _>>()?,
+            "data": soroban_spec_tools::to_json(data)?,
+        });
+        eprintln!("{event}");
+    }
+    Ok(())
+}
+
 const DEFAULT_ACCOUNT_ID: AccountId = AccountId(PublicKey::PublicKeyTypeEd25519(Uint256([0; 32])));
 
 // fn log_auth_cost_and_footprint(resources: Option<&SorobanResources>) {
@@ -445,16 +974,16 @@ pub fn output_to_string(
     spec: &Spec,
     res: &ScVal,
     function: &str,
+    bytes: BytesFormat,
 ) -> Result<TxnResult<String>, Error> {
     let mut res_str = String::new();
     if let Some(output) = spec.find_function(function)?.outputs.first() {
         res_str = spec
-            .xdr_to_json(res, output)
+            .xdr_to_json_string_with(res, output, bytes)
             .map_err(|e| Error::CannotPrintResult {
                 result: res.clone(),
                 error: e,
-            })?
-            .to_string();
+            })?;
     }
     Ok(TxnResult::Res(res_str))
 }
@@ -483,6 +1012,45 @@ fn build_invoke_contract_tx(
     })
 }
 
+fn build_restore_tx(
+    transaction_data: SorobanTransactionData,
+    min_resource_fee: u64,
+    sequence: i64,
+    fee: u32,
+    source_account_id: Uint256,
+) -> Result<Transaction, Error> {
+    let resource_fee = u32::try_from(min_resource_fee).unwrap_or(u32::MAX);
+    Ok(Transaction {
+        source_account: MuxedAccount::Ed25519(source_account_id),
+        fee: fee.saturating_add(resource_fee),
+        seq_num: SequenceNumber(sequence),
+        cond: Preconditions::None,
+        memo: Memo::None,
+        operations: vec![Operation {
+            source_account: None,
+            body: OperationBody::RestoreFootprint(RestoreFootprintOp {
+                ext: ExtensionPoint::V0,
+            }),
+        }]
+        .try_into()?,
+        ext: TransactionExt::V1(transaction_data),
+    })
+}
+
+/// Build the implicit CLI of a contract, with a subcommand for each of its functions
+pub(crate) fn build_contract_cmd(contract_id: &str, spec: &Spec) -> Result<clap::Command, Error> {
+    let mut cmd = clap::Command::new(contract_id.to_string())
+        .no_binary_name(true)
+        .term_width(300)
+        .max_term_width(300);
+
+    for ScSpecFunctionV0 { name, .. } in spec.find_functions()? {
+        cmd = cmd.subcommand(build_custom_cmd(&name.to_utf8_string_lossy(), spec)?);
+    }
+    cmd.build();
+    Ok(cmd)
+}
+
 fn build_custom_cmd(name: &str, spec: &Spec) -> Result<clap::Command, Error> {
     let func = spec
         .find_function(name)
@@ -526,6 +1094,16 @@ fn build_custom_cmd(name: &str, spec: &Spec) -> Result<clap::Command, Error> {
             .value_parser(value_parser!(PathBuf))
             .conflicts_with(name);
 
+        let xdr_arg_name = fmt_arg_xdr_name(name);
+        let xdr_arg = clap::Arg::new(&xdr_arg_name)
+            .long(&xdr_arg_name)
+            .alias(xdr_arg_name.to_kebab_case())
+            .num_args(1)
+            .hide(true)
+            .value_parser(clap::builder::NonEmptyStringValueParser::new())
+            .conflicts_with(name)
+            .conflicts_with(&file_arg_name);
+
         if let Some(value_name) = spec.arg_value_name(type_, 0) {
             let value_name: &'static str = Box::leak(value_name.into_boxed_str());
             arg = arg.value_name(value_name);
@@ -547,6 +1125,7 @@ fn build_custom_cmd(name: &str, spec: &Spec) -> Result<clap::Command, Error> {
 
         cmd = cmd.arg(arg);
         cmd = cmd.arg(file_arg);
+        cmd = cmd.arg(xdr_arg);
     }
     Ok(cmd)
 }
@@ -555,11 +1134,535 @@ fn fmt_arg_file_name(name: &str) -> String {
     format!("{name}-file-path")
 }
 
+fn fmt_arg_xdr_name(name: &str) -> String {
+    format!("{name}-xdr")
+}
+
 fn arg_file_help(docs: &str) -> String {
     format!(
         r#"{docs}
 Usage Notes:
 Each arg has a corresponding --<arg_name>-file-path which is a path to a file containing the corresponding JSON argument.
-Note: The only types which aren't JSON are Bytes and BytesN, which are raw bytes"#
+Note: The only types which aren't JSON are Bytes and BytesN, which are raw bytes
+Each arg also has a corresponding --<arg_name>-xdr which takes the argument as a base64 XDR ScVal, used as is."#
     )
 }
+
+/// Whether `val` can be passed for an argument of `type_`. Only primitive types are checked,
+/// values for other types are passed as is and left to the contract to reject.
+fn is_value_of_type(val: &ScVal, type_: &ScSpecTypeDef) -> bool {
+    match type_ {
+        ScSpecTypeDef::Bool => matches!(val, ScVal::Bool(_)),
+        ScSpecTypeDef::Void => matches!(val, ScVal::Void),
+        ScSpecTypeDef::U32 => matches!(val, ScVal::U32(_)),
+        ScSpecTypeDef::I32 => matches!(val, ScVal::I32(_)),
+        ScSpecTypeDef::U64 => matches!(val, ScVal::U64(_)),
+        ScSpecTypeDef::I64 => matches!(val, ScVal::I64(_)),
+        ScSpecTypeDef::U128 => matches!(val, ScVal::U128(_)),
+        ScSpecTypeDef::I128 => matches!(val, ScVal::I128(_)),
+        ScSpecTypeDef::U256 => matches!(val, ScVal::U256(_)),
+        ScSpecTypeDef::I256 => matches!(val, ScVal::I256(_)),
+        ScSpecTypeDef::Timepoint => matches!(val, ScVal::Timepoint(_)),
+        ScSpecTypeDef::Duration => matches!(val, ScVal::Duration(_)),
+        ScSpecTypeDef::String => matches!(val, ScVal::String(_)),
+        ScSpecTypeDef::Symbol => matches!(val, ScVal::Symbol(_)),
+        ScSpecTypeDef::Address => matches!(val, ScVal::Address(_)),
+        ScSpecTypeDef::Bytes => matches!(val, ScVal::Bytes(_)),
+        ScSpecTypeDef::BytesN(bytes_n) => {
+            matches!(val, ScVal::Bytes(bytes) if bytes.len() == bytes_n.n as usize)
+        }
+        ScSpecTypeDef::Option(option) => {
+            matches!(val, ScVal::Void) || is_value_of_type(val, &option.value_type)
+        }
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use httpmock::prelude::*;
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        signer,
+        test_utils::rpc_response,
+        xdr::{
+            ConfigSettingId, ContractDataDurability, LedgerEntryChanges, LedgerFootprint,
+            LedgerKeyConfigSetting, LedgerKeyContractData, ScSymbol, SorobanResources,
+            SorobanTransactionMeta, SorobanTransactionMetaExt, TransactionMeta, TransactionMetaV3,
+            TransactionResult, TransactionResultExt, TransactionResultResult,
+        },
+    };
+
+    const PASSPHRASE: &str = "Test SDF Network ; September 2015";
+    const SECRET: &str = "SBFGFF27Y64ZUGFAIG5AMJGQODZZKV2YQKAVUUN4HNE24XZXD2OEUVUP";
+
+    #[test]
+    fn contract_error_code_is_the_last_contract_error_in_the_events() {
+        let event = |topics: Vec<ScVal>| DiagnosticEvent {
+            in_successful_contract_call: false,
+            event: ContractEvent {
+                ext: ExtensionPoint::V0,
+                contract_id: Some(Hash([1; 32])),
+                type_: ContractEventType::Diagnostic,
+                body: ContractEventBody::V0(ContractEventV0 {
+                    topics: topics.try_into().unwrap(),
+                    data: ScVal::Void,
+                }),
+            },
+        };
+        let error = |code| {
+            event(vec![
+                ScVal::Symbol(ScSymbol("error".try_into().unwrap())),
+                ScVal::Error(ScError::Contract(code)),
+            ])
+        };
+        let call = event(vec![ScVal::Symbol(ScSymbol("fn_call".try_into().unwrap()))]);
+        assert_eq!(contract_error_code(&[call.clone()]), None);
+        assert_eq!(contract_error_code(&[call, error(1), error(10)]), Some(10));
+    }
+
+    #[tokio::test]
+    async fn json_result_has_the_sent_transaction_hash() {
+        let server = MockServer::start();
+        let config = test_config(&server);
+        let key = config.key_pair().unwrap();
+        let tx = build_invoke_contract_tx(
+            InvokeContractArgs {
+                contract_address: ScAddress::Contract(Hash([1; 32])),
+                function_name: "hello".try_into().unwrap(),
+                args: VecM::default(),
+            },
+            42,
+            100,
+            Uint256(key.verifying_key().to_bytes()),
+        )
+        .unwrap();
+        let send = server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .json_body_partial(r#"{ "method": "sendTransaction" }"#);
+            then.status(200).json_body(rpc_response(json!({
+                "status": "PENDING",
+                "hash": "00".repeat(32),
+                "latestLedger": 10,
+                "latestLedgerCloseTime": "0"
+            })));
+        });
+        let get_transaction = server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .json_body_partial(r#"{ "method": "getTransaction" }"#);
+            then.status(200).json_body(rpc_response(json!({
+                "status": "SUCCESS",
+                "envelopeXdr": signer::sign_tx(&key, &tx, PASSPHRASE)
+                    .unwrap()
+                    .to_xdr_base64(Limits::none())
+                    .unwrap(),
+                "resultXdr": TransactionResult {
+                    fee_charged: 100,
+                    result: TransactionResultResult::TxSuccess(VecM::default()),
+                    ext: TransactionResultExt::V0,
+                }
+                .to_xdr_base64(Limits::none())
+                .unwrap(),
+                "latestLedger": 11
+            })));
+        });
+
+        let network = config.get_network().await.unwrap();
+        let client = network.rpc_client().unwrap();
+        let res = config
+            .sign_and_send(&client, &network, tx.clone(), false, None)
+            .await
+            .unwrap();
+        let hash = hex::encode(utils::transaction_hash(&tx, PASSPHRASE).unwrap());
+        let explorer_url = network.explorer_url();
+        assert_eq!(
+            json_result(
+                json!("hello"),
+                Some(sent_hash(&res, &tx, PASSPHRASE).unwrap()),
+                explorer_url
+            ),
+            json!({
+                "result": "hello",
+                "tx_hash": hash,
+                "explorer_url": format!("https://stellar.expert/explorer/testnet/tx/{hash}"),
+            })
+        );
+        assert_eq!(
+            json_result(json!("hello"), None, explorer_url),
+            json!({ "result": "hello" })
+        );
+        send.assert();
+        get_transaction.assert();
+    }
+
+    fn transaction_data(read_write: Vec<LedgerKey>, resource_fee: i64) -> SorobanTransactionData {
+        SorobanTransactionData {
+            ext: ExtensionPoint::V0,
+            resources: SorobanResources {
+                footprint: LedgerFootprint {
+                    read_only: VecM::default(),
+                    read_write: read_write.try_into().unwrap(),
+                },
+                instructions: 1000,
+                read_bytes: 100,
+                write_bytes: 100,
+            },
+            resource_fee,
+        }
+    }
+
+    fn read_write(tx: &Transaction) -> Vec<LedgerKey> {
+        let TransactionExt::V1(data) = &tx.ext else {
+            panic!("expected soroban transaction data");
+        };
+        data.resources.footprint.read_write.to_vec()
+    }
+
+    fn test_config(server: &MockServer) -> config::Args {
+        config::Args {
+            network: network::Args {
+                rpc_url: Some(server.url("/")),
+                network_passphrase: Some(PASSPHRASE.to_string()),
+                ..Default::default()
+            },
+            source_account: SECRET.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Serve `key`'s account, at sequence number 42, for every ledger entry requested
+    fn mock_account(server: &MockServer, key: &SigningKey) {
+        server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .json_body_partial(r#"{ "method": "getLedgerEntries" }"#);
+            then.status(200).json_body(account_response(key, 42));
+        });
+    }
+
+    /// A `getLedgerEntries` response with `key`'s account at `sequence`
+    fn account_response(key: &SigningKey, sequence: i64) -> serde_json::Value {
+        let account = LedgerEntryData::Account(AccountEntry {
+            account_id: AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(
+                key.verifying_key().to_bytes(),
+            ))),
+            balance: 10_000_000_000,
+            seq_num: SequenceNumber(sequence),
+            num_sub_entries: 0,
+            inflation_dest: None,
+            flags: 0,
+            home_domain: String32::default(),
+            thresholds: Thresholds([1, 0, 0, 0]),
+            signers: VecM::default(),
+            ext: AccountEntryExt::V0,
+        });
+        rpc_response(json!({
+            "entries": [{
+                "key": "",
+                "xdr": account.to_xdr_base64(Limits::none()).unwrap(),
+                "lastModifiedLedgerSeq": 10
+            }],
+            "latestLedger": 10
+        }))
+    }
+
+    fn archived_key() -> LedgerKey {
+        LedgerKey::ContractData(LedgerKeyContractData {
+            contract: ScAddress::Contract(Hash([1; 32])),
+            key: ScVal::Symbol(ScSymbol("COUNTER".try_into().unwrap())),
+            durability: ContractDataDurability::Persistent,
+        })
+    }
+
+    /// A simulation returning 1, which with `archived` also has a restore preamble for it
+    fn simulation(archived: Option<&LedgerKey>) -> serde_json::Value {
+        let read_write = archived.into_iter().cloned().collect::<Vec<_>>();
+        let mut result = json!({
+            "transactionData": transaction_data(read_write.clone(), 2000)
+                .to_xdr_base64(Limits::none())
+                .unwrap(),
+            "minResourceFee": "2000",
+            "results": [{ "auth": [], "xdr": ScVal::U32(1).to_xdr_base64(Limits::none()).unwrap() }],
+            "cost": { "cpuInsns": "1000", "memBytes": "1000" },
+            "latestLedger": 10
+        });
+        if archived.is_some() {
+            result["restorePreamble"] = json!({
+                "transactionData": transaction_data(read_write, 500)
+                    .to_xdr_base64(Limits::none())
+                    .unwrap(),
+                "minResourceFee": "500"
+            });
+        }
+        rpc_response(result)
+    }
+
+    /// A command invoking `inc`, which returns a `u32`, on contract `[1; 32]`
+    fn inc_cmd(config: config::Args) -> Cmd {
+        Cmd {
+            contract_id: stellar_strkey::Contract([1; 32]).to_string(),
+            spec: Some(vec![ScSpecEntry::FunctionV0(ScSpecFunctionV0 {
+                doc: StringM::default(),
+                name: "inc".try_into().unwrap(),
+                inputs: VecM::default(),
+                outputs: vec![ScSpecTypeDef::U32].try_into().unwrap(),
+            })]),
+            slop: vec!["inc".into()],
+            config,
+            ..Default::default()
+        }
+    }
+
+    fn inc_tx(key: &SigningKey, sequence: i64) -> Transaction {
+        build_invoke_contract_tx(
+            InvokeContractArgs {
+                contract_address: ScAddress::Contract(Hash([1; 32])),
+                function_name: "inc".try_into().unwrap(),
+                args: VecM::default(),
+            },
+            sequence,
+            100,
+            Uint256(key.verifying_key().to_bytes()),
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn no_restore_lists_the_archived_entries() {
+        let server = MockServer::start();
+        let config = test_config(&server);
+        mock_account(&server, &config.key_pair().unwrap());
+        let archived = archived_key();
+        server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .json_body_partial(r#"{ "method": "simulateTransaction" }"#);
+            then.status(200).json_body(simulation(Some(&archived)));
+        });
+        let send = server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .json_body_partial(r#"{ "method": "sendTransaction" }"#);
+            then.status(500);
+        });
+        let cmd = Cmd {
+            no_restore: true,
+            ..inc_cmd(config)
+        };
+        let global_args = global::Args {
+            ignore_network_check: true,
+            no_cache: true,
+            ..Default::default()
+        };
+
+        let Err(Error::ArchivedEntries(keys)) =
+            cmd.invoke_and_send(Some(&global_args), None, false).await
+        else {
+            panic!("expected the archived entries error");
+        };
+        assert_eq!(keys, [archived.to_xdr_base64(Limits::none()).unwrap()]);
+        send.assert_hits(0);
+    }
+
+    #[tokio::test]
+    async fn restores_archived_entries_before_invoking() {
+        let server = MockServer::start();
+        let config = test_config(&server);
+        let key = config.key_pair().unwrap();
+        // The account is fetched with the compute settings before the restore, and on its own
+        // after it, once the restore has taken sequence number 43. Mocks are matched in the order
+        // they're made
+        let compute_key = LedgerKey::ConfigSetting(LedgerKeyConfigSetting {
+            config_setting_id: ConfigSettingId::ContractComputeV0,
+        });
+        server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .json_body_partial(r#"{ "method": "getLedgerEntries" }"#)
+                .body_contains(compute_key.to_xdr_base64(Limits::none()).unwrap());
+            then.status(200).json_body(account_response(&key, 42));
+        });
+        server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .json_body_partial(r#"{ "method": "getLedgerEntries" }"#);
+            then.status(200).json_body(account_response(&key, 43));
+        });
+        let archived = archived_key();
+        // So the invocation is simulated again with 44, this time without anything to restore
+        let resimulate = server.mock(|when, then| {
+            let tx: TransactionEnvelope = inc_tx(&key, 44).into();
+            when.method(POST)
+                .path("/")
+                .json_body_partial(r#"{ "method": "simulateTransaction" }"#)
+                .body_contains(tx.to_xdr_base64(Limits::none()).unwrap());
+            then.status(200).json_body(simulation(None));
+        });
+        let simulate = server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .json_body_partial(r#"{ "method": "simulateTransaction" }"#);
+            then.status(200).json_body(simulation(Some(&archived)));
+        });
+        let send = server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .json_body_partial(r#"{ "method": "sendTransaction" }"#);
+            then.status(200).json_body(rpc_response(json!({
+                "status": "PENDING",
+                "hash": "00".repeat(32),
+                "latestLedger": 10,
+                "latestLedgerCloseTime": "0"
+            })));
+        });
+        let meta = TransactionMeta::V3(TransactionMetaV3 {
+            ext: ExtensionPoint::V0,
+            tx_changes_before: LedgerEntryChanges::default(),
+            operations: VecM::default(),
+            tx_changes_after: LedgerEntryChanges::default(),
+            soroban_meta: Some(SorobanTransactionMeta {
+                ext: SorobanTransactionMetaExt::V0,
+                events: VecM::default(),
+                return_value: ScVal::U32(1),
+                diagnostic_events: VecM::default(),
+            }),
+        });
+        server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .json_body_partial(r#"{ "method": "getTransaction" }"#);
+            then.status(200).json_body(rpc_response(json!({
+                "status": "SUCCESS",
+                "envelopeXdr": signer::sign_tx(&key, &inc_tx(&key, 44), PASSPHRASE)
+                    .unwrap()
+                    .to_xdr_base64(Limits::none())
+                    .unwrap(),
+                "resultXdr": TransactionResult {
+                    fee_charged: 100,
+                    result: TransactionResultResult::TxSuccess(VecM::default()),
+                    ext: TransactionResultExt::V0,
+                }
+                .to_xdr_base64(Limits::none())
+                .unwrap(),
+                "resultMetaXdr": meta.to_xdr_base64(Limits::none()).unwrap(),
+                "latestLedger": 11
+            })));
+        });
+        let global_args = global::Args {
+            ignore_network_check: true,
+            no_cache: true,
+            ..Default::default()
+        };
+
+        let (res, sent_hash, restore_tx) = inc_cmd(config)
+            .invoke_and_send(Some(&global_args), None, false)
+            .await
+            .unwrap();
+        assert_eq!(res, TxnResult::Res("1".to_string()));
+        assert!(sent_hash.is_some());
+        assert_eq!(restore_tx, None);
+        simulate.assert_hits(1);
+        resimulate.assert_hits(1);
+        // The restore, then the invocation
+        send.assert_hits(2);
+    }
+
+    #[tokio::test]
+    async fn build_only_bundles_the_restore_with_the_invocation() {
+        let server = MockServer::start();
+        let config = test_config(&server);
+        mock_account(&server, &config.key_pair().unwrap());
+        let archived = archived_key();
+        let simulate = server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .json_body_partial(r#"{ "method": "simulateTransaction" }"#);
+            then.status(200).json_body(simulation(Some(&archived)));
+        });
+        let cmd = Cmd {
+            fee: crate::fee::Args {
+                build_only: true,
+                ..Default::default()
+            },
+            ..inc_cmd(config)
+        };
+        let global_args = global::Args {
+            ignore_network_check: true,
+            ..Default::default()
+        };
+
+        let (res, sent_hash, restore_tx) = cmd
+            .invoke_and_send(Some(&global_args), None, true)
+            .await
+            .unwrap();
+        assert_eq!(sent_hash, None);
+        let TxnResult::Txn(invoke_tx) = res else {
+            panic!("expected a transaction");
+        };
+        let restore_tx = restore_tx.expect("a restore transaction");
+        assert_eq!(restore_tx.seq_num, SequenceNumber(43));
+        assert!(matches!(
+            restore_tx.operations[0].body,
+            OperationBody::RestoreFootprint(_)
+        ));
+        assert_eq!(read_write(&restore_tx), [archived.clone()]);
+        assert_eq!(invoke_tx.seq_num, SequenceNumber(44));
+        assert!(matches!(
+            invoke_tx.operations[0].body,
+            OperationBody::InvokeHostFunction(_)
+        ));
+        assert_eq!(read_write(&invoke_tx), [archived]);
+        simulate.assert();
+
+        let invoke_env: TransactionEnvelope = invoke_tx.into();
+        let bundle = restore_bundle(restore_tx.clone(), invoke_env.clone()).unwrap();
+        let envelopes = bundle
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|env| {
+                TransactionEnvelope::from_xdr_base64(env.as_str().unwrap(), Limits::none()).unwrap()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(envelopes, [restore_tx.into(), invoke_env]);
+    }
+
+    #[tokio::test]
+    async fn build_only_without_a_bundle_leaves_out_the_restore() {
+        let server = MockServer::start();
+        let config = test_config(&server);
+        mock_account(&server, &config.key_pair().unwrap());
+        let archived = archived_key();
+        server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .json_body_partial(r#"{ "method": "simulateTransaction" }"#);
+            then.status(200).json_body(simulation(Some(&archived)));
+        });
+        let cmd = Cmd {
+            fee: crate::fee::Args {
+                build_only: true,
+                ..Default::default()
+            },
+            ..inc_cmd(config)
+        };
+        let global_args = global::Args {
+            ignore_network_check: true,
+            ..Default::default()
+        };
+
+        // As for `contract invoke-batch`, the REPL and tests, which only get the invocation back
+        let res = cmd
+            .run_against_rpc_server(Some(&global_args), None)
+            .await
+            .unwrap();
+        let TxnResult::Txn(invoke_tx) = res else {
+            panic!("expected a transaction");
+        };
+        assert_eq!(invoke_tx.seq_num, SequenceNumber(43));
+    }
+}
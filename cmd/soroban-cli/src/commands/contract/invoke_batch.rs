@@ -0,0 +1,187 @@
+use std::{
+    ffi::OsString,
+    fs,
+    io::{self, BufRead},
+    path::PathBuf,
+};
+
+use clap::{arg, command, Parser};
+use futures_util::{stream, StreamExt};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::commands::{
+    config, global,
+    txn_result::{TxnEnvelopeResult, TxnResult},
+};
+use crate::xdr::{Limits, WriteXdr};
+
+use super::invoke;
+
+#[derive(Parser, Debug, Clone)]
+#[group(skip)]
+pub struct Cmd {
+    /// Contract ID to invoke
+    #[arg(long = "id", env = "STELLAR_CONTRACT_ID")]
+    pub contract_id: String,
+    /// File of invocations, one JSON object per line: `{ "function": "inc", "args": { ... } }`.
+    /// Reads from stdin if `-`
+    #[arg(long)]
+    pub file: PathBuf,
+    /// Number of invocations to run at once. Transactions from the same source account have to
+    /// be submitted one at a time, so this requires `--is-view`
+    #[arg(long, default_value = "1", requires = "is_view")]
+    pub parallel: usize,
+    /// Keep going after an invocation fails instead of stopping at the first failure
+    #[arg(long)]
+    pub continue_on_error: bool,
+    /// View the results simulating and do not sign and submit transactions
+    #[arg(long, env = "STELLAR_INVOKE_VIEW")]
+    pub is_view: bool,
+    #[command(flatten)]
+    pub config: config::Args,
+    #[command(flatten)]
+    pub fee: crate::fee::Args,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("reading {0:?}: {1}")]
+    CannotReadFile(PathBuf, io::Error),
+    #[error("line {line}: {error}")]
+    CannotParseLine {
+        line: usize,
+        error: serde_json::Error,
+    },
+    #[error("line {line}: {error}")]
+    Invoke { line: usize, error: invoke::Error },
+    #[error("--parallel must be at least 1")]
+    ParallelIsZero,
+}
+
+/// A single line of the batch file
+#[derive(Debug, Deserialize)]
+pub struct Invocation {
+    pub function: String,
+    #[serde(default)]
+    pub args: serde_json::Map<String, Value>,
+}
+
+impl Invocation {
+    /// Arguments for the contract's implicit CLI, as `invoke` would get them after the `--`
+    pub fn slop(&self) -> Vec<OsString> {
+        let mut slop = vec![OsString::from(&self.function)];
+        for (name, value) in &self.args {
+            let value = match value {
+                Value::Null => continue,
+                Value::String(s) => s.clone(),
+                v => v.to_string(),
+            };
+            slop.push(format!("--{name}={value}").into());
+        }
+        slop
+    }
+}
+
+impl Cmd {
+    pub async fn run(&self, global_args: &global::Args) -> Result<(), Error> {
+        if self.parallel == 0 {
+            return Err(Error::ParallelIsZero);
+        }
+        let invocations = self.invocations()?;
+        let mut results = stream::iter(invocations.into_iter().enumerate())
+            .map(|(i, invocation)| async move {
+                let res = self.invoke_one(&invocation, global_args).await;
+                (i + 1, invocation, res)
+            })
+            .buffered(self.parallel);
+        let mut first_error = None;
+        while let Some((line, invocation, res)) = results.next().await {
+            match res {
+                Ok(result) => println!(
+                    "{}",
+                    json!({ "function": invocation.function, "result": result })
+                ),
+                Err(error) => {
+                    println!(
+                        "{}",
+                        json!({ "function": invocation.function, "error": error.to_string() })
+                    );
+                    if first_error.is_none() {
+                        first_error = Some(Error::Invoke { line, error });
+                    }
+                    if !self.continue_on_error {
+                        break;
+                    }
+                }
+            }
+        }
+        first_error.map_or(Ok(()), Err)
+    }
+
+    pub fn invocations(&self) -> Result<Vec<Invocation>, Error> {
+        let read_err = |e| Error::CannotReadFile(self.file.clone(), e);
+        let lines = if self.file.as_os_str() == "-" {
+            io::stdin()
+                .lock()
+                .lines()
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(read_err)?
+        } else {
+            fs::read_to_string(&self.file)
+                .map_err(read_err)?
+                .lines()
+                .map(ToString::to_string)
+                .collect()
+        };
+        lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                serde_json::from_str(line)
+                    .map_err(|error| Error::CannotParseLine { line: i + 1, error })
+            })
+            .collect()
+    }
+
+    async fn invoke_one(
+        &self,
+        invocation: &Invocation,
+        global_args: &global::Args,
+    ) -> Result<Value, invoke::Error> {
+        let cmd = invoke::Cmd {
+            contract_id: self.contract_id.clone(),
+            is_view: self.is_view,
+            slop: invocation.slop(),
+            config: self.config.clone(),
+            fee: self.fee.clone(),
+            ..Default::default()
+        };
+        Ok(match cmd.invoke(global_args).await?.to_envelope() {
+            TxnEnvelopeResult::TxnEnvelope(tx) => Value::String(tx.to_xdr_base64(Limits::none())?),
+            TxnEnvelopeResult::Res(output) => {
+                serde_json::from_str(&output).unwrap_or(Value::String(output))
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn slop() {
+        let invocation: Invocation = serde_json::from_str(
+            r#"{ "function": "hello", "args": { "world": "there", "count": 2, "maybe": null, "list": [1, 2] } }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            invocation.slop(),
+            ["hello", "--count=2", "--list=[1,2]", "--world=there"]
+                .map(OsString::from)
+                .to_vec()
+        );
+    }
+}
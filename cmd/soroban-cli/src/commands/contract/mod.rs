@@ -1,22 +1,34 @@
+pub mod alias;
 pub mod asset;
 pub mod bindings;
 pub mod build;
+pub mod complete;
 pub mod deploy;
+pub mod diff_cost;
 pub mod extend;
 pub mod fetch;
 pub mod id;
+pub mod info;
 pub mod init;
 pub mod inspect;
 pub mod install;
 pub mod invoke;
+pub mod invoke_batch;
 pub mod optimize;
 pub mod read;
+pub mod repl;
 pub mod restore;
+pub mod simulate_auth;
+pub mod snapshot;
+pub mod verify;
 
 use crate::commands::global;
 
 #[derive(Debug, clap::Subcommand)]
 pub enum Cmd {
+    /// Utilities to add, list and remove contract aliases
+    #[command(subcommand)]
+    Alias(alias::Cmd),
     /// Utilities to deploy a Stellar Asset Contract or get its id
     #[command(subcommand)]
     Asset(asset::Cmd),
@@ -26,6 +38,13 @@ pub enum Cmd {
 
     Build(build::Cmd),
 
+    /// Print completions for the function name and arguments of a `contract invoke`
+    ///
+    /// Reads the contract's spec and prints, one per line, the function names starting with the
+    /// last word, or once a function is typed, its `--arg-name` flags not used yet. Meant to be
+    /// called from shell completion scripts.
+    Complete(complete::Cmd),
+
     /// Extend the time to live ledger of a contract-data ledger entry.
     ///
     /// If no keys are specified the contract itself is extended.
@@ -34,6 +53,13 @@ pub enum Cmd {
     /// Deploy a wasm contract
     Deploy(deploy::wasm::Cmd),
 
+    /// Compare the simulated cost of an invocation across two versions of a contract
+    ///
+    /// Simulates the invocation after `--` against both `--old` and `--new`, two deployed
+    /// contracts, and prints the CPU instructions, memory bytes and minimum resource fee of each
+    /// with the change between them. Nothing is submitted to the network.
+    DiffCost(diff_cost::Cmd),
+
     /// Fetch a contract's Wasm binary
     Fetch(fetch::Cmd),
 
@@ -41,6 +67,10 @@ pub enum Cmd {
     #[command(subcommand)]
     Id(id::Cmd),
 
+    /// List a contract's functions, types and meta entries, read from its Wasm
+    #[command(subcommand)]
+    Info(info::Cmd),
+
     /// Initialize a Soroban project with an example contract
     Init(init::Cmd),
 
@@ -58,22 +88,61 @@ pub enum Cmd {
     /// contract:
     ///
     ///     stellar contract invoke ... -- --help
+    ///
+    /// When the function fails with one of the contract's errors, it is printed by name and the
+    /// command exits with 100 plus the error's code, up to 255.
     Invoke(invoke::Cmd),
 
-    /// Optimize a WASM file
+    /// Invoke contract functions listed in a file, one JSON invocation per line
+    ///
+    /// Each line looks like `{ "function": "inc", "args": { "amount": 2 } }`. One JSON result
+    /// object is printed per line.
+    InvokeBatch(invoke_batch::Cmd),
+
+    /// Optimize a WASM file for size, with the bundled optimizer or else `wasm-opt`. Custom
+    /// sections other than the contract's spec and meta are removed
     Optimize(optimize::Cmd),
 
     /// Print the current value of a contract-data ledger entry
     Read(read::Cmd),
 
+    /// Start an interactive session for invoking a contract's functions
+    ///
+    /// Fetches the contract's spec once, then reads lines like `hello world=there` and prints each
+    /// result as JSON. Function names complete with tab. Exit with Ctrl-D.
+    Repl(repl::Cmd),
+
     /// Restore an evicted value for a contract-data legder entry.
     ///
     /// If no keys are specificed the contract itself is restored.
     Restore(restore::Cmd),
+
+    /// Check whether a signature would pass a custom account's `__check_auth`
+    ///
+    /// Simulates the `--auth-context` invocation with an auth entry for the account holding the
+    /// signature, and reports whether `__check_auth` accepted it or the error it returned. The
+    /// signature payload the host passes to `__check_auth` is printed to stderr.
+    SimulateAuth(simulate_auth::Cmd),
+
+    /// Write a contract's ledger entries, fetched from the network, to a JSON file
+    ///
+    /// The snapshot has the contract's instance entry, which includes its instance storage, the
+    /// entry of the Wasm code it runs, and the storage entries for `--ledger-key`. Each entry is
+    /// a base64-encoded `LedgerKey` and `LedgerEntry` pair with its live-until ledger, e.g. to
+    /// replay the contract's state in local tests.
+    Snapshot(snapshot::Cmd),
+
+    /// Check that a local Wasm file is the one a contract runs
+    ///
+    /// Compares the SHA-256 hash of the file with the hash of the contract's Wasm fetched from the
+    /// network, or with `--against-hash`.
+    Verify(verify::Cmd),
 }
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
+    #[error(transparent)]
+    Alias(#[from] alias::Error),
     #[error(transparent)]
     Asset(#[from] asset::Error),
 
@@ -83,15 +152,24 @@ pub enum Error {
     #[error(transparent)]
     Build(#[from] build::Error),
 
+    #[error(transparent)]
+    Complete(#[from] complete::Error),
+
     #[error(transparent)]
     Extend(#[from] extend::Error),
 
     #[error(transparent)]
     Deploy(#[from] deploy::wasm::Error),
 
+    #[error(transparent)]
+    DiffCost(#[from] diff_cost::Error),
+
     #[error(transparent)]
     Fetch(#[from] fetch::Error),
 
+    #[error(transparent)]
+    Info(#[from] info::Error),
+
     #[error(transparent)]
     Init(#[from] init::Error),
 
@@ -107,36 +185,68 @@ pub enum Error {
     #[error(transparent)]
     Invoke(#[from] invoke::Error),
 
+    #[error(transparent)]
+    InvokeBatch(#[from] invoke_batch::Error),
+
     #[error(transparent)]
     Optimize(#[from] optimize::Error),
 
     #[error(transparent)]
     Read(#[from] read::Error),
 
+    #[error(transparent)]
+    Repl(#[from] repl::Error),
+
     #[error(transparent)]
     Restore(#[from] restore::Error),
+
+    #[error(transparent)]
+    SimulateAuth(#[from] simulate_auth::Error),
+
+    #[error(transparent)]
+    Snapshot(#[from] snapshot::Error),
+
+    #[error(transparent)]
+    Verify(#[from] verify::Error),
 }
 
 impl Cmd {
     pub async fn run(&self, global_args: &global::Args) -> Result<(), Error> {
         match &self {
-            Cmd::Asset(asset) => asset.run().await?,
+            Cmd::Alias(alias) => alias.run().await?,
+            Cmd::Asset(asset) => asset.run(global_args).await?,
             Cmd::Bindings(bindings) => bindings.run().await?,
             Cmd::Build(build) => build.run()?,
-            Cmd::Extend(extend) => extend.run().await?,
-            Cmd::Deploy(deploy) => deploy.run().await?,
-            Cmd::Id(id) => id.run()?,
+            Cmd::Complete(complete) => complete.run(global_args).await?,
+            Cmd::Extend(extend) => extend.run(global_args).await?,
+            Cmd::Deploy(deploy) => deploy.run(global_args).await?,
+            Cmd::DiffCost(diff_cost) => diff_cost.run(global_args).await?,
+            Cmd::Id(id) => id.run().await?,
+            Cmd::Info(info) => info.run(global_args).await?,
             Cmd::Init(init) => init.run()?,
             Cmd::Inspect(inspect) => inspect.run()?,
-            Cmd::Install(install) => install.run().await?,
+            Cmd::Install(install) => install.run(global_args).await?,
             Cmd::Invoke(invoke) => invoke.run(global_args).await?,
+            Cmd::InvokeBatch(invoke_batch) => invoke_batch.run(global_args).await?,
             Cmd::Optimize(optimize) => optimize.run()?,
-            Cmd::Fetch(fetch) => fetch.run().await?,
-            Cmd::Read(read) => read.run().await?,
-            Cmd::Restore(restore) => restore.run().await?,
+            Cmd::Fetch(fetch) => fetch.run(global_args).await?,
+            Cmd::Read(read) => read.run(global_args).await?,
+            Cmd::Repl(repl) => repl.run(global_args).await?,
+            Cmd::Restore(restore) => restore.run(global_args).await?,
+            Cmd::SimulateAuth(simulate_auth) => simulate_auth.run(global_args).await?,
+            Cmd::Snapshot(snapshot) => snapshot.run(global_args).await?,
+            Cmd::Verify(verify) => verify.run(global_args).await?,
         }
         Ok(())
     }
+
+    /// Whether the command prints its result as JSON with the global `--json`
+    pub fn supports_json(&self) -> bool {
+        matches!(
+            self,
+            Cmd::Asset(asset::Cmd::Deploy(_)) | Cmd::Deploy(_) | Cmd::Install(_) | Cmd::Invoke(_)
+        )
+    }
 }
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, clap::ValueEnum)]
@@ -1,10 +1,14 @@
 use clap::{arg, command, Parser};
-use std::fmt::Debug;
+use std::{fmt::Debug, fs, path::Path};
 #[cfg(feature = "opt")]
 use wasm_opt::{Feature, OptimizationError, OptimizationOptions};
 
 use crate::wasm;
 
+/// Custom sections the network and tooling read from a contract, kept when optimizing. Any other
+/// custom section, such as `name` or `producers`, isn't needed at runtime and is removed.
+const KEPT_CUSTOM_SECTIONS: [&str; 3] = ["contractenvmetav0", "contractmetav0", "contractspecv0"];
+
 #[derive(Parser, Debug, Clone)]
 #[group(skip)]
 pub struct Cmd {
@@ -23,17 +27,21 @@ pub enum Error {
     #[error("optimization error: {0}")]
     OptimizationError(OptimizationError),
     #[cfg(not(feature = "opt"))]
-    #[error("Must install with \"opt\" feature, e.g. `cargo install --locked soroban-cli --features opt")]
+    #[error("Must install with \"opt\" feature, e.g. `cargo install --locked soroban-cli --features opt`, or have `wasm-opt` on the PATH")]
     Install,
+    #[cfg(not(feature = "opt"))]
+    #[error("running wasm-opt: {0}")]
+    WasmOpt(std::io::Error),
+    #[cfg(not(feature = "opt"))]
+    #[error("wasm-opt failed: {0}")]
+    WasmOptFailed(std::process::ExitStatus),
+    #[error("writing {0:?}: {1}")]
+    CannotWriteWasm(std::path::PathBuf, std::io::Error),
+    #[error("cannot strip custom sections of malformed Wasm")]
+    MalformedWasm,
 }
 
 impl Cmd {
-    #[cfg(not(feature = "opt"))]
-    pub fn run(&self) -> Result<(), Error> {
-        Err(Error::Install)
-    }
-
-    #[cfg(feature = "opt")]
     pub fn run(&self) -> Result<(), Error> {
         let wasm_size = self.wasm.len()?;
 
@@ -49,31 +57,175 @@ impl Cmd {
             wasm_out
         });
 
-        let mut options = OptimizationOptions::new_optimize_for_size_aggressively();
-        options.converge = true;
-
-        // Explicitly set to MVP + sign-ext + mutable-globals, which happens to
-        // also be the default featureset, but just to be extra clear we set it
-        // explicitly.
-        //
-        // Formerly Soroban supported only the MVP feature set, but Rust 1.70 as
-        // well as Clang generate code with sign-ext + mutable-globals enabled,
-        // so Soroban has taken a change to support them also.
-        options.mvp_features_only();
-        options.enable_feature(Feature::MutableGlobals);
-        options.enable_feature(Feature::SignExt);
-
-        options
-            .run(&self.wasm.wasm, &wasm_out)
-            .map_err(Error::OptimizationError)?;
-
-        let wasm_out_size = wasm::len(&wasm_out)?;
+        optimize(&self.wasm.wasm, &wasm_out)?;
+        let optimized = wasm::Args {
+            wasm: wasm_out.clone(),
+        }
+        .read()?;
+        let stripped = strip_custom_sections(&optimized)?;
+        fs::write(&wasm_out, &stripped).map_err(|e| Error::CannotWriteWasm(wasm_out.clone(), e))?;
+
         println!(
             "Optimized: {} ({} bytes)",
             wasm_out.to_string_lossy(),
-            wasm_out_size
+            stripped.len()
         );
 
         Ok(())
     }
 }
+
+#[cfg(feature = "opt")]
+fn optimize(wasm: &Path, wasm_out: &Path) -> Result<(), Error> {
+    let mut options = OptimizationOptions::new_optimize_for_size_aggressively();
+    options.converge = true;
+
+    // Explicitly set to MVP + sign-ext + mutable-globals, which happens to
+    // also be the default featureset, but just to be extra clear we set it
+    // explicitly.
+    //
+    // Formerly Soroban supported only the MVP feature set, but Rust 1.70 as
+    // well as Clang generate code with sign-ext + mutable-globals enabled,
+    // so Soroban has taken a change to support them also.
+    options.mvp_features_only();
+    options.enable_feature(Feature::MutableGlobals);
+    options.enable_feature(Feature::SignExt);
+
+    options
+        .run(wasm, wasm_out)
+        .map_err(Error::OptimizationError)
+}
+
+/// Without the bundled optimizer, run a `wasm-opt` from the PATH with the same options
+#[cfg(not(feature = "opt"))]
+fn optimize(wasm: &Path, wasm_out: &Path) -> Result<(), Error> {
+    let status = std::process::Command::new("wasm-opt")
+        .args([
+            "-Oz",
+            "--converge",
+            "--mvp-features",
+            "--enable-mutable-globals",
+            "--enable-sign-ext",
+        ])
+        .arg(wasm)
+        .arg("-o")
+        .arg(wasm_out)
+        .status()
+        .map_err(|e| {
+            if e.kind() == std::io::ErrorKind::NotFound {
+                Error::Install
+            } else {
+                Error::WasmOpt(e)
+            }
+        })?;
+    if !status.success() {
+        return Err(Error::WasmOptFailed(status));
+    }
+    Ok(())
+}
+
+/// The Wasm with only its [`KEPT_CUSTOM_SECTIONS`] among custom sections, other sections are
+/// copied as is
+pub fn strip_custom_sections(wasm: &[u8]) -> Result<Vec<u8>, Error> {
+    // Magic number and version
+    let mut stripped = wasm.get(..8).ok_or(Error::MalformedWasm)?.to_vec();
+    let mut pos = 8;
+    while pos < wasm.len() {
+        let start = pos;
+        let id = wasm[pos];
+        pos += 1;
+        let size = read_u32_leb128(wasm, &mut pos)?;
+        let end = pos
+            .checked_add(size)
+            .filter(|end| *end <= wasm.len())
+            .ok_or(Error::MalformedWasm)?;
+        if id == 0 {
+            let len = read_u32_leb128(wasm, &mut pos)?;
+            let name = wasm
+                .get(pos..end)
+                .and_then(|rest| rest.get(..len))
+                .ok_or(Error::MalformedWasm)?;
+            if !KEPT_CUSTOM_SECTIONS
+                .iter()
+                .any(|kept| kept.as_bytes() == name)
+            {
+                pos = end;
+                continue;
+            }
+        }
+        stripped.extend_from_slice(&wasm[start..end]);
+        pos = end;
+    }
+    Ok(stripped)
+}
+
+fn read_u32_leb128(wasm: &[u8], pos: &mut usize) -> Result<usize, Error> {
+    let mut value = 0;
+    for shift in (0..35).step_by(7) {
+        let byte = *wasm.get(*pos).ok_or(Error::MalformedWasm)?;
+        *pos += 1;
+        value |= usize::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(Error::MalformedWasm)
+}
+
+#[cfg(test)]
+mod test {
+    use soroban_spec_tools::contract::Spec;
+
+    use super::*;
+
+    const HELLO_WORLD: &[u8] = include_bytes!(
+        "../../../../../target/wasm32-unknown-unknown/test-wasms/test_hello_world.wasm"
+    );
+
+    fn custom_sections(wasm: &[u8]) -> Vec<String> {
+        wasmparser::Parser::new(0)
+            .parse_all(wasm)
+            .filter_map(|payload| match payload.unwrap() {
+                wasmparser::Payload::CustomSection(section) => Some(section.name().to_string()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn strip_keeps_spec_and_meta() {
+        let stripped = strip_custom_sections(HELLO_WORLD).unwrap();
+        assert!(stripped.len() <= HELLO_WORLD.len());
+        assert!(custom_sections(&stripped)
+            .iter()
+            .all(|name| KEPT_CUSTOM_SECTIONS.contains(&name.as_str())));
+        let (original, stripped) = (
+            Spec::new(HELLO_WORLD).unwrap(),
+            Spec::new(&stripped).unwrap(),
+        );
+        assert_eq!(original.spec, stripped.spec);
+        assert_eq!(original.meta, stripped.meta);
+        assert_eq!(original.env_meta, stripped.env_meta);
+    }
+
+    #[cfg(feature = "opt")]
+    #[test]
+    fn optimize_keeps_spec() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let wasm = dir.path().join("hello_world.wasm");
+        fs::write(&wasm, HELLO_WORLD).unwrap();
+        let wasm_out = dir.path().join("out.wasm");
+        Cmd {
+            wasm: wasm::Args { wasm },
+            wasm_out: Some(wasm_out.clone()),
+        }
+        .run()
+        .unwrap();
+        let optimized = fs::read(wasm_out).unwrap();
+        assert!(optimized.len() <= HELLO_WORLD.len());
+        assert_eq!(
+            soroban_spec::read::from_wasm(&optimized).unwrap(),
+            soroban_spec::read::from_wasm(HELLO_WORLD).unwrap()
+        );
+    }
+}
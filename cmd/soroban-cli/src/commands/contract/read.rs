@@ -1,6 +1,6 @@
 use std::{
     fmt::Debug,
-    io::{self, stdout},
+    io::{self, stdout, IsTerminal, Write},
 };
 
 use clap::{command, Parser, ValueEnum};
@@ -11,14 +11,18 @@ use soroban_env_host::{
     },
     HostError,
 };
+use soroban_spec_tools::{BytesFormat, Spec};
+use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
 
 use crate::{
     commands::{
         config::{self, locator},
         global, NetworkRunnable,
     },
-    key,
-    rpc::{self, Client, FullLedgerEntries, FullLedgerEntry},
+    get_spec::get_remote_contract_spec,
+    key, msgpack,
+    rpc::{self, FullLedgerEntries, FullLedgerEntry},
+    rpc_ext::{self, ClientExt, ContractStorageEntry},
 };
 
 #[derive(Parser, Debug, Clone)]
@@ -27,6 +31,22 @@ pub struct Cmd {
     /// Type of output to generate
     #[arg(long, value_enum, default_value("string"))]
     pub output: Output,
+    /// Layout of the output. `pretty` and `json` decode keys and values with the contract's spec
+    /// when it can be fetched
+    #[arg(long, value_enum, default_value("csv"))]
+    pub format: Format,
+    /// Read every storage entry that can be discovered: the contract instance's storage plus any
+    /// entries for the provided keys. Adds a durability column to the output
+    #[arg(
+        long,
+        conflicts_with = "wasm",
+        conflicts_with = "wasm_hash",
+        conflicts_with = "key_file"
+    )]
+    pub all: bool,
+    /// Prefix hex-encoded `Bytes` values in `string` output with `0x`
+    #[arg(long)]
+    pub hex_prefix: bool,
     #[command(flatten)]
     pub key: key::Args,
     #[command(flatten)]
@@ -41,6 +61,18 @@ pub enum Output {
     Json,
     /// XDR
     Xdr,
+    /// Base64-encoded MessagePack, keeping integers that fit in 64 bits as integers
+    Msgpack,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, ValueEnum)]
+pub enum Format {
+    /// One CSV row per entry, rendered according to `--output`
+    Csv,
+    /// Colorful, human-oriented console output
+    Pretty,
+    /// JSON array of entries
+    Json,
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -76,7 +108,9 @@ pub enum Error {
     #[error("either `--key` or `--key-xdr` are required when querying a network")]
     KeyIsRequired,
     #[error(transparent)]
-    Rpc(#[from] rpc::Error),
+    Rpc(rpc::Error),
+    #[error(transparent)]
+    RpcError(#[from] crate::rpc_ext::RpcError),
     #[error(transparent)]
     Xdr(#[from] XdrError),
     #[error(transparent)]
@@ -91,14 +125,235 @@ pub enum Error {
     OnlyDataAllowed,
     #[error(transparent)]
     Locator(#[from] locator::Error),
+    #[error(transparent)]
+    RpcExt(#[from] rpc_ext::Error),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Msgpack(#[from] msgpack::Error),
+}
+
+impl From<rpc::Error> for Error {
+    fn from(e: rpc::Error) -> Self {
+        crate::rpc_ext::RpcError::from_rpc(e).map_or_else(Error::Rpc, Error::RpcError)
+    }
 }
 
 impl Cmd {
-    pub async fn run(&self) -> Result<(), Error> {
-        let entries = self.run_against_rpc_server(None, None).await?;
+    pub async fn run(&self, global_args: &global::Args) -> Result<(), Error> {
+        if self.format != Format::Csv {
+            let entries = if self.all {
+                self.get_storage(Some(global_args), None).await?
+            } else {
+                storage_entries(self.run_against_rpc_server(Some(global_args), None).await?)?
+            };
+            let spec = self.get_spec(Some(global_args)).await;
+            return self.output_decoded(&entries, spec.as_ref());
+        }
+        if self.all {
+            let entries = self.get_storage(Some(global_args), None).await?;
+            return self.output_storage(&entries);
+        }
+        let entries = self.run_against_rpc_server(Some(global_args), None).await?;
         self.output_entries(&entries)
     }
 
+    fn bytes_format(&self) -> BytesFormat {
+        if self.hex_prefix {
+            BytesFormat::PrefixedHex
+        } else {
+            BytesFormat::Hex
+        }
+    }
+
+    pub async fn get_storage(
+        &self,
+        global_args: Option<&global::Args>,
+        config: Option<&config::Args>,
+    ) -> Result<Vec<ContractStorageEntry>, Error> {
+        let config = config.unwrap_or(&self.config);
+        let network = config.get_network().await?;
+        tracing::trace!(?network);
+        let client = network.rpc_client()?;
+        client
+            .verify_network_if_strict(&network.network_passphrase, global_args)
+            .await?;
+        let contract = config.locator.resolve_contract_id(
+            self.key.contract_id.as_ref().unwrap(),
+            &network.network_passphrase,
+        )?;
+        let keys = self.key.parse_keys(contract)?;
+        Ok(client.get_contract_storage(&contract.0, &keys).await?)
+    }
+
+    /// The contract's spec, or `None` if it can't be fetched, in which case values are output
+    /// without it
+    async fn get_spec(&self, global_args: Option<&global::Args>) -> Option<Spec> {
+        let network = self.config.get_network().await.ok()?;
+        let contract = self
+            .config
+            .locator
+            .resolve_contract_id(self.key.contract_id.as_ref()?, &network.network_passphrase)
+            .ok()?;
+        match get_remote_contract_spec(
+            &contract.0,
+            &self.config.locator,
+            &self.config.network,
+            global_args,
+            Some(&self.config),
+        )
+        .await
+        {
+            Ok(entries) => Some(Spec(Some(entries))),
+            Err(e) => {
+                tracing::debug!("cannot fetch the contract spec, decoding without it: {e}");
+                None
+            }
+        }
+    }
+
+    fn decode(&self, spec: Option<&Spec>, val: &ScVal) -> Result<serde_json::Value, Error> {
+        let bytes = self.bytes_format();
+        match spec.and_then(|spec| Some((spec, spec.infer_type(val)?))) {
+            Some((spec, type_)) => spec.xdr_to_json_with(val, &type_, bytes),
+            None => soroban_spec_tools::to_json_with(val, bytes),
+        }
+        .map_err(|error| Error::CannotPrintResult {
+            result: val.clone(),
+            error,
+        })
+    }
+
+    fn output_decoded(
+        &self,
+        entries: &[ContractStorageEntry],
+        spec: Option<&Spec>,
+    ) -> Result<(), Error> {
+        if entries.is_empty() {
+            return Err(Error::NoContractDataEntryFoundForContractID);
+        }
+        if self.format == Format::Json {
+            let entries = entries
+                .iter()
+                .map(|entry| {
+                    Ok(serde_json::json!({
+                        "key": self.decode(spec, &entry.key)?,
+                        "value": self.decode(spec, &entry.val)?,
+                        "durability": entry.durability.to_string(),
+                        "last_modified_ledger": entry.last_modified_ledger,
+                        "live_until_ledger": entry.live_until_ledger_seq,
+                    }))
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+            println!("{}", serde_json::to_string_pretty(&entries)?);
+            return Ok(());
+        }
+        let color = if stdout().is_terminal() {
+            ColorChoice::Auto
+        } else {
+            ColorChoice::Never
+        };
+        let mut out = StandardStream::stdout(color);
+        for entry in entries {
+            // Symbols read better without their quotes
+            let key = match self.decode(spec, &entry.key)? {
+                serde_json::Value::String(s) => s,
+                key => key.to_string(),
+            };
+            out.set_color(ColorSpec::new().set_bold(true))?;
+            write!(out, "{key}")?;
+            out.reset()?;
+            writeln!(out, " = {}", self.decode(spec, &entry.val)?)?;
+            out.set_color(ColorSpec::new().set_fg(Some(Color::Cyan)))?;
+            writeln!(
+                out,
+                "  {}, live until ledger {}, last modified in ledger {}",
+                entry.durability, entry.live_until_ledger_seq, entry.last_modified_ledger
+            )?;
+            out.reset()?;
+        }
+        Ok(())
+    }
+
+    fn output_storage(&self, entries: &[ContractStorageEntry]) -> Result<(), Error> {
+        if entries.is_empty() {
+            return Err(Error::NoContractDataEntryFoundForContractID);
+        }
+        tracing::trace!("{entries:#?}");
+        let mut out = csv::Writer::from_writer(stdout());
+        for ContractStorageEntry {
+            key,
+            val,
+            durability,
+            live_until_ledger_seq,
+            last_modified_ledger,
+        } in entries
+        {
+            let [key, val] = self.format_key_val(key, val)?;
+            let (last_modified_ledger, live_until_ledger_seq) = match self.output {
+                Output::Xdr => (
+                    last_modified_ledger.to_xdr_base64(Limits::none())?,
+                    live_until_ledger_seq.to_xdr_base64(Limits::none())?,
+                ),
+                Output::String | Output::Json | Output::Msgpack => (
+                    last_modified_ledger.to_string(),
+                    live_until_ledger_seq.to_string(),
+                ),
+            };
+            out.write_record([
+                key,
+                val,
+                durability.to_string(),
+                last_modified_ledger,
+                live_until_ledger_seq,
+            ])
+            .map_err(|e| Error::CannotPrintAsCsv { error: e })?;
+        }
+        out.flush()
+            .map_err(|e| Error::CannotPrintFlush { error: e })?;
+        Ok(())
+    }
+
+    fn format_key_val(&self, key: &ScVal, val: &ScVal) -> Result<[String; 2], Error> {
+        Ok(match self.output {
+            Output::String => [
+                soroban_spec_tools::to_string_with(key, self.bytes_format()).map_err(|e| {
+                    Error::CannotPrintResult {
+                        result: key.clone(),
+                        error: e,
+                    }
+                })?,
+                soroban_spec_tools::to_string_with(val, self.bytes_format()).map_err(|e| {
+                    Error::CannotPrintResult {
+                        result: val.clone(),
+                        error: e,
+                    }
+                })?,
+            ],
+            Output::Json => [
+                serde_json::to_string_pretty(&key).map_err(|error| {
+                    Error::CannotPrintJsonResult {
+                        result: key.clone(),
+                        error,
+                    }
+                })?,
+                serde_json::to_string_pretty(&val).map_err(|error| {
+                    Error::CannotPrintJsonResult {
+                        result: val.clone(),
+                        error,
+                    }
+                })?,
+            ],
+            Output::Xdr => [
+                key.to_xdr_base64(Limits::none())?,
+                val.to_xdr_base64(Limits::none())?,
+            ],
+            Output::Msgpack => [msgpack::to_base64(key)?, msgpack::to_base64(val)?],
+        })
+    }
+
     fn output_entries(&self, entries: &FullLedgerEntries) -> Result<(), Error> {
         if entries.entries.is_empty() {
             return Err(Error::NoContractDataEntryFoundForContractID);
@@ -121,13 +376,17 @@ impl Cmd {
             };
             let output = match self.output {
                 Output::String => [
-                    soroban_spec_tools::to_string(key).map_err(|e| Error::CannotPrintResult {
-                        result: key.clone(),
-                        error: e,
+                    soroban_spec_tools::to_string_with(key, self.bytes_format()).map_err(|e| {
+                        Error::CannotPrintResult {
+                            result: key.clone(),
+                            error: e,
+                        }
                     })?,
-                    soroban_spec_tools::to_string(val).map_err(|e| Error::CannotPrintResult {
-                        result: val.clone(),
-                        error: e,
+                    soroban_spec_tools::to_string_with(val, self.bytes_format()).map_err(|e| {
+                        Error::CannotPrintResult {
+                            result: val.clone(),
+                            error: e,
+                        }
                     })?,
                     last_modified_ledger.to_string(),
                     live_until_ledger_seq.to_string(),
@@ -164,6 +423,12 @@ impl Cmd {
                     last_modified_ledger.to_xdr_base64(Limits::none())?,
                     live_until_ledger_seq.to_xdr_base64(Limits::none())?,
                 ],
+                Output::Msgpack => [
+                    msgpack::to_base64(key)?,
+                    msgpack::to_base64(val)?,
+                    last_modified_ledger.to_string(),
+                    live_until_ledger_seq.to_string(),
+                ],
             };
             out.write_record(output)
                 .map_err(|e| Error::CannotPrintAsCsv { error: e })?;
@@ -174,6 +439,39 @@ impl Cmd {
     }
 }
 
+/// Contract data entries of a ledger entries response, with the durability from their keys
+fn storage_entries(entries: FullLedgerEntries) -> Result<Vec<ContractStorageEntry>, Error> {
+    entries
+        .entries
+        .into_iter()
+        .map(
+            |FullLedgerEntry {
+                 key,
+                 val,
+                 live_until_ledger_seq,
+                 last_modified_ledger,
+             }| {
+                let (
+                    LedgerKey::ContractData(LedgerKeyContractData {
+                        key, durability, ..
+                    }),
+                    LedgerEntryData::ContractData(ContractDataEntry { val, .. }),
+                ) = (key, val)
+                else {
+                    return Err(Error::OnlyDataAllowed);
+                };
+                Ok(ContractStorageEntry {
+                    key,
+                    val,
+                    durability: durability.into(),
+                    live_until_ledger_seq,
+                    last_modified_ledger,
+                })
+            },
+        )
+        .collect()
+}
+
 #[async_trait::async_trait]
 impl NetworkRunnable for Cmd {
     type Error = Error;
@@ -181,18 +479,25 @@ impl NetworkRunnable for Cmd {
 
     async fn run_against_rpc_server(
         &self,
-        _: Option<&global::Args>,
+        global_args: Option<&global::Args>,
         config: Option<&config::Args>,
     ) -> Result<FullLedgerEntries, Error> {
         let config = config.unwrap_or(&self.config);
-        let network = config.get_network()?;
+        let network = config.get_network().await?;
         tracing::trace!(?network);
-        let client = Client::new(&network.rpc_url)?;
-        let contract = config.locator.resolve_contract_id(
-            self.key.contract_id.as_ref().unwrap(),
-            &network.network_passphrase,
-        )?;
-        let keys = self.key.parse_keys(contract)?;
+        let client = network.rpc_client()?;
+        client
+            .verify_network_if_strict(&network.network_passphrase, global_args)
+            .await?;
+        let keys = if let Some(keys) = self.key.file_keys()? {
+            keys
+        } else {
+            let contract = config.locator.resolve_contract_id(
+                self.key.contract_id.as_ref().unwrap(),
+                &network.network_passphrase,
+            )?;
+            self.key.parse_keys(contract)?
+        };
         Ok(client.get_full_ledger_entries(&keys).await?)
     }
 }
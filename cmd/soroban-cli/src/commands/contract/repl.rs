@@ -0,0 +1,216 @@
+use std::{collections::BTreeMap, ffi::OsString};
+
+use clap::{arg, command, Parser};
+use rustyline::{
+    completion::Completer, error::ReadlineError, highlight::Highlighter, hint::Hinter,
+    history::DefaultHistory, validate::Validator, Context, Editor, Helper,
+};
+use soroban_spec_tools::Spec;
+
+use crate::commands::{
+    config::{self, locator},
+    global,
+    txn_result::{TxnEnvelopeResult, TxnResult},
+};
+use crate::get_spec::{self, get_remote_contract_spec};
+use crate::xdr::{self, Limits, WriteXdr};
+
+use super::invoke;
+
+#[derive(Parser, Debug, Clone)]
+#[group(skip)]
+pub struct Cmd {
+    /// Contract ID to invoke
+    #[arg(long = "id", env = "STELLAR_CONTRACT_ID")]
+    pub contract_id: String,
+    /// View the results simulating and do not sign and submit transactions
+    #[arg(long, env = "STELLAR_INVOKE_VIEW")]
+    pub is_view: bool,
+    #[command(flatten)]
+    pub config: config::Args,
+    #[command(flatten)]
+    pub fee: crate::fee::Args,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Config(#[from] config::Error),
+    #[error(transparent)]
+    Locator(#[from] locator::Error),
+    #[error(transparent)]
+    GetSpec(#[from] get_spec::Error),
+    #[error(transparent)]
+    Invoke(#[from] invoke::Error),
+    #[error(transparent)]
+    Spec(#[from] soroban_spec_tools::Error),
+    #[error(transparent)]
+    Xdr(#[from] xdr::Error),
+    #[error(transparent)]
+    Readline(#[from] ReadlineError),
+    #[error("cannot parse line, check the quoting: {0}")]
+    CannotSplitLine(String),
+}
+
+impl Cmd {
+    pub async fn run(&self, global_args: &global::Args) -> Result<(), Error> {
+        let network = self.config.get_network().await?;
+        let contract_id = self
+            .config
+            .locator
+            .resolve_contract_id(&self.contract_id, &network.network_passphrase)?
+            .0;
+        let spec_entries = get_remote_contract_spec(
+            &contract_id,
+            &self.config.locator,
+            &self.config.network,
+            Some(global_args),
+            Some(&self.config),
+        )
+        .await?;
+        let spec = Spec(Some(spec_entries.clone()));
+        let contract_cmd = invoke::build_contract_cmd(&self.contract_id, &spec)?;
+
+        let mut editor = Editor::<FunctionCompleter, DefaultHistory>::new()?;
+        editor.set_helper(Some(FunctionCompleter::new(&spec)?));
+        loop {
+            let line = match editor.readline("> ") {
+                Ok(line) => line,
+                Err(ReadlineError::Interrupted) => continue,
+                Err(ReadlineError::Eof) => return Ok(()),
+                Err(e) => return Err(e.into()),
+            };
+            let slop = match parse_line(&line) {
+                Ok(Some(slop)) => slop,
+                Ok(None) => continue,
+                Err(e) => {
+                    eprintln!("{e}");
+                    continue;
+                }
+            };
+            editor.add_history_entry(line.as_str())?;
+            // Check the arguments here, the implicit CLI exits the process on bad input
+            if let Err(e) = contract_cmd.clone().try_get_matches_from(&slop) {
+                eprintln!("{e}");
+                continue;
+            }
+            let cmd = invoke::Cmd {
+                contract_id: self.contract_id.clone(),
+                spec: Some(spec_entries.clone()),
+                is_view: self.is_view,
+                slop,
+                config: self.config.clone(),
+                fee: self.fee.clone(),
+                ..Default::default()
+            };
+            match cmd.invoke(global_args).await.map(TxnResult::to_envelope) {
+                Ok(TxnEnvelopeResult::TxnEnvelope(tx)) => {
+                    println!("{}", tx.to_xdr_base64(Limits::none())?);
+                }
+                Ok(TxnEnvelopeResult::Res(output)) => println!("{output}"),
+                Err(e) => eprintln!("error: {e}"),
+            }
+        }
+    }
+}
+
+/// Turn a `funcname arg=val ...` line into the slop of the contract's implicit CLI
+pub fn parse_line(line: &str) -> Result<Option<Vec<OsString>>, Error> {
+    let words = shlex::split(line).ok_or_else(|| Error::CannotSplitLine(line.to_string()))?;
+    let mut words = words.into_iter();
+    let Some(function) = words.next() else {
+        return Ok(None);
+    };
+    let mut slop = vec![OsString::from(function)];
+    slop.extend(words.map(|word| {
+        if word.starts_with("--") {
+            word.into()
+        } else {
+            format!("--{word}").into()
+        }
+    }));
+    Ok(Some(slop))
+}
+
+/// Completes function names, and the argument names of the function once one is typed
+pub struct FunctionCompleter {
+    functions: BTreeMap<String, Vec<String>>,
+}
+
+impl FunctionCompleter {
+    pub fn new(spec: &Spec) -> Result<Self, Error> {
+        let functions = spec
+            .find_functions()?
+            .map(|f| {
+                let inputs = f
+                    .inputs
+                    .iter()
+                    .map(|i| i.name.to_utf8_string_lossy())
+                    .collect();
+                (f.name.to_utf8_string_lossy(), inputs)
+            })
+            .collect();
+        Ok(Self { functions })
+    }
+}
+
+impl Completer for FunctionCompleter {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        let line = &line[..pos];
+        let start = line.rfind(char::is_whitespace).map_or(0, |i| i + 1);
+        let word = &line[start..];
+        let candidates = if start == 0 {
+            self.functions
+                .keys()
+                .filter(|name| name.starts_with(word))
+                .cloned()
+                .collect()
+        } else {
+            let function = line.split_whitespace().next().unwrap_or_default();
+            self.functions
+                .get(function)
+                .into_iter()
+                .flatten()
+                .filter(|name| name.starts_with(word))
+                .map(|name| format!("{name}="))
+                .collect()
+        };
+        Ok((start, candidates))
+    }
+}
+
+impl Hinter for FunctionCompleter {
+    type Hint = String;
+}
+
+impl Highlighter for FunctionCompleter {}
+
+impl Validator for FunctionCompleter {}
+
+impl Helper for FunctionCompleter {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_lines() {
+        assert_eq!(parse_line("  ").unwrap(), None);
+        assert_eq!(
+            parse_line(r#"hello world=there --flag 'json={"a": 1}'"#).unwrap(),
+            Some(
+                ["hello", "--world=there", "--flag", r#"--json={"a": 1}"#]
+                    .map(OsString::from)
+                    .to_vec()
+            )
+        );
+        assert!(parse_line("hello world='there").is_err());
+    }
+}
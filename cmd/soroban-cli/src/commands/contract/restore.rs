@@ -1,11 +1,14 @@
-use std::{fmt::Debug, path::Path, str::FromStr};
+use std::{collections::HashMap, fmt::Debug, path::Path, str::FromStr};
 
 use clap::{command, Parser};
+use sha2::{Digest, Sha256};
 use soroban_env_host::xdr::{
-    Error as XdrError, ExtensionPoint, LedgerEntry, LedgerEntryChange, LedgerEntryData,
-    LedgerFootprint, Limits, Memo, MuxedAccount, Operation, OperationBody, OperationMeta,
-    Preconditions, RestoreFootprintOp, SequenceNumber, SorobanResources, SorobanTransactionData,
-    Transaction, TransactionExt, TransactionMeta, TransactionMetaV3, TtlEntry, Uint256, WriteXdr,
+    ContractDataDurability, ContractDataEntry, ContractExecutable, Error as XdrError,
+    ExtensionPoint, Hash, LedgerEntry, LedgerEntryChange, LedgerEntryData, LedgerFootprint,
+    LedgerKey, LedgerKeyContractCode, LedgerKeyContractData, Limits, Memo, MuxedAccount, Operation,
+    OperationBody, OperationMeta, Preconditions, RestoreFootprintOp, ScAddress, ScContractInstance,
+    ScVal, SequenceNumber, SorobanResources, SorobanTransactionData, Transaction, TransactionExt,
+    TransactionMeta, TransactionMetaV3, TtlEntry, Uint256, WriteXdr,
 };
 use stellar_strkey::DecodeError;
 
@@ -17,8 +20,8 @@ use crate::{
         txn_result::{TxnEnvelopeResult, TxnResult},
         NetworkRunnable,
     },
-    key,
-    rpc::{self, Client},
+    key, rpc,
+    rpc_ext::{self, Client, ClientExt},
     wasm, Pwd,
 };
 
@@ -33,6 +36,14 @@ pub struct Cmd {
     /// Only print the new Time To Live ledger
     #[arg(long)]
     pub ttl_ledger_only: bool,
+    /// Restore whichever of the contract's instance, its Wasm code and the `--key` entries are
+    /// archived, in one transaction, instead of restoring the given keys as is
+    #[arg(
+        long,
+        requires = "contract_id",
+        conflicts_with_all = ["wasm", "wasm_hash", "key_file", "ledgers_to_extend"]
+    )]
+    pub auto: bool,
     #[command(flatten)]
     pub config: config::Args,
     #[command(flatten)]
@@ -73,12 +84,16 @@ pub enum Error {
     Xdr(#[from] XdrError),
     #[error("Ledger entry not found")]
     LedgerEntryNotFound,
+    #[error("ledger entry {0} is still archived after restoring it")]
+    EntryNotRestored(String),
     #[error(transparent)]
     Locator(#[from] locator::Error),
     #[error("missing operation result")]
     MissingOperationResult,
     #[error(transparent)]
-    Rpc(#[from] rpc::Error),
+    Rpc(rpc::Error),
+    #[error(transparent)]
+    RpcError(#[from] crate::rpc_ext::RpcError),
     #[error(transparent)]
     Wasm(#[from] wasm::Error),
     #[error(transparent)]
@@ -89,13 +104,27 @@ pub enum Error {
     Data(#[from] data::Error),
     #[error(transparent)]
     Network(#[from] network::Error),
+    #[error(transparent)]
+    RpcExt(#[from] rpc_ext::Error),
+}
+
+impl From<rpc::Error> for Error {
+    fn from(e: rpc::Error) -> Self {
+        crate::rpc_ext::RpcError::from_rpc(e).map_or_else(Error::Rpc, Error::RpcError)
+    }
 }
 
 impl Cmd {
     #[allow(clippy::too_many_lines)]
-    pub async fn run(&self) -> Result<(), Error> {
-        let res = self.run_against_rpc_server(None, None).await?.to_envelope();
-        let expiration_ledger_seq = match res {
+    pub async fn run(&self, global_args: &global::Args) -> Result<(), Error> {
+        if self.auto {
+            return self.run_auto(global_args).await;
+        }
+        let res = self
+            .run_against_rpc_server(Some(global_args), None)
+            .await?
+            .to_envelope();
+        let ttl_ledgers = match res {
             TxnEnvelopeResult::TxnEnvelope(tx) => {
                 println!("{}", tx.to_xdr_base64(Limits::none())?);
                 return Ok(());
@@ -110,45 +139,255 @@ impl Cmd {
                 fee: self.fee.clone(),
                 ttl_ledger_only: false,
             }
-            .run()
+            .run(global_args)
             .await?;
-        } else {
-            println!("New ttl ledger: {expiration_ledger_seq}");
+        } else if self.key.key_file.is_some() {
+            let keys = self.key.file_keys()?.unwrap_or_default();
+            self.print_ttl_ledgers(&keys, &ttl_ledgers)?;
+        } else if let Some(ttl_ledger) = ttl_ledgers.first() {
+            if self.ttl_ledger_only {
+                println!("{ttl_ledger}");
+            } else {
+                println!("New ttl ledger: {ttl_ledger}");
+            }
         }
 
         Ok(())
     }
+
+    fn print_ttl_ledgers(&self, keys: &[LedgerKey], ttl_ledgers: &[u32]) -> Result<(), Error> {
+        for (key, ttl_ledger) in keys.iter().zip(ttl_ledgers) {
+            if self.ttl_ledger_only {
+                println!("{ttl_ledger}");
+            } else {
+                println!(
+                    "New ttl ledger: {ttl_ledger} ({})",
+                    key.to_xdr_base64(Limits::none())?
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Find the archived entries among the contract's instance, its Wasm code and `--key`, and
+    /// restore them in one simulated transaction
+    async fn run_auto(&self, global_args: &global::Args) -> Result<(), Error> {
+        let config = &self.config;
+        let network = config.get_network().await?;
+        let contract = config.locator.resolve_contract_id(
+            self.key.contract_id.as_ref().unwrap(),
+            &network.network_passphrase,
+        )?;
+        let client = network.rpc_client()?;
+        client
+            .verify_network_if_strict(&network.network_passphrase, Some(global_args))
+            .await?;
+        let contract_id = contract.0;
+        let keys = archived_keys(&client, &contract_id, self.key.parse_keys(contract)?).await?;
+        if keys.is_empty() {
+            eprintln!("No archived entries to restore");
+            return Ok(());
+        }
+
+        let key = config.public_key().await?;
+        let public_strkey = key.to_string();
+        let sequence: i64 = client.get_account(&public_strkey).await?.seq_num.into();
+        let tx = Transaction {
+            source_account: MuxedAccount::Ed25519(Uint256(key.0)),
+            fee: self.fee.fee,
+            seq_num: SequenceNumber(sequence + 1),
+            cond: Preconditions::None,
+            memo: Memo::None,
+            operations: vec![Operation {
+                source_account: None,
+                body: OperationBody::RestoreFootprint(RestoreFootprintOp {
+                    ext: ExtensionPoint::V0,
+                }),
+            }]
+            .try_into()?,
+            ext: TransactionExt::V1(SorobanTransactionData {
+                ext: ExtensionPoint::V0,
+                resources: SorobanResources {
+                    footprint: LedgerFootprint {
+                        read_only: vec![].try_into()?,
+                        read_write: keys.clone().try_into()?,
+                    },
+                    instructions: self.fee.instructions.unwrap_or_default(),
+                    read_bytes: 0,
+                    write_bytes: 0,
+                },
+                resource_fee: 0,
+            }),
+        };
+        let tx = client
+            .simulate_and_assemble_transaction(&tx)
+            .await?
+            .transaction()
+            .clone();
+        if self.fee.build_only {
+            println!("{}", tx.to_xdr_base64(Limits::none())?);
+            return Ok(());
+        }
+        eprintln!(
+            "Restoring {} archived entries, fee: {} stroops",
+            keys.len(),
+            tx.fee
+        );
+        let res = config
+            .sign_and_send(
+                &client,
+                &network,
+                tx,
+                !self.fee.no_seq_retry,
+                Some(global_args),
+            )
+            .await?;
+        if !global_args.no_cache {
+            data::write(res.clone().try_into()?, &network.rpc_uri()?)?;
+        }
+        let TransactionMeta::V3(TransactionMetaV3 { operations, .. }) = res
+            .result_meta
+            .as_ref()
+            .ok_or(Error::MissingOperationResult)?
+        else {
+            return Err(Error::LedgerEntryNotFound);
+        };
+        let op = operations.first().ok_or(Error::MissingOperationResult)?;
+        let ttl_ledgers = ttl_ledgers(&client, op, &keys).await?;
+        self.print_ttl_ledgers(&keys, &ttl_ledgers)
+    }
+}
+
+/// The live-until ledger of each of `keys` after the restore `op`. Keys that weren't archived are
+/// left untouched by it, so their TTL is fetched instead.
+async fn ttl_ledgers(
+    client: &Client,
+    op: &OperationMeta,
+    keys: &[LedgerKey],
+) -> Result<Vec<u32>, Error> {
+    let restored = ttl_changes(op);
+    let mut ttl_ledgers = Vec::with_capacity(keys.len());
+    for key in keys {
+        let key_hash: [u8; 32] = Sha256::digest(key.to_xdr(Limits::none())?).into();
+        if let Some(ttl_ledger) = restored.get(&key_hash) {
+            ttl_ledgers.push(*ttl_ledger);
+            continue;
+        }
+        let entries = client
+            .get_full_ledger_entries(std::slice::from_ref(key))
+            .await?;
+        let entry = entries.entries.first().ok_or(Error::LedgerEntryNotFound)?;
+        if i64::from(entry.live_until_ledger_seq) < entries.latest_ledger {
+            return Err(Error::EntryNotRestored(key.to_xdr_base64(Limits::none())?));
+        }
+        ttl_ledgers.push(entry.live_until_ledger_seq);
+    }
+    Ok(ttl_ledgers)
+}
+
+/// The keys among `keys`, the contract's instance and the Wasm code the instance runs, whose
+/// entries exist but are archived
+async fn archived_keys(
+    client: &Client,
+    contract_id: &[u8; 32],
+    mut keys: Vec<LedgerKey>,
+) -> Result<Vec<LedgerKey>, Error> {
+    let instance_key = LedgerKey::ContractData(LedgerKeyContractData {
+        contract: ScAddress::Contract(Hash(*contract_id)),
+        key: ScVal::LedgerKeyContractInstance,
+        durability: ContractDataDurability::Persistent,
+    });
+    if !keys.contains(&instance_key) {
+        keys.insert(0, instance_key);
+    }
+    let mut entries = client.get_full_ledger_entries(&keys).await?;
+    let code_key = entries.entries.iter().find_map(|entry| match &entry.val {
+        LedgerEntryData::ContractData(ContractDataEntry {
+            val:
+                ScVal::ContractInstance(ScContractInstance {
+                    executable: ContractExecutable::Wasm(hash),
+                    ..
+                }),
+            ..
+        }) => Some(LedgerKey::ContractCode(LedgerKeyContractCode {
+            hash: hash.clone(),
+        })),
+        _ => None,
+    });
+    if let Some(code_key) = code_key {
+        let code = client.get_full_ledger_entries(&[code_key]).await?;
+        entries.entries.extend(code.entries);
+    }
+    let latest_ledger = entries.latest_ledger;
+    Ok(entries
+        .entries
+        .into_iter()
+        .filter(|entry| i64::from(entry.live_until_ledger_seq) < latest_ledger)
+        .map(|entry| entry.key)
+        .collect())
+}
+
+/// New live-until ledgers of an operation's TTL changes, by the hash of the key they belong to
+fn ttl_changes(op: &OperationMeta) -> HashMap<[u8; 32], u32> {
+    op.changes
+        .iter()
+        .filter_map(|change| match change {
+            LedgerEntryChange::Updated(LedgerEntry {
+                data:
+                    LedgerEntryData::Ttl(TtlEntry {
+                        key_hash,
+                        live_until_ledger_seq,
+                    }),
+                ..
+            })
+            | LedgerEntryChange::Created(LedgerEntry {
+                data:
+                    LedgerEntryData::Ttl(TtlEntry {
+                        key_hash,
+                        live_until_ledger_seq,
+                    }),
+                ..
+            }) => Some((key_hash.0, *live_until_ledger_seq)),
+            _ => None,
+        })
+        .collect()
 }
 
 #[async_trait::async_trait]
 impl NetworkRunnable for Cmd {
     type Error = Error;
-    type Result = TxnResult<u32>;
+    type Result = TxnResult<Vec<u32>>;
 
     async fn run_against_rpc_server(
         &self,
         args: Option<&global::Args>,
         config: Option<&config::Args>,
-    ) -> Result<TxnResult<u32>, Error> {
+    ) -> Result<TxnResult<Vec<u32>>, Error> {
         let config = config.unwrap_or(&self.config);
-        let network = config.get_network()?;
+        let network = config.get_network().await?;
         tracing::trace!(?network);
-        let contract = config.locator.resolve_contract_id(
-            self.key.contract_id.as_ref().unwrap(),
-            &network.network_passphrase,
-        )?;
-        let entry_keys = self.key.parse_keys(contract)?;
-        let client = Client::new(&network.rpc_url)?;
-        let key = config.key_pair()?;
+        let entry_keys = if let Some(keys) = self.key.file_keys()? {
+            keys
+        } else {
+            let contract = config.locator.resolve_contract_id(
+                self.key.contract_id.as_ref().unwrap(),
+                &network.network_passphrase,
+            )?;
+            self.key.parse_keys(contract)?
+        };
+        let client = network.rpc_client()?;
+        client
+            .verify_network_if_strict(&network.network_passphrase, args)
+            .await?;
+        let key = config.public_key().await?;
 
         // Get the account sequence number
-        let public_strkey =
-            stellar_strkey::ed25519::PublicKey(key.verifying_key().to_bytes()).to_string();
+        let public_strkey = key.to_string();
         let account_details = client.get_account(&public_strkey).await?;
         let sequence: i64 = account_details.seq_num.into();
 
         let tx = Transaction {
-            source_account: MuxedAccount::Ed25519(Uint256(key.verifying_key().to_bytes())),
+            source_account: MuxedAccount::Ed25519(Uint256(key.0)),
             fee: self.fee.fee,
             seq_num: SequenceNumber(sequence + 1),
             cond: Preconditions::None,
@@ -165,7 +404,7 @@ impl NetworkRunnable for Cmd {
                 resources: SorobanResources {
                     footprint: LedgerFootprint {
                         read_only: vec![].try_into()?,
-                        read_write: entry_keys.try_into()?,
+                        read_write: entry_keys.clone().try_into()?,
                     },
                     instructions: self.fee.instructions.unwrap_or_default(),
                     read_bytes: 0,
@@ -177,8 +416,8 @@ impl NetworkRunnable for Cmd {
         if self.fee.build_only {
             return Ok(TxnResult::Txn(tx));
         }
-        let res = client
-            .send_transaction_polling(&config.sign_with_local_key(tx).await?)
+        let res = config
+            .sign_and_send(&client, &network, tx, !self.fee.no_seq_retry, args)
             .await?;
         if args.map_or(true, |a| !a.no_cache) {
             data::write(res.clone().try_into()?, &network.rpc_uri()?)?;
@@ -200,44 +439,242 @@ impl NetworkRunnable for Cmd {
         };
         tracing::debug!("Operations:\nlen:{}\n{operations:#?}", operations.len());
 
-        // Simply check if there is exactly one entry here. We only support extending a single
-        // entry via this command (which we should fix separately, but).
-        if operations.len() == 0 {
-            return Err(Error::LedgerEntryNotFound);
-        }
-
-        if operations.len() != 1 {
-            tracing::warn!(
-                "Unexpected number of operations: {}. Currently only handle one.",
-                operations[0].changes.len()
-            );
-        }
-        Ok(TxnResult::Res(
-            parse_operations(operations).ok_or(Error::MissingOperationResult)?,
-        ))
+        let op = operations.first().ok_or(Error::MissingOperationResult)?;
+        Ok(TxnResult::Res(ttl_ledgers(&client, op, &entry_keys).await?))
     }
 }
 
-fn parse_operations(ops: &[OperationMeta]) -> Option<u32> {
-    ops.first().and_then(|op| {
-        op.changes.iter().find_map(|entry| match entry {
-            LedgerEntryChange::Updated(LedgerEntry {
-                data:
-                    LedgerEntryData::Ttl(TtlEntry {
-                        live_until_ledger_seq,
-                        ..
-                    }),
-                ..
-            })
-            | LedgerEntryChange::Created(LedgerEntry {
-                data:
-                    LedgerEntryData::Ttl(TtlEntry {
-                        live_until_ledger_seq,
-                        ..
+#[cfg(test)]
+mod test {
+    use httpmock::prelude::*;
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        commands::contract::Durability,
+        test_utils::rpc_response,
+        xdr::{
+            AccountEntry, AccountEntryExt, AccountId, BytesM, ContractCodeEntry,
+            ContractCodeEntryExt, LedgerEntryChanges, LedgerEntryExt, LedgerKeyAccount, PublicKey,
+            String32, Thresholds, TransactionEnvelope, TransactionResult, TransactionResultExt,
+            TransactionResultResult, VecM,
+        },
+    };
+
+    const PASSPHRASE: &str = "Test SDF Network ; September 2015";
+    const SECRET: &str = "SBFGFF27Y64ZUGFAIG5AMJGQODZZKV2YQKAVUUN4HNE24XZXD2OEUVUP";
+
+    /// Serve `entry` under `key`, live until `live_until`, when `key` is requested, as of ledger 10
+    fn mock_entry(server: &MockServer, key: &LedgerKey, entry: &LedgerEntryData, live_until: u32) {
+        let key = key.to_xdr_base64(Limits::none()).unwrap();
+        server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .json_body_partial(r#"{ "method": "getLedgerEntries" }"#)
+                .body_contains(&key);
+            then.status(200).json_body(rpc_response(json!({
+                "entries": [{
+                    "key": key,
+                    "xdr": entry.to_xdr_base64(Limits::none()).unwrap(),
+                    "lastModifiedLedgerSeq": 1,
+                    "liveUntilLedgerSeq": live_until
+                }],
+                "latestLedger": 10
+            })));
+        });
+    }
+
+    #[tokio::test]
+    async fn auto_restores_the_archived_instance_only() {
+        let server = MockServer::start();
+        let config = config::Args {
+            network: network::Args {
+                rpc_url: Some(server.url("/")),
+                network_passphrase: Some(PASSPHRASE.to_string()),
+                ..Default::default()
+            },
+            source_account: SECRET.to_string(),
+            ..Default::default()
+        };
+        let source = Uint256(config.key_pair().unwrap().verifying_key().to_bytes());
+        let account_id = AccountId(PublicKey::PublicKeyTypeEd25519(source.clone()));
+        mock_entry(
+            &server,
+            &LedgerKey::Account(LedgerKeyAccount {
+                account_id: account_id.clone(),
+            }),
+            &LedgerEntryData::Account(AccountEntry {
+                account_id,
+                balance: 10_000_000_000,
+                seq_num: SequenceNumber(42),
+                num_sub_entries: 0,
+                inflation_dest: None,
+                flags: 0,
+                home_domain: String32::default(),
+                thresholds: Thresholds([1, 0, 0, 0]),
+                signers: VecM::default(),
+                ext: AccountEntryExt::V0,
+            }),
+            0,
+        );
+        // The instance was archived at ledger 5, the code it runs is still live
+        let instance_key = LedgerKey::ContractData(LedgerKeyContractData {
+            contract: ScAddress::Contract(Hash([1; 32])),
+            key: ScVal::LedgerKeyContractInstance,
+            durability: ContractDataDurability::Persistent,
+        });
+        mock_entry(
+            &server,
+            &instance_key,
+            &LedgerEntryData::ContractData(ContractDataEntry {
+                ext: ExtensionPoint::V0,
+                contract: ScAddress::Contract(Hash([1; 32])),
+                key: ScVal::LedgerKeyContractInstance,
+                durability: ContractDataDurability::Persistent,
+                val: ScVal::ContractInstance(ScContractInstance {
+                    executable: ContractExecutable::Wasm(Hash([2; 32])),
+                    storage: None,
+                }),
+            }),
+            5,
+        );
+        mock_entry(
+            &server,
+            &LedgerKey::ContractCode(LedgerKeyContractCode {
+                hash: Hash([2; 32]),
+            }),
+            &LedgerEntryData::ContractCode(ContractCodeEntry {
+                ext: ContractCodeEntryExt::V0,
+                hash: Hash([2; 32]),
+                code: BytesM::default(),
+            }),
+            100,
+        );
+        let transaction_data = SorobanTransactionData {
+            ext: ExtensionPoint::V0,
+            resources: SorobanResources {
+                footprint: LedgerFootprint {
+                    read_only: VecM::default(),
+                    read_write: vec![instance_key.clone()].try_into().unwrap(),
+                },
+                instructions: 0,
+                read_bytes: 100,
+                write_bytes: 100,
+            },
+            resource_fee: 500,
+        };
+        // Only the instance is in the footprint of the simulated restore
+        let restore_tx: TransactionEnvelope = Transaction {
+            source_account: MuxedAccount::Ed25519(source),
+            fee: 100,
+            seq_num: SequenceNumber(43),
+            cond: Preconditions::None,
+            memo: Memo::None,
+            operations: vec![Operation {
+                source_account: None,
+                body: OperationBody::RestoreFootprint(RestoreFootprintOp {
+                    ext: ExtensionPoint::V0,
+                }),
+            }]
+            .try_into()
+            .unwrap(),
+            ext: TransactionExt::V1(SorobanTransactionData {
+                resources: SorobanResources {
+                    instructions: 0,
+                    read_bytes: 0,
+                    write_bytes: 0,
+                    ..transaction_data.resources.clone()
+                },
+                resource_fee: 0,
+                ..transaction_data.clone()
+            }),
+        }
+        .into();
+        let simulate = server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .json_body_partial(r#"{ "method": "simulateTransaction" }"#)
+                .body_contains(restore_tx.to_xdr_base64(Limits::none()).unwrap());
+            then.status(200).json_body(rpc_response(json!({
+                "transactionData": transaction_data.to_xdr_base64(Limits::none()).unwrap(),
+                "minResourceFee": "500",
+                "cost": { "cpuInsns": "0", "memBytes": "0" },
+                "latestLedger": 10
+            })));
+        });
+        let send = server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .json_body_partial(r#"{ "method": "sendTransaction" }"#);
+            then.status(200).json_body(rpc_response(json!({
+                "status": "PENDING",
+                "hash": "00".repeat(32),
+                "latestLedger": 10,
+                "latestLedgerCloseTime": "0"
+            })));
+        });
+        let key_hash = Hash(Sha256::digest(instance_key.to_xdr(Limits::none()).unwrap()).into());
+        let meta = TransactionMeta::V3(TransactionMetaV3 {
+            ext: ExtensionPoint::V0,
+            tx_changes_before: LedgerEntryChanges::default(),
+            operations: vec![OperationMeta {
+                changes: vec![LedgerEntryChange::Updated(LedgerEntry {
+                    last_modified_ledger_seq: 11,
+                    data: LedgerEntryData::Ttl(TtlEntry {
+                        key_hash,
+                        live_until_ledger_seq: 1000,
                     }),
-                ..
-            }) => Some(*live_until_ledger_seq),
-            _ => None,
-        })
-    })
+                    ext: LedgerEntryExt::V0,
+                })]
+                .try_into()
+                .unwrap(),
+            }]
+            .try_into()
+            .unwrap(),
+            tx_changes_after: LedgerEntryChanges::default(),
+            soroban_meta: None,
+        });
+        server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .json_body_partial(r#"{ "method": "getTransaction" }"#);
+            then.status(200).json_body(rpc_response(json!({
+                "status": "SUCCESS",
+                "resultXdr": TransactionResult {
+                    fee_charged: 600,
+                    result: TransactionResultResult::TxSuccess(VecM::default()),
+                    ext: TransactionResultExt::V0,
+                }
+                .to_xdr_base64(Limits::none())
+                .unwrap(),
+                "resultMetaXdr": meta.to_xdr_base64(Limits::none()).unwrap(),
+                "latestLedger": 11
+            })));
+        });
+        let cmd = Cmd {
+            key: key::Args {
+                contract_id: Some(stellar_strkey::Contract([1; 32]).to_string()),
+                key: None,
+                key_xdr: None,
+                wasm: None,
+                wasm_hash: None,
+                key_file: None,
+                durability: Durability::Persistent,
+            },
+            ledgers_to_extend: None,
+            ttl_ledger_only: true,
+            auto: true,
+            config,
+            fee: crate::fee::Args::default(),
+        };
+        let global_args = global::Args {
+            ignore_network_check: true,
+            no_cache: true,
+            ..Default::default()
+        };
+
+        cmd.run_auto(&global_args).await.unwrap();
+        simulate.assert_hits(1);
+        send.assert_hits(1);
+    }
 }
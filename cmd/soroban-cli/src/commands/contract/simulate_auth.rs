@@ -0,0 +1,206 @@
+use clap::{arg, command, Parser};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use soroban_spec_tools::Spec;
+
+use crate::commands::{config, global};
+use crate::get_spec::{self, get_remote_contract_spec};
+use crate::rpc_ext::{self, ClientExt};
+use crate::xdr::{
+    self, Hash, HostFunction, InvokeContractArgs, InvokeHostFunctionOp, Memo, MuxedAccount,
+    Operation, OperationBody, Preconditions, ScAddress, ScSymbol, SequenceNumber,
+    SorobanAddressCredentials, SorobanAuthorizationEntry, SorobanAuthorizedFunction,
+    SorobanAuthorizedInvocation, SorobanCredentials, Transaction, TransactionExt, Uint256, VecM,
+};
+use crate::{rpc, signer};
+
+#[derive(Parser, Debug, Clone)]
+#[group(skip)]
+pub struct Cmd {
+    /// Contract ID of the custom account
+    #[arg(long = "id", env = "STELLAR_CONTRACT_ID")]
+    pub contract_id: String,
+    /// Signature to check, as JSON of the type the account's `__check_auth` takes
+    #[arg(long)]
+    pub signature: String,
+    /// Invocation to authorize, as JSON like
+    /// `{ "contract": "C...", "fn_name": "transfer", "args": [...] }`, with each argument as
+    /// JSON of the type the function takes
+    #[arg(long)]
+    pub auth_context: String,
+    #[command(flatten)]
+    pub config: config::Args,
+    #[command(flatten)]
+    pub expiration: config::SignatureExpirationArgs,
+}
+
+#[derive(Deserialize)]
+struct AuthContext {
+    contract: String,
+    fn_name: String,
+    #[serde(default)]
+    args: Vec<serde_json::Value>,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Config(#[from] config::Error),
+    #[error(transparent)]
+    Locator(#[from] config::locator::Error),
+    #[error(transparent)]
+    Rpc(rpc::Error),
+    #[error(transparent)]
+    RpcError(#[from] crate::rpc_ext::RpcError),
+    #[error(transparent)]
+    RpcExt(#[from] rpc_ext::Error),
+    #[error(transparent)]
+    GetSpec(#[from] get_spec::Error),
+    #[error(transparent)]
+    Spec(#[from] soroban_spec_tools::Error),
+    #[error(transparent)]
+    Signer(#[from] signer::Error),
+    #[error(transparent)]
+    Xdr(#[from] xdr::Error),
+    #[error("invalid --signature: {0}")]
+    InvalidSignature(serde_json::Error),
+    #[error("invalid --auth-context: {0}")]
+    InvalidAuthContext(serde_json::Error),
+    #[error("contract {0} is not a custom account, it has no __check_auth function")]
+    NotCustomAccount(String),
+    #[error("{fn_name} takes {expected} arguments, but the auth context has {found}")]
+    ArgCountMismatch {
+        fn_name: String,
+        expected: usize,
+        found: usize,
+    },
+    #[error("__check_auth failed: {0}")]
+    CheckAuthFailed(String),
+}
+
+impl From<rpc::Error> for Error {
+    fn from(e: rpc::Error) -> Self {
+        crate::rpc_ext::RpcError::from_rpc(e).map_or_else(Error::Rpc, Error::RpcError)
+    }
+}
+
+impl Cmd {
+    pub async fn run(&self, global_args: &global::Args) -> Result<(), Error> {
+        let network = self.config.get_network().await?;
+        let client = network.rpc_client()?;
+        client
+            .verify_network(&network.network_passphrase, Some(global_args))
+            .await?;
+        let resolve = |id: &str| {
+            self.config
+                .locator
+                .resolve_contract_id(id, &network.network_passphrase)
+                .map(|id| id.0)
+        };
+
+        let account = resolve(&self.contract_id)?;
+        let account_spec = self.spec(&account, global_args).await?;
+        let signature_type = account_spec
+            .find_function("__check_auth")
+            .ok()
+            .and_then(|f| f.inputs.get(1))
+            .map(|input| input.type_.clone())
+            .ok_or_else(|| Error::NotCustomAccount(self.contract_id.clone()))?;
+        let signature = account_spec.from_json(
+            &serde_json::from_str(&self.signature).map_err(Error::InvalidSignature)?,
+            &signature_type,
+        )?;
+
+        let context: AuthContext =
+            serde_json::from_str(&self.auth_context).map_err(Error::InvalidAuthContext)?;
+        let contract = resolve(&context.contract)?;
+        let spec = self.spec(&contract, global_args).await?;
+        let function = spec.find_function(&context.fn_name)?;
+        if function.inputs.len() != context.args.len() {
+            return Err(Error::ArgCountMismatch {
+                fn_name: context.fn_name,
+                expected: function.inputs.len(),
+                found: context.args.len(),
+            });
+        }
+        let args = function
+            .inputs
+            .iter()
+            .zip(&context.args)
+            .map(|(input, arg)| spec.from_json(arg, &input.type_))
+            .collect::<Result<Vec<_>, _>>()?;
+        let invocation = InvokeContractArgs {
+            contract_address: ScAddress::Contract(Hash(contract)),
+            function_name: ScSymbol(context.fn_name.as_str().try_into()?),
+            args: args.try_into()?,
+        };
+
+        // `__check_auth` cannot be invoked directly, so simulate the invocation it would authorize
+        // with the signature attached; the host then runs `__check_auth` on the payload it derives.
+        let credentials = SorobanAddressCredentials {
+            address: ScAddress::Contract(Hash(account)),
+            nonce: rand::random(),
+            signature_expiration_ledger: self.expiration.ledger(&client).await?,
+            signature,
+        };
+        let root_invocation = SorobanAuthorizedInvocation {
+            function: SorobanAuthorizedFunction::ContractFn(invocation.clone()),
+            sub_invocations: VecM::default(),
+        };
+        let network_id = Hash(Sha256::digest(network.network_passphrase.as_bytes()).into());
+        let (_, payload, _) = signer::auth_payload(
+            &credentials,
+            &root_invocation,
+            credentials.signature_expiration_ledger,
+            &network_id,
+        )?;
+        eprintln!("Signature payload: {}", hex::encode(payload));
+
+        let key = self.config.public_key().await?;
+        let sequence: i64 = client.get_account(&key.to_string()).await?.seq_num.into();
+        let tx = Transaction {
+            source_account: MuxedAccount::Ed25519(Uint256(key.0)),
+            fee: 100,
+            seq_num: SequenceNumber(sequence + 1),
+            cond: Preconditions::None,
+            memo: Memo::None,
+            operations: vec![Operation {
+                source_account: None,
+                body: OperationBody::InvokeHostFunction(InvokeHostFunctionOp {
+                    host_function: HostFunction::InvokeContract(invocation),
+                    auth: vec![SorobanAuthorizationEntry {
+                        credentials: SorobanCredentials::Address(credentials),
+                        root_invocation,
+                    }]
+                    .try_into()?,
+                }),
+            }]
+            .try_into()?,
+            ext: TransactionExt::V0,
+        };
+        match client.simulate_and_assemble_transaction(&tx).await {
+            Ok(_) => {
+                println!("__check_auth passed");
+                Ok(())
+            }
+            Err(rpc::Error::TransactionSimulationFailed(e)) => Err(Error::CheckAuthFailed(e)),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn spec(
+        &self,
+        contract_id: &[u8; 32],
+        global_args: &global::Args,
+    ) -> Result<Spec, Error> {
+        let spec = get_remote_contract_spec(
+            contract_id,
+            &self.config.locator,
+            &self.config.network,
+            Some(global_args),
+            Some(&self.config),
+        )
+        .await?;
+        Ok(Spec(Some(spec)))
+    }
+}
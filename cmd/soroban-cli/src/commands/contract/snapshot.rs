@@ -0,0 +1,193 @@
+use std::{fmt::Debug, fs, io, path::PathBuf};
+
+use clap::{arg, command, Parser};
+use serde::{Deserialize, Serialize};
+use soroban_env_host::xdr::{
+    self, ContractDataDurability, ContractDataEntry, ContractExecutable, Hash, LedgerEntry,
+    LedgerEntryData, LedgerEntryExt, LedgerKey, LedgerKeyContractCode, LedgerKeyContractData,
+    Limits, ReadXdr, ScAddress, ScContractInstance, ScVal, WriteXdr,
+};
+
+use crate::{
+    commands::{config::locator, global, network},
+    rpc::{self, FullLedgerEntry},
+    rpc_ext::{self, ClientExt},
+};
+
+#[derive(Parser, Debug, Clone)]
+#[group(skip)]
+pub struct Cmd {
+    /// Contract ID to snapshot
+    #[arg(long = "id", env = "STELLAR_CONTRACT_ID")]
+    pub contract_id: String,
+    /// Ledger key (base64-encoded `LedgerKey` XDR) of another persistent or temporary storage
+    /// entry to include. Entries in the instance's storage are always included, as part of the
+    /// instance. Can be passed multiple times
+    #[arg(long)]
+    pub ledger_key: Vec<String>,
+    /// File to write the snapshot to
+    #[arg(long)]
+    pub out: PathBuf,
+    #[command(flatten)]
+    pub locator: locator::Args,
+    #[command(flatten)]
+    pub network: network::Args,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Network(#[from] network::Error),
+    #[error(transparent)]
+    Locator(#[from] locator::Error),
+    #[error(transparent)]
+    Rpc(rpc::Error),
+    #[error(transparent)]
+    RpcError(#[from] crate::rpc_ext::RpcError),
+    #[error(transparent)]
+    RpcExt(#[from] rpc_ext::Error),
+    #[error(transparent)]
+    Xdr(#[from] xdr::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("parsing ledger key {key}: {error}")]
+    CannotParseLedgerKey { key: String, error: xdr::Error },
+    #[error("contract instance not found for {0}")]
+    MissingContractInstance(stellar_strkey::Contract),
+    #[error("writing snapshot to {0:?}: {1}")]
+    CannotWriteSnapshot(PathBuf, io::Error),
+}
+
+impl From<rpc::Error> for Error {
+    fn from(e: rpc::Error) -> Self {
+        crate::rpc_ext::RpcError::from_rpc(e).map_or_else(Error::Rpc, Error::RpcError)
+    }
+}
+
+/// A contract's ledger entries, as written by `contract snapshot`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Contract the entries belong to
+    pub contract_id: String,
+    /// Latest ledger of the network when the entries were fetched
+    pub latest_ledger: i64,
+    /// The instance entry, the entry of the Wasm code it runs, if any, and the other storage
+    /// entries that were asked for and exist
+    pub entries: Vec<SnapshotEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotEntry {
+    /// Base64-encoded `LedgerKey` XDR
+    pub key: String,
+    /// Base64-encoded `LedgerEntry` XDR
+    pub entry: String,
+    /// Last ledger the entry is live in, after which it's archived, or deleted if temporary
+    pub live_until_ledger: u32,
+}
+
+impl SnapshotEntry {
+    /// The decoded key and entry, e.g. to load them into a local ledger for tests
+    pub fn decode(&self) -> Result<(LedgerKey, LedgerEntry), xdr::Error> {
+        Ok((
+            LedgerKey::from_xdr_base64(&self.key, Limits::none())?,
+            LedgerEntry::from_xdr_base64(&self.entry, Limits::none())?,
+        ))
+    }
+}
+
+impl TryFrom<FullLedgerEntry> for SnapshotEntry {
+    type Error = xdr::Error;
+
+    fn try_from(entry: FullLedgerEntry) -> Result<Self, Self::Error> {
+        let FullLedgerEntry {
+            key,
+            val,
+            last_modified_ledger,
+            live_until_ledger_seq,
+        } = entry;
+        Ok(SnapshotEntry {
+            key: key.to_xdr_base64(Limits::none())?,
+            entry: LedgerEntry {
+                last_modified_ledger_seq: last_modified_ledger,
+                data: val,
+                ext: LedgerEntryExt::V0,
+            }
+            .to_xdr_base64(Limits::none())?,
+            live_until_ledger: live_until_ledger_seq,
+        })
+    }
+}
+
+impl Cmd {
+    pub async fn run(&self, global_args: &global::Args) -> Result<(), Error> {
+        let snapshot = self.snapshot(Some(global_args)).await?;
+        fs::write(&self.out, serde_json::to_string_pretty(&snapshot)?)
+            .map_err(|e| Error::CannotWriteSnapshot(self.out.clone(), e))?;
+        eprintln!(
+            "Wrote {} ledger entries of ledger {} to {}",
+            snapshot.entries.len(),
+            snapshot.latest_ledger,
+            self.out.display()
+        );
+        Ok(())
+    }
+
+    /// Fetch the contract's instance, then the Wasm code it runs and the `--ledger-key` entries
+    pub async fn snapshot(&self, global_args: Option<&global::Args>) -> Result<Snapshot, Error> {
+        let network = self.network.get(&self.locator).await?;
+        let client = network.rpc_client()?;
+        client
+            .verify_network_if_strict(&network.network_passphrase, global_args)
+            .await?;
+        let contract = self
+            .locator
+            .resolve_contract_id(&self.contract_id, &network.network_passphrase)?;
+        let instance_key = LedgerKey::ContractData(LedgerKeyContractData {
+            contract: ScAddress::Contract(Hash(contract.0)),
+            key: ScVal::LedgerKeyContractInstance,
+            durability: ContractDataDurability::Persistent,
+        });
+        let mut keys = vec![instance_key.clone()];
+        for key in &self.ledger_key {
+            let key = LedgerKey::from_xdr_base64(key, Limits::none()).map_err(|error| {
+                Error::CannotParseLedgerKey {
+                    key: key.clone(),
+                    error,
+                }
+            })?;
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+        let mut entries = client.get_full_ledger_entries(&keys).await?;
+        let instance = entries
+            .entries
+            .iter()
+            .find(|entry| entry.key == instance_key)
+            .ok_or(Error::MissingContractInstance(contract))?;
+        // Stellar Asset Contracts are built into the network and have no code entry
+        if let LedgerEntryData::ContractData(ContractDataEntry {
+            val:
+                ScVal::ContractInstance(ScContractInstance {
+                    executable: ContractExecutable::Wasm(hash),
+                    ..
+                }),
+            ..
+        }) = &instance.val
+        {
+            let code_key = LedgerKey::ContractCode(LedgerKeyContractCode { hash: hash.clone() });
+            let code = client.get_full_ledger_entries(&[code_key]).await?;
+            entries.entries.extend(code.entries);
+        }
+        Ok(Snapshot {
+            contract_id: contract.to_string(),
+            latest_ledger: entries.latest_ledger,
+            entries: entries
+                .entries
+                .into_iter()
+                .map(SnapshotEntry::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
@@ -0,0 +1,68 @@
+use clap::{arg, command, Parser};
+use sha2::{Digest, Sha256};
+
+use crate::commands::{config::locator, global, network};
+use crate::wasm;
+use crate::xdr::Hash;
+
+use super::{deploy::wasm::parse_wasm_hash, fetch};
+
+#[derive(Parser, Debug, Clone)]
+#[command(group(
+    clap::ArgGroup::new("expected")
+        .required(true)
+        .args(&["contract_id", "against_hash"]),
+))]
+#[group(skip)]
+pub struct Cmd {
+    #[command(flatten)]
+    pub wasm: wasm::Args,
+    /// Contract ID whose on-chain Wasm is compared with the local file
+    #[arg(long = "id")]
+    pub contract_id: Option<String>,
+    /// Hex SHA-256 hash to compare with the local file, instead of fetching a contract's Wasm
+    #[arg(long)]
+    pub against_hash: Option<String>,
+    #[command(flatten)]
+    pub locator: locator::Args,
+    #[command(flatten)]
+    pub network: network::Args,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Wasm(#[from] wasm::Error),
+    #[error(transparent)]
+    Fetch(#[from] fetch::Error),
+    #[error("cannot parse hash {0}, expected 32 bytes of hex")]
+    CannotParseHash(String),
+    #[error("wasm does not match: local hash is {local}, expected {expected}")]
+    Mismatch { local: Hash, expected: Hash },
+}
+
+impl Cmd {
+    pub async fn run(&self, global_args: &global::Args) -> Result<(), Error> {
+        let local = self.wasm.hash()?;
+        let expected = self.expected_hash(global_args).await?;
+        if local != expected {
+            return Err(Error::Mismatch { local, expected });
+        }
+        println!("wasm matches: {local}");
+        Ok(())
+    }
+
+    async fn expected_hash(&self, global_args: &global::Args) -> Result<Hash, Error> {
+        if let Some(hash) = &self.against_hash {
+            return parse_wasm_hash(hash).map_err(|_| Error::CannotParseHash(hash.clone()));
+        }
+        let fetch = fetch::Cmd {
+            contract_id: self.contract_id.clone().unwrap_or_default(),
+            out_file: None,
+            locator: self.locator.clone(),
+            network: self.network.clone(),
+        };
+        let wasm = fetch.get_bytes(Some(global_args)).await?;
+        Ok(Hash(Sha256::digest(wasm).into()))
+    }
+}
@@ -1,34 +1,70 @@
 use clap::{arg, command, Parser};
-use std::io;
+use std::{
+    io::{self, Write},
+    time::Duration,
+};
 
-use soroban_env_host::xdr::{self, Limits, ReadXdr};
+use soroban_env_host::xdr::{self, Limits, ReadXdr, ScSpecTypeDef, WriteXdr};
 
 use super::{
     config::{self, locator},
-    global, network, NetworkRunnable,
+    global,
+    network::{self, Network},
+    NetworkRunnable,
 };
 use crate::rpc;
+use crate::rpc_ext::{self, ClientExt};
 
 #[derive(Parser, Debug, Clone)]
 #[group(skip)]
 pub struct Cmd {
     /// The first ledger sequence number in the range to pull events
     /// https://developers.stellar.org/docs/encyclopedia/ledger-headers#ledger-sequence
-    #[arg(long, conflicts_with = "cursor", required_unless_present = "cursor")]
+    #[arg(
+        long,
+        conflicts_with_all = ["cursor", "start_ago"],
+        required_unless_present_any = ["cursor", "start_ago"]
+    )]
     start_ledger: Option<u32>,
     /// The cursor corresponding to the start of the event range.
     #[arg(
         long,
-        conflicts_with = "start_ledger",
-        required_unless_present = "start_ledger"
+        conflicts_with_all = ["start_ledger", "start_ago"],
+        required_unless_present_any = ["start_ledger", "start_ago"]
     )]
     cursor: Option<String>,
+    /// Start from the ledger closed about this long ago, e.g. `90s`, `30m`, `1h` or `2d`. It is
+    /// estimated from the latest ledger and `--ledger-close-time`, and is never older than the
+    /// oldest ledger the RPC server retains.
+    #[arg(
+        long,
+        value_parser = parse_duration,
+        conflicts_with_all = ["start_ledger", "cursor"],
+        required_unless_present_any = ["start_ledger", "cursor"]
+    )]
+    start_ago: Option<u64>,
+    /// Average number of seconds between ledgers, used to estimate the start ledger of
+    /// `--start-ago`
+    #[arg(long, default_value = "5", requires = "start_ago")]
+    ledger_close_time: u64,
     /// Output formatting options for event stream
     #[arg(long, value_enum, default_value = "pretty")]
     output: OutputFormat,
-    /// The maximum number of events to display (defer to the server-defined limit).
+    /// The maximum number of events to display. Pages of events are fetched
+    /// until this many are found.
     #[arg(short, long, default_value = "10")]
     count: usize,
+    /// Keep polling for new events after the ones found, until interrupted with Ctrl-C or until
+    /// `--duration` has passed. The cursor to resume from with `--cursor` is then printed to
+    /// stderr.
+    #[arg(long)]
+    follow: bool,
+    /// Stop following events after this long, e.g. `90s`, `30m` or `1h`
+    #[arg(long, value_parser = parse_duration, requires = "follow")]
+    duration: Option<u64>,
+    /// Time to wait between polls for new events when following them, e.g. `5s`
+    #[arg(long, value_parser = parse_duration, default_value = "5s", requires = "follow")]
+    poll_interval: u64,
     /// A set of (up to 5) contract IDs to filter events on. This parameter can
     /// be passed multiple times, e.g. `--id C123.. --id C456..`, or passed with
     /// multiple parameters, e.g. `--id C123 C456`.
@@ -62,6 +98,30 @@ pub struct Cmd {
         help_heading = "FILTERS"
     )]
     topic_filters: Vec<String>,
+    /// One more topic filter, with its segments given as symbols instead of
+    /// base64 XDR. Segments are separated by commas, with an asterisk (*
+    /// character) indicating a wildcard segment.
+    ///
+    /// It is combined segment by segment with `--topic-address`, so this
+    /// matches `transfer` events from `G123..`:
+    ///
+    ///     --topic-symbol transfer --topic-address '*,G123..'
+    #[arg(long, help_heading = "FILTERS")]
+    topic_symbol: Option<String>,
+    /// Like `--topic-symbol`, for segments that are account (`G...`) or
+    /// contract (`C...`) addresses.
+    #[arg(long, help_heading = "FILTERS")]
+    topic_address: Option<String>,
+    /// Only show events whose value, decoded to JSON, contains this JSON
+    /// fragment at any depth. An object contains a fragment object whose keys
+    /// it has with matching values, an array contains a fragment array whose
+    /// elements each match one of its elements, and other values must be
+    /// equal. For example, `--value-contains '{"amount":"100"}'`.
+    ///
+    /// RPC servers can't filter on values, so the fetched events are filtered
+    /// and fewer than `--count` may be shown.
+    #[arg(long, value_parser = parse_json, help_heading = "FILTERS")]
+    value_contains: Option<serde_json::Value>,
     /// Specifies which type of contract events to display.
     #[arg(
         long = "type",
@@ -92,6 +152,15 @@ pub enum Error {
         segment: String,
         error: xdr::Error,
     },
+    #[error("invalid value ({value}) in topic filter: {error}")]
+    InvalidTopicValue {
+        value: String,
+        error: soroban_spec_tools::Error,
+    },
+    #[error(
+        "segment {position} of the topic filter is set by both --topic-symbol and --topic-address"
+    )]
+    ConflictingTopicSegment { position: usize },
     #[error("cannot parse contract ID {contract_id}: {error}")]
     InvalidContractId {
         contract_id: String,
@@ -104,12 +173,14 @@ pub enum Error {
     },
     #[error("invalid timestamp in event: {ts}")]
     InvalidTimestamp { ts: String },
-    #[error("missing start_ledger and cursor")]
+    #[error("missing start_ledger, cursor and start_ago")]
     MissingStartLedgerAndCursor,
     #[error("missing target")]
     MissingTarget,
     #[error(transparent)]
-    Rpc(#[from] rpc::Error),
+    Rpc(rpc::Error),
+    #[error(transparent)]
+    RpcError(#[from] crate::rpc_ext::RpcError),
     #[error(transparent)]
     Generic(#[from] Box<dyn std::error::Error>),
     #[error(transparent)]
@@ -124,6 +195,14 @@ pub enum Error {
     Locator(#[from] locator::Error),
     #[error(transparent)]
     Config(#[from] config::Error),
+    #[error(transparent)]
+    RpcExt(#[from] rpc_ext::Error),
+}
+
+impl From<rpc::Error> for Error {
+    fn from(e: rpc::Error) -> Self {
+        crate::rpc_ext::RpcError::from_rpc(e).map_or_else(Error::Rpc, Error::RpcError)
+    }
 }
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, clap::ValueEnum)]
@@ -137,7 +216,7 @@ pub enum OutputFormat {
 }
 
 impl Cmd {
-    pub async fn run(&mut self) -> Result<(), Error> {
+    pub async fn run(&mut self, global_args: &global::Args) -> Result<(), Error> {
         // Validate that topics are made up of segments.
         for topic in &self.topic_filters {
             for (i, segment) in topic.split(',').enumerate() {
@@ -159,9 +238,28 @@ impl Cmd {
             }
         }
 
-        let response = self.run_against_rpc_server(None, None).await?;
+        let response = self.run_against_rpc_server(Some(global_args), None).await?;
+        self.print_events(&response.events)?;
+        if self.follow {
+            let (cursor, latest_ledger) = self.follow(response).await?;
+            io::stdout().flush()?;
+            match cursor {
+                Some(cursor) => {
+                    eprintln!("Stopped following events, resume with --cursor {cursor}")
+                }
+                None => eprintln!(
+                    "Stopped following events, resume with --start-ledger {latest_ledger}"
+                ),
+            }
+            return Ok(());
+        }
+        println!("Latest Ledger: {}", response.latest_ledger);
 
-        for event in &response.events {
+        Ok(())
+    }
+
+    fn print_events(&self, events: &[rpc::Event]) -> Result<(), Error> {
+        for event in events {
             match self.output {
                 // Should we pretty-print the JSON like we're doing here or just
                 // dump an event in raw JSON on each line? The latter is easier
@@ -181,20 +279,228 @@ impl Cmd {
                 OutputFormat::Pretty => event.pretty_print()?,
             }
         }
-        println!("Latest Ledger: {}", response.latest_ledger);
-
         Ok(())
     }
 
-    fn start(&self) -> Result<rpc::EventStart, Error> {
-        let start = match (self.start_ledger, self.cursor.clone()) {
-            (Some(start), _) => rpc::EventStart::Ledger(start),
-            (_, Some(c)) => rpc::EventStart::Cursor(c),
-            // should never happen because of required_unless_present flags
+    /// Print the events following `response` as they come, polling every `--poll-interval`,
+    /// until Ctrl-C or `--duration`. Returns the cursor of the last event printed, if any, and
+    /// the latest ledger seen.
+    async fn follow(
+        &self,
+        response: rpc::GetEventsResponse,
+    ) -> Result<(Option<String>, u32), Error> {
+        let network = self.network.get(&self.locator).await?;
+        let client = network.rpc_client()?;
+        let deadline = self
+            .duration
+            .map(|secs| tokio::time::Instant::now() + Duration::from_secs(secs));
+        let mut cursor = response.events.last().map(|event| event.id.clone());
+        let mut latest_ledger = response.latest_ledger;
+        // Created once, so a Ctrl-C is caught whenever it comes, also during a fetch
+        let ctrl_c = tokio::signal::ctrl_c();
+        tokio::pin!(ctrl_c);
+        let stop = async {
+            match deadline {
+                Some(deadline) => tokio::time::sleep_until(deadline).await,
+                None => std::future::pending().await,
+            }
+        };
+        tokio::pin!(stop);
+        loop {
+            tokio::select! {
+                _ = &mut ctrl_c => break,
+                () = &mut stop => break,
+                () = tokio::time::sleep(Duration::from_secs(self.poll_interval)) => {}
+            }
+            let start = match &cursor {
+                Some(cursor) => rpc::EventStart::Cursor(cursor.clone()),
+                None => rpc::EventStart::Ledger(latest_ledger),
+            };
+            // A fetch started before the deadline is finished, so its events aren't lost
+            let response = tokio::select! {
+                _ = &mut ctrl_c => break,
+                response = self.events_from(&client, &network, start) => response?,
+            };
+            self.print_events(&response.events)?;
+            io::stdout().flush()?;
+            if let Some(event) = response.events.last() {
+                cursor = Some(event.id.clone());
+            }
+            latest_ledger = response.latest_ledger;
+        }
+        Ok((cursor, latest_ledger))
+    }
+
+    /// The events from `start` matching the filters, at most `--count` of them
+    async fn events_from(
+        &self,
+        client: &rpc_ext::Client,
+        network: &Network,
+        start: rpc::EventStart,
+    ) -> Result<rpc::GetEventsResponse, Error> {
+        let contract_ids: Vec<String> = self
+            .contract_ids
+            .iter()
+            .map(|id| {
+                Ok(self
+                    .locator
+                    .resolve_contract_id(id, &network.network_passphrase)?
+                    .to_string())
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let mut response = client
+            .get_all_events(
+                start,
+                Some(self.event_type),
+                &contract_ids,
+                &self.topic_filters()?,
+                Some(self.count),
+            )
+            .await?;
+        if let Some(fragment) = &self.value_contains {
+            response
+                .events
+                .retain(|event| event_value_contains(&event.value, fragment));
+        }
+        Ok(response)
+    }
+
+    async fn start(
+        &self,
+        client: &rpc_ext::Client,
+        network: &Network,
+    ) -> Result<rpc::EventStart, Error> {
+        let start = match (self.start_ledger, self.cursor.clone(), self.start_ago) {
+            (Some(start), _, _) => rpc::EventStart::Ledger(start),
+            (_, Some(c), _) => rpc::EventStart::Cursor(c),
+            (_, _, Some(ago)) => {
+                rpc::EventStart::Ledger(self.start_ledger_ago(ago, client, network).await?)
+            }
+            // should never happen because of required_unless_present_any flags
             _ => return Err(Error::MissingStartLedgerAndCursor),
         };
         Ok(start)
     }
+
+    /// The ledger closed about `ago` seconds before the latest one, clamped to the oldest ledger
+    /// the server retains
+    async fn start_ledger_ago(
+        &self,
+        ago: u64,
+        client: &rpc_ext::Client,
+        network: &Network,
+    ) -> Result<u32, Error> {
+        let latest = client.get_latest_ledger().await?.sequence;
+        let ledgers = u32::try_from(ago / self.ledger_close_time.max(1)).unwrap_or(u32::MAX);
+        let oldest = network.get_health().await?.oldest_ledger;
+        Ok(latest.saturating_sub(ledgers).max(oldest))
+    }
+
+    /// All topic filters, with the one built from `--topic-symbol` and
+    /// `--topic-address` last
+    fn topic_filters(&self) -> Result<Vec<String>, Error> {
+        let mut topic_filters = self.topic_filters.clone();
+        topic_filters.extend(self.typed_topic_filter()?);
+        Ok(topic_filters)
+    }
+
+    fn typed_topic_filter(&self) -> Result<Option<String>, Error> {
+        let typed = [
+            (&self.topic_symbol, ScSpecTypeDef::Symbol),
+            (&self.topic_address, ScSpecTypeDef::Address),
+        ];
+        if typed.iter().all(|(filter, _)| filter.is_none()) {
+            return Ok(None);
+        }
+        let mut segments: Vec<Option<String>> = Vec::new();
+        for (filter, t) in typed {
+            let Some(filter) = filter else { continue };
+            for (i, value) in filter.split(',').enumerate() {
+                if i >= segments.len() {
+                    segments.resize(i + 1, None);
+                }
+                if value == "*" {
+                    continue;
+                }
+                if segments[i].is_some() {
+                    return Err(Error::ConflictingTopicSegment { position: i + 1 });
+                }
+                let val = soroban_spec_tools::Spec::from_string_primitive(value, &t).map_err(
+                    |error| Error::InvalidTopicValue {
+                        value: value.to_string(),
+                        error,
+                    },
+                )?;
+                segments[i] = Some(val.to_xdr_base64(Limits::none())?);
+            }
+        }
+        let topic = segments
+            .into_iter()
+            .map(|segment| segment.unwrap_or_else(|| "*".to_string()))
+            .collect::<Vec<_>>()
+            .join(",");
+        if topic.split(',').count() > 4 {
+            return Err(Error::InvalidTopicFilter { topic });
+        }
+        Ok(Some(topic))
+    }
+}
+
+/// Parse a duration such as `90s`, `30m`, `1h` or `2d` into seconds. A number without a unit is
+/// in seconds.
+fn parse_duration(s: &str) -> Result<u64, String> {
+    let (number, unit) = s.split_at(s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len()));
+    let number: u64 = number
+        .parse()
+        .map_err(|_| format!("expected a number followed by s, m, h or d, got {s:?}"))?;
+    let unit = match unit {
+        "" | "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        _ => return Err(format!("unknown unit {unit:?}, expected s, m, h or d")),
+    };
+    number
+        .checked_mul(unit)
+        .ok_or_else(|| format!("duration {s:?} is too long"))
+}
+
+fn parse_json(s: &str) -> Result<serde_json::Value, String> {
+    serde_json::from_str(s).map_err(|e| format!("invalid JSON: {e}"))
+}
+
+/// Whether the base64 `ScVal` XDR of an event's value, decoded to JSON, contains `fragment`.
+/// Values that can't be decoded contain nothing.
+fn event_value_contains(value: &str, fragment: &serde_json::Value) -> bool {
+    xdr::ScVal::from_xdr_base64(value, Limits::none())
+        .ok()
+        .and_then(|val| soroban_spec_tools::to_json(&val).ok())
+        .is_some_and(|json| json_contains(&json, fragment))
+}
+
+/// Whether `value` or any value nested in it matches `fragment`
+fn json_contains(value: &serde_json::Value, fragment: &serde_json::Value) -> bool {
+    use serde_json::Value;
+    json_matches(value, fragment)
+        || match value {
+            Value::Array(items) => items.iter().any(|item| json_contains(item, fragment)),
+            Value::Object(map) => map.values().any(|item| json_contains(item, fragment)),
+            _ => false,
+        }
+}
+
+fn json_matches(value: &serde_json::Value, fragment: &serde_json::Value) -> bool {
+    use serde_json::Value;
+    match (value, fragment) {
+        (Value::Object(map), Value::Object(fragment)) => fragment
+            .iter()
+            .all(|(key, f)| map.get(key).is_some_and(|v| json_matches(v, f))),
+        (Value::Array(items), Value::Array(fragment)) => fragment
+            .iter()
+            .all(|f| items.iter().any(|item| json_matches(item, f))),
+        _ => value == fragment,
+    }
 }
 
 #[async_trait::async_trait]
@@ -204,41 +510,346 @@ impl NetworkRunnable for Cmd {
 
     async fn run_against_rpc_server(
         &self,
-        _args: Option<&global::Args>,
+        args: Option<&global::Args>,
         config: Option<&config::Args>,
     ) -> Result<rpc::GetEventsResponse, Error> {
-        let start = self.start()?;
         let network = if let Some(config) = config {
-            Ok(config.get_network()?)
+            Ok(config.get_network().await?)
         } else {
-            self.network.get(&self.locator)
+            self.network.get(&self.locator).await
         }?;
 
-        let client = rpc::Client::new(&network.rpc_url)?;
+        let client = network.rpc_client()?;
         client
-            .verify_network_passphrase(Some(&network.network_passphrase))
+            .verify_network(&network.network_passphrase, args)
             .await?;
+        let start = self.start(&client, &network).await?;
+        self.events_from(&client, &network, start).await
+    }
+}
 
-        let contract_ids: Vec<String> = self
-            .contract_ids
+#[cfg(test)]
+mod test {
+    use httpmock::prelude::*;
+    use serde_json::json;
+
+    use super::*;
+
+    const PASSPHRASE: &str = "Standalone Network ; February 2017";
+
+    #[tokio::test]
+    async fn topic_symbol_is_sent_as_xdr() {
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .json_body_partial(r#"{ "method": "getEvents" }"#)
+                // ScVal::Symbol("transfer")
+                .body_contains("AAAADwAAAAh0cmFuc2Zlcg==");
+            then.status(200).json_body(json!({
+                "jsonrpc": "2.0",
+                "id": 0,
+                "result": { "events": [], "latestLedger": 1 }
+            }));
+        });
+        let cmd = Cmd::parse_from([
+            "events",
+            "--start-ledger=1",
+            "--rpc-url",
+            &server.url("/"),
+            "--network-passphrase",
+            PASSPHRASE,
+            "--topic-symbol=transfer",
+            "--topic-address=*,GAAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQDZ7H",
+        ]);
+        let args = global::Args {
+            ignore_network_check: true,
+            ..Default::default()
+        };
+        cmd.run_against_rpc_server(Some(&args), None).await.unwrap();
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn contract_ids_are_sent_in_one_filter() {
+        let first = stellar_strkey::Contract([1; 32]).to_string();
+        let second = stellar_strkey::Contract([2; 32]).to_string();
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .json_body_partial(r#"{ "method": "getEvents" }"#)
+                .body_contains(format!(r#""contractIds":["{first}","{second}"]"#));
+            then.status(200).json_body(json!({
+                "jsonrpc": "2.0",
+                "id": 0,
+                "result": { "events": [], "latestLedger": 1 }
+            }));
+        });
+        let cmd = Cmd::parse_from([
+            "events",
+            "--start-ledger=1",
+            "--rpc-url",
+            &server.url("/"),
+            "--network-passphrase",
+            PASSPHRASE,
+            "--id",
+            &first,
+            "--id",
+            &second,
+        ]);
+        let args = global::Args {
+            ignore_network_check: true,
+            ..Default::default()
+        };
+        cmd.run_against_rpc_server(Some(&args), None).await.unwrap();
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn start_ago_estimates_start_ledger() {
+        let server = MockServer::start();
+        let latest = server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .json_body_partial(r#"{ "method": "getLatestLedger" }"#);
+            then.status(200).json_body(json!({
+                "jsonrpc": "2.0",
+                "id": 0,
+                "result": { "id": "0", "protocolVersion": 21, "sequence": 10000 }
+            }));
+        });
+        let health = server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .json_body_partial(r#"{ "method": "getHealth" }"#);
+            then.status(200).json_body(json!({
+                "jsonrpc": "2.0",
+                "id": 0,
+                "result": {
+                    "status": "healthy",
+                    "latestLedger": 10000,
+                    "oldestLedger": 9000,
+                    "ledgerRetentionWindow": 1000
+                }
+            }));
+        });
+        let network = Network {
+            rpc_url: server.url("/"),
+            network_passphrase: PASSPHRASE.to_string(),
+            rpc_timeout: network::DEFAULT_RPC_TIMEOUT,
+        };
+        let client = network.rpc_client().unwrap();
+        let start = |args: &[&str]| {
+            let cmd = Cmd::parse_from(["events"].iter().chain(args).copied());
+            let (client, network) = (&client, &network);
+            async move {
+                let ago = cmd.start_ago.unwrap();
+                cmd.start_ledger_ago(ago, client, network).await.unwrap()
+            }
+        };
+        // 3600s at 5s per ledger
+        assert_eq!(start(&["--start-ago=1h"]).await, 10000 - 720);
+        // 3600s at 6s per ledger
+        assert_eq!(
+            start(&["--start-ago=1h", "--ledger-close-time=6"]).await,
+            10000 - 600
+        );
+        // 2 days ago is before the oldest retained ledger
+        assert_eq!(start(&["--start-ago=2d"]).await, 9000);
+        latest.assert_hits(3);
+        health.assert_hits(3);
+    }
+
+    #[tokio::test]
+    async fn follow_stops_after_duration_with_last_cursor() {
+        tokio::time::pause();
+        let server = MockServer::start();
+        let from_start = server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .json_body_partial(r#"{ "method": "getEvents" }"#)
+                .body_contains(r#""startLedger":1"#);
+            then.status(200).json_body(json!({
+                "jsonrpc": "2.0",
+                "id": 0,
+                "result": {
+                    "events": [{
+                        "type": "contract",
+                        "ledger": 2,
+                        "ledgerClosedAt": "2024-01-01T00:00:00Z",
+                        "id": "0002-1",
+                        "pagingToken": "0002-1",
+                        "contractId": "CAAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQC526",
+                        "topic": [],
+                        "value": xdr::ScVal::U32(1).to_xdr_base64(Limits::none()).unwrap()
+                    }],
+                    "latestLedger": 2
+                }
+            }));
+        });
+        let from_cursor = server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .json_body_partial(r#"{ "method": "getEvents" }"#)
+                .body_contains("0002-1");
+            then.status(200).json_body(json!({
+                "jsonrpc": "2.0",
+                "id": 0,
+                "result": { "events": [], "latestLedger": 3 }
+            }));
+        });
+        let cmd = Cmd::parse_from([
+            "events",
+            "--start-ledger=1",
+            "--rpc-url",
+            &server.url("/"),
+            "--network-passphrase",
+            PASSPHRASE,
+            "--follow",
+            "--duration=3s",
+            "--poll-interval=1s",
+        ]);
+        let started = tokio::time::Instant::now();
+        let (cursor, latest_ledger) = cmd
+            .follow(rpc::GetEventsResponse {
+                events: vec![],
+                latest_ledger: 1,
+            })
+            .await
+            .unwrap();
+        let elapsed = started.elapsed();
+        assert!(elapsed >= Duration::from_secs(3), "{elapsed:?}");
+        assert!(elapsed < Duration::from_secs(10), "{elapsed:?}");
+        assert_eq!(cursor.as_deref(), Some("0002-1"));
+        // The paused clock skips ahead while a fetch waits on the server, so the deadline may
+        // pass during the first fetch, before the second one
+        assert!(
+            (latest_ledger == 2 && from_cursor.hits() == 0)
+                || (latest_ledger == 3 && from_cursor.hits() >= 1),
+            "{latest_ledger} after {} hits from the cursor",
+            from_cursor.hits()
+        );
+        from_start.assert_hits(1);
+    }
+
+    #[test]
+    fn parse_durations() {
+        assert_eq!(parse_duration("90"), Ok(90));
+        assert_eq!(parse_duration("90s"), Ok(90));
+        assert_eq!(parse_duration("30m"), Ok(1800));
+        assert_eq!(parse_duration("1h"), Ok(3600));
+        assert!(parse_duration("1w").is_err());
+        assert!(parse_duration("h").is_err());
+    }
+
+    #[tokio::test]
+    async fn events_are_filtered_by_value() {
+        use crate::xdr::{ScMap, ScMapEntry, ScSymbol};
+
+        let symbol = |s: &str| xdr::ScVal::Symbol(ScSymbol(s.try_into().unwrap()));
+        let transfer = |amount: u32| {
+            xdr::ScVal::Map(Some(ScMap(
+                vec![
+                    ScMapEntry {
+                        key: symbol("amount"),
+                        val: xdr::ScVal::U32(amount),
+                    },
+                    ScMapEntry {
+                        key: symbol("memo"),
+                        val: symbol("rent"),
+                    },
+                ]
+                .try_into()
+                .unwrap(),
+            )))
+        };
+        let values = [
+            transfer(100),
+            transfer(200),
+            xdr::ScVal::Vec(Some(
+                vec![symbol("other"), transfer(100)].try_into().unwrap(),
+            )),
+            symbol("rent"),
+        ];
+        let events = values
             .iter()
-            .map(|id| {
-                Ok(self
-                    .locator
-                    .resolve_contract_id(id, &network.network_passphrase)?
-                    .to_string())
+            .enumerate()
+            .map(|(i, value)| {
+                json!({
+                    "type": "contract",
+                    "ledger": 1,
+                    "ledgerClosedAt": "2024-01-01T00:00:00Z",
+                    "id": format!("0001-{i}"),
+                    "pagingToken": format!("0001-{i}"),
+                    "contractId": "CAAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQC526",
+                    "topic": [],
+                    "value": value.to_xdr_base64(Limits::none()).unwrap()
+                })
             })
-            .collect::<Result<Vec<_>, Error>>()?;
+            .collect::<Vec<_>>();
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .json_body_partial(r#"{ "method": "getEvents" }"#);
+            then.status(200).json_body(json!({
+                "jsonrpc": "2.0",
+                "id": 0,
+                "result": { "events": events, "latestLedger": 1 }
+            }));
+        });
+        let args = global::Args {
+            ignore_network_check: true,
+            ..Default::default()
+        };
+        let filtered = |fragment: &str| {
+            let cmd = Cmd::parse_from([
+                "events",
+                "--start-ledger=1",
+                "--count=4",
+                "--rpc-url",
+                &server.url("/"),
+                "--network-passphrase",
+                PASSPHRASE,
+                "--value-contains",
+                fragment,
+            ]);
+            let args = &args;
+            async move {
+                cmd.run_against_rpc_server(Some(args), None)
+                    .await
+                    .unwrap()
+                    .events
+                    .into_iter()
+                    .map(|event| event.id)
+                    .collect::<Vec<_>>()
+            }
+        };
+        assert_eq!(filtered(r#"{"amount":100}"#).await, ["0001-0", "0001-2"]);
+        assert_eq!(
+            filtered(r#"{"memo":"rent"}"#).await,
+            ["0001-0", "0001-1", "0001-2"]
+        );
+        assert_eq!(filtered(r#"["other"]"#).await, ["0001-2"]);
+        assert_eq!(
+            filtered(r#""rent""#).await,
+            ["0001-0", "0001-1", "0001-2", "0001-3"]
+        );
+        mock.assert_hits(4);
+    }
 
-        Ok(client
-            .get_events(
-                start,
-                Some(self.event_type),
-                &contract_ids,
-                &self.topic_filters,
-                Some(self.count),
-            )
-            .await
-            .map_err(Error::Rpc)?)
+    #[test]
+    fn conflicting_topic_segments() {
+        let cmd = Cmd::parse_from([
+            "events",
+            "--start-ledger=1",
+            "--topic-symbol=transfer",
+            "--topic-address=GAAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQDZ7H",
+        ]);
+        assert!(matches!(
+            cmd.topic_filters(),
+            Err(Error::ConflictingTopicSegment { position: 1 })
+        ));
     }
 }
@@ -33,6 +33,26 @@ pub struct Args {
     /// Do not cache your simulations and transactions
     #[arg(long, env = "STELLAR_NO_CACHE")]
     pub no_cache: bool,
+
+    /// Check the network passphrase against the RPC server before every network command, not only
+    /// those that submit transactions or read contract code
+    #[arg(long, conflicts_with = "ignore_network_check")]
+    pub strict_network: bool,
+
+    /// Skip checking the network passphrase against the RPC server
+    #[arg(long)]
+    pub ignore_network_check: bool,
+
+    /// Abort before submitting a transaction whose total fee, the inclusion fee plus the resource
+    /// fee, is above this many stroops
+    #[arg(long, env = "STELLAR_MAX_FEE")]
+    pub max_fee: Option<u32>,
+
+    /// Print the result as a single JSON object on stdout, e.g. `{"result":...}`, with all other
+    /// output on stderr. Supported by `contract invoke`, `contract deploy`, `contract asset deploy`,
+    /// `contract install`, `keys rotate` and `tx new`, other commands fail with it
+    #[arg(long, env = "STELLAR_JSON")]
+    pub json: bool,
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -0,0 +1,30 @@
+use clap::command;
+
+use super::super::config::locator;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Locator(#[from] locator::Error),
+    #[error("no default identity, set one with `stellar keys use`")]
+    NoDefaultIdentity,
+}
+
+#[derive(Debug, clap::Parser, Clone)]
+#[group(skip)]
+pub struct Cmd {
+    #[command(flatten)]
+    pub config: locator::Args,
+}
+
+impl Cmd {
+    pub fn run(&self) -> Result<(), Error> {
+        println!(
+            "{}",
+            self.config
+                .default_identity()?
+                .ok_or(Error::NoDefaultIdentity)?
+        );
+        Ok(())
+    }
+}
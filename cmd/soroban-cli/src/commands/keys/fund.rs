@@ -1,15 +1,20 @@
-use clap::command;
+use clap::{arg, command};
 
-use crate::commands::network;
+use crate::commands::{config::locator, network};
 
 use super::address;
 
+/// Requests made to friendbot at once when funding several accounts
+const MAX_CONCURRENT_FUNDING: usize = 4;
+
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     #[error(transparent)]
     Address(#[from] address::Error),
     #[error(transparent)]
     Network(#[from] network::Error),
+    #[error("failed to fund {}", .0.join(", "))]
+    FundingFailed(Vec<String>),
 }
 
 #[derive(Debug, clap::Parser, Clone)]
@@ -17,18 +22,210 @@ pub enum Error {
 pub struct Cmd {
     #[command(flatten)]
     pub network: network::Args,
-    /// Address to fund
+
+    /// Names of identities or addresses to fund; several are funded concurrently
+    #[arg(required = true)]
+    pub names: Vec<String>,
+
+    /// If identity is a seed phrase use this hd path, default is 0
+    #[arg(long)]
+    pub hd_path: Option<usize>,
+
+    /// URL to POST to, with the `addr` query, to fund accounts if the network's friendbot is
+    /// unavailable or fails
+    #[arg(long)]
+    pub helper_url: Option<String>,
+
     #[command(flatten)]
-    pub address: address::Cmd,
+    pub locator: locator::Args,
 }
 
 impl Cmd {
     pub async fn run(&self) -> Result<(), Error> {
-        let addr = self.address.public_key()?;
-        self.network
-            .get(&self.address.locator)?
-            .fund_address(&addr)
-            .await?;
-        Ok(())
+        let addrs = self
+            .names
+            .iter()
+            .map(|name| {
+                address::Cmd {
+                    name: name.clone(),
+                    hd_path: self.hd_path,
+                    locator: self.locator.clone(),
+                }
+                .public_key()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let network = self.network.get(&self.locator).await?;
+        let helper_url = self.helper_url.as_deref();
+        if let ([addr], [name]) = (addrs.as_slice(), self.names.as_slice()) {
+            let funded_by = network.fund_address_with_helper(addr, helper_url).await?;
+            eprintln!("Funded {name} from {funded_by}");
+            return Ok(());
+        }
+        let failed = network
+            .fund_addresses(&addrs, MAX_CONCURRENT_FUNDING, helper_url)
+            .await?
+            .into_iter()
+            .zip(&self.names)
+            .filter_map(|(res, name)| match res {
+                Ok(funded_by) => {
+                    eprintln!("Funded {name} from {funded_by}");
+                    None
+                }
+                Err(e) => {
+                    eprintln!("Failed to fund {name}: {e}");
+                    Some(name.clone())
+                }
+            })
+            .collect::<Vec<_>>();
+        if failed.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::FundingFailed(failed))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use httpmock::prelude::*;
+    use serde_json::json;
+
+    use super::*;
+    use crate::xdr::{
+        AccountEntry, AccountEntryExt, AccountId, LedgerEntryData, Limits, PublicKey,
+        SequenceNumber, String32, Thresholds, Uint256, VecM, WriteXdr,
+    };
+
+    fn generated_addresses(n: u8) -> Vec<String> {
+        (1..=n)
+            .map(|i| {
+                let key = ed25519_dalek::SigningKey::from_bytes(&[i; 32]);
+                stellar_strkey::ed25519::PublicKey(key.verifying_key().to_bytes()).to_string()
+            })
+            .collect()
+    }
+
+    fn fund_cmd(server: &MockServer, names: Vec<String>) -> Cmd {
+        Cmd {
+            network: network::Args {
+                rpc_url: Some(server.url("/")),
+                network_passphrase: Some(network::LOCAL_NETWORK_PASSPHRASE.to_string()),
+                ..Default::default()
+            },
+            names,
+            hd_path: None,
+            helper_url: None,
+            locator: locator::Args::default(),
+        }
+    }
+
+    /// Answer every account lookup with an existing account, so funding is confirmed
+    fn mock_accounts(server: &MockServer) -> httpmock::Mock<'_> {
+        let account = LedgerEntryData::Account(AccountEntry {
+            account_id: AccountId(PublicKey::PublicKeyTypeEd25519(Uint256([0; 32]))),
+            balance: 10_000_000_000,
+            seq_num: SequenceNumber(1),
+            num_sub_entries: 0,
+            inflation_dest: None,
+            flags: 0,
+            home_domain: String32::default(),
+            thresholds: Thresholds([1, 0, 0, 0]),
+            signers: VecM::default(),
+            ext: AccountEntryExt::V0,
+        });
+        server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .json_body_partial(r#"{ "method": "getLedgerEntries" }"#);
+            then.status(200).json_body(json!({
+                "jsonrpc": "2.0",
+                "id": 0,
+                "result": {
+                    "entries": [{
+                        "key": "",
+                        "xdr": account.to_xdr_base64(Limits::none()).unwrap(),
+                        "lastModifiedLedgerSeq": 10
+                    }],
+                    "latestLedger": 10
+                }
+            }));
+        })
+    }
+
+    #[tokio::test]
+    async fn funds_several_accounts_with_one_invocation() {
+        let server = MockServer::start();
+        let names = generated_addresses(3);
+        let friendbot = names
+            .iter()
+            .map(|name| {
+                server.mock(|when, then| {
+                    when.method(GET)
+                        .path("/friendbot")
+                        .query_param("addr", name);
+                    then.status(200).json_body(json!({ "successful": true }));
+                })
+            })
+            .collect::<Vec<_>>();
+        let accounts = mock_accounts(&server);
+        fund_cmd(&server, names).run().await.unwrap();
+        for mock in friendbot {
+            mock.assert();
+        }
+        accounts.assert_hits(3);
+    }
+
+    #[tokio::test]
+    async fn retries_rate_limited_friendbot() {
+        let server = MockServer::start_async().await;
+        let accounts = mock_accounts(&server);
+        let mut limited = server
+            .mock_async(|when, then| {
+                when.method(GET).path("/friendbot");
+                then.status(429);
+            })
+            .await;
+        let cmd = fund_cmd(&server, generated_addresses(1));
+        // Let friendbot through once the first request has been turned away
+        let recover = async {
+            while limited.hits_async().await == 0 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+            limited.delete_async().await;
+            server
+                .mock_async(|when, then| {
+                    when.method(GET).path("/friendbot");
+                    then.status(200).json_body(json!({ "successful": true }));
+                })
+                .await
+        };
+        let (res, funded) = tokio::join!(cmd.run(), recover);
+        res.unwrap();
+        funded.assert_async().await;
+        accounts.assert();
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_helper_url() {
+        let server = MockServer::start();
+        let accounts = mock_accounts(&server);
+        let friendbot = server.mock(|when, then| {
+            when.method(GET).path("/friendbot");
+            then.status(500);
+        });
+        let helper = server.mock(|when, then| {
+            when.method(POST).path("/helper").query_param_exists("addr");
+            then.status(200).json_body(json!({ "successful": true }));
+        });
+        let cmd = Cmd {
+            helper_url: Some(server.url("/helper")),
+            ..fund_cmd(&server, generated_addresses(1))
+        };
+        cmd.run().await.unwrap();
+        friendbot.assert();
+        helper.assert();
+        accounts.assert();
     }
 }
@@ -65,7 +65,7 @@ impl Cmd {
         self.config_locator.write_identity(&self.name, &secret)?;
         if !self.no_fund {
             let addr = secret.public_key(self.hd_path)?;
-            let network = self.network.get(&self.config_locator)?;
+            let network = self.network.get(&self.config_locator).await?;
             network
                 .fund_address(&addr)
                 .await
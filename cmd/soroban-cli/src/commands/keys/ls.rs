@@ -1,11 +1,14 @@
 use clap::command;
+use serde::Serialize;
 
-use super::super::config::locator;
+use super::super::config::{locator, secret::Secret};
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     #[error(transparent)]
     Config(#[from] locator::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
 }
 
 #[derive(Debug, clap::Parser, Clone)]
@@ -16,10 +19,45 @@ pub struct Cmd {
 
     #[arg(long, short = 'l')]
     pub long: bool,
+
+    /// Format of the output
+    #[arg(long, value_enum, default_value_t, conflicts_with = "long")]
+    pub output: Output,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq, clap::ValueEnum)]
+pub enum Output {
+    /// One identity name per line
+    #[default]
+    Text,
+    /// JSON array of identities with their kind and public key
+    Json,
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Kind {
+    SecretKey,
+    SeedPhrase,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Entry {
+    pub name: String,
+    pub kind: Kind,
+    /// `None` if the public key could not be derived from the stored secret
+    pub public_key: Option<String>,
+    /// Path the public key of a seed phrase is derived at
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hd_path: Option<usize>,
 }
 
 impl Cmd {
     pub fn run(&self) -> Result<(), Error> {
+        if self.output == Output::Json {
+            println!("{}", serde_json::to_string_pretty(&self.ls_entries()?)?);
+            return Ok(());
+        }
         let res = if self.long { self.ls_l() } else { self.ls() }?.join("\n");
         println!("{res}");
         Ok(())
@@ -38,4 +76,24 @@ impl Cmd {
             .map(|(name, location)| format!("{location}\nName: {name}\n"))
             .collect::<Vec<String>>())
     }
+
+    pub fn ls_entries(&self) -> Result<Vec<Entry>, Error> {
+        self.ls()?
+            .into_iter()
+            .map(|name| {
+                let secret = self.config_locator.read_identity(&name)?;
+                let (kind, hd_path) = match secret {
+                    Secret::SecretKey { .. } => (Kind::SecretKey, None),
+                    Secret::SeedPhrase { .. } => (Kind::SeedPhrase, Some(0)),
+                };
+                let public_key = secret.public_key(hd_path).ok().map(|key| key.to_string());
+                Ok(Entry {
+                    name,
+                    kind,
+                    public_key,
+                    hd_path,
+                })
+            })
+            .collect()
+    }
 }
@@ -1,12 +1,18 @@
 use clap::Parser;
 
+use super::global;
+
 pub mod add;
 pub mod address;
+pub mod default;
 pub mod fund;
 pub mod generate;
 pub mod ls;
 pub mod rm;
+pub mod rotate;
 pub mod show;
+pub mod sign;
+pub mod use_identity;
 
 #[derive(Debug, Parser)]
 pub enum Cmd {
@@ -14,6 +20,8 @@ pub enum Cmd {
     Add(add::Cmd),
     /// Given an identity return its address (public key)
     Address(address::Cmd),
+    /// Print the identity set with `keys use`
+    Default(default::Cmd),
     /// Fund an identity on a test network
     Fund(fund::Cmd),
     /// Generate a new identity with a seed phrase, currently 12 words
@@ -22,8 +30,14 @@ pub enum Cmd {
     Ls(ls::Cmd),
     /// Remove an identity
     Rm(rm::Cmd),
+    /// Add or remove signers of a multisig account and set its thresholds
+    Rotate(rotate::Cmd),
     /// Given an identity return its private key
     Show(show::Cmd),
+    /// Sign a hex payload with an identity and print the hex signature
+    Sign(sign::Cmd),
+    /// Use an identity as the source account of commands that are given none
+    Use(use_identity::Cmd),
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -34,6 +48,8 @@ pub enum Error {
     #[error(transparent)]
     Address(#[from] address::Error),
     #[error(transparent)]
+    Default(#[from] default::Error),
+    #[error(transparent)]
     Fund(#[from] fund::Error),
 
     #[error(transparent)]
@@ -42,21 +58,32 @@ pub enum Error {
     Rm(#[from] rm::Error),
     #[error(transparent)]
     Ls(#[from] ls::Error),
+    #[error(transparent)]
+    Rotate(#[from] rotate::Error),
 
     #[error(transparent)]
     Show(#[from] show::Error),
+
+    #[error(transparent)]
+    Sign(#[from] sign::Error),
+    #[error(transparent)]
+    Use(#[from] use_identity::Error),
 }
 
 impl Cmd {
-    pub async fn run(&self) -> Result<(), Error> {
+    pub async fn run(&self, global_args: &global::Args) -> Result<(), Error> {
         match self {
             Cmd::Add(cmd) => cmd.run()?,
             Cmd::Address(cmd) => cmd.run()?,
+            Cmd::Default(cmd) => cmd.run()?,
             Cmd::Fund(cmd) => cmd.run().await?,
             Cmd::Generate(cmd) => cmd.run().await?,
             Cmd::Ls(cmd) => cmd.run()?,
             Cmd::Rm(cmd) => cmd.run()?,
+            Cmd::Rotate(cmd) => cmd.run(global_args).await?,
             Cmd::Show(cmd) => cmd.run()?,
+            Cmd::Sign(cmd) => cmd.run()?,
+            Cmd::Use(cmd) => cmd.run()?,
         };
         Ok(())
     }
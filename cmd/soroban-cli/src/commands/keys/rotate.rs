@@ -0,0 +1,242 @@
+use clap::{arg, command};
+
+use crate::{
+    commands::{config, global},
+    print::Print,
+    rpc, utils,
+    xdr::{
+        self, Limits, Memo, MuxedAccount, Operation, OperationBody, Preconditions, SequenceNumber,
+        SetOptionsOp, Signer, SignerKey, Transaction, TransactionEnvelope, TransactionExt, Uint256,
+        WriteXdr,
+    },
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Config(#[from] config::Error),
+    #[error(transparent)]
+    Rpc(rpc::Error),
+    #[error(transparent)]
+    RpcError(#[from] crate::rpc_ext::RpcError),
+    #[error(transparent)]
+    Xdr(#[from] xdr::Error),
+    #[error("total fee of {0} operations is out of range")]
+    FeeOutOfRange(usize),
+}
+
+impl From<rpc::Error> for Error {
+    fn from(e: rpc::Error) -> Self {
+        crate::rpc_ext::RpcError::from_rpc(e).map_or_else(Error::Rpc, Error::RpcError)
+    }
+}
+
+/// Build, and unless `--build-only` is set, submit a transaction changing the signers and
+/// thresholds of a multisig account. Signers are added first, then removed, then the thresholds
+/// are set. `--fee` is paid for each operation. With `--build-only` the transaction can be signed
+/// with the account's other signers using `tx sign`.
+#[derive(Debug, clap::Parser, Clone)]
+#[command(group(
+    clap::ArgGroup::new("changes")
+        .required(true)
+        .multiple(true)
+        .args(&["add", "remove", "threshold"]),
+))]
+#[group(skip)]
+pub struct Cmd {
+    /// Account whose signers change, as an identity or a public key (G...). The transaction needs
+    /// enough of its current signers' signatures to meet its high threshold
+    #[arg(long)]
+    pub account: String,
+    /// Signer to add, as an identity, a public key (G...) or a secret key (S...). Can be passed
+    /// multiple times
+    #[arg(long)]
+    pub add: Vec<String>,
+    /// Weight of the added signers
+    #[arg(long, default_value = "1", requires = "add")]
+    pub weight: u8,
+    /// Signer to remove, as an identity or a public key (G...). Can be passed multiple times
+    #[arg(long)]
+    pub remove: Vec<String>,
+    /// New low, medium and high threshold of the account
+    #[arg(long)]
+    pub threshold: Option<u8>,
+    #[command(flatten)]
+    pub fee: crate::fee::Args,
+    #[command(flatten)]
+    pub config: config::Args,
+}
+
+impl Cmd {
+    pub async fn run(&self, global_args: &global::Args) -> Result<(), Error> {
+        let source = self.config.public_key().await?.0;
+        let network = self.config.get_network().await?;
+        let client = network.rpc_client()?;
+        let account = client
+            .get_account(&stellar_strkey::ed25519::PublicKey(source).to_string())
+            .await?;
+        let print = Print::new(Some(global_args));
+        let tx = self.build_tx(source, account.seq_num.0 + 1)?;
+        if self.fee.build_only {
+            let tx_env: TransactionEnvelope = tx.into();
+            print.result("xdr", tx_env.to_xdr_base64(Limits::none())?);
+            return Ok(());
+        }
+        self.config
+            .sign_and_send(
+                &client,
+                &network,
+                tx.clone(),
+                !self.fee.no_seq_retry,
+                Some(global_args),
+            )
+            .await?;
+        print.result(
+            "hash",
+            hex::encode(utils::transaction_hash(&tx, &network.network_passphrase)?),
+        );
+        Ok(())
+    }
+
+    /// The transaction with a `SetOptions` operation, on behalf of `--account`, for each signer
+    /// to add or remove and for the new thresholds
+    pub fn build_tx(&self, source: [u8; 32], sequence: i64) -> Result<Transaction, Error> {
+        let account = MuxedAccount::Ed25519(Uint256(self.public_key(&self.account)?));
+        let set_signer = |key: [u8; 32], weight: u32| SetOptionsOp {
+            signer: Some(Signer {
+                key: SignerKey::Ed25519(Uint256(key)),
+                weight,
+            }),
+            ..empty_set_options()
+        };
+        let mut ops = Vec::new();
+        for name in &self.add {
+            ops.push(set_signer(self.public_key(name)?, self.weight.into()));
+        }
+        for name in &self.remove {
+            ops.push(set_signer(self.public_key(name)?, 0));
+        }
+        if let Some(threshold) = self.threshold {
+            ops.push(SetOptionsOp {
+                low_threshold: Some(threshold.into()),
+                med_threshold: Some(threshold.into()),
+                high_threshold: Some(threshold.into()),
+                ..empty_set_options()
+            });
+        }
+        let fee = u32::try_from(ops.len())
+            .ok()
+            .and_then(|count| count.checked_mul(self.fee.fee))
+            .ok_or(Error::FeeOutOfRange(ops.len()))?;
+        Ok(Transaction {
+            source_account: MuxedAccount::Ed25519(Uint256(source)),
+            fee,
+            seq_num: SequenceNumber(sequence),
+            cond: Preconditions::None,
+            memo: Memo::None,
+            operations: ops
+                .into_iter()
+                .map(|op| Operation {
+                    source_account: Some(account.clone()),
+                    body: OperationBody::SetOptions(op),
+                })
+                .collect::<Vec<_>>()
+                .try_into()?,
+            ext: TransactionExt::V0,
+        })
+    }
+
+    fn public_key(&self, name: &str) -> Result<[u8; 32], Error> {
+        if let Ok(key) = stellar_strkey::ed25519::PublicKey::from_string(name) {
+            return Ok(key.0);
+        }
+        Ok(self
+            .config
+            .account(name)?
+            .key_pair(self.config.hd_path)
+            .map_err(config::Error::from)?
+            .verifying_key()
+            .to_bytes())
+    }
+}
+
+fn empty_set_options() -> SetOptionsOp {
+    SetOptionsOp {
+        inflation_dest: None,
+        clear_flags: None,
+        set_flags: None,
+        master_weight: None,
+        low_threshold: None,
+        med_threshold: None,
+        high_threshold: None,
+        home_domain: None,
+        signer: None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use clap::Parser;
+
+    use super::*;
+
+    const ACCOUNT: &str = "GAAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQDZ7H";
+
+    fn signer_changes(tx: &Transaction) -> Vec<(Option<[u8; 32]>, Option<u32>, Option<u32>)> {
+        tx.operations
+            .iter()
+            .map(|op| {
+                assert_eq!(
+                    op.source_account,
+                    Some(MuxedAccount::Ed25519(Uint256([1; 32])))
+                );
+                let OperationBody::SetOptions(set_options) = &op.body else {
+                    panic!("expected a set options operation");
+                };
+                let signer = set_options.signer.as_ref().map(|signer| {
+                    let SignerKey::Ed25519(Uint256(key)) = signer.key else {
+                        panic!("expected an ed25519 signer");
+                    };
+                    key
+                });
+                (
+                    signer,
+                    set_options.signer.as_ref().map(|signer| signer.weight),
+                    set_options.high_threshold,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn adds_and_removes_signers() {
+        let added = ed25519_dalek::SigningKey::from_bytes(&[2; 32]);
+        let added_secret = stellar_strkey::ed25519::PrivateKey([2; 32]).to_string();
+        let removed = stellar_strkey::ed25519::PublicKey([3; 32]).to_string();
+        let cmd = Cmd::parse_from([
+            "rotate",
+            "--account",
+            ACCOUNT,
+            "--add",
+            added_secret.as_str(),
+            "--weight=2",
+            "--remove",
+            removed.as_str(),
+            "--threshold=3",
+            "--source-account",
+            ACCOUNT,
+        ]);
+        let tx = cmd.build_tx([4; 32], 10).unwrap();
+        assert_eq!(tx.source_account, MuxedAccount::Ed25519(Uint256([4; 32])));
+        assert_eq!(tx.seq_num, SequenceNumber(10));
+        assert_eq!(tx.fee, 300);
+        assert_eq!(
+            signer_changes(&tx),
+            [
+                (Some(added.verifying_key().to_bytes()), Some(2), None),
+                (Some([3; 32]), Some(0), None),
+                (None, None, Some(3)),
+            ]
+        );
+    }
+}
@@ -0,0 +1,61 @@
+use clap::arg;
+use ed25519_dalek::ed25519::signature::Signer;
+
+use super::super::config::{locator, secret};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Config(#[from] locator::Error),
+
+    #[error(transparent)]
+    Secret(#[from] secret::Error),
+
+    #[error("cannot parse payload, expected hex: {0}")]
+    InvalidPayload(hex::FromHexError),
+
+    #[error("payload is {0} bytes instead of a 32 byte hash, pass --raw to sign it anyway")]
+    PayloadNotHash(usize),
+}
+
+#[derive(Debug, clap::Parser, Clone)]
+#[group(skip)]
+pub struct Cmd {
+    /// Hex-encoded bytes to sign, a 32 byte hash unless `--raw` is passed
+    #[arg(long)]
+    pub payload: String,
+
+    /// Sign a payload of any length as is
+    #[arg(long)]
+    pub raw: bool,
+
+    /// Identity, secret key, or seed phrase to sign with
+    #[arg(long)]
+    pub sign_with_key: String,
+
+    /// If identity is a seed phrase use this hd path, default is 0
+    #[arg(long)]
+    pub hd_path: Option<usize>,
+
+    #[command(flatten)]
+    pub locator: locator::Args,
+}
+
+impl Cmd {
+    pub fn run(&self) -> Result<(), Error> {
+        println!("{}", hex::encode(self.sign()?));
+        Ok(())
+    }
+
+    pub fn sign(&self) -> Result<[u8; 64], Error> {
+        let payload = hex::decode(&self.payload).map_err(Error::InvalidPayload)?;
+        if !self.raw && payload.len() != 32 {
+            return Err(Error::PayloadNotHash(payload.len()));
+        }
+        let key = self
+            .locator
+            .read_key(&self.sign_with_key)?
+            .key_pair(self.hd_path)?;
+        Ok(key.sign(&payload).to_bytes())
+    }
+}
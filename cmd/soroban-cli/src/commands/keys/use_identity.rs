@@ -0,0 +1,29 @@
+use clap::command;
+
+use super::super::config::locator;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Locator(#[from] locator::Error),
+}
+
+#[derive(Debug, clap::Parser, Clone)]
+#[group(skip)]
+pub struct Cmd {
+    /// Identity to use as the source account when none is given
+    pub name: String,
+
+    #[command(flatten)]
+    pub config: locator::Args,
+}
+
+impl Cmd {
+    pub fn run(&self) -> Result<(), Error> {
+        // Fail early for identities that don't exist
+        self.config.read_identity(&self.name)?;
+        self.config.write_default_identity(&self.name)?;
+        println!("The source account is now {} by default", self.name);
+        Ok(())
+    }
+}
@@ -0,0 +1,37 @@
+pub mod scval;
+pub mod strkey;
+pub mod wasm;
+
+#[derive(Debug, clap::Subcommand)]
+pub enum Cmd {
+    /// Encode JSON values as `ScVal` XDR and decode them back
+    #[command(subcommand)]
+    Scval(scval::Cmd),
+    /// Convert strkeys, e.g. `G...` or `C...`, to their type and raw payload and back
+    #[command(subcommand)]
+    Strkey(strkey::Cmd),
+    /// Inspect the custom sections of any Wasm file
+    #[command(subcommand)]
+    Wasm(wasm::Cmd),
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Scval(#[from] scval::Error),
+    #[error(transparent)]
+    Strkey(#[from] strkey::Error),
+    #[error(transparent)]
+    Wasm(#[from] wasm::Error),
+}
+
+impl Cmd {
+    pub fn run(&self) -> Result<(), Error> {
+        match &self {
+            Cmd::Scval(scval) => scval.run()?,
+            Cmd::Strkey(strkey) => strkey.run()?,
+            Cmd::Wasm(wasm) => wasm.run()?,
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,28 @@
+pub mod decode;
+pub mod encode;
+
+#[derive(Debug, clap::Subcommand)]
+pub enum Cmd {
+    /// Encode a JSON value of the given type as base64 `ScVal` XDR
+    Encode(encode::Cmd),
+    /// Decode base64 `ScVal` XDR to JSON
+    Decode(decode::Cmd),
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Encode(#[from] encode::Error),
+    #[error(transparent)]
+    Decode(#[from] decode::Error),
+}
+
+impl Cmd {
+    pub fn run(&self) -> Result<(), Error> {
+        match &self {
+            Cmd::Encode(encode) => encode.run()?,
+            Cmd::Decode(decode) => decode.run()?,
+        }
+        Ok(())
+    }
+}
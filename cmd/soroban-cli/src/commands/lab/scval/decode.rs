@@ -0,0 +1,27 @@
+use clap::{arg, command, Parser};
+
+use crate::xdr::{self, Limits, ReadXdr, ScVal};
+
+#[derive(Parser, Debug, Clone)]
+#[group(skip)]
+pub struct Cmd {
+    /// Base64 `ScVal` XDR to decode
+    #[arg(long)]
+    pub xdr: String,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Spec(#[from] soroban_spec_tools::Error),
+    #[error(transparent)]
+    Xdr(#[from] xdr::Error),
+}
+
+impl Cmd {
+    pub fn run(&self) -> Result<(), Error> {
+        let val = ScVal::from_xdr_base64(&self.xdr, Limits::none())?;
+        println!("{}", soroban_spec_tools::to_json_string(&val)?);
+        Ok(())
+    }
+}
@@ -0,0 +1,42 @@
+use clap::{arg, command, Parser};
+use soroban_spec_tools::{type_from_name, Spec};
+
+use crate::xdr::{self, Limits, WriteXdr};
+
+#[derive(Parser, Debug, Clone)]
+#[group(skip)]
+pub struct Cmd {
+    /// Type of the value, named as in `contract invoke` help, e.g. `u128`, `Option<u32>` or
+    /// `Map<Symbol, i128>`
+    #[arg(long = "type", value_name = "TYPE")]
+    pub type_: String,
+    /// JSON value to encode, e.g. `'"42"'` for a `u128`
+    #[arg(long)]
+    pub value: String,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Spec(#[from] soroban_spec_tools::Error),
+    #[error(transparent)]
+    Xdr(#[from] xdr::Error),
+}
+
+impl Cmd {
+    pub fn run(&self) -> Result<(), Error> {
+        let type_ = type_from_name(&self.type_)?;
+        let spec = Spec::default();
+        // Take the value as JSON where it is, e.g. `"42"` for a `u128`, and otherwise as the
+        // plain string `contract invoke` arguments also accept, e.g. `hello` for a `Symbol`.
+        let val = match serde_json::from_str(&self.value)
+            .ok()
+            .and_then(|json| spec.from_json(&json, &type_).ok())
+        {
+            Some(val) => val,
+            None => spec.from_string(&self.value, &type_)?,
+        };
+        println!("{}", val.to_xdr_base64(Limits::none())?);
+        Ok(())
+    }
+}
@@ -0,0 +1,171 @@
+use stellar_strkey::{ed25519, Contract, HashX, PreAuthTx, Strkey};
+
+pub mod decode;
+pub mod encode;
+
+#[derive(Debug, clap::Subcommand)]
+pub enum Cmd {
+    /// Print the type and hex payload of a strkey, e.g. `G...`, `C...` or `M...`
+    Decode(decode::Cmd),
+    /// Encode a hex payload as a strkey of the given type
+    Encode(encode::Cmd),
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Decode(#[from] decode::Error),
+    #[error(transparent)]
+    Encode(#[from] encode::Error),
+}
+
+impl Cmd {
+    pub fn run(&self) -> Result<(), Error> {
+        match &self {
+            Cmd::Decode(decode) => decode.run()?,
+            Cmd::Encode(encode) => encode.run()?,
+        }
+        Ok(())
+    }
+}
+
+/// Type of a strkey, named after its payload
+#[derive(Clone, Copy, Debug, Eq, PartialEq, clap::ValueEnum)]
+pub enum KeyType {
+    /// `G...`, a 32 byte ed25519 public key
+    Ed25519Public,
+    /// `S...`, a 32 byte ed25519 secret key seed
+    Ed25519Secret,
+    /// `T...`, the 32 byte hash of a pre-authorized transaction
+    PreAuthTx,
+    /// `X...`, the 32 byte sha256 hash of a hash-x signer's preimage
+    HashX,
+    /// `M...`, a 32 byte ed25519 public key followed by the 8 byte big-endian id of the muxed
+    /// account
+    MuxedEd25519,
+    /// `P...`, a 32 byte ed25519 public key followed by the signed payload of 1 to 64 bytes
+    SignedPayloadEd25519,
+    /// `C...`, a 32 byte contract ID
+    Contract,
+}
+
+impl std::fmt::Display for KeyType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = clap::ValueEnum::to_possible_value(self).expect("no skipped variants");
+        f.write_str(value.get_name())
+    }
+}
+
+/// The type and raw payload of a strkey
+pub fn to_raw(strkey: &Strkey) -> (KeyType, Vec<u8>) {
+    match strkey {
+        Strkey::PublicKeyEd25519(ed25519::PublicKey(key)) => (KeyType::Ed25519Public, key.to_vec()),
+        Strkey::PrivateKeyEd25519(ed25519::PrivateKey(key)) => {
+            (KeyType::Ed25519Secret, key.to_vec())
+        }
+        Strkey::PreAuthTx(PreAuthTx(hash)) => (KeyType::PreAuthTx, hash.to_vec()),
+        Strkey::HashX(HashX(hash)) => (KeyType::HashX, hash.to_vec()),
+        Strkey::MuxedAccountEd25519(ed25519::MuxedAccount { ed25519, id }) => (
+            KeyType::MuxedEd25519,
+            [&ed25519[..], &id.to_be_bytes()].concat(),
+        ),
+        Strkey::SignedPayloadEd25519(ed25519::SignedPayload { ed25519, payload }) => (
+            KeyType::SignedPayloadEd25519,
+            [&ed25519[..], payload].concat(),
+        ),
+        Strkey::Contract(Contract(id)) => (KeyType::Contract, id.to_vec()),
+    }
+}
+
+/// The strkey of the given type with the raw payload, or `None` if the payload has the wrong
+/// length for the type
+pub fn from_raw(key_type: KeyType, raw: &[u8]) -> Option<Strkey> {
+    let key = |raw: &[u8]| <[u8; 32]>::try_from(raw).ok();
+    Some(match key_type {
+        KeyType::Ed25519Public => Strkey::PublicKeyEd25519(ed25519::PublicKey(key(raw)?)),
+        KeyType::Ed25519Secret => Strkey::PrivateKeyEd25519(ed25519::PrivateKey(key(raw)?)),
+        KeyType::PreAuthTx => Strkey::PreAuthTx(PreAuthTx(key(raw)?)),
+        KeyType::HashX => Strkey::HashX(HashX(key(raw)?)),
+        KeyType::MuxedEd25519 => {
+            let (public_key, id) = (raw.get(..32)?, raw.get(32..)?);
+            Strkey::MuxedAccountEd25519(ed25519::MuxedAccount {
+                ed25519: key(public_key)?,
+                id: u64::from_be_bytes(id.try_into().ok()?),
+            })
+        }
+        KeyType::SignedPayloadEd25519 => {
+            let (public_key, payload) = (raw.get(..32)?, raw.get(32..)?);
+            if payload.is_empty() || payload.len() > 64 {
+                return None;
+            }
+            Strkey::SignedPayloadEd25519(ed25519::SignedPayload {
+                ed25519: key(public_key)?,
+                payload: payload.to_vec(),
+            })
+        }
+        KeyType::Contract => Strkey::Contract(Contract(key(raw)?)),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use clap::Parser;
+
+    use super::*;
+
+    fn round_trip(strkey: &str, key_type: KeyType, hex: &str) {
+        let decoded = decode::Cmd::parse_from(["decode", strkey])
+            .decode()
+            .unwrap();
+        assert_eq!(decoded, (key_type, hex.to_string()));
+        let encoded = encode::Cmd::parse_from([
+            "encode",
+            "--type",
+            key_type.to_string().as_str(),
+            "--hex",
+            hex,
+        ])
+        .encode()
+        .unwrap();
+        assert_eq!(encoded, strkey);
+    }
+
+    #[test]
+    fn public_key_round_trip() {
+        round_trip(
+            "GAAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQDZ7H",
+            KeyType::Ed25519Public,
+            &"01".repeat(32),
+        );
+    }
+
+    #[test]
+    fn contract_id_round_trip() {
+        round_trip(
+            "CAAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQC526",
+            KeyType::Contract,
+            &"01".repeat(32),
+        );
+    }
+
+    #[test]
+    fn muxed_account_round_trip() {
+        let muxed = ed25519::MuxedAccount {
+            ed25519: [1; 32],
+            id: 42,
+        }
+        .to_string();
+        round_trip(
+            &muxed,
+            KeyType::MuxedEd25519,
+            &format!("{}000000000000002a", "01".repeat(32)),
+        );
+    }
+
+    #[test]
+    fn wrong_payload_length() {
+        assert!(from_raw(KeyType::Contract, &[1; 31]).is_none());
+        assert!(from_raw(KeyType::MuxedEd25519, &[1; 32]).is_none());
+        assert!(from_raw(KeyType::SignedPayloadEd25519, &[1; 32]).is_none());
+    }
+}
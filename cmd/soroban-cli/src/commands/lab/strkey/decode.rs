@@ -0,0 +1,38 @@
+use clap::{command, Parser};
+use stellar_strkey::Strkey;
+
+use super::KeyType;
+
+#[derive(Parser, Debug, Clone)]
+#[group(skip)]
+pub struct Cmd {
+    /// Strkey to decode, e.g. `G...`, `S...`, `M...` or `C...`
+    pub strkey: String,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("cannot decode strkey {strkey}: {error}")]
+    CannotDecode {
+        strkey: String,
+        error: stellar_strkey::DecodeError,
+    },
+}
+
+impl Cmd {
+    pub fn run(&self) -> Result<(), Error> {
+        let (key_type, hex) = self.decode()?;
+        println!("{key_type} {hex}");
+        Ok(())
+    }
+
+    /// The type of the strkey and its payload as hex
+    pub fn decode(&self) -> Result<(KeyType, String), Error> {
+        let strkey = Strkey::from_string(&self.strkey).map_err(|error| Error::CannotDecode {
+            strkey: self.strkey.clone(),
+            error,
+        })?;
+        let (key_type, raw) = super::to_raw(&strkey);
+        Ok((key_type, hex::encode(raw)))
+    }
+}
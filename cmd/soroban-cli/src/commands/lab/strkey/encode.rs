@@ -0,0 +1,38 @@
+use clap::{arg, command, Parser};
+
+use super::KeyType;
+
+#[derive(Parser, Debug, Clone)]
+#[group(skip)]
+pub struct Cmd {
+    /// Type of the strkey to encode
+    #[arg(long = "type", value_enum, value_name = "TYPE")]
+    pub key_type: KeyType,
+    /// Payload as hex, e.g. 32 bytes for an ed25519 public key or a contract ID
+    #[arg(long)]
+    pub hex: String,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("invalid hex: {0}")]
+    InvalidHex(#[from] hex::FromHexError),
+    #[error("a payload of {len} bytes isn't valid for a {key_type} strkey")]
+    InvalidLength { key_type: KeyType, len: usize },
+}
+
+impl Cmd {
+    pub fn run(&self) -> Result<(), Error> {
+        println!("{}", self.encode()?);
+        Ok(())
+    }
+
+    pub fn encode(&self) -> Result<String, Error> {
+        let raw = hex::decode(&self.hex)?;
+        let strkey = super::from_raw(self.key_type, &raw).ok_or(Error::InvalidLength {
+            key_type: self.key_type,
+            len: raw.len(),
+        })?;
+        Ok(strkey.to_string())
+    }
+}
@@ -0,0 +1,22 @@
+pub mod meta;
+
+#[derive(Debug, clap::Subcommand)]
+pub enum Cmd {
+    /// List the custom sections of a Wasm file, decoding the contract spec and meta sections
+    Meta(meta::Cmd),
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Meta(#[from] meta::Error),
+}
+
+impl Cmd {
+    pub fn run(&self) -> Result<(), Error> {
+        match &self {
+            Cmd::Meta(meta) => meta.run()?,
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,134 @@
+use std::io::Cursor;
+
+use clap::{command, Parser};
+use serde_json::{json, Map, Value};
+use soroban_spec_json::types::Entry;
+
+use crate::{
+    wasm,
+    xdr::{self, Limited, Limits, ReadXdr, ScEnvMetaEntry, ScMetaEntry, ScMetaV0, ScSpecEntry},
+};
+
+#[derive(Parser, Debug, Clone)]
+#[group(skip)]
+pub struct Cmd {
+    #[command(flatten)]
+    pub wasm: wasm::Args,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Wasm(#[from] wasm::Error),
+    #[error(transparent)]
+    Parser(#[from] wasmparser::BinaryReaderError),
+    #[error("decoding custom section {name}: {error}")]
+    CannotDecodeSection { name: String, error: xdr::Error },
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+impl Cmd {
+    pub fn run(&self) -> Result<(), Error> {
+        let sections = sections(&self.wasm.read()?)?;
+        println!("{}", serde_json::to_string_pretty(&sections)?);
+        Ok(())
+    }
+}
+
+/// Each custom section of the Wasm, in order, with its name and size in bytes. The sections
+/// contracts are built with are also decoded:
+/// - `contractenvmetav0`, the interface version of the environment the contract was built for
+/// - `contractmetav0`, the meta entries as an object
+/// - `contractspecv0`, the spec entries as in `contract bindings json`
+pub fn sections(wasm: &[u8]) -> Result<Vec<Value>, Error> {
+    let mut sections = Vec::new();
+    for payload in wasmparser::Parser::new(0).parse_all(wasm) {
+        let wasmparser::Payload::CustomSection(section) = payload? else {
+            continue;
+        };
+        let name = section.name();
+        let mut entry = json!({ "name": name, "size": section.data().len() });
+        if let Some(decoded) = decode(name, section.data())? {
+            entry["decoded"] = decoded;
+        }
+        sections.push(entry);
+    }
+    Ok(sections)
+}
+
+fn decode(name: &str, data: &[u8]) -> Result<Option<Value>, Error> {
+    Ok(Some(match name {
+        "contractenvmetav0" => Value::Array(
+            read_entries::<ScEnvMetaEntry>(name, data)?
+                .into_iter()
+                .map(|ScEnvMetaEntry::ScEnvMetaKindInterfaceVersion(version)| {
+                    json!({
+                        "interface_version": version,
+                        "protocol": version >> 32,
+                        "pre_release": version & 0xffff_ffff,
+                    })
+                })
+                .collect(),
+        ),
+        "contractmetav0" => Value::Object(
+            read_entries::<ScMetaEntry>(name, data)?
+                .into_iter()
+                .map(|ScMetaEntry::ScMetaV0(ScMetaV0 { key, val })| {
+                    (
+                        key.to_utf8_string_lossy(),
+                        Value::String(val.to_utf8_string_lossy()),
+                    )
+                })
+                .collect::<Map<_, _>>(),
+        ),
+        "contractspecv0" => serde_json::to_value(
+            read_entries::<ScSpecEntry>(name, data)?
+                .iter()
+                .map(Entry::from)
+                .collect::<Vec<_>>(),
+        )?,
+        _ => return Ok(None),
+    }))
+}
+
+fn read_entries<T: ReadXdr>(name: &str, data: &[u8]) -> Result<Vec<T>, Error> {
+    let mut read = Limited::new(Cursor::new(data), Limits::none());
+    T::read_xdr_iter(&mut read)
+        .collect::<Result<_, _>>()
+        .map_err(|error| Error::CannotDecodeSection {
+            name: name.to_string(),
+            error,
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const CONTRACT_META: &[u8] = include_bytes!(
+        "../../../../../../target/wasm32-unknown-unknown/test-wasms/test_contract_meta.wasm"
+    );
+
+    #[test]
+    fn lists_and_decodes_sections() {
+        let sections = sections(CONTRACT_META).unwrap();
+        let find = |name: &str| {
+            sections
+                .iter()
+                .find(|section| section["name"] == name)
+                .unwrap_or_else(|| panic!("no {name} section in {sections:?}"))
+        };
+        let env_meta = &find("contractenvmetav0")["decoded"][0];
+        assert_eq!(env_meta["protocol"], 21);
+        assert_eq!(
+            find("contractmetav0")["decoded"]["Description"],
+            "A test contract with meta entries"
+        );
+        let spec = find("contractspecv0")["decoded"].as_array().unwrap();
+        assert!(spec.iter().any(|entry| entry["name"] == "hello"));
+        assert!(sections
+            .iter()
+            .all(|section| section["size"].as_u64().is_some()));
+    }
+}
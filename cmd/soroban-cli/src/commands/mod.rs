@@ -10,6 +10,7 @@ pub mod contract;
 pub mod events;
 pub mod global;
 pub mod keys;
+pub mod lab;
 pub mod network;
 pub mod plugin;
 pub mod tx;
@@ -93,14 +94,18 @@ impl Root {
         Self::from_arg_matches_mut(&mut Self::command().get_matches_from(itr))
     }
     pub async fn run(&mut self) -> Result<(), Error> {
+        if self.global_args.json && !self.cmd.supports_json() {
+            return Err(Error::JsonNotSupported);
+        }
         match &mut self.cmd {
             Cmd::Completion(completion) => completion.run(),
             Cmd::Contract(contract) => contract.run(&self.global_args).await?,
-            Cmd::Events(events) => events.run().await?,
+            Cmd::Events(events) => events.run(&self.global_args).await?,
             Cmd::Xdr(xdr) => xdr.run()?,
             Cmd::Network(network) => network.run().await?,
             Cmd::Version(version) => version.run(),
-            Cmd::Keys(id) => id.run().await?,
+            Cmd::Keys(id) => id.run(&self.global_args).await?,
+            Cmd::Lab(lab) => lab.run()?,
             Cmd::Tx(tx) => tx.run(&self.global_args).await?,
             Cmd::Cache(data) => data.run()?,
         };
@@ -129,6 +134,9 @@ pub enum Cmd {
     /// Create and manage identities including keys and addresses
     #[command(subcommand)]
     Keys(keys::Cmd),
+    /// Experiment with contract values and types, and inspect Wasm files
+    #[command(subcommand)]
+    Lab(lab::Cmd),
     /// Decode and encode XDR
     Xdr(stellar_xdr::cli::Root),
     /// Start and configure networks
@@ -144,6 +152,17 @@ pub enum Cmd {
     Cache(cache::Cmd),
 }
 
+impl Cmd {
+    /// Whether the command prints its result as JSON with the global `--json`
+    pub fn supports_json(&self) -> bool {
+        match self {
+            Cmd::Contract(contract) => contract.supports_json(),
+            Cmd::Keys(keys::Cmd::Rotate(_)) | Cmd::Tx(tx::Cmd::New(_)) => true,
+            _ => false,
+        }
+    }
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     // TODO: stop using Debug for displaying errors
@@ -154,6 +173,8 @@ pub enum Error {
     #[error(transparent)]
     Keys(#[from] keys::Error),
     #[error(transparent)]
+    Lab(#[from] lab::Error),
+    #[error(transparent)]
     Xdr(#[from] stellar_xdr::cli::Error),
     #[error(transparent)]
     Clap(#[from] clap::error::Error),
@@ -165,6 +186,18 @@ pub enum Error {
     Tx(#[from] tx::Error),
     #[error(transparent)]
     Cache(#[from] cache::Error),
+    #[error("--json is not supported by this command")]
+    JsonNotSupported,
+}
+
+impl Error {
+    /// The process exit status for the error, 1 unless the command gives the error one of its own
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::Contract(contract::Error::Invoke(e)) => e.exit_code(),
+            _ => 1,
+        }
+    }
 }
 
 #[async_trait]
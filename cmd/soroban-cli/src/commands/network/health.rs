@@ -0,0 +1,138 @@
+use clap::command;
+
+use super::{locator, Error};
+
+#[derive(Debug, clap::Parser, Clone)]
+#[group(skip)]
+pub struct Cmd {
+    #[command(flatten)]
+    pub network: super::Args,
+
+    #[command(flatten)]
+    pub config_locator: locator::Args,
+}
+
+impl Cmd {
+    pub async fn run(&self) -> Result<(), Error> {
+        let health = self
+            .network
+            .get(&self.config_locator)
+            .await?
+            .get_health()
+            .await?;
+        println!("Status: {}", health.status);
+        println!("Latest ledger: {}", health.latest_ledger);
+        println!("Oldest ledger: {}", health.oldest_ledger);
+        println!(
+            "Ledger retention window: {}",
+            health.ledger_retention_window
+        );
+        if !health.is_healthy() {
+            return Err(Error::Unhealthy(health.status));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use httpmock::prelude::*;
+    use serde_json::json;
+
+    use super::super::{Error, GetHealthResponse, Network};
+    use crate::rpc_ext::RpcError;
+
+    fn network(server: &MockServer) -> Network {
+        Network {
+            rpc_url: server.url("/"),
+            network_passphrase: super::super::LOCAL_NETWORK_PASSPHRASE.to_string(),
+            rpc_timeout: super::super::DEFAULT_RPC_TIMEOUT,
+        }
+    }
+
+    #[tokio::test]
+    async fn healthy() {
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .json_body_partial(r#"{ "method": "getHealth" }"#);
+            then.status(200).json_body(json!({
+                "jsonrpc": "2.0",
+                "id": 0,
+                "result": {
+                    "status": "healthy",
+                    "latestLedger": 51583,
+                    "oldestLedger": 49856,
+                    "ledgerRetentionWindow": 1728
+                }
+            }));
+        });
+        let health = network(&server).get_health().await.unwrap();
+        assert_eq!(
+            health,
+            GetHealthResponse {
+                status: "healthy".to_string(),
+                latest_ledger: 51583,
+                oldest_ledger: 49856,
+                ledger_retention_window: 1728,
+            }
+        );
+        assert!(health.is_healthy());
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn rpc_error() {
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(POST).path("/");
+            then.status(200).json_body(json!({
+                "jsonrpc": "2.0",
+                "id": 0,
+                "error": {
+                    "code": -32601,
+                    "message": "method not found",
+                    "data": { "method": "getHealth" }
+                }
+            }));
+        });
+        let err = network(&server).get_health().await.unwrap_err();
+        let Error::RpcError(RpcError {
+            code,
+            message,
+            data,
+        }) = &err
+        else {
+            panic!("expected an RPC error, got {err:?}");
+        };
+        assert_eq!(*code, -32601);
+        assert_eq!(message, "method not found");
+        assert_eq!(data.as_deref(), Some(r#"{"method":"getHealth"}"#));
+        assert!(err.to_string().contains("-32601"), "{err}");
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn slow_server_times_out() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(POST).path("/");
+            then.status(200)
+                .delay(Duration::from_secs(5))
+                .json_body(json!({
+                    "jsonrpc": "2.0",
+                    "id": 0,
+                    "result": { "status": "healthy" }
+                }));
+        });
+        let network = Network {
+            rpc_timeout: Duration::from_millis(100),
+            ..network(&server)
+        };
+        let res = network.get_health().await;
+        assert!(matches!(res, Err(Error::RequestTimeout)), "{res:?}");
+    }
+}
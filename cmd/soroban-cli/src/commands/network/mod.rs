@@ -1,21 +1,36 @@
-use std::str::FromStr;
+use std::{str::FromStr, time::Duration};
 
 use clap::{arg, Parser};
+use jsonrpsee_core::{client::ClientT, rpc_params};
+use jsonrpsee_http_client::HttpClientBuilder;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use stellar_strkey::ed25519::PublicKey;
 
-use crate::{
-    commands::HEADING_RPC,
-    rpc::{self, Client},
-};
+use crate::{commands::HEADING_RPC, rpc, rpc_ext};
 
 use super::config::locator;
 
 pub const LOCAL_NETWORK_PASSPHRASE: &str = "Standalone Network ; February 2017";
 
+/// `--network-passphrase` value that uses the passphrase the RPC server reports
+pub const AUTO_NETWORK_PASSPHRASE: &str = "auto";
+
+/// Requests made to friendbot while it is rate limited or unavailable, before giving up
+const FRIENDBOT_ATTEMPTS: u32 = 4;
+/// Wait before retrying friendbot, doubled after every retry
+const FRIENDBOT_BACKOFF: Duration = Duration::from_millis(500);
+/// Times to look up a funded account, which the RPC server may not have ingested yet
+const CONFIRM_FUNDED_ATTEMPTS: u32 = 5;
+/// Wait between looking up a funded account
+const CONFIRM_FUNDED_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Time to wait for the response to an RPC request, unless overridden with `--rpc-timeout`
+pub const DEFAULT_RPC_TIMEOUT: Duration = Duration::from_secs(30);
+
 pub mod add;
 pub mod container;
+pub mod health;
 pub mod ls;
 pub mod rm;
 
@@ -27,6 +42,10 @@ pub enum Cmd {
     Rm(rm::Cmd),
     /// List networks
     Ls(ls::Cmd),
+    /// Check the health of a network's RPC server
+    ///
+    /// Exits with a non-zero status if the server does not report itself as healthy.
+    Health(health::Cmd),
     /// ⚠️ Deprecated: use `stellar container start` instead
     ///
     /// Start network
@@ -78,25 +97,62 @@ pub enum Error {
     #[error(transparent)]
     Http(#[from] http::Error),
     #[error(transparent)]
-    Rpc(#[from] rpc::Error),
+    Rpc(rpc::Error),
     #[error(transparent)]
     Hyper(#[from] hyper::Error),
+    #[error(transparent)]
+    JsonRpc(jsonrpsee_core::Error),
+    #[error(transparent)]
+    RpcError(#[from] rpc_ext::RpcError),
+    #[error("RPC request timed out, pass a longer --rpc-timeout to wait longer")]
+    RequestTimeout,
+    #[error("RPC server is not healthy, status: {0}")]
+    Unhealthy(String),
     #[error("Failed to parse JSON from {0}, {1}")]
     FailedToParseJSON(String, serde_json::Error),
     #[error("Invalid URL {0}")]
     InvalidUrl(String),
     #[error("Inproper response {0}")]
     InproperResponse(String),
+    #[error("friendbot is unavailable ({status}), fund the account manually by opening {url}")]
+    FriendbotUnavailable {
+        status: http::StatusCode,
+        url: String,
+    },
+    #[error("friendbot responded, but account {0} does not exist on the network")]
+    FundingNotConfirmed(String),
+    #[error("no friendbot or helper URL to fund account {0} from")]
+    NoFundingSource(String),
     #[error("Currently not supported on windows. Please visit:\n{0}")]
     WindowsNotSupported(String),
 }
 
+impl From<jsonrpsee_core::Error> for Error {
+    fn from(e: jsonrpsee_core::Error) -> Self {
+        match rpc_ext::RpcError::from_json_rpc(e) {
+            Ok(e) => Error::RpcError(e),
+            Err(jsonrpsee_core::Error::RequestTimeout) => Error::RequestTimeout,
+            Err(e) => Error::JsonRpc(e),
+        }
+    }
+}
+
+impl From<rpc::Error> for Error {
+    fn from(e: rpc::Error) -> Self {
+        match e {
+            rpc::Error::JsonRpc(e) => e.into(),
+            e => Error::Rpc(e),
+        }
+    }
+}
+
 impl Cmd {
     pub async fn run(&self) -> Result<(), Error> {
         match self {
             Cmd::Add(cmd) => cmd.run()?,
             Cmd::Rm(new) => new.run()?,
             Cmd::Ls(cmd) => cmd.run()?,
+            Cmd::Health(cmd) => cmd.run().await?,
             Cmd::Container(cmd) => cmd.run().await?,
 
             // TODO Remove this once `network start` is removed
@@ -126,7 +182,8 @@ pub struct Args {
         help_heading = HEADING_RPC,
     )]
     pub rpc_url: Option<String>,
-    /// Network passphrase to sign the transaction sent to the rpc server
+    /// Network passphrase to sign the transaction sent to the rpc server. Use `auto` to fetch it
+    /// from the rpc server
     #[arg(
         long = "network-passphrase",
         requires = "rpc_url",
@@ -143,21 +200,39 @@ pub struct Args {
         help_heading = HEADING_RPC,
     )]
     pub network: Option<String>,
+    /// Seconds to wait for the response to each RPC request. Doesn't limit waiting for a sent
+    /// transaction to be included. Default: `30`
+    #[arg(long, env = "STELLAR_RPC_TIMEOUT", help_heading = HEADING_RPC)]
+    pub rpc_timeout: Option<u64>,
 }
 
 impl Args {
-    pub fn get(&self, locator: &locator::Args) -> Result<Network, Error> {
+    /// Resolve the network to use. A `--network-passphrase auto` is fetched from the RPC server
+    /// here, so resolve the network once and pass the [`Network`] on.
+    pub async fn get(&self, locator: &locator::Args) -> Result<Network, Error> {
+        let rpc_timeout = self
+            .rpc_timeout
+            .map_or(DEFAULT_RPC_TIMEOUT, Duration::from_secs);
         if let Some(name) = self.network.as_deref() {
             if let Ok(network) = locator.read_network(name) {
-                return Ok(network);
+                return Ok(Network {
+                    rpc_timeout,
+                    ..network
+                });
             }
         }
         if let (Some(rpc_url), Some(network_passphrase)) =
             (self.rpc_url.clone(), self.network_passphrase.clone())
         {
+            let network_passphrase = if network_passphrase == AUTO_NETWORK_PASSPHRASE {
+                fetch_passphrase(&rpc_url, rpc_timeout).await?
+            } else {
+                network_passphrase
+            };
             Ok(Network {
                 rpc_url,
                 network_passphrase,
+                rpc_timeout,
             })
         } else {
             Err(Error::Network)
@@ -165,6 +240,14 @@ impl Args {
     }
 }
 
+/// The passphrase reported by the RPC server at `rpc_url`
+async fn fetch_passphrase(rpc_url: &str, rpc_timeout: Duration) -> Result<String, Error> {
+    let client = rpc_ext::Client::new(rpc_url, rpc_timeout)?;
+    let passphrase = client.get_network().await?.passphrase;
+    tracing::debug!("using network passphrase {passphrase:?} from {rpc_url}");
+    Ok(passphrase)
+}
+
 #[derive(Debug, clap::Args, Serialize, Deserialize, Clone)]
 #[group(skip)]
 pub struct Network {
@@ -182,12 +265,26 @@ pub struct Network {
             help_heading = HEADING_RPC,
         )]
     pub network_passphrase: String,
+    /// Time to wait for the response to each RPC request
+    #[arg(skip = DEFAULT_RPC_TIMEOUT)]
+    #[serde(skip, default = "default_rpc_timeout")]
+    pub rpc_timeout: Duration,
+}
+
+fn default_rpc_timeout() -> Duration {
+    DEFAULT_RPC_TIMEOUT
 }
 
 impl Network {
-    pub async fn helper_url(&self, addr: &str) -> Result<http::Uri, Error> {
+    /// Client for the network's RPC server, waiting for each response for at most the network's
+    /// RPC timeout
+    pub fn rpc_client(&self) -> Result<rpc_ext::Client, rpc::Error> {
+        rpc_ext::Client::new(&self.rpc_url, self.rpc_timeout)
+    }
+
+    /// Friendbot endpoint for this network, without the `addr` query
+    pub async fn friendbot_url(&self) -> Result<String, Error> {
         use http::Uri;
-        tracing::debug!("address {addr:?}");
         let rpc_uri = Uri::from_str(&self.rpc_url)
             .map_err(|_| Error::InvalidUrl(self.rpc_url.to_string()))?;
         if self.network_passphrase.as_str() == LOCAL_NETWORK_PASSPHRASE {
@@ -196,55 +293,241 @@ impl Network {
             Ok(Uri::builder()
                 .authority(auth)
                 .scheme(scheme)
-                .path_and_query(format!("/friendbot?addr={addr}"))
-                .build()?)
+                .path_and_query("/friendbot")
+                .build()?
+                .to_string())
         } else {
-            let client = Client::new(&self.rpc_url)?;
+            let client = self.rpc_client()?;
             let network = client.get_network().await?;
             tracing::debug!("network {network:?}");
-            let uri = client.friendbot_url().await?;
-            tracing::debug!("URI {uri:?}");
-            Uri::from_str(&format!("{uri}?addr={addr}")).map_err(|e| {
-                tracing::error!("{e}");
-                Error::InvalidUrl(uri.to_string())
-            })
+            Ok(client.friendbot_url().await?)
         }
     }
 
-    #[allow(clippy::similar_names)]
+    pub async fn helper_url(&self, addr: &str) -> Result<http::Uri, Error> {
+        tracing::debug!("address {addr:?}");
+        let uri = self.friendbot_url().await?;
+        tracing::debug!("URI {uri:?}");
+        friendbot_addr_url(&uri, addr)
+    }
+
+    /// Fund an address with friendbot, then check that the account exists on the network.
     pub async fn fund_address(&self, addr: &PublicKey) -> Result<(), Error> {
-        let uri = self.helper_url(&addr.to_string()).await?;
-        tracing::debug!("URL {uri:?}");
-        let response = match uri.scheme_str() {
-            Some("http") => hyper::Client::new().get(uri.clone()).await?,
-            Some("https") => {
-                let https = hyper_tls::HttpsConnector::new();
-                hyper::Client::builder()
-                    .build::<_, hyper::Body>(https)
-                    .get(uri.clone())
-                    .await?
-            }
-            _ => {
-                return Err(Error::InvalidUrl(uri.to_string()));
+        self.fund_address_with_helper(addr, None).await?;
+        Ok(())
+    }
+
+    /// Fund an address with friendbot, falling back to a POST to `helper_url` if friendbot is
+    /// unavailable or fails, then check that the account exists on the network.
+    ///
+    /// Returns the URL the account was funded from.
+    pub async fn fund_address_with_helper(
+        &self,
+        addr: &PublicKey,
+        helper_url: Option<&str>,
+    ) -> Result<String, Error> {
+        let sources = self.funding_sources(helper_url).await?;
+        let funded_by = fund_from(&sources, &addr.to_string()).await?;
+        self.confirm_funded(addr).await?;
+        Ok(funded_by)
+    }
+
+    /// Fund several addresses, at most `concurrency` at a time, looking up the friendbot URL once.
+    ///
+    /// Returns the URL each address was funded from, or its error, in the order given.
+    pub async fn fund_addresses(
+        &self,
+        addrs: &[PublicKey],
+        concurrency: usize,
+        helper_url: Option<&str>,
+    ) -> Result<Vec<Result<String, Error>>, Error> {
+        use futures_util::{stream, StreamExt};
+        let sources = self.funding_sources(helper_url).await?;
+        let sources = &sources;
+        Ok(stream::iter(addrs)
+            .map(|addr| async move {
+                let funded_by = fund_from(sources, &addr.to_string()).await?;
+                self.confirm_funded(addr).await?;
+                Ok::<_, Error>(funded_by)
+            })
+            .buffered(concurrency.max(1))
+            .collect()
+            .await)
+    }
+
+    /// Friendbot, unless it can't be looked up and there's a helper URL to use instead, then the
+    /// helper URL, each with the method to request funding with
+    async fn funding_sources(
+        &self,
+        helper_url: Option<&str>,
+    ) -> Result<Vec<(http::Method, String)>, Error> {
+        let mut sources = Vec::new();
+        match self.friendbot_url().await {
+            Ok(friendbot) => {
+                tracing::debug!("URI {friendbot:?}");
+                sources.push((http::Method::GET, friendbot));
             }
-        };
-        let body = hyper::body::to_bytes(response.into_body()).await?;
-        let res = serde_json::from_slice::<serde_json::Value>(&body)
-            .map_err(|e| Error::FailedToParseJSON(uri.to_string(), e))?;
-        tracing::debug!("{res:#?}");
-        if let Some(detail) = res.get("detail").and_then(Value::as_str) {
-            if detail.contains("createAccountAlreadyExist") {
-                eprintln!("Account already exists");
+            Err(e) if helper_url.is_some() => tracing::debug!("no friendbot: {e}"),
+            Err(e) => return Err(e),
+        }
+        sources.extend(helper_url.map(|url| (http::Method::POST, url.to_string())));
+        Ok(sources)
+    }
+
+    async fn confirm_funded(&self, addr: &PublicKey) -> Result<(), Error> {
+        let addr = addr.to_string();
+        let client = self.rpc_client()?;
+        let mut attempt = 1;
+        loop {
+            match client.get_account(&addr).await {
+                Ok(_) => return Ok(()),
+                Err(e) if attempt == CONFIRM_FUNDED_ATTEMPTS => {
+                    tracing::debug!("{e}");
+                    return Err(Error::FundingNotConfirmed(addr));
+                }
+                Err(e) => {
+                    tracing::debug!(
+                        "{addr} not found yet, retrying in {CONFIRM_FUNDED_INTERVAL:?}: {e}"
+                    );
+                    tokio::time::sleep(CONFIRM_FUNDED_INTERVAL).await;
+                    attempt += 1;
+                }
             }
-        } else if res.get("successful").is_none() {
-            return Err(Error::InproperResponse(res.to_string()));
         }
-        Ok(())
+    }
+
+    pub async fn get_health(&self) -> Result<GetHealthResponse, Error> {
+        let client = HttpClientBuilder::default()
+            .request_timeout(self.rpc_timeout)
+            .build(&self.rpc_url)?;
+        let health = client.request("getHealth", rpc_params![]).await?;
+        tracing::trace!("{health:?}");
+        Ok(health)
     }
 
     pub fn rpc_uri(&self) -> Result<http::Uri, Error> {
         http::Uri::from_str(&self.rpc_url).map_err(|_| Error::InvalidUrl(self.rpc_url.to_string()))
     }
+
+    /// Base URL of this network on stellar.expert, if it's one of the public networks it indexes
+    pub fn explorer_url(&self) -> Option<&'static str> {
+        match self.network_passphrase.as_str() {
+            "Public Global Stellar Network ; September 2015" => {
+                Some("https://stellar.expert/explorer/public")
+            }
+            "Test SDF Network ; September 2015" => Some("https://stellar.expert/explorer/testnet"),
+            "Test SDF Future Network ; October 2022" => {
+                Some("https://stellar.expert/explorer/futurenet")
+            }
+            _ => None,
+        }
+    }
+}
+
+fn friendbot_addr_url(friendbot: &str, addr: &str) -> Result<http::Uri, Error> {
+    let mut url = url::Url::parse(friendbot).map_err(|e| {
+        tracing::error!("{e}");
+        Error::InvalidUrl(friendbot.to_string())
+    })?;
+    url.query_pairs_mut().append_pair("addr", addr);
+    http::Uri::from_str(url.as_str()).map_err(|_| Error::InvalidUrl(url.to_string()))
+}
+
+/// Fund `addr` from the first of `sources` that succeeds, returning its URL, or the error of the
+/// last one if they all fail
+async fn fund_from(sources: &[(http::Method, String)], addr: &str) -> Result<String, Error> {
+    let mut error = None;
+    for (method, url) in sources {
+        match fund_at(friendbot_addr_url(url, addr)?, method).await {
+            Ok(()) => return Ok(url.clone()),
+            Err(e) => {
+                tracing::debug!("funding from {url} failed: {e}");
+                error = Some(e);
+            }
+        }
+    }
+    Err(error.unwrap_or_else(|| Error::NoFundingSource(addr.to_string())))
+}
+
+/// Request funding from a friendbot URL, retrying with backoff while friendbot is rate limited
+/// or unavailable.
+async fn fund_at(uri: http::Uri, method: &http::Method) -> Result<(), Error> {
+    let mut backoff = FRIENDBOT_BACKOFF;
+    let mut attempt = 1;
+    let response = loop {
+        let response = friendbot_request(&uri, method).await?;
+        let status = response.status();
+        if status != http::StatusCode::TOO_MANY_REQUESTS
+            && status != http::StatusCode::SERVICE_UNAVAILABLE
+        {
+            break response;
+        }
+        if attempt == FRIENDBOT_ATTEMPTS {
+            return Err(Error::FriendbotUnavailable {
+                status,
+                url: uri.to_string(),
+            });
+        }
+        tracing::debug!("friendbot responded {status}, retrying in {backoff:?}");
+        tokio::time::sleep(backoff).await;
+        backoff *= 2;
+        attempt += 1;
+    };
+    let body = hyper::body::to_bytes(response.into_body()).await?;
+    let res = serde_json::from_slice::<serde_json::Value>(&body)
+        .map_err(|e| Error::FailedToParseJSON(uri.to_string(), e))?;
+    tracing::debug!("{res:#?}");
+    if let Some(detail) = res.get("detail").and_then(Value::as_str) {
+        if detail.contains("createAccountAlreadyExist") {
+            eprintln!("Account already exists");
+        }
+    } else if res.get("successful").is_none() {
+        return Err(Error::InproperResponse(res.to_string()));
+    }
+    Ok(())
+}
+
+#[allow(clippy::similar_names)]
+async fn friendbot_request(
+    uri: &http::Uri,
+    method: &http::Method,
+) -> Result<hyper::Response<hyper::Body>, Error> {
+    tracing::debug!("{method} {uri:?}");
+    let request = hyper::Request::builder()
+        .method(method.clone())
+        .uri(uri.clone())
+        .body(hyper::Body::empty())?;
+    Ok(match uri.scheme_str() {
+        Some("http") => hyper::Client::new().request(request).await?,
+        Some("https") => {
+            let https = hyper_tls::HttpsConnector::new();
+            hyper::Client::builder()
+                .build::<_, hyper::Body>(https)
+                .request(request)
+                .await?
+        }
+        _ => {
+            return Err(Error::InvalidUrl(uri.to_string()));
+        }
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetHealthResponse {
+    pub status: String,
+    #[serde(default)]
+    pub latest_ledger: u32,
+    #[serde(default)]
+    pub oldest_ledger: u32,
+    #[serde(default)]
+    pub ledger_retention_window: u32,
+}
+
+impl GetHealthResponse {
+    pub fn is_healthy(&self) -> bool {
+        self.status == "healthy"
+    }
 }
 
 impl Network {
@@ -252,6 +535,7 @@ impl Network {
         Network {
             rpc_url: "https://rpc-futurenet.stellar.org:443".to_owned(),
             network_passphrase: "Test SDF Future Network ; October 2022".to_owned(),
+            rpc_timeout: DEFAULT_RPC_TIMEOUT,
         }
     }
 }
@@ -0,0 +1,106 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::xdr::{
+    self, InvokeHostFunctionOp, Limits, Operation, OperationBody, ReadXdr,
+    SorobanAuthorizationEntry, Transaction, TransactionEnvelope, WriteXdr,
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    XdrArgs(#[from] super::xdr::Error),
+    #[error(transparent)]
+    Xdr(#[from] xdr::Error),
+    #[error("reading {0:?}: {1}")]
+    CannotReadAuthFile(PathBuf, io::Error),
+    #[error("writing {0:?}: {1}")]
+    CannotWriteAuthFile(PathBuf, io::Error),
+    #[error("{0:?} is not a JSON array of base64 auth entries: {1}")]
+    InvalidAuthFile(PathBuf, serde_json::Error),
+    #[error("serializing auth entries: {0}")]
+    CannotSerializeAuth(serde_json::Error),
+    #[error("transaction must have a single invoke host function operation to hold auth entries")]
+    NotInvokeHostFunction,
+    #[error("transaction has {expected} auth entries, but the file has {found}")]
+    AuthCountMismatch { expected: usize, found: usize },
+}
+
+/// Command to replace the auth entries of a transaction envelope from stdin with ones from a file,
+/// e.g. after they were signed offline
+/// e.g. `cat file.txt | soroban tx attach-auth --auth auth.json`
+#[derive(Debug, clap::Parser, Clone, Default)]
+#[group(skip)]
+pub struct Cmd {
+    /// JSON array of base64 XDR `SorobanAuthorizationEntry`s, as written by
+    /// `tx simulate --save-auth`
+    #[arg(long)]
+    pub auth: PathBuf,
+}
+
+impl Cmd {
+    pub fn run(&self) -> Result<(), Error> {
+        let mut tx = super::xdr::unwrap_envelope_v1(super::xdr::tx_envelope_from_stdin()?)?;
+        set_auth_entries(&mut tx, read_auth_file(&self.auth)?)?;
+        let tx_env: TransactionEnvelope = tx.into();
+        println!("{}", tx_env.to_xdr_base64(Limits::none())?);
+        Ok(())
+    }
+}
+
+fn invoke_op(tx: &Transaction) -> Result<&InvokeHostFunctionOp, Error> {
+    match tx.operations.as_slice() {
+        [Operation {
+            body: OperationBody::InvokeHostFunction(invoke),
+            ..
+        }] => Ok(invoke),
+        _ => Err(Error::NotInvokeHostFunction),
+    }
+}
+
+/// The auth entries of a transaction's invoke host function operation
+pub fn auth_entries(tx: &Transaction) -> Result<Vec<SorobanAuthorizationEntry>, Error> {
+    Ok(invoke_op(tx)?.auth.to_vec())
+}
+
+/// Replace the auth entries of a transaction's invoke host function operation. There must be as
+/// many entries as the transaction already has.
+pub fn set_auth_entries(
+    tx: &mut Transaction,
+    auth: Vec<SorobanAuthorizationEntry>,
+) -> Result<(), Error> {
+    let mut invoke = invoke_op(tx)?.clone();
+    if invoke.auth.len() != auth.len() {
+        return Err(Error::AuthCountMismatch {
+            expected: invoke.auth.len(),
+            found: auth.len(),
+        });
+    }
+    invoke.auth = auth.try_into()?;
+    let mut op = tx.operations[0].clone();
+    op.body = OperationBody::InvokeHostFunction(invoke);
+    tx.operations = vec![op].try_into()?;
+    Ok(())
+}
+
+pub fn write_auth_file(path: &Path, auth: &[SorobanAuthorizationEntry]) -> Result<(), Error> {
+    let entries = auth
+        .iter()
+        .map(|entry| entry.to_xdr_base64(Limits::none()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let json = serde_json::to_string_pretty(&entries).map_err(Error::CannotSerializeAuth)?;
+    fs::write(path, json).map_err(|e| Error::CannotWriteAuthFile(path.to_path_buf(), e))
+}
+
+pub fn read_auth_file(path: &Path) -> Result<Vec<SorobanAuthorizationEntry>, Error> {
+    let json =
+        fs::read_to_string(path).map_err(|e| Error::CannotReadAuthFile(path.to_path_buf(), e))?;
+    let entries: Vec<String> =
+        serde_json::from_str(&json).map_err(|e| Error::InvalidAuthFile(path.to_path_buf(), e))?;
+    Ok(entries
+        .iter()
+        .map(|entry| SorobanAuthorizationEntry::from_xdr_base64(entry, Limits::none()))
+        .collect::<Result<_, _>>()?)
+}
@@ -0,0 +1,237 @@
+use crate::xdr::{
+    self, InnerTransactionResultResult, InvokeHostFunctionResult, Limits, OperationResult,
+    OperationResultTr, ReadXdr, TransactionResult, TransactionResultCode, TransactionResultResult,
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Xdr(#[from] xdr::Error),
+}
+
+/// Command to describe a `TransactionResult`, e.g. one the RPC server returned for a transaction
+/// e.g. `soroban tx decode-result --xdr AAAAAAAAAGT////...`
+#[derive(Debug, clap::Parser, Clone, Default)]
+#[group(skip)]
+pub struct Cmd {
+    /// Base64 encoded `TransactionResult` XDR
+    #[arg(long)]
+    pub xdr: String,
+}
+
+impl Cmd {
+    pub fn run(&self) -> Result<(), Error> {
+        let result = TransactionResult::from_xdr_base64(&self.xdr, Limits::none())?;
+        println!("{}", describe(&result).join("\n"));
+        Ok(())
+    }
+}
+
+/// One line for the transaction's result code, its fee and each of its operations' results. The
+/// inner transaction of a fee bump is described after it, indented.
+pub fn describe(result: &TransactionResult) -> Vec<String> {
+    let mut lines = vec![
+        describe_code(result.result.discriminant()),
+        format!("Fee charged: {} stroops", result.fee_charged),
+    ];
+    match &result.result {
+        TransactionResultResult::TxFeeBumpInnerSuccess(inner)
+        | TransactionResultResult::TxFeeBumpInnerFailed(inner) => {
+            lines.push(format!(
+                "Inner transaction {}:",
+                hex::encode(inner.transaction_hash.0)
+            ));
+            let inner = &inner.result;
+            let mut inner_lines = vec![
+                describe_code(inner.result.discriminant()),
+                format!("Fee charged: {} stroops", inner.fee_charged),
+            ];
+            if let InnerTransactionResultResult::TxSuccess(ops)
+            | InnerTransactionResultResult::TxFailed(ops) = &inner.result
+            {
+                inner_lines.extend(describe_ops(ops));
+            }
+            lines.extend(inner_lines.into_iter().map(|line| format!("  {line}")));
+        }
+        TransactionResultResult::TxSuccess(ops) | TransactionResultResult::TxFailed(ops) => {
+            lines.extend(describe_ops(ops));
+        }
+        _ => {}
+    }
+    lines
+}
+
+fn describe_code(code: TransactionResultCode) -> String {
+    let (name, message) = transaction_result_message(code);
+    format!("{name}: {message}")
+}
+
+fn describe_ops(ops: &[OperationResult]) -> impl Iterator<Item = String> + '_ {
+    ops.iter()
+        .enumerate()
+        .map(|(i, op)| format!("Operation {i}: {}", operation_result_message(op)))
+}
+
+/// The name of a transaction result code as in the XDR definition, and what it means
+pub fn transaction_result_message(code: TransactionResultCode) -> (&'static str, &'static str) {
+    match code {
+        TransactionResultCode::TxFeeBumpInnerSuccess => (
+            "txFEE_BUMP_INNER_SUCCESS",
+            "the fee bump's inner transaction succeeded",
+        ),
+        TransactionResultCode::TxFeeBumpInnerFailed => (
+            "txFEE_BUMP_INNER_FAILED",
+            "the fee bump's inner transaction failed",
+        ),
+        TransactionResultCode::TxSuccess => ("txSUCCESS", "all operations succeeded"),
+        TransactionResultCode::TxFailed => (
+            "txFAILED",
+            "one of the operations failed, so none were applied",
+        ),
+        TransactionResultCode::TxTooEarly => (
+            "txTOO_EARLY",
+            "the ledger closed before the transaction's minimum time",
+        ),
+        TransactionResultCode::TxTooLate => (
+            "txTOO_LATE",
+            "the ledger closed after the transaction's maximum time",
+        ),
+        TransactionResultCode::TxMissingOperation => {
+            ("txMISSING_OPERATION", "the transaction has no operations")
+        }
+        TransactionResultCode::TxBadSeq => (
+            "txBAD_SEQ",
+            "the sequence number doesn't follow the source account's",
+        ),
+        TransactionResultCode::TxBadAuth => (
+            "txBAD_AUTH",
+            "too few valid signatures, or signed for the wrong network",
+        ),
+        TransactionResultCode::TxInsufficientBalance => (
+            "txINSUFFICIENT_BALANCE",
+            "the fee would bring the source account below its reserve",
+        ),
+        TransactionResultCode::TxNoAccount => ("txNO_ACCOUNT", "the source account doesn't exist"),
+        TransactionResultCode::TxInsufficientFee => (
+            "txINSUFFICIENT_FEE",
+            "the fee is below the network's minimum",
+        ),
+        TransactionResultCode::TxBadAuthExtra => (
+            "txBAD_AUTH_EXTRA",
+            "the transaction has signatures that aren't needed",
+        ),
+        TransactionResultCode::TxInternalError => ("txINTERNAL_ERROR", "an unknown error occurred"),
+        TransactionResultCode::TxNotSupported => {
+            ("txNOT_SUPPORTED", "the transaction type isn't supported")
+        }
+        TransactionResultCode::TxBadSponsorship => {
+            ("txBAD_SPONSORSHIP", "a sponsorship was begun but not ended")
+        }
+        TransactionResultCode::TxBadMinSeqAgeOrGap => (
+            "txBAD_MIN_SEQ_AGE_OR_GAP",
+            "the minimum sequence age or ledger gap precondition isn't met",
+        ),
+        TransactionResultCode::TxMalformed => ("txMALFORMED", "a precondition is invalid"),
+        TransactionResultCode::TxSorobanInvalid => (
+            "txSOROBAN_INVALID",
+            "the Soroban resources or fees of the transaction are invalid",
+        ),
+    }
+}
+
+/// What an operation's result means. Results of contract invocations are explained, other
+/// operations' results are given by their XDR name.
+pub fn operation_result_message(result: &OperationResult) -> String {
+    match result {
+        OperationResult::OpInner(tr) => match tr {
+            OperationResultTr::InvokeHostFunction(r) => format!(
+                "InvokeHostFunction {}: {}",
+                r.name(),
+                invoke_host_function_message(r)
+            ),
+            OperationResultTr::CreateAccount(r) => format!("CreateAccount {}", r.name()),
+            OperationResultTr::Payment(r) => format!("Payment {}", r.name()),
+            OperationResultTr::PathPaymentStrictReceive(r) => {
+                format!("PathPaymentStrictReceive {}", r.name())
+            }
+            OperationResultTr::ManageSellOffer(r) => format!("ManageSellOffer {}", r.name()),
+            OperationResultTr::CreatePassiveSellOffer(r) => {
+                format!("CreatePassiveSellOffer {}", r.name())
+            }
+            OperationResultTr::SetOptions(r) => format!("SetOptions {}", r.name()),
+            OperationResultTr::ChangeTrust(r) => format!("ChangeTrust {}", r.name()),
+            OperationResultTr::AllowTrust(r) => format!("AllowTrust {}", r.name()),
+            OperationResultTr::AccountMerge(r) => format!("AccountMerge {}", r.name()),
+            OperationResultTr::Inflation(r) => format!("Inflation {}", r.name()),
+            OperationResultTr::ManageData(r) => format!("ManageData {}", r.name()),
+            OperationResultTr::BumpSequence(r) => format!("BumpSequence {}", r.name()),
+            OperationResultTr::ManageBuyOffer(r) => format!("ManageBuyOffer {}", r.name()),
+            OperationResultTr::PathPaymentStrictSend(r) => {
+                format!("PathPaymentStrictSend {}", r.name())
+            }
+            OperationResultTr::CreateClaimableBalance(r) => {
+                format!("CreateClaimableBalance {}", r.name())
+            }
+            OperationResultTr::ClaimClaimableBalance(r) => {
+                format!("ClaimClaimableBalance {}", r.name())
+            }
+            OperationResultTr::BeginSponsoringFutureReserves(r) => {
+                format!("BeginSponsoringFutureReserves {}", r.name())
+            }
+            OperationResultTr::EndSponsoringFutureReserves(r) => {
+                format!("EndSponsoringFutureReserves {}", r.name())
+            }
+            OperationResultTr::RevokeSponsorship(r) => format!("RevokeSponsorship {}", r.name()),
+            OperationResultTr::Clawback(r) => format!("Clawback {}", r.name()),
+            OperationResultTr::ClawbackClaimableBalance(r) => {
+                format!("ClawbackClaimableBalance {}", r.name())
+            }
+            OperationResultTr::SetTrustLineFlags(r) => format!("SetTrustLineFlags {}", r.name()),
+            OperationResultTr::LiquidityPoolDeposit(r) => {
+                format!("LiquidityPoolDeposit {}", r.name())
+            }
+            OperationResultTr::LiquidityPoolWithdraw(r) => {
+                format!("LiquidityPoolWithdraw {}", r.name())
+            }
+            OperationResultTr::ExtendFootprintTtl(r) => format!("ExtendFootprintTtl {}", r.name()),
+            OperationResultTr::RestoreFootprint(r) => format!("RestoreFootprint {}", r.name()),
+        },
+        OperationResult::OpBadAuth => {
+            "opBAD_AUTH: too few valid signatures, or signed for the wrong network".to_string()
+        }
+        OperationResult::OpNoAccount => {
+            "opNO_ACCOUNT: the source account doesn't exist".to_string()
+        }
+        OperationResult::OpNotSupported => {
+            "opNOT_SUPPORTED: the operation isn't supported".to_string()
+        }
+        OperationResult::OpTooManySubentries => {
+            "opTOO_MANY_SUBENTRIES: the account has the maximum number of subentries".to_string()
+        }
+        OperationResult::OpExceededWorkLimit => {
+            "opEXCEEDED_WORK_LIMIT: the operation did too much work".to_string()
+        }
+        OperationResult::OpTooManySponsoring => {
+            "opTOO_MANY_SPONSORING: the account sponsors too many entries".to_string()
+        }
+    }
+}
+
+fn invoke_host_function_message(result: &InvokeHostFunctionResult) -> &'static str {
+    match result {
+        InvokeHostFunctionResult::Success(_) => "the contract function was invoked",
+        InvokeHostFunctionResult::Malformed => "the operation is malformed",
+        InvokeHostFunctionResult::Trapped => {
+            "the contract function failed, its diagnostic events have the contract's error"
+        }
+        InvokeHostFunctionResult::ResourceLimitExceeded => {
+            "the invocation used more resources than the transaction declares"
+        }
+        InvokeHostFunctionResult::EntryArchived => {
+            "an entry in the footprint is archived, restore it with `contract restore`"
+        }
+        InvokeHostFunctionResult::InsufficientRefundableFee => {
+            "the refundable fee doesn't cover the rent and events of the invocation"
+        }
+    }
+}
@@ -0,0 +1,351 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::xdr::{
+    self, DecoratedSignature, InvokeHostFunctionOp, Limits, Operation, OperationBody, ReadXdr,
+    ScVal, SorobanAddressCredentials, SorobanAuthorizationEntry, SorobanCredentials, Transaction,
+    TransactionEnvelope, TransactionV1Envelope, VecM,
+};
+
+use super::simulate::describe_address;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    XdrArgs(#[from] super::xdr::Error),
+    #[error("reading {path:?}: {error}")]
+    CannotReadEnvelope {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+    #[error("decoding the transaction envelope in {path:?}: {error}")]
+    CannotDecodeEnvelope { path: PathBuf, error: xdr::Error },
+}
+
+/// Command to compare two versions of a transaction envelope, e.g. before and after it was signed
+/// e.g. `soroban tx diff --before unsigned.xdr --after signed.xdr`
+#[derive(Debug, clap::Parser, Clone, Default)]
+#[group(skip)]
+pub struct Cmd {
+    /// File with the base64 transaction envelope before the change
+    #[arg(long)]
+    pub before: PathBuf,
+    /// File with the base64 transaction envelope after the change
+    #[arg(long)]
+    pub after: PathBuf,
+}
+
+/// Differences between two versions of a transaction envelope
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Diff {
+    /// Signatures and auth entry signatures that were added, changed or removed
+    pub signatures: Vec<String>,
+    /// Changes to the transaction itself, such as to its fee or operations, which signing alone
+    /// doesn't make
+    pub warnings: Vec<String>,
+}
+
+impl Cmd {
+    pub fn run(&self) -> Result<(), Error> {
+        let diff = diff(&read_envelope(&self.before)?, &read_envelope(&self.after)?)?;
+        if diff.warnings.is_empty() {
+            println!("Transaction: identical");
+        } else {
+            println!("Transaction: changed");
+        }
+        for line in &diff.signatures {
+            println!("{line}");
+        }
+        for warning in &diff.warnings {
+            eprintln!("warning: {warning}");
+        }
+        Ok(())
+    }
+}
+
+fn read_envelope(path: &Path) -> Result<TransactionEnvelope, Error> {
+    let base64 = fs::read_to_string(path).map_err(|error| Error::CannotReadEnvelope {
+        path: path.to_path_buf(),
+        error,
+    })?;
+    TransactionEnvelope::from_xdr_base64(base64.trim(), Limits::none()).map_err(|error| {
+        Error::CannotDecodeEnvelope {
+            path: path.to_path_buf(),
+            error,
+        }
+    })
+}
+
+/// Compare the signatures, auth entries and the rest of two versions of a transaction envelope
+pub fn diff(before: &TransactionEnvelope, after: &TransactionEnvelope) -> Result<Diff, Error> {
+    let (before_tx, before_signatures) = unwrap_envelope(before)?;
+    let (after_tx, after_signatures) = unwrap_envelope(after)?;
+    let mut diff = Diff::default();
+    for signature in after_signatures.iter() {
+        if !before_signatures.contains(signature) {
+            diff.signatures.push(format!(
+                "signature added: {}",
+                hex::encode(signature.hint.0)
+            ));
+        }
+    }
+    for signature in before_signatures.iter() {
+        if !after_signatures.contains(signature) {
+            diff.signatures.push(format!(
+                "signature removed: {}",
+                hex::encode(signature.hint.0)
+            ));
+        }
+    }
+
+    if before_tx.fee != after_tx.fee {
+        diff.warnings.push(format!(
+            "fee changed from {} to {}",
+            before_tx.fee, after_tx.fee
+        ));
+    }
+    if before_tx.seq_num != after_tx.seq_num {
+        diff.warnings.push(format!(
+            "sequence number changed from {} to {}",
+            before_tx.seq_num.0, after_tx.seq_num.0
+        ));
+    }
+    if before_tx.source_account != after_tx.source_account {
+        diff.warnings.push("source account changed".to_string());
+    }
+    if before_tx.cond != after_tx.cond {
+        diff.warnings.push("preconditions changed".to_string());
+    }
+    if before_tx.memo != after_tx.memo {
+        diff.warnings.push("memo changed".to_string());
+    }
+    if before_tx.ext != after_tx.ext {
+        diff.warnings.push("Soroban resources changed".to_string());
+    }
+    if before_tx.operations.len() != after_tx.operations.len() {
+        diff.warnings.push(format!(
+            "number of operations changed from {} to {}",
+            before_tx.operations.len(),
+            after_tx.operations.len()
+        ));
+    }
+    for (i, (before_op, after_op)) in before_tx
+        .operations
+        .iter()
+        .zip(after_tx.operations.iter())
+        .enumerate()
+    {
+        if without_auth(before_op) != without_auth(after_op) {
+            diff.warnings.push(format!("operation {i} changed"));
+        }
+        let (before_auth, after_auth) = (auth(before_op), auth(after_op));
+        if before_auth.len() != after_auth.len() {
+            diff.warnings.push(format!(
+                "number of auth entries of operation {i} changed from {} to {}",
+                before_auth.len(),
+                after_auth.len()
+            ));
+        }
+        for (j, (before_entry, after_entry)) in before_auth.iter().zip(after_auth).enumerate() {
+            diff_auth_entry(&format!("{i}.{j}"), before_entry, after_entry, &mut diff);
+        }
+    }
+    Ok(diff)
+}
+
+/// Only the signature, and the ledger it expires after, of an auth entry may change with signing
+fn diff_auth_entry(
+    index: &str,
+    before: &SorobanAuthorizationEntry,
+    after: &SorobanAuthorizationEntry,
+    diff: &mut Diff,
+) {
+    if before.root_invocation != after.root_invocation {
+        diff.warnings
+            .push(format!("invocation of auth entry {index} changed"));
+    }
+    match (&before.credentials, &after.credentials) {
+        (SorobanCredentials::SourceAccount, SorobanCredentials::SourceAccount) => {}
+        (
+            SorobanCredentials::Address(SorobanAddressCredentials {
+                address: before_address,
+                nonce: before_nonce,
+                signature_expiration_ledger: before_expiration,
+                signature: before_signature,
+            }),
+            SorobanCredentials::Address(SorobanAddressCredentials {
+                address,
+                nonce,
+                signature_expiration_ledger,
+                signature,
+            }),
+        ) if before_address == address && before_nonce == nonce => {
+            if before_signature == signature {
+                if before_expiration != signature_expiration_ledger {
+                    diff.warnings.push(format!(
+                        "signature expiration of auth entry {index} changed without its signature"
+                    ));
+                }
+                return;
+            }
+            let address = describe_address(address);
+            let change = match (before_signature, signature) {
+                (ScVal::Void, _) => "signed",
+                (_, ScVal::Void) => "signature removed",
+                _ => "signature changed",
+            };
+            diff.signatures
+                .push(format!("auth entry {index} of {address}: {change}"));
+        }
+        _ => diff
+            .warnings
+            .push(format!("credentials of auth entry {index} changed")),
+    }
+}
+
+fn unwrap_envelope(
+    tx_env: &TransactionEnvelope,
+) -> Result<(&Transaction, &VecM<DecoratedSignature, 20>), Error> {
+    let TransactionEnvelope::Tx(TransactionV1Envelope { tx, signatures }) = tx_env else {
+        return Err(super::xdr::Error::OnlyTransactionV1Supported.into());
+    };
+    Ok((tx, signatures))
+}
+
+fn auth(op: &Operation) -> &[SorobanAuthorizationEntry] {
+    match &op.body {
+        OperationBody::InvokeHostFunction(InvokeHostFunctionOp { auth, .. }) => auth.as_slice(),
+        _ => &[],
+    }
+}
+
+fn without_auth(op: &Operation) -> Operation {
+    let mut op = op.clone();
+    if let OperationBody::InvokeHostFunction(invoke) = &mut op.body {
+        invoke.auth = VecM::default();
+    }
+    op
+}
+
+#[cfg(test)]
+mod test {
+    use crate::signer;
+    use crate::xdr::{
+        AccountId, Hash, HostFunction, InvokeContractArgs, Memo, MuxedAccount, Preconditions,
+        PublicKey, ScAddress, ScSymbol, SequenceNumber, SorobanAuthorizedFunction,
+        SorobanAuthorizedInvocation, TransactionExt, Uint256,
+    };
+
+    use super::*;
+
+    const PASSPHRASE: &str = "Test SDF Network ; September 2015";
+
+    fn key(seed: u8) -> ed25519_dalek::SigningKey {
+        ed25519_dalek::SigningKey::from_bytes(&[seed; 32])
+    }
+
+    fn unsigned_entry(signer: &ed25519_dalek::SigningKey) -> SorobanAuthorizationEntry {
+        SorobanAuthorizationEntry {
+            credentials: SorobanCredentials::Address(SorobanAddressCredentials {
+                address: ScAddress::Account(AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(
+                    signer.verifying_key().to_bytes(),
+                )))),
+                nonce: 1,
+                signature_expiration_ledger: 0,
+                signature: ScVal::Void,
+            }),
+            root_invocation: SorobanAuthorizedInvocation {
+                function: SorobanAuthorizedFunction::ContractFn(InvokeContractArgs {
+                    contract_address: ScAddress::Contract(Hash([0; 32])),
+                    function_name: ScSymbol("hello".try_into().unwrap()),
+                    args: VecM::default(),
+                }),
+                sub_invocations: VecM::default(),
+            },
+        }
+    }
+
+    /// A transaction invoking a contract that needs the auth of two other accounts
+    fn unsigned_tx(source: &ed25519_dalek::SigningKey) -> Transaction {
+        Transaction {
+            source_account: MuxedAccount::Ed25519(Uint256(source.verifying_key().to_bytes())),
+            fee: 100,
+            seq_num: SequenceNumber(1),
+            cond: Preconditions::None,
+            memo: Memo::None,
+            operations: vec![Operation {
+                source_account: None,
+                body: OperationBody::InvokeHostFunction(InvokeHostFunctionOp {
+                    host_function: HostFunction::InvokeContract(InvokeContractArgs {
+                        contract_address: ScAddress::Contract(Hash([0; 32])),
+                        function_name: ScSymbol("hello".try_into().unwrap()),
+                        args: VecM::default(),
+                    }),
+                    auth: vec![unsigned_entry(&key(2)), unsigned_entry(&key(3))]
+                        .try_into()
+                        .unwrap(),
+                }),
+            }]
+            .try_into()
+            .unwrap(),
+            ext: TransactionExt::V0,
+        }
+    }
+
+    fn envelope(tx: Transaction) -> TransactionEnvelope {
+        TransactionEnvelope::Tx(TransactionV1Envelope {
+            tx,
+            signatures: VecM::default(),
+        })
+    }
+
+    #[test]
+    fn only_signatures_differ_after_signing() {
+        let source = key(1);
+        let unsigned = unsigned_tx(&source);
+        let signed = signer::sign_soroban_authorizations(
+            &unsigned,
+            &source,
+            &[key(2), key(3)],
+            &[],
+            100,
+            PASSPHRASE,
+        )
+        .unwrap()
+        .unwrap();
+        let signed = signer::sign_tx_env(&[source], envelope(signed), PASSPHRASE).unwrap();
+
+        let diff = diff(&envelope(unsigned), &signed).unwrap();
+        assert_eq!(diff.warnings, Vec::<String>::new());
+        assert_eq!(diff.signatures.len(), 3, "{:?}", diff.signatures);
+        assert!(diff.signatures[0].starts_with("signature added: "));
+        assert!(diff.signatures[1].starts_with("auth entry 0.0 of G"));
+        assert!(diff.signatures[1].ends_with(": signed"));
+        assert!(diff.signatures[2].starts_with("auth entry 0.1 of G"));
+    }
+
+    #[test]
+    fn fee_and_operation_changes_are_warnings() {
+        let unsigned = unsigned_tx(&key(1));
+        let mut changed = unsigned.clone();
+        changed.fee = 10_000;
+        let mut op = changed.operations[0].clone();
+        if let OperationBody::InvokeHostFunction(invoke) = &mut op.body {
+            invoke.host_function = HostFunction::InvokeContract(InvokeContractArgs {
+                contract_address: ScAddress::Contract(Hash([9; 32])),
+                function_name: ScSymbol("hello".try_into().unwrap()),
+                args: VecM::default(),
+            });
+        }
+        changed.operations = vec![op].try_into().unwrap();
+
+        let diff = diff(&envelope(unsigned), &envelope(changed)).unwrap();
+        assert_eq!(diff.signatures, Vec::<String>::new());
+        assert_eq!(
+            diff.warnings,
+            ["fee changed from 100 to 10000", "operation 0 changed"]
+        );
+    }
+}
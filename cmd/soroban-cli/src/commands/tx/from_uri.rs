@@ -0,0 +1,57 @@
+use url::Url;
+
+use crate::xdr::{self, Limits, ReadXdr, TransactionEnvelope, WriteXdr};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Url(#[from] url::ParseError),
+    #[error("not a SEP-0007 transaction URI, expected it to start with `web+stellar:tx?`: {0}")]
+    NotTxUri(String),
+    #[error("URI has no `xdr` parameter")]
+    MissingXdr,
+    #[error(transparent)]
+    Xdr(#[from] xdr::Error),
+}
+
+/// Command to print the transaction envelope of a SEP-0007 `web+stellar:tx` URI
+/// e.g. `soroban tx from-uri "web+stellar:tx?xdr=..."`
+#[derive(Debug, clap::Parser, Clone, Default)]
+#[group(skip)]
+pub struct Cmd {
+    /// `web+stellar:tx?...` URI to read the transaction envelope from
+    pub uri: String,
+}
+
+impl Cmd {
+    pub fn run(&self) -> Result<(), Error> {
+        println!("{}", tx_from_uri(&self.uri)?.to_xdr_base64(Limits::none())?);
+        Ok(())
+    }
+}
+
+/// The envelope in the `xdr` parameter of a SEP-0007 `tx` operation URI. The XDR may use either
+/// the standard or the URL-safe base64 alphabet.
+pub fn tx_from_uri(uri: &str) -> Result<TransactionEnvelope, Error> {
+    let url = Url::parse(uri)?;
+    if url.scheme() != "web+stellar" || url.path() != "tx" {
+        return Err(Error::NotTxUri(uri.to_string()));
+    }
+    let (_, xdr) = url
+        .query_pairs()
+        .find(|(name, _)| name == "xdr")
+        .ok_or(Error::MissingXdr)?;
+    // Query decoding turns an unescaped `+` into a space, base64 never contains spaces
+    let mut xdr: String = xdr
+        .chars()
+        .map(|c| match c {
+            ' ' | '-' => '+',
+            '_' => '/',
+            c => c,
+        })
+        .collect();
+    while xdr.len() % 4 != 0 {
+        xdr.push('=');
+    }
+    Ok(TransactionEnvelope::from_xdr_base64(xdr, Limits::none())?)
+}
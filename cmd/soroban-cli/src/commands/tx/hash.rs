@@ -0,0 +1,44 @@
+use crate::{
+    signer,
+    xdr::{self, TransactionEnvelope, TransactionSignaturePayloadTaggedTransaction},
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    XdrArgs(#[from] super::xdr::Error),
+    #[error(transparent)]
+    Xdr(#[from] xdr::Error),
+    #[error("v0 transaction envelopes are not supported, only v1 and fee bump envelopes")]
+    TxV0NotSupported,
+}
+
+/// Command to calculate the hash of a transaction envelope from stdin
+/// e.g. `cat file.txt | soroban tx hash --network-passphrase "..."`
+#[derive(Debug, clap::Parser, Clone, Default)]
+#[group(skip)]
+pub struct Cmd {
+    /// Network passphrase the transaction will be signed for
+    #[arg(long, env = "STELLAR_NETWORK_PASSPHRASE")]
+    pub network_passphrase: String,
+}
+
+impl Cmd {
+    pub fn run(&self) -> Result<(), Error> {
+        let tx_env = super::xdr::tx_envelope_from_stdin()?;
+        println!("{}", hex::encode(hash(&tx_env, &self.network_passphrase)?));
+        Ok(())
+    }
+}
+
+/// The hash of the signature payload for an envelope, i.e. what signers sign.
+pub fn hash(tx_env: &TransactionEnvelope, network_passphrase: &str) -> Result<[u8; 32], Error> {
+    Ok(match tx_env {
+        TransactionEnvelope::Tx(env) => signer::hash(&env.tx, network_passphrase)?,
+        TransactionEnvelope::TxFeeBump(env) => signer::payload_hash(
+            TransactionSignaturePayloadTaggedTransaction::TxFeeBump(env.tx.clone()),
+            network_passphrase,
+        )?,
+        TransactionEnvelope::TxV0(_) => return Err(Error::TxV0NotSupported),
+    })
+}
@@ -2,26 +2,87 @@ use clap::Parser;
 
 use super::global;
 
+pub mod attach_auth;
+pub mod decode_result;
+pub mod diff;
+pub mod from_uri;
+pub mod hash;
+pub mod new;
+pub mod sign;
 pub mod simulate;
+pub mod to_uri;
+pub mod verify_auth;
 pub mod xdr;
 
 #[derive(Debug, Parser)]
 pub enum Cmd {
+    /// Calculate the hash of a transaction envelope from stdin
+    Hash(hash::Cmd),
     /// Simulate a transaction envelope from stdin
     Simulate(simulate::Cmd),
+    /// Sign a transaction envelope from stdin with one or more local keys
+    Sign(sign::Cmd),
+    /// Build a SEP-0007 `web+stellar:tx` URI from a transaction envelope from stdin
+    ToUri(to_uri::Cmd),
+    /// Print the transaction envelope of a SEP-0007 `web+stellar:tx` URI
+    FromUri(from_uri::Cmd),
+    /// Replace the auth entries of a transaction envelope from stdin with ones from a file
+    AttachAuth(attach_auth::Cmd),
+    /// Check which keys validly signed the auth entries of a transaction envelope from stdin
+    ///
+    /// Prints the auth entry index, signer and `valid` or `invalid` for each signature, and exits
+    /// with a non-zero status if any are invalid.
+    VerifyAuth(verify_auth::Cmd),
+    /// Describe a base64 `TransactionResult`, such as the one of a failed transaction
+    DecodeResult(decode_result::Cmd),
+    /// Compare two versions of a transaction envelope, e.g. before and after it was signed
+    ///
+    /// Prints the signatures and auth entry signatures that changed, and warns about any other
+    /// change to the transaction, such as to its fee or operations.
+    Diff(diff::Cmd),
+    /// Build a new transaction with a single operation
+    #[command(subcommand)]
+    New(new::Cmd),
 }
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
+    #[error(transparent)]
+    Hash(#[from] hash::Error),
     /// An error during the simulation
     #[error(transparent)]
     Simulate(#[from] simulate::Error),
+    #[error(transparent)]
+    Sign(#[from] sign::Error),
+    #[error(transparent)]
+    ToUri(#[from] to_uri::Error),
+    #[error(transparent)]
+    FromUri(#[from] from_uri::Error),
+    #[error(transparent)]
+    AttachAuth(#[from] attach_auth::Error),
+    #[error(transparent)]
+    VerifyAuth(#[from] verify_auth::Error),
+    #[error(transparent)]
+    DecodeResult(#[from] decode_result::Error),
+    #[error(transparent)]
+    Diff(#[from] diff::Error),
+    #[error(transparent)]
+    New(#[from] new::Error),
 }
 
 impl Cmd {
     pub async fn run(&self, global_args: &global::Args) -> Result<(), Error> {
         match self {
+            Cmd::Hash(cmd) => cmd.run()?,
             Cmd::Simulate(cmd) => cmd.run(global_args).await?,
+            Cmd::Sign(cmd) => cmd.run().await?,
+            Cmd::ToUri(cmd) => cmd.run()?,
+            Cmd::FromUri(cmd) => cmd.run()?,
+            Cmd::AttachAuth(cmd) => cmd.run()?,
+            Cmd::VerifyAuth(cmd) => cmd.run()?,
+            Cmd::DecodeResult(cmd) => cmd.run()?,
+            Cmd::Diff(cmd) => cmd.run()?,
+            Cmd::New(cmd) => cmd.run(global_args).await?,
         };
         Ok(())
     }
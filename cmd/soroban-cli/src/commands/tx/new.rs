@@ -0,0 +1,24 @@
+use super::global;
+
+pub mod payment;
+
+#[derive(Debug, clap::Subcommand)]
+pub enum Cmd {
+    /// Build, and unless `--build-only` is set, submit a transaction with a payment operation
+    Payment(payment::Cmd),
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Payment(#[from] payment::Error),
+}
+
+impl Cmd {
+    pub async fn run(&self, global_args: &global::Args) -> Result<(), Error> {
+        match &self {
+            Cmd::Payment(cmd) => cmd.run(global_args).await?,
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,186 @@
+use clap::{arg, command};
+
+use crate::{
+    commands::{config, global},
+    print::Print,
+    rpc,
+    utils::{self, parsing},
+    xdr::{
+        self, Limits, Memo, MuxedAccount, Operation, OperationBody, PaymentOp, Preconditions,
+        SequenceNumber, Transaction, TransactionEnvelope, TransactionExt, Uint256, WriteXdr,
+    },
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Config(#[from] config::Error),
+    #[error(transparent)]
+    Rpc(rpc::Error),
+    #[error(transparent)]
+    RpcError(#[from] crate::rpc_ext::RpcError),
+    #[error(transparent)]
+    Xdr(#[from] xdr::Error),
+    #[error(transparent)]
+    Parsing(#[from] parsing::Error),
+}
+
+impl From<rpc::Error> for Error {
+    fn from(e: rpc::Error) -> Self {
+        crate::rpc_ext::RpcError::from_rpc(e).map_or_else(Error::Rpc, Error::RpcError)
+    }
+}
+
+#[derive(Debug, clap::Parser, Clone)]
+#[group(skip)]
+pub struct Cmd {
+    /// Account receiving the payment, as an identity or a public key (G...)
+    #[arg(long)]
+    pub destination: String,
+    /// Amount to send, in stroops. 1 stroop = 0.0000001 of the asset
+    #[arg(long)]
+    pub amount: i64,
+    /// Asset to send, `native` or `CODE:ISSUER`
+    #[arg(long, default_value = "native")]
+    pub asset: String,
+    #[command(flatten)]
+    pub fee: crate::fee::Args,
+    #[command(flatten)]
+    pub config: config::Args,
+}
+
+impl Cmd {
+    pub async fn run(&self, global_args: &global::Args) -> Result<(), Error> {
+        let source = self.config.public_key().await?.0;
+        let network = self.config.get_network().await?;
+        let client = network.rpc_client()?;
+        let account = client
+            .get_account(&stellar_strkey::ed25519::PublicKey(source).to_string())
+            .await?;
+        let print = Print::new(Some(global_args));
+        let tx = self.build_tx(source, account.seq_num.0 + 1)?;
+        if self.fee.build_only {
+            let tx_env: TransactionEnvelope = tx.into();
+            print.result("xdr", tx_env.to_xdr_base64(Limits::none())?);
+            return Ok(());
+        }
+        self.config
+            .sign_and_send(
+                &client,
+                &network,
+                tx.clone(),
+                !self.fee.no_seq_retry,
+                Some(global_args),
+            )
+            .await?;
+        print.result(
+            "hash",
+            hex::encode(utils::transaction_hash(&tx, &network.network_passphrase)?),
+        );
+        Ok(())
+    }
+
+    /// The transaction, from `source`, with a payment of `--amount` of `--asset` to
+    /// `--destination`
+    pub fn build_tx(&self, source: [u8; 32], sequence: i64) -> Result<Transaction, Error> {
+        let payment = PaymentOp {
+            destination: MuxedAccount::Ed25519(Uint256(self.destination()?)),
+            asset: parsing::parse_asset(&self.asset)?,
+            amount: self.amount,
+        };
+        Ok(Transaction {
+            source_account: MuxedAccount::Ed25519(Uint256(source)),
+            fee: self.fee.fee,
+            seq_num: SequenceNumber(sequence),
+            cond: Preconditions::None,
+            memo: Memo::None,
+            operations: vec![Operation {
+                source_account: None,
+                body: OperationBody::Payment(payment),
+            }]
+            .try_into()?,
+            ext: TransactionExt::V0,
+        })
+    }
+
+    /// The destination's public key. An identity is resolved at its default hd path, as
+    /// `--hd-path` is for the source account
+    fn destination(&self) -> Result<[u8; 32], Error> {
+        if let Ok(key) = stellar_strkey::ed25519::PublicKey::from_string(&self.destination) {
+            return Ok(key.0);
+        }
+        Ok(self
+            .config
+            .account(&self.destination)?
+            .public_key(None)
+            .map_err(config::Error::from)?
+            .0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use clap::Parser;
+
+    use super::*;
+    use crate::xdr::Asset;
+
+    const DESTINATION: &str = "GAAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQDZ7H";
+
+    #[test]
+    fn builds_native_payment() {
+        let cmd = Cmd::parse_from([
+            "payment",
+            "--destination",
+            DESTINATION,
+            "--amount",
+            "10000000",
+            "--asset",
+            "native",
+            "--source-account",
+            DESTINATION,
+        ]);
+        let tx = cmd.build_tx([4; 32], 10).unwrap();
+        assert_eq!(tx.source_account, MuxedAccount::Ed25519(Uint256([4; 32])));
+        assert_eq!(tx.seq_num, SequenceNumber(10));
+        assert_eq!(tx.fee, 100);
+        let [Operation {
+            source_account: None,
+            body: OperationBody::Payment(payment),
+        }] = tx.operations.as_slice()
+        else {
+            panic!("expected a single payment operation");
+        };
+        assert_eq!(
+            payment,
+            &PaymentOp {
+                destination: MuxedAccount::Ed25519(Uint256([1; 32])),
+                asset: Asset::Native,
+                amount: 10_000_000,
+            }
+        );
+    }
+
+    #[test]
+    fn destination_ignores_the_source_hd_path() {
+        let secret = config::secret::Secret::test_seed_phrase().unwrap();
+        let config::secret::Secret::SeedPhrase { seed_phrase } = &secret else {
+            panic!("expected a seed phrase");
+        };
+        let cmd = Cmd::parse_from([
+            "payment",
+            "--destination",
+            seed_phrase.as_str(),
+            "--amount",
+            "1",
+            "--source-account",
+            DESTINATION,
+            "--hd-path",
+            "1",
+        ]);
+        assert_eq!(
+            cmd.destination().unwrap(),
+            secret.public_key(None).unwrap().0
+        );
+    }
+}
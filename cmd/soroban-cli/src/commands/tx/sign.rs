@@ -0,0 +1,64 @@
+use crate::xdr::{self, Limits, TransactionEnvelope, WriteXdr};
+
+use crate::{
+    commands::{
+        config::{locator, secret},
+        network,
+    },
+    signer,
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    XdrArgs(#[from] super::xdr::Error),
+    #[error(transparent)]
+    Locator(#[from] locator::Error),
+    #[error(transparent)]
+    Network(#[from] network::Error),
+    #[error(transparent)]
+    Secret(#[from] secret::Error),
+    #[error(transparent)]
+    Signer(#[from] signer::Error),
+    #[error(transparent)]
+    Xdr(#[from] xdr::Error),
+}
+
+/// Command to sign a transaction envelope from stdin with one or more local keys
+/// e.g. `cat file.txt | soroban tx sign --sign-with-key alice,bob`
+#[derive(Debug, clap::Parser, Clone, Default)]
+#[group(skip)]
+pub struct Cmd {
+    /// Identity, secret key, or seed phrase to sign with. Can be repeated or given as a comma separated list to sign with several keys
+    #[arg(long, required = true, value_delimiter = ',')]
+    pub sign_with_key: Vec<String>,
+    /// If using a seed phrase, which hierarchical deterministic path to use, e.g. `m/44'/148'/{hd_path}`. Example: `--hd-path 1`. Default: `0`
+    #[arg(long)]
+    pub hd_path: Option<usize>,
+    #[command(flatten)]
+    pub network: network::Args,
+    #[command(flatten)]
+    pub locator: locator::Args,
+}
+
+impl Cmd {
+    pub async fn run(&self) -> Result<(), Error> {
+        let tx_env = self.sign(super::xdr::tx_envelope_from_stdin()?).await?;
+        println!("{}", tx_env.to_xdr_base64(Limits::none())?);
+        Ok(())
+    }
+
+    pub async fn sign(&self, tx_env: TransactionEnvelope) -> Result<TransactionEnvelope, Error> {
+        let network = self.network.get(&self.locator).await?;
+        let keys = self
+            .sign_with_key
+            .iter()
+            .map(|key| Ok(self.locator.read_key(key)?.key_pair(self.hd_path)?))
+            .collect::<Result<Vec<_>, Error>>()?;
+        Ok(signer::sign_tx_env(
+            &keys,
+            tx_env,
+            &network.network_passphrase,
+        )?)
+    }
+}
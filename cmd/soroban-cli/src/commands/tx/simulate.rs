@@ -1,8 +1,16 @@
-use crate::xdr::{self, TransactionEnvelope, WriteXdr};
+use std::path::PathBuf;
+
+use crate::xdr::{
+    self, AccountId, LedgerKey, LedgerKeyContractData, Limits, PublicKey, ScAddress, ScVal,
+    SorobanTransactionData, TransactionEnvelope, Uint256, WriteXdr,
+};
 use async_trait::async_trait;
 use soroban_rpc::Assembled;
 
-use crate::commands::{config, global, NetworkRunnable};
+use crate::commands::{config, global, NetworkRunnable, HEADING_RPC};
+use crate::rpc_ext::{self, ClientExt, StorageDurability};
+
+use super::attach_auth;
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
@@ -11,9 +19,21 @@ pub enum Error {
     #[error(transparent)]
     Config(#[from] super::super::config::Error),
     #[error(transparent)]
-    Rpc(#[from] crate::rpc::Error),
+    Rpc(crate::rpc::Error),
+    #[error(transparent)]
+    RpcError(#[from] crate::rpc_ext::RpcError),
     #[error(transparent)]
     Xdr(#[from] xdr::Error),
+    #[error(transparent)]
+    Fee(#[from] crate::fee::Error),
+    #[error(transparent)]
+    Auth(#[from] super::attach_auth::Error),
+}
+
+impl From<crate::rpc::Error> for Error {
+    fn from(e: crate::rpc::Error) -> Self {
+        crate::rpc_ext::RpcError::from_rpc(e).map_or_else(Error::Rpc, Error::RpcError)
+    }
 }
 
 /// Command to simulate a transaction envelope via rpc
@@ -23,6 +43,19 @@ pub enum Error {
 pub struct Cmd {
     #[clap(flatten)]
     pub config: super::super::config::Args,
+    /// Inclusion fee to use instead of the one in the envelope, in stroops
+    #[arg(long, help_heading = HEADING_RPC)]
+    pub fee: Option<u32>,
+    #[clap(flatten)]
+    pub resources: crate::fee::ResourceArgs,
+    /// Also write the simulated auth entries to this file, as a JSON array of base64 XDR, to be
+    /// signed offline and put back with `tx attach-auth`
+    #[arg(long)]
+    pub save_auth: Option<PathBuf>,
+    /// Print the ledger keys the simulated transaction reads and writes, and its instructions and
+    /// read and write bytes, to stderr
+    #[arg(long)]
+    pub show_footprint: bool,
 }
 
 impl Cmd {
@@ -30,10 +63,103 @@ impl Cmd {
         let res = self
             .run_against_rpc_server(Some(global_args), Some(&self.config))
             .await?;
-        let tx_env: TransactionEnvelope = res.transaction().clone().into();
+        let client = self.config.get_network().await?.rpc_client()?;
+        let tx = self.apply_overrides(&client, &res).await?;
+        if let Some(path) = &self.save_auth {
+            attach_auth::write_auth_file(path, &attach_auth::auth_entries(&tx)?)?;
+        }
+        if self.show_footprint {
+            if let xdr::TransactionExt::V1(data) = &tx.ext {
+                for line in footprint_lines(data)? {
+                    eprintln!("{line}");
+                }
+            }
+        }
+        let tx_env: TransactionEnvelope = tx.into();
         println!("{}", tx_env.to_xdr_base64(xdr::Limits::none())?);
         Ok(())
     }
+
+    /// Apply the fee and resource overrides to the simulated transaction
+    pub async fn apply_overrides(
+        &self,
+        client: &rpc_ext::Client,
+        assembled: &Assembled,
+    ) -> Result<xdr::Transaction, Error> {
+        let mut tx = self
+            .resources
+            .apply(
+                client,
+                assembled.transaction().clone(),
+                assembled.sim_response(),
+            )
+            .await?;
+        if let Some(fee) = self.fee {
+            let resource_fee = match &tx.ext {
+                xdr::TransactionExt::V1(data) => data.resource_fee,
+                xdr::TransactionExt::V0 => 0,
+            };
+            tx.fee = crate::fee::total_fee(i64::from(fee), resource_fee)?;
+        }
+        Ok(tx)
+    }
+}
+
+/// The resources of a Soroban transaction, then its read-only and read-write ledger keys, one per
+/// line
+pub fn footprint_lines(data: &SorobanTransactionData) -> Result<Vec<String>, Error> {
+    let resources = &data.resources;
+    let mut lines = vec![
+        format!("Instructions: {}", resources.instructions),
+        format!("Read bytes: {}", resources.read_bytes),
+        format!("Write bytes: {}", resources.write_bytes),
+    ];
+    for (heading, keys) in [
+        ("Read only:", &resources.footprint.read_only),
+        ("Read write:", &resources.footprint.read_write),
+    ] {
+        lines.push(heading.to_string());
+        for key in keys.iter() {
+            lines.push(format!("  {}", describe_ledger_key(key)?));
+        }
+    }
+    Ok(lines)
+}
+
+pub fn describe_ledger_key(key: &LedgerKey) -> Result<String, xdr::Error> {
+    Ok(match key {
+        LedgerKey::ContractData(LedgerKeyContractData {
+            contract,
+            key,
+            durability,
+        }) => {
+            let key = match key {
+                ScVal::LedgerKeyContractInstance => "instance".to_string(),
+                key => soroban_spec_tools::to_string(key)
+                    .or_else(|_| key.to_xdr_base64(Limits::none()))?,
+            };
+            format!(
+                "contract data {} {} {key}",
+                describe_address(contract),
+                StorageDurability::from(*durability)
+            )
+        }
+        LedgerKey::ContractCode(code) => format!("contract code {}", hex::encode(code.hash.0)),
+        LedgerKey::Account(account) => format!(
+            "account {}",
+            describe_address(&ScAddress::Account(account.account_id.clone()))
+        ),
+        key => format!("{} {}", key.name(), key.to_xdr_base64(Limits::none())?),
+    })
+}
+
+pub fn describe_address(address: &ScAddress) -> String {
+    match address {
+        ScAddress::Account(AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(key)))) => {
+            stellar_strkey::ed25519::PublicKey(*key).to_string()
+        }
+        ScAddress::Contract(xdr::Hash(contract)) => stellar_strkey::Contract(*contract).to_string(),
+    }
 }
 
 #[async_trait]
@@ -47,9 +173,77 @@ impl NetworkRunnable for Cmd {
         config: Option<&config::Args>,
     ) -> Result<Self::Result, Self::Error> {
         let config = config.unwrap_or(&self.config);
-        let network = config.get_network()?;
-        let client = crate::rpc::Client::new(&network.rpc_url)?;
+        let network = config.get_network().await?;
+        let client = network.rpc_client()?;
         let tx = super::xdr::unwrap_envelope_v1(super::xdr::tx_envelope_from_stdin()?)?;
-        Ok(client.simulate_and_assemble_transaction(&tx).await?)
+        let assembled = client.simulate_and_assemble_transaction(&tx).await?;
+        let compute = match client.get_contract_compute().await {
+            Ok(compute) => Some(compute),
+            Err(e) => {
+                tracing::debug!("cannot fetch the network's instruction limit: {e}");
+                None
+            }
+        };
+        self.resources
+            .warn_on_instructions(compute.as_ref(), assembled.sim_response());
+        Ok(assembled)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::xdr::{
+        ContractDataDurability, ExtensionPoint, Hash, LedgerFootprint, LedgerKeyContractCode,
+        ScSymbol, SorobanResources,
+    };
+
+    use super::*;
+
+    #[test]
+    fn footprint_of_contract_data() {
+        let contract = ScAddress::Contract(Hash([0; 32]));
+        let data = SorobanTransactionData {
+            ext: ExtensionPoint::V0,
+            resources: SorobanResources {
+                footprint: LedgerFootprint {
+                    read_only: vec![
+                        LedgerKey::ContractCode(LedgerKeyContractCode {
+                            hash: Hash([1; 32]),
+                        }),
+                        LedgerKey::ContractData(LedgerKeyContractData {
+                            contract: contract.clone(),
+                            key: ScVal::LedgerKeyContractInstance,
+                            durability: ContractDataDurability::Persistent,
+                        }),
+                    ]
+                    .try_into()
+                    .unwrap(),
+                    read_write: vec![LedgerKey::ContractData(LedgerKeyContractData {
+                        contract,
+                        key: ScVal::Symbol(ScSymbol("COUNTER".try_into().unwrap())),
+                        durability: ContractDataDurability::Temporary,
+                    })]
+                    .try_into()
+                    .unwrap(),
+                },
+                instructions: 1000,
+                read_bytes: 200,
+                write_bytes: 30,
+            },
+            resource_fee: 100,
+        };
+        assert_eq!(
+            footprint_lines(&data).unwrap(),
+            [
+                "Instructions: 1000",
+                "Read bytes: 200",
+                "Write bytes: 30",
+                "Read only:",
+                format!("  contract code {}", "01".repeat(32)).as_str(),
+                "  contract data CAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAABSC4 persistent instance",
+                "Read write:",
+                "  contract data CAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAABSC4 temporary COUNTER",
+            ]
+        );
     }
 }
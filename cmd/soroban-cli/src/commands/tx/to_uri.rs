@@ -0,0 +1,81 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+
+use crate::xdr::{self, Limits, TransactionEnvelope, WriteXdr};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    XdrArgs(#[from] super::xdr::Error),
+    #[error(transparent)]
+    Xdr(#[from] xdr::Error),
+}
+
+/// Command to build a SEP-0007 `web+stellar:tx` URI from a transaction envelope from stdin
+/// e.g. `cat file.txt | soroban tx to-uri --network-passphrase "..." --callback https://...`
+#[derive(Debug, clap::Parser, Clone, Default)]
+#[group(skip)]
+pub struct Cmd {
+    /// Network passphrase the transaction will be signed for
+    #[arg(long, env = "STELLAR_NETWORK_PASSPHRASE")]
+    pub network_passphrase: String,
+    /// URL the wallet should POST the signed transaction to, instead of submitting it to the network
+    #[arg(long)]
+    pub callback: Option<String>,
+    /// Domain the request originates from. Wallets only show it once the URI is signed with the
+    /// domain's `URI_REQUEST_SIGNING_KEY`, which this command does not do
+    #[arg(long)]
+    pub origin_domain: Option<String>,
+    /// Put the XDR in unpadded URL-safe base64 instead of percent-encoded standard base64. Not
+    /// every wallet reads this form
+    #[arg(long)]
+    pub base64url: bool,
+}
+
+impl Cmd {
+    pub fn run(&self) -> Result<(), Error> {
+        let tx_env = super::xdr::tx_envelope_from_stdin()?;
+        println!(
+            "{}",
+            tx_uri(
+                &tx_env,
+                &self.network_passphrase,
+                self.callback.as_deref(),
+                self.origin_domain.as_deref(),
+                self.base64url,
+            )?
+        );
+        Ok(())
+    }
+}
+
+/// The SEP-0007 `tx` operation URI for an envelope, with every parameter URL-encoded. With
+/// `base64url` the XDR is in unpadded URL-safe base64 rather than standard base64.
+pub fn tx_uri(
+    tx_env: &TransactionEnvelope,
+    network_passphrase: &str,
+    callback: Option<&str>,
+    origin_domain: Option<&str>,
+    base64url: bool,
+) -> Result<String, Error> {
+    let xdr = if base64url {
+        URL_SAFE_NO_PAD.encode(tx_env.to_xdr(Limits::none())?)
+    } else {
+        tx_env.to_xdr_base64(Limits::none())?
+    };
+    let callback = callback.map(|c| format!("url:{c}"));
+    let params = [
+        ("xdr", Some(xdr.as_str())),
+        ("callback", callback.as_deref()),
+        ("network_passphrase", Some(network_passphrase)),
+        ("origin_domain", origin_domain),
+    ];
+    let query = params
+        .iter()
+        .filter_map(|(name, value)| {
+            value.map(|v| format!("{name}={}", utf8_percent_encode(v, NON_ALPHANUMERIC)))
+        })
+        .collect::<Vec<_>>()
+        .join("&");
+    Ok(format!("web+stellar:tx?{query}"))
+}
@@ -0,0 +1,51 @@
+use crate::signer;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    XdrArgs(#[from] super::xdr::Error),
+    #[error(transparent)]
+    AttachAuth(#[from] super::attach_auth::Error),
+    #[error(transparent)]
+    Signer(#[from] signer::Error),
+    #[error("{0} auth entry signature(s) are invalid")]
+    InvalidSignatures(usize),
+}
+
+/// Command to check the signatures of the auth entries of a transaction envelope from stdin
+/// e.g. `cat file.txt | soroban tx verify-auth --network-passphrase "..."`
+#[derive(Debug, clap::Parser, Clone, Default)]
+#[group(skip)]
+pub struct Cmd {
+    /// Network passphrase the auth entries were signed for
+    #[arg(long, env = "STELLAR_NETWORK_PASSPHRASE")]
+    pub network_passphrase: String,
+}
+
+impl Cmd {
+    pub fn run(&self) -> Result<(), Error> {
+        let tx = super::xdr::unwrap_envelope_v1(super::xdr::tx_envelope_from_stdin()?)?;
+        let mut invalid = 0;
+        for (i, entry) in super::attach_auth::auth_entries(&tx)?.iter().enumerate() {
+            for sig in signer::verify_soroban_authorization_entry(entry, &self.network_passphrase)?
+            {
+                let signer = match <[u8; 32]>::try_from(sig.public_key.as_slice()) {
+                    Ok(key) => stellar_strkey::ed25519::PublicKey(key).to_string(),
+                    Err(_) => hex::encode(&sig.public_key),
+                };
+                let status = if sig.valid {
+                    "valid"
+                } else {
+                    invalid += 1;
+                    "invalid"
+                };
+                println!("{i} {signer} {status}");
+            }
+        }
+        if invalid == 0 {
+            Ok(())
+        } else {
+            Err(Error::InvalidSignatures(invalid))
+        }
+    }
+}
@@ -1,9 +1,12 @@
 use clap::arg;
 
 use soroban_env_host::xdr;
-use soroban_rpc::Assembled;
+use soroban_rpc::{Assembled, SimulateTransactionResponse};
 
-use crate::commands::HEADING_RPC;
+use crate::{
+    commands::HEADING_RPC,
+    rpc_ext::{self, Client, ClientExt},
+};
 
 #[derive(Debug, clap::Args, Clone)]
 #[group(skip)]
@@ -23,6 +26,10 @@ pub struct Args {
     /// Simulate the transaction and only write the base64 xdr to stdout
     #[arg(long, help_heading = HEADING_RPC, conflicts_with = "build_only")]
     pub sim_only: bool,
+    /// Don't fetch the current sequence number and resubmit once when the transaction fails with
+    /// `txBAD_SEQ`
+    #[arg(long, help_heading = HEADING_RPC)]
+    pub no_seq_retry: bool,
 }
 
 impl Args {
@@ -48,6 +55,134 @@ pub fn add_padding_to_instructions(txn: Assembled) -> Assembled {
     txn.set_max_instructions(instructions)
 }
 
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("resource fee {given} is below the simulated minimum of {minimum}, use --force to use it anyway")]
+    ResourceFeeBelowMinimum { given: i64, minimum: i64 },
+    #[error("total fee of {0} stroops is out of range")]
+    FeeOutOfRange(i64),
+    #[error(transparent)]
+    Xdr(#[from] xdr::Error),
+    #[error(transparent)]
+    RpcExt(#[from] rpc_ext::Error),
+}
+
+/// Instructions the network charges `fee_rate_per_instructions_increment` for
+const INSTRUCTIONS_INCREMENT: i64 = 10_000;
+
+/// Overrides for the resources and fees of a simulated transaction
+#[derive(Debug, clap::Args, Clone)]
+#[group(skip)]
+pub struct ResourceArgs {
+    /// Number of instructions to add on top of the simulated amount, replacing the default padding.
+    /// The resource fee is raised to pay for them
+    #[arg(long, help_heading = HEADING_RPC)]
+    pub instruction_leeway: Option<u32>,
+    /// Resource fee to use instead of the simulated one, in stroops
+    #[arg(long, help_heading = HEADING_RPC)]
+    pub resource_fee: Option<i64>,
+    /// Allow a `--resource-fee` below the simulated minimum
+    #[arg(long, help_heading = HEADING_RPC)]
+    pub force: bool,
+    /// Warn when the simulation uses more than this fraction of the network's per-transaction
+    /// instruction limit
+    #[arg(long, default_value = "0.8", help_heading = HEADING_RPC)]
+    pub instruction_warning_threshold: f64,
+}
+
+impl ResourceArgs {
+    /// Apply the overrides to an assembled transaction, checking them against its simulation.
+    /// The network's instruction fee rate is only fetched for `--instruction-leeway`, whose
+    /// instructions raise the resource fee
+    pub async fn apply(
+        &self,
+        client: &Client,
+        mut tx: xdr::Transaction,
+        sim_res: &SimulateTransactionResponse,
+    ) -> Result<xdr::Transaction, Error> {
+        let xdr::TransactionExt::V1(data) = &tx.ext else {
+            return Ok(tx);
+        };
+        let inclusion_fee = i64::from(tx.fee) - data.resource_fee;
+        let mut data = data.clone();
+        if let Some(leeway) = self.instruction_leeway {
+            let simulated = sim_res.transaction_data()?.resources.instructions;
+            let fee_rate = client
+                .get_contract_compute()
+                .await?
+                .fee_rate_per_instructions_increment;
+            data.resources.instructions = simulated.saturating_add(leeway);
+            data.resource_fee = data
+                .resource_fee
+                .saturating_add(instruction_fee(leeway, fee_rate));
+        }
+        if let Some(resource_fee) = self.resource_fee {
+            let minimum = i64::try_from(sim_res.min_resource_fee).unwrap_or(i64::MAX);
+            if resource_fee < minimum && !self.force {
+                return Err(Error::ResourceFeeBelowMinimum {
+                    given: resource_fee,
+                    minimum,
+                });
+            }
+            data.resource_fee = resource_fee;
+        }
+        tx.fee = total_fee(inclusion_fee, data.resource_fee)?;
+        tx.ext = xdr::TransactionExt::V1(data);
+        Ok(tx)
+    }
+
+    /// Warn if the simulation came close to the instruction limit of the network's `compute`
+    /// settings, as the transaction may then run out of instructions once the ledger changes a
+    /// little. Without the settings there is nothing to compare against
+    pub fn warn_on_instructions(
+        &self,
+        compute: Option<&xdr::ConfigSettingContractComputeV0>,
+        sim_res: &SimulateTransactionResponse,
+    ) {
+        let Some(limit) = compute.and_then(|c| u64::try_from(c.tx_max_instructions).ok()) else {
+            return;
+        };
+        if let Some(warning) = self.instruction_warning(sim_res.cost.cpu_insns, limit) {
+            tracing::warn!("{warning}");
+        }
+    }
+
+    /// The warning for `cpu_insns` simulated instructions, if above `--instruction-warning-threshold`
+    /// of `limit`
+    pub fn instruction_warning(&self, cpu_insns: u64, limit: u64) -> Option<String> {
+        #[allow(clippy::cast_precision_loss)]
+        let used = cpu_insns as f64 / limit as f64;
+        (used > self.instruction_warning_threshold).then(|| {
+            format!(
+                "the simulation used {cpu_insns} instructions, {:.0}% of the network's limit of {limit} per transaction, so the transaction may fail if the ledger changes before it is applied. Consider adding instructions with --instruction-leeway",
+                used * 100.0
+            )
+        })
+    }
+}
+
+/// The fee of `instructions` at the network's `fee_rate` per 10,000 instructions, rounded up
+pub fn instruction_fee(instructions: u32, fee_rate: i64) -> i64 {
+    (i64::from(instructions) * fee_rate + INSTRUCTIONS_INCREMENT - 1) / INSTRUCTIONS_INCREMENT
+}
+
+/// A transaction's fee, the inclusion fee plus the resource fee, checked to fit its `u32`
+pub fn total_fee(inclusion_fee: i64, resource_fee: i64) -> Result<u32, Error> {
+    let total = inclusion_fee.saturating_add(resource_fee);
+    u32::try_from(total).map_err(|_| Error::FeeOutOfRange(total))
+}
+
+impl Default for ResourceArgs {
+    fn default() -> Self {
+        Self {
+            instruction_leeway: None,
+            resource_fee: None,
+            force: false,
+            instruction_warning_threshold: 0.8,
+        }
+    }
+}
+
 impl Default for Args {
     fn default() -> Self {
         Self {
@@ -56,6 +191,40 @@ impl Default for Args {
             instructions: None,
             build_only: false,
             sim_only: false,
+            no_seq_retry: false,
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn warns_above_instruction_threshold() {
+        let args = ResourceArgs::default();
+        let warning = args.instruction_warning(90_000_000, 100_000_000).unwrap();
+        assert!(warning.contains("90%"), "{warning}");
+        assert!(warning.contains("--instruction-leeway"), "{warning}");
+        assert_eq!(args.instruction_warning(80_000_000, 100_000_000), None);
+
+        let args = ResourceArgs {
+            instruction_warning_threshold: 0.5,
+            ..Default::default()
+        };
+        assert!(args.instruction_warning(60_000_000, 100_000_000).is_some());
+    }
+
+    #[test]
+    fn leeway_raises_the_fee() {
+        assert_eq!(instruction_fee(0, 25), 0);
+        assert_eq!(instruction_fee(10_000, 25), 25);
+        assert_eq!(instruction_fee(10_001, 25), 26);
+        assert_eq!(instruction_fee(1_000_000, 25), 2500);
+        assert_eq!(total_fee(100, 2500).unwrap(), 2600);
+        assert!(matches!(
+            total_fee(100, i64::from(u32::MAX)),
+            Err(Error::FeeOutOfRange(_))
+        ));
+    }
+}
@@ -1,7 +1,9 @@
+use std::path::{Path, PathBuf};
+
 use soroban_env_host::xdr;
 
 use soroban_env_host::xdr::{
-    ContractDataEntry, ContractExecutable, ScContractInstance, ScSpecEntry, ScVal,
+    ContractDataEntry, ContractExecutable, Hash, ScContractInstance, ScSpecEntry, ScVal,
 };
 
 use soroban_spec::read::FromWasmError;
@@ -11,13 +13,16 @@ use crate::commands::config::{self, locator};
 use crate::commands::network;
 use crate::commands::{config::data, global};
 use crate::rpc;
+use crate::rpc_ext;
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     #[error("parsing contract spec: {0}")]
     CannotParseContractSpec(FromWasmError),
     #[error(transparent)]
-    Rpc(#[from] rpc::Error),
+    Rpc(rpc::Error),
+    #[error(transparent)]
+    RpcError(#[from] crate::rpc_ext::RpcError),
     #[error("missing result")]
     MissingResult,
     #[error(transparent)]
@@ -32,6 +37,12 @@ pub enum Error {
     ContractSpec(#[from] contract_spec::Error),
 }
 
+impl From<rpc::Error> for Error {
+    fn from(e: rpc::Error) -> Self {
+        crate::rpc_ext::RpcError::from_rpc(e).map_or_else(Error::Rpc, Error::RpcError)
+    }
+}
+
 ///
 /// # Errors
 pub async fn get_remote_contract_spec(
@@ -41,36 +52,52 @@ pub async fn get_remote_contract_spec(
     global_args: Option<&global::Args>,
     config: Option<&config::Args>,
 ) -> Result<Vec<ScSpecEntry>, Error> {
-    let network = config.map_or_else(
-        || network.get(locator).map_err(Error::from),
-        |c| c.get_network().map_err(Error::from),
-    )?;
+    let network = match config {
+        Some(c) => c.get_network().await?,
+        None => network.get(locator).await?,
+    };
     tracing::trace!(?network);
-    let client = rpc::Client::new(&network.rpc_url)?;
-    // Get contract data
-    let r = client.get_contract_data(contract_id).await?;
-    tracing::trace!("{r:?}");
+    let cache = cache_dir(global_args.map_or(false, |a| a.no_cache))?;
+    let client = network.rpc_client()?;
+    get_contract_spec(
+        &client,
+        &network.network_passphrase,
+        contract_id,
+        cache.as_deref(),
+    )
+    .await
+}
 
-    let ContractDataEntry {
-        val: ScVal::ContractInstance(ScContractInstance { executable, .. }),
-        ..
-    } = r
-    else {
-        return Err(Error::MissingResult);
-    };
+/// The data directory that fetched contracts are cached in, unless `no_cache` is set
+fn cache_dir(no_cache: bool) -> Result<Option<PathBuf>, Error> {
+    Ok(if no_cache {
+        None
+    } else {
+        Some(data::data_local_dir()?)
+    })
+}
+
+async fn get_contract_spec(
+    client: &rpc_ext::Client,
+    network_passphrase: &str,
+    contract_id: &[u8; 32],
+    cache: Option<&Path>,
+) -> Result<Vec<ScSpecEntry>, Error> {
+    let executable = get_executable(client, network_passphrase, contract_id, cache).await?;
 
     // Get the contract spec entries based on the executable type
     Ok(match executable {
         ContractExecutable::Wasm(hash) => {
             let hash_str = hash.to_string();
-            if let Ok(entries) = data::read_spec(&hash_str) {
+            let cached = cache.and_then(|dir| data::read_spec(dir, &hash_str).ok());
+            if let Some(entries) = cached {
                 entries
             } else {
-                let raw_wasm = client.get_remote_wasm_from_hash(hash).await?;
+                let raw_wasm = get_wasm_from_hash(client, hash, cache).await?;
                 let res = contract_spec::Spec::new(&raw_wasm)?;
                 let res = res.spec;
-                if global_args.map_or(true, |a| !a.no_cache) {
-                    data::write_spec(&hash_str, &res)?;
+                if let Some(dir) = cache {
+                    data::write_spec(dir, &hash_str, &res)?;
                 }
                 res
             }
@@ -80,3 +107,115 @@ pub async fn get_remote_contract_spec(
         }
     })
 }
+
+/// Fetch the Wasm of a contract on the network with `network_passphrase`, skipping the RPC
+/// round-trips when the contract's hash and code are already cached.
+///
+/// # Errors
+pub async fn get_remote_wasm(
+    client: &rpc_ext::Client,
+    network_passphrase: &str,
+    contract_id: &[u8; 32],
+    no_cache: bool,
+) -> Result<Vec<u8>, Error> {
+    let cache = cache_dir(no_cache)?;
+    match get_executable(client, network_passphrase, contract_id, cache.as_deref()).await? {
+        ContractExecutable::Wasm(hash) => get_wasm_from_hash(client, hash, cache.as_deref()).await,
+        ContractExecutable::StellarAsset => Err(Error::MissingResult),
+    }
+}
+
+async fn get_executable(
+    client: &rpc_ext::Client,
+    network_passphrase: &str,
+    contract_id: &[u8; 32],
+    cache: Option<&Path>,
+) -> Result<ContractExecutable, Error> {
+    let contract = stellar_strkey::Contract(*contract_id).to_string();
+    if let Some(dir) = cache {
+        let cached = data::read_contract_hash(dir, network_passphrase, &contract)?;
+        if let Some(hash) = cached.and_then(|hash| hash.parse().ok()) {
+            return Ok(ContractExecutable::Wasm(hash));
+        }
+    }
+    // Get contract data
+    let r = client.get_contract_data(contract_id).await?;
+    tracing::trace!("{r:?}");
+
+    let ContractDataEntry {
+        val: ScVal::ContractInstance(ScContractInstance { executable, .. }),
+        ..
+    } = r
+    else {
+        return Err(Error::MissingResult);
+    };
+    if let ContractExecutable::Wasm(hash) = &executable {
+        if let Some(dir) = cache {
+            data::write_contract_hash(dir, network_passphrase, &contract, &hash.to_string())?;
+        }
+    }
+    Ok(executable)
+}
+
+async fn get_wasm_from_hash(
+    client: &rpc_ext::Client,
+    hash: Hash,
+    cache: Option<&Path>,
+) -> Result<Vec<u8>, Error> {
+    let hash_str = hash.to_string();
+    if let Some(dir) = cache {
+        if let Ok(wasm) = data::read_wasm(dir, &hash_str) {
+            return Ok(wasm);
+        }
+    }
+    let wasm = client.get_remote_wasm_from_hash(hash).await?;
+    if let Some(dir) = cache {
+        data::write_wasm(dir, &hash_str, &wasm)?;
+    }
+    Ok(wasm)
+}
+
+#[cfg(test)]
+mod test {
+    use httpmock::prelude::*;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn cached_spec_skips_rpc() {
+        let t = assert_fs::TempDir::new().unwrap();
+        let server = MockServer::start();
+        let any_request = server.mock(|when, then| {
+            when.any_request();
+            then.status(500);
+        });
+
+        let contract_id = [1; 32];
+        let hash = Hash([2; 32]).to_string();
+        let spec = soroban_spec::read::parse_raw(&soroban_sdk::token::StellarAssetSpec::spec_xdr())
+            .unwrap();
+        // Prime the cache as a previous invocation would have
+        data::write_contract_hash(
+            t.path(),
+            network::LOCAL_NETWORK_PASSPHRASE,
+            &stellar_strkey::Contract(contract_id).to_string(),
+            &hash,
+        )
+        .unwrap();
+        data::write_spec(t.path(), &hash, &spec).unwrap();
+
+        let client = rpc_ext::Client::new(&server.url("/"), network::DEFAULT_RPC_TIMEOUT).unwrap();
+        let passphrase = network::LOCAL_NETWORK_PASSPHRASE;
+        let entries = get_contract_spec(&client, passphrase, &contract_id, Some(t.path()))
+            .await
+            .unwrap();
+        assert_eq!(entries, spec);
+        any_request.assert_hits(0);
+
+        // Bypassing the cache goes to the server
+        assert!(get_contract_spec(&client, passphrase, &contract_id, None)
+            .await
+            .is_err());
+        assert!(any_request.hits() > 0);
+    }
+}
@@ -18,6 +18,10 @@ pub enum Error {
     CannotParseContractId(String, stellar_strkey::DecodeError),
     #[error(transparent)]
     Wasm(#[from] wasm::Error),
+    #[error("reading key file {0:?}: {1}")]
+    CannotReadKeyFile(PathBuf, std::io::Error),
+    #[error("{0:?} is not a JSON array of base64 ledger keys: {1}")]
+    InvalidKeyFile(PathBuf, serde_json::Error),
 }
 
 #[derive(Debug, clap::Args, Clone)]
@@ -27,8 +31,7 @@ pub struct Args {
     /// If no keys provided the Contract's instance will be extended
     #[arg(
         long = "id",
-        required_unless_present = "wasm",
-        required_unless_present = "wasm_hash"
+        required_unless_present_any = ["wasm", "wasm_hash", "key_file"]
     )]
     pub contract_id: Option<String>,
     /// Storage key (symbols only)
@@ -55,12 +58,35 @@ pub struct Args {
         conflicts_with = "wasm"
     )]
     pub wasm_hash: Option<String>,
+    /// JSON file with an array of base64-encoded XDR ledger keys, to act on many entries, of any
+    /// contract, at once
+    #[arg(
+        long,
+        conflicts_with_all = ["contract_id", "key", "key_xdr", "wasm", "wasm_hash"]
+    )]
+    pub key_file: Option<PathBuf>,
     /// Storage entry durability
     #[arg(long, value_enum, required = true, default_value = "persistent")]
     pub durability: Durability,
 }
 
 impl Args {
+    /// The ledger keys of `--key-file`, if given
+    pub fn file_keys(&self) -> Result<Option<Vec<LedgerKey>>, Error> {
+        let Some(path) = &self.key_file else {
+            return Ok(None);
+        };
+        let json =
+            std::fs::read_to_string(path).map_err(|e| Error::CannotReadKeyFile(path.clone(), e))?;
+        let keys: Vec<String> =
+            serde_json::from_str(&json).map_err(|e| Error::InvalidKeyFile(path.clone(), e))?;
+        Ok(Some(
+            keys.iter()
+                .map(|key| LedgerKey::from_xdr_base64(key, Limits::none()))
+                .collect::<Result<_, _>>()?,
+        ))
+    }
+
     pub fn parse_keys(&self, contract: Contract) -> Result<Vec<LedgerKey>, Error> {
         let keys = if let Some(keys) = &self.key {
             keys.iter()
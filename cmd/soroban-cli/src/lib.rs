@@ -16,11 +16,17 @@ pub mod fee;
 pub mod get_spec;
 pub mod key;
 pub mod log;
+pub mod msgpack;
+pub mod print;
+pub mod rpc_ext;
 pub mod signer;
 pub mod toid;
 pub mod utils;
 pub mod wasm;
 
+#[cfg(test)]
+mod test_utils;
+
 pub use commands::Root;
 
 pub fn parse_cmd<T>(s: &str) -> Result<T, clap::Error>
@@ -0,0 +1,294 @@
+//! MessagePack rendering of contract values.
+//!
+//! Unlike JSON, integers keep their type: 128 and 256-bit integers are written as MessagePack
+//! integers whenever they fit in 64 bits, and only fall back to decimal strings when they don't.
+//! Bytes are written as binary and maps keep their non-string keys. Given a spec type, structs
+//! and unions take the same shape as in the spec's JSON.
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use rmpv::Value;
+use serde_json::Value as Json;
+use soroban_spec_tools::Spec;
+
+use crate::xdr::{ScSpecEntry, ScSpecTypeDef, ScSpecTypeUdt, ScSpecUdtUnionCaseV0, ScVal};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Spec(#[from] soroban_spec_tools::Error),
+    #[error(transparent)]
+    Encode(#[from] rmpv::encode::Error),
+    #[error("{0:?} is not a value of the union {1}")]
+    NotUnionCase(ScVal, String),
+}
+
+/// Base64 of the MessagePack encoding of `val`
+pub fn to_base64(val: &ScVal) -> Result<String, Error> {
+    encode(&to_value(val)?)
+}
+
+/// Base64 of the MessagePack encoding of `val`, a value of `type_` in `spec`
+pub fn to_base64_typed(spec: &Spec, val: &ScVal, type_: &ScSpecTypeDef) -> Result<String, Error> {
+    encode(&to_value_typed(spec, val, type_)?)
+}
+
+fn encode(value: &Value) -> Result<String, Error> {
+    let mut buf = Vec::new();
+    rmpv::encode::write_value(&mut buf, value)?;
+    Ok(STANDARD.encode(buf))
+}
+
+/// Like [`to_value`], with structs as maps of their field names and unions as their case name,
+/// or a map of it to the case's values, the way [`Spec::xdr_to_json`] renders them
+pub fn to_value_typed(spec: &Spec, val: &ScVal, type_: &ScSpecTypeDef) -> Result<Value, Error> {
+    Ok(match (val, type_) {
+        (ScVal::Void, ScSpecTypeDef::Option(_)) => Value::Nil,
+        (val, ScSpecTypeDef::Option(option)) => to_value_typed(spec, val, &option.value_type)?,
+        (val, ScSpecTypeDef::Result(result)) => to_value_typed(spec, val, &result.ok_type)?,
+        (ScVal::Vec(Some(vec_)), ScSpecTypeDef::Vec(vec_type)) => Value::Array(
+            vec_.iter()
+                .map(|v| to_value_typed(spec, v, &vec_type.element_type))
+                .collect::<Result<_, _>>()?,
+        ),
+        (ScVal::Vec(Some(vec_)), ScSpecTypeDef::Tuple(tuple)) => Value::Array(
+            vec_.iter()
+                .zip(tuple.value_types.iter())
+                .map(|(v, t)| to_value_typed(spec, v, t))
+                .collect::<Result<_, _>>()?,
+        ),
+        (ScVal::Map(Some(map)), ScSpecTypeDef::Map(map_type)) => Value::Map(
+            map.iter()
+                .map(|e| {
+                    Ok((
+                        to_value_typed(spec, &e.key, &map_type.key_type)?,
+                        to_value_typed(spec, &e.val, &map_type.value_type)?,
+                    ))
+                })
+                .collect::<Result<_, Error>>()?,
+        ),
+        (val, ScSpecTypeDef::Udt(ScSpecTypeUdt { name })) => {
+            udt_to_value(spec, val, &name.to_utf8_string_lossy())?
+        }
+        (val, _) => to_value(val)?,
+    })
+}
+
+fn udt_to_value(spec: &Spec, val: &ScVal, name: &str) -> Result<Value, Error> {
+    Ok(match (val, spec.find(name)?) {
+        (ScVal::Map(Some(map)), ScSpecEntry::UdtStructV0(strukt)) => Value::Map(
+            strukt
+                .fields
+                .iter()
+                .zip(map.iter())
+                .map(|(field, e)| {
+                    Ok((
+                        Value::from(field.name.to_utf8_string_lossy()),
+                        to_value_typed(spec, &e.val, &field.type_)?,
+                    ))
+                })
+                .collect::<Result<_, Error>>()?,
+        ),
+        (ScVal::Vec(Some(vec_)), ScSpecEntry::UdtStructV0(strukt)) => Value::Array(
+            strukt
+                .fields
+                .iter()
+                .zip(vec_.iter())
+                .map(|(field, v)| to_value_typed(spec, v, &field.type_))
+                .collect::<Result<_, _>>()?,
+        ),
+        (ScVal::Vec(Some(vec_)), ScSpecEntry::UdtUnionV0(union)) => {
+            let not_case = || Error::NotUnionCase(val.clone(), name.to_string());
+            let Some((ScVal::Symbol(case_name), rest)) = vec_.split_first() else {
+                return Err(not_case());
+            };
+            let case = union
+                .cases
+                .iter()
+                .find(|case| match case {
+                    ScSpecUdtUnionCaseV0::VoidV0(v) => v.name.as_vec() == case_name.as_vec(),
+                    ScSpecUdtUnionCaseV0::TupleV0(v) => v.name.as_vec() == case_name.as_vec(),
+                })
+                .ok_or_else(not_case)?;
+            let case_name = Value::from(case_name.to_utf8_string_lossy());
+            match case {
+                ScSpecUdtUnionCaseV0::VoidV0(_) => case_name,
+                ScSpecUdtUnionCaseV0::TupleV0(tuple) => {
+                    let mut values = tuple
+                        .type_
+                        .iter()
+                        .zip(rest)
+                        .map(|(t, v)| to_value_typed(spec, v, t))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    let values = if values.len() == 1 {
+                        values.remove(0)
+                    } else {
+                        Value::Array(values)
+                    };
+                    Value::Map(vec![(case_name, values)])
+                }
+            }
+        }
+        (val, _) => to_value(val)?,
+    })
+}
+
+pub fn to_value(val: &ScVal) -> Result<Value, Error> {
+    Ok(match val {
+        ScVal::Bool(b) => Value::from(*b),
+        ScVal::Void => Value::Nil,
+        ScVal::U32(v) => Value::from(*v),
+        ScVal::I32(v) => Value::from(*v),
+        ScVal::U64(v) => Value::from(*v),
+        ScVal::I64(v) => Value::from(*v),
+        ScVal::Timepoint(v) => Value::from(v.0),
+        ScVal::Duration(v) => Value::from(v.0),
+        ScVal::Bytes(v) => Value::from(v.to_vec()),
+        ScVal::Vec(v) => Value::Array(
+            v.iter()
+                .flat_map(|v| v.iter())
+                .map(to_value)
+                .collect::<Result<_, _>>()?,
+        ),
+        ScVal::Map(m) => Value::Map(
+            m.iter()
+                .flat_map(|m| m.iter())
+                .map(|e| Ok((to_value(&e.key)?, to_value(&e.val)?)))
+                .collect::<Result<_, Error>>()?,
+        ),
+        // Wide integers come out of `to_json` as decimal strings, keep them as integers if they
+        // fit
+        ScVal::U128(_) | ScVal::I128(_) | ScVal::U256(_) | ScVal::I256(_) => {
+            match soroban_spec_tools::to_json(val)? {
+                Json::String(s) => s
+                    .parse::<i64>()
+                    .map(Value::from)
+                    .or_else(|_| s.parse::<u64>().map(Value::from))
+                    .unwrap_or_else(|_| Value::from(s)),
+                json => from_json(json),
+            }
+        }
+        _ => from_json(soroban_spec_tools::to_json(val)?),
+    })
+}
+
+fn from_json(json: Json) -> Value {
+    match json {
+        Json::Null => Value::Nil,
+        Json::Bool(b) => Value::from(b),
+        Json::Number(n) => n
+            .as_i64()
+            .map(Value::from)
+            .or_else(|| n.as_u64().map(Value::from))
+            .or_else(|| n.as_f64().map(Value::from))
+            .unwrap_or(Value::Nil),
+        Json::String(s) => Value::from(s),
+        Json::Array(a) => Value::Array(a.into_iter().map(from_json).collect()),
+        Json::Object(o) => Value::Map(
+            o.into_iter()
+                .map(|(k, v)| (Value::from(k), from_json(v)))
+                .collect(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::xdr::{
+        Int128Parts, ScMapEntry, ScSpecUdtStructFieldV0, ScSpecUdtStructV0,
+        ScSpecUdtUnionCaseTupleV0, ScSpecUdtUnionCaseVoidV0, ScSpecUdtUnionV0, ScSymbol, StringM,
+    };
+
+    fn i128(v: i128) -> ScVal {
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        ScVal::I128(Int128Parts {
+            hi: (v >> 64) as i64,
+            lo: v as u64,
+        })
+    }
+
+    #[test]
+    fn integers_keep_their_type() {
+        assert_eq!(to_value(&i128(-42)).unwrap(), Value::from(-42));
+        assert_eq!(
+            to_value(&i128(i128::MAX)).unwrap(),
+            Value::from(i128::MAX.to_string())
+        );
+        let map = ScVal::Map(Some(
+            vec![ScMapEntry {
+                key: ScVal::Symbol(ScSymbol("a".try_into().unwrap())),
+                val: ScVal::U64(u64::MAX),
+            }]
+            .try_into()
+            .unwrap(),
+        ));
+        assert_eq!(
+            to_value(&map).unwrap(),
+            Value::Map(vec![(Value::from("a"), Value::from(u64::MAX))])
+        );
+    }
+    fn symbol(s: &str) -> ScVal {
+        ScVal::Symbol(ScSymbol(s.try_into().unwrap()))
+    }
+
+    #[test]
+    fn unions_and_structs_take_their_spec_shape() {
+        let udt = |name: &str| {
+            ScSpecTypeDef::Udt(ScSpecTypeUdt {
+                name: name.try_into().unwrap(),
+            })
+        };
+        let spec = Spec(Some(vec![
+            ScSpecEntry::UdtStructV0(ScSpecUdtStructV0 {
+                doc: StringM::default(),
+                lib: StringM::default(),
+                name: "Test".try_into().unwrap(),
+                fields: vec![ScSpecUdtStructFieldV0 {
+                    doc: StringM::default(),
+                    name: "a".try_into().unwrap(),
+                    type_: ScSpecTypeDef::I128,
+                }]
+                .try_into()
+                .unwrap(),
+            }),
+            ScSpecEntry::UdtUnionV0(ScSpecUdtUnionV0 {
+                doc: StringM::default(),
+                lib: StringM::default(),
+                name: "ComplexEnum".try_into().unwrap(),
+                cases: vec![
+                    ScSpecUdtUnionCaseV0::TupleV0(ScSpecUdtUnionCaseTupleV0 {
+                        doc: StringM::default(),
+                        name: "Struct".try_into().unwrap(),
+                        type_: vec![udt("Test")].try_into().unwrap(),
+                    }),
+                    ScSpecUdtUnionCaseV0::VoidV0(ScSpecUdtUnionCaseVoidV0 {
+                        doc: StringM::default(),
+                        name: "Void".try_into().unwrap(),
+                    }),
+                ]
+                .try_into()
+                .unwrap(),
+            }),
+        ]));
+        let strukt = ScVal::Map(Some(
+            vec![ScMapEntry {
+                key: symbol("a"),
+                val: i128(-42),
+            }]
+            .try_into()
+            .unwrap(),
+        ));
+        let case = ScVal::Vec(Some(vec![symbol("Struct"), strukt].try_into().unwrap()));
+        assert_eq!(
+            to_value_typed(&spec, &case, &udt("ComplexEnum")).unwrap(),
+            Value::Map(vec![(
+                Value::from("Struct"),
+                Value::Map(vec![(Value::from("a"), Value::from(-42))])
+            )])
+        );
+        let void = ScVal::Vec(Some(vec![symbol("Void")].try_into().unwrap()));
+        assert_eq!(
+            to_value_typed(&spec, &void, &udt("ComplexEnum")).unwrap(),
+            Value::from("Void")
+        );
+    }
+}
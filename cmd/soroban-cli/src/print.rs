@@ -0,0 +1,49 @@
+use std::fmt::Display;
+
+use serde::Serialize;
+
+use crate::commands::global;
+
+/// Writes the result of a command to stdout, as text or, with the global `--json`, as a single
+/// JSON object. Progress and other messages go to stderr so stdout only has the result.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Print {
+    json: bool,
+}
+
+impl Print {
+    pub fn new(global_args: Option<&global::Args>) -> Self {
+        Print {
+            json: global_args.is_some_and(|args| args.json),
+        }
+    }
+
+    pub fn json(&self) -> bool {
+        self.json
+    }
+
+    /// Print `value` as is, or with `--json` as the `key` field of a JSON object
+    pub fn result(&self, key: &str, value: impl Serialize + Display) {
+        if self.json {
+            println!("{}", serde_json::json!({ key: value }));
+        } else {
+            println!("{value}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn json_is_only_used_with_the_flag() {
+        assert!(!Print::new(None).json());
+        assert!(!Print::new(Some(&global::Args::default())).json());
+        let global_args = global::Args {
+            json: true,
+            ..Default::default()
+        };
+        assert!(Print::new(Some(&global_args)).json());
+    }
+}
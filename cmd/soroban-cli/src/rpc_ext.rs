@@ -0,0 +1,748 @@
+use std::{fmt::Display, future::Future, time::Duration};
+
+use soroban_env_host::xdr::{
+    self, AccountEntry, AccountId, ConfigSettingContractComputeV0, ConfigSettingEntry,
+    ConfigSettingId, ContractDataDurability, ContractDataEntry, Hash, LedgerEntryData, LedgerKey,
+    LedgerKeyAccount, LedgerKeyConfigSetting, LedgerKeyContractData, PublicKey, ScAddress,
+    ScContractInstance, ScVal, Transaction, TransactionEnvelope, Uint256,
+};
+
+use crate::{
+    commands::global,
+    rpc::{
+        self, Assembled, FullLedgerEntries, FullLedgerEntry, GetEventsResponse,
+        GetLatestLedgerResponse, GetLedgerEntriesResponse, GetNetworkResponse,
+        GetTransactionResponse, SimulateTransactionResponse,
+    },
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Rpc(rpc::Error),
+    #[error(transparent)]
+    RpcError(#[from] RpcError),
+    #[error(transparent)]
+    Xdr(#[from] xdr::Error),
+    #[error("contract instance not found for {0}")]
+    MissingContractInstance(stellar_strkey::Contract),
+    #[error("Only contract data keys are allowed")]
+    OnlyDataAllowed,
+    #[error(
+        "network passphrase mismatch: configured {expected:?}, but the RPC server is on {server:?}"
+    )]
+    NetworkPassphraseMismatch { expected: String, server: String },
+    #[error("network config setting {0:?} not found")]
+    MissingConfigSetting(ConfigSettingId),
+}
+
+impl From<rpc::Error> for Error {
+    fn from(e: rpc::Error) -> Self {
+        RpcError::from_rpc(e).map_or_else(Error::Rpc, Error::RpcError)
+    }
+}
+
+/// JSON-RPC code of a request for a method the server doesn't have
+pub const METHOD_NOT_FOUND: i32 = -32601;
+
+/// An error the RPC server returned for a call, with its JSON-RPC code and data
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("RPC server returned error {code}: {message}{}", details(*.code, .data.as_deref()))]
+pub struct RpcError {
+    pub code: i32,
+    pub message: String,
+    pub data: Option<String>,
+}
+
+impl RpcError {
+    /// The error the server returned for a call, or the error as is if the call failed otherwise
+    pub fn from_json_rpc(e: jsonrpsee_core::Error) -> Result<Self, jsonrpsee_core::Error> {
+        match e {
+            jsonrpsee_core::Error::Call(e) => Ok(RpcError {
+                code: e.code(),
+                message: e.message().to_string(),
+                data: e.data().map(|data| data.get().to_string()),
+            }),
+            e => Err(e),
+        }
+    }
+
+    /// The error the server returned for a call of the client, or the error as is if the call
+    /// failed otherwise
+    pub fn from_rpc(e: rpc::Error) -> Result<Self, rpc::Error> {
+        match e {
+            rpc::Error::JsonRpc(e) => Self::from_json_rpc(e).map_err(rpc::Error::JsonRpc),
+            e => Err(e),
+        }
+    }
+}
+
+fn details(code: i32, data: Option<&str>) -> String {
+    let mut details = data
+        .map(|data| format!(", data: {data}"))
+        .unwrap_or_default();
+    if code == METHOD_NOT_FOUND {
+        details.push_str(" (the RPC server may be too old to support this method)");
+    }
+    details
+}
+
+/// RPC client that waits for the response to each request for at most its timeout, failing like
+/// the underlying client does when its own request timeout passes. Commands get one for their
+/// network with [`Network::rpc_client`](crate::commands::network::Network::rpc_client).
+pub struct Client {
+    client: rpc::Client,
+    timeout: Duration,
+}
+
+impl Client {
+    pub fn new(base_url: &str, timeout: Duration) -> Result<Self, rpc::Error> {
+        Ok(Client {
+            client: rpc::Client::new(base_url)?,
+            timeout,
+        })
+    }
+
+    async fn request<T>(
+        &self,
+        request: impl Future<Output = Result<T, rpc::Error>>,
+    ) -> Result<T, rpc::Error> {
+        tokio::time::timeout(self.timeout, request)
+            .await
+            .unwrap_or(Err(rpc::Error::JsonRpc(
+                jsonrpsee_core::Error::RequestTimeout,
+            )))
+    }
+
+    pub async fn friendbot_url(&self) -> Result<String, rpc::Error> {
+        self.request(self.client.friendbot_url()).await
+    }
+
+    pub async fn get_network(&self) -> Result<GetNetworkResponse, rpc::Error> {
+        self.request(self.client.get_network()).await
+    }
+
+    pub async fn get_latest_ledger(&self) -> Result<GetLatestLedgerResponse, rpc::Error> {
+        self.request(self.client.get_latest_ledger()).await
+    }
+
+    pub async fn get_account(&self, address: &str) -> Result<AccountEntry, rpc::Error> {
+        self.request(self.client.get_account(address)).await
+    }
+
+    pub async fn get_contract_data(
+        &self,
+        contract_id: &[u8; 32],
+    ) -> Result<ContractDataEntry, rpc::Error> {
+        self.request(self.client.get_contract_data(contract_id))
+            .await
+    }
+
+    pub async fn get_ledger_entries(
+        &self,
+        keys: &[LedgerKey],
+    ) -> Result<GetLedgerEntriesResponse, rpc::Error> {
+        self.request(self.client.get_ledger_entries(keys)).await
+    }
+
+    pub async fn get_full_ledger_entries(
+        &self,
+        keys: &[LedgerKey],
+    ) -> Result<FullLedgerEntries, rpc::Error> {
+        self.request(self.client.get_full_ledger_entries(keys))
+            .await
+    }
+
+    pub async fn get_remote_wasm_from_hash(&self, hash: Hash) -> Result<Vec<u8>, rpc::Error> {
+        self.request(self.client.get_remote_wasm_from_hash(hash))
+            .await
+    }
+
+    pub async fn get_events(
+        &self,
+        start: rpc::EventStart,
+        event_type: Option<rpc::EventType>,
+        contract_ids: &[String],
+        topics: &[String],
+        limit: Option<usize>,
+    ) -> Result<GetEventsResponse, rpc::Error> {
+        self.request(
+            self.client
+                .get_events(start, event_type, contract_ids, topics, limit),
+        )
+        .await
+    }
+
+    pub async fn simulate_transaction_envelope(
+        &self,
+        tx: &TransactionEnvelope,
+    ) -> Result<SimulateTransactionResponse, rpc::Error> {
+        self.request(self.client.simulate_transaction_envelope(tx))
+            .await
+    }
+
+    pub async fn simulate_and_assemble_transaction(
+        &self,
+        tx: &Transaction,
+    ) -> Result<Assembled, rpc::Error> {
+        self.request(self.client.simulate_and_assemble_transaction(tx))
+            .await
+    }
+
+    /// Send a transaction and wait for it to be included. Not limited by the timeout, which is
+    /// for the response to a single request
+    pub async fn send_transaction_polling(
+        &self,
+        tx: &TransactionEnvelope,
+    ) -> Result<GetTransactionResponse, rpc::Error> {
+        self.client.send_transaction_polling(tx).await
+    }
+}
+
+/// Where a contract storage entry lives
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum StorageDurability {
+    /// Stored in the contract instance's storage map, shares the instance's TTL
+    Instance,
+    Persistent,
+    Temporary,
+}
+
+impl Display for StorageDurability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            StorageDurability::Instance => "instance",
+            StorageDurability::Persistent => "persistent",
+            StorageDurability::Temporary => "temporary",
+        })
+    }
+}
+
+impl From<ContractDataDurability> for StorageDurability {
+    fn from(d: ContractDataDurability) -> Self {
+        match d {
+            ContractDataDurability::Persistent => StorageDurability::Persistent,
+            ContractDataDurability::Temporary => StorageDurability::Temporary,
+        }
+    }
+}
+
+/// A decoded contract storage entry
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContractStorageEntry {
+    pub key: ScVal,
+    pub val: ScVal,
+    pub durability: StorageDurability,
+    pub live_until_ledger_seq: u32,
+    pub last_modified_ledger: u32,
+}
+
+/// Helpers built on top of the RPC client's methods
+#[async_trait::async_trait]
+pub trait ClientExt {
+    /// Fetch every storage entry of a contract that can be discovered: the entries of the
+    /// instance's storage map, plus the entries for `keys`. Provided keys that have no entry on
+    /// the network are skipped.
+    async fn get_contract_storage(
+        &self,
+        contract_id: &[u8; 32],
+        keys: &[LedgerKey],
+    ) -> Result<Vec<ContractStorageEntry>, Error>;
+
+    /// Fetch the server's network passphrase and fail if it isn't `expected`.
+    async fn require_network_passphrase(&self, expected: &str) -> Result<String, Error>;
+
+    /// Check the network passphrase for commands that always verify it, unless
+    /// `--ignore-network-check` was passed.
+    async fn verify_network(
+        &self,
+        expected: &str,
+        global_args: Option<&global::Args>,
+    ) -> Result<(), Error>;
+
+    /// Check the network passphrase for commands that only verify it when `--strict-network` was
+    /// passed.
+    async fn verify_network_if_strict(
+        &self,
+        expected: &str,
+        global_args: Option<&global::Args>,
+    ) -> Result<(), Error>;
+
+    /// Fetch events like `get_events`, following the cursor of the last event of each page until
+    /// `max` events were returned or a page comes back empty.
+    async fn get_all_events(
+        &self,
+        start: rpc::EventStart,
+        event_type: Option<rpc::EventType>,
+        contract_ids: &[String],
+        topics: &[String],
+        max: Option<usize>,
+    ) -> Result<rpc::GetEventsResponse, Error>;
+
+    /// Fetch the network's compute settings: its instruction limits and the fee rate of
+    /// instructions.
+    async fn get_contract_compute(&self) -> Result<ConfigSettingContractComputeV0, Error>;
+
+    /// Fetch an account like `get_account`, along with the network's compute settings in the same
+    /// request. The settings are `None` if the network doesn't return them.
+    async fn get_account_and_contract_compute(
+        &self,
+        account: &stellar_strkey::ed25519::PublicKey,
+    ) -> Result<(AccountEntry, Option<ConfigSettingContractComputeV0>), Error>;
+}
+
+#[async_trait::async_trait]
+impl ClientExt for Client {
+    async fn get_contract_storage(
+        &self,
+        contract_id: &[u8; 32],
+        keys: &[LedgerKey],
+    ) -> Result<Vec<ContractStorageEntry>, Error> {
+        let instance_key = LedgerKey::ContractData(LedgerKeyContractData {
+            contract: ScAddress::Contract(Hash(*contract_id)),
+            key: ScVal::LedgerKeyContractInstance,
+            durability: ContractDataDurability::Persistent,
+        });
+        let mut all_keys = vec![instance_key.clone()];
+        all_keys.extend(keys.iter().filter(|k| **k != instance_key).cloned());
+        let entries = self.get_full_ledger_entries(&all_keys).await?.entries;
+
+        let mut storage = Vec::new();
+        let mut found_instance = false;
+        for FullLedgerEntry {
+            key,
+            val,
+            live_until_ledger_seq,
+            last_modified_ledger,
+        } in entries
+        {
+            let (
+                LedgerKey::ContractData(LedgerKeyContractData {
+                    key, durability, ..
+                }),
+                LedgerEntryData::ContractData(ContractDataEntry { val, .. }),
+            ) = (key, val)
+            else {
+                return Err(Error::OnlyDataAllowed);
+            };
+            if let ScVal::ContractInstance(ScContractInstance { storage: map, .. }) = &val {
+                found_instance = true;
+                storage.extend(map.iter().flat_map(|m| m.iter()).map(|entry| {
+                    ContractStorageEntry {
+                        key: entry.key.clone(),
+                        val: entry.val.clone(),
+                        durability: StorageDurability::Instance,
+                        live_until_ledger_seq,
+                        last_modified_ledger,
+                    }
+                }));
+            } else {
+                storage.push(ContractStorageEntry {
+                    key,
+                    val,
+                    durability: durability.into(),
+                    live_until_ledger_seq,
+                    last_modified_ledger,
+                });
+            }
+        }
+        if !found_instance {
+            return Err(Error::MissingContractInstance(stellar_strkey::Contract(
+                *contract_id,
+            )));
+        }
+        Ok(storage)
+    }
+
+    async fn require_network_passphrase(&self, expected: &str) -> Result<String, Error> {
+        let server = self.get_network().await?.passphrase;
+        if server != expected {
+            return Err(Error::NetworkPassphraseMismatch {
+                expected: expected.to_string(),
+                server,
+            });
+        }
+        Ok(server)
+    }
+
+    async fn verify_network(
+        &self,
+        expected: &str,
+        global_args: Option<&global::Args>,
+    ) -> Result<(), Error> {
+        if global_args.is_some_and(|a| a.ignore_network_check) {
+            tracing::debug!("skipping network passphrase check");
+            return Ok(());
+        }
+        self.require_network_passphrase(expected).await?;
+        Ok(())
+    }
+
+    async fn verify_network_if_strict(
+        &self,
+        expected: &str,
+        global_args: Option<&global::Args>,
+    ) -> Result<(), Error> {
+        if global_args.is_some_and(|a| a.strict_network) {
+            self.require_network_passphrase(expected).await?;
+        }
+        Ok(())
+    }
+
+    async fn get_all_events(
+        &self,
+        start: rpc::EventStart,
+        event_type: Option<rpc::EventType>,
+        contract_ids: &[String],
+        topics: &[String],
+        max: Option<usize>,
+    ) -> Result<rpc::GetEventsResponse, Error> {
+        let mut start = start;
+        let mut events = Vec::new();
+        loop {
+            let limit = max.map(|max| max - events.len());
+            let page = self
+                .get_events(start, event_type, contract_ids, topics, limit)
+                .await?;
+            let Some(last) = page.events.last() else {
+                return Ok(rpc::GetEventsResponse {
+                    events,
+                    latest_ledger: page.latest_ledger,
+                });
+            };
+            start = rpc::EventStart::Cursor(last.id.clone());
+            events.extend(page.events);
+            if let Some(max) = max {
+                if events.len() >= max {
+                    events.truncate(max);
+                    return Ok(rpc::GetEventsResponse {
+                        events,
+                        latest_ledger: page.latest_ledger,
+                    });
+                }
+            }
+        }
+    }
+
+    async fn get_contract_compute(&self) -> Result<ConfigSettingContractComputeV0, Error> {
+        self.get_full_ledger_entries(&[contract_compute_key()])
+            .await?
+            .entries
+            .into_iter()
+            .find_map(|entry| match entry.val {
+                LedgerEntryData::ConfigSetting(ConfigSettingEntry::ContractComputeV0(compute)) => {
+                    Some(compute)
+                }
+                _ => None,
+            })
+            .ok_or(Error::MissingConfigSetting(
+                ConfigSettingId::ContractComputeV0,
+            ))
+    }
+
+    async fn get_account_and_contract_compute(
+        &self,
+        account: &stellar_strkey::ed25519::PublicKey,
+    ) -> Result<(AccountEntry, Option<ConfigSettingContractComputeV0>), Error> {
+        let account_key = LedgerKey::Account(LedgerKeyAccount {
+            account_id: AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(account.0))),
+        });
+        let mut account_entry = None;
+        let mut compute = None;
+        for entry in self
+            .get_full_ledger_entries(&[account_key, contract_compute_key()])
+            .await?
+            .entries
+        {
+            match entry.val {
+                LedgerEntryData::Account(entry) => account_entry = Some(entry),
+                LedgerEntryData::ConfigSetting(ConfigSettingEntry::ContractComputeV0(entry)) => {
+                    compute = Some(entry);
+                }
+                _ => {}
+            }
+        }
+        let account_entry = match account_entry {
+            Some(entry) => entry,
+            // Fetch it again for the client's own error, with its hint to fund the account
+            None => self.get_account(&account.to_string()).await?,
+        };
+        Ok((account_entry, compute))
+    }
+}
+
+fn contract_compute_key() -> LedgerKey {
+    LedgerKey::ConfigSetting(LedgerKeyConfigSetting {
+        config_setting_id: ConfigSettingId::ContractComputeV0,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use httpmock::prelude::*;
+    use serde_json::json;
+
+    use super::*;
+    use crate::commands::network::DEFAULT_RPC_TIMEOUT;
+
+    const PASSPHRASE: &str = "Standalone Network ; February 2017";
+
+    fn get_network(server: &MockServer) -> httpmock::Mock<'_> {
+        server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .json_body_partial(r#"{ "method": "getNetwork" }"#);
+            then.status(200).json_body(json!({
+                "jsonrpc": "2.0",
+                "id": 0,
+                "result": {
+                    "passphrase": PASSPHRASE,
+                    "protocolVersion": 21
+                }
+            }));
+        })
+    }
+
+    #[tokio::test]
+    async fn network_passphrase_matches() {
+        let server = MockServer::start();
+        let mock = get_network(&server);
+        let client = Client::new(&server.url("/"), DEFAULT_RPC_TIMEOUT).unwrap();
+        assert_eq!(
+            client.require_network_passphrase(PASSPHRASE).await.unwrap(),
+            PASSPHRASE
+        );
+        let strict = global::Args {
+            strict_network: true,
+            ..Default::default()
+        };
+        client
+            .verify_network_if_strict(PASSPHRASE, Some(&strict))
+            .await
+            .unwrap();
+        mock.assert_hits(2);
+    }
+
+    #[tokio::test]
+    async fn network_passphrase_mismatch() {
+        let server = MockServer::start();
+        let mock = get_network(&server);
+        let client = Client::new(&server.url("/"), DEFAULT_RPC_TIMEOUT).unwrap();
+        let res = client
+            .verify_network("Test SDF Network ; September 2015", None)
+            .await;
+        assert!(
+            matches!(&res, Err(Error::NetworkPassphraseMismatch { server, .. }) if server == PASSPHRASE),
+            "{res:?}"
+        );
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn rpc_error_code_is_surfaced() {
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(POST).path("/");
+            then.status(200).json_body(json!({
+                "jsonrpc": "2.0",
+                "id": 0,
+                "error": {
+                    "code": -32602,
+                    "message": "invalid params",
+                    "data": "unknown field `foo`"
+                }
+            }));
+        });
+        let client = Client::new(&server.url("/"), DEFAULT_RPC_TIMEOUT).unwrap();
+        let err = client
+            .require_network_passphrase(PASSPHRASE)
+            .await
+            .unwrap_err();
+        let Error::RpcError(RpcError {
+            code,
+            message,
+            data,
+        }) = &err
+        else {
+            panic!("expected an RPC error, got {err:?}");
+        };
+        assert_eq!(*code, -32602);
+        assert_eq!(message, "invalid params");
+        assert_eq!(data.as_deref(), Some(r#""unknown field `foo`""#));
+        assert!(err.to_string().contains("-32602"), "{err}");
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn request_times_out() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(POST).path("/");
+            then.status(500).delay(Duration::from_secs(5));
+        });
+        let client = Client::new(&server.url("/"), Duration::from_millis(100)).unwrap();
+        let err = client.get_latest_ledger().await.unwrap_err();
+        assert!(
+            matches!(
+                &err,
+                rpc::Error::JsonRpc(jsonrpsee_core::Error::RequestTimeout)
+            ),
+            "{err:?}"
+        );
+        assert!(matches!(
+            crate::commands::network::Error::from(err),
+            crate::commands::network::Error::RequestTimeout
+        ));
+    }
+
+    #[tokio::test]
+    async fn network_check_skipped() {
+        let server = MockServer::start();
+        let mock = get_network(&server);
+        let client = Client::new(&server.url("/"), DEFAULT_RPC_TIMEOUT).unwrap();
+        let ignore = global::Args {
+            ignore_network_check: true,
+            ..Default::default()
+        };
+        client
+            .verify_network("Test SDF Network ; September 2015", Some(&ignore))
+            .await
+            .unwrap();
+        // Without --strict-network, commands that don't check by default skip the round-trip too
+        client
+            .verify_network_if_strict("Test SDF Network ; September 2015", None)
+            .await
+            .unwrap();
+        mock.assert_hits(0);
+    }
+
+    fn event(id: &str) -> serde_json::Value {
+        json!({
+            "type": "contract",
+            "ledger": 1,
+            "ledgerClosedAt": "2024-01-01T00:00:00Z",
+            "id": id,
+            "pagingToken": id,
+            "contractId": "CAAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQC526",
+            "topic": [],
+            "value": "AAAAAQ=="
+        })
+    }
+
+    fn get_events_page<'a>(
+        server: &'a MockServer,
+        cursor: Option<&str>,
+        ids: &[&str],
+    ) -> httpmock::Mock<'a> {
+        let events: Vec<_> = ids.iter().map(|id| event(id)).collect();
+        server.mock(|when, then| {
+            let when = when
+                .method(POST)
+                .path("/")
+                .json_body_partial(r#"{ "method": "getEvents" }"#);
+            match cursor {
+                Some(cursor) => when.body_contains(cursor),
+                None => when.body_contains("startLedger"),
+            };
+            then.status(200).json_body(json!({
+                "jsonrpc": "2.0",
+                "id": 0,
+                "result": { "events": events, "latestLedger": 10 }
+            }));
+        })
+    }
+
+    #[tokio::test]
+    async fn get_all_events_follows_cursors() {
+        let server = MockServer::start();
+        let first = get_events_page(&server, None, &["0001-1", "0001-2"]);
+        let second = get_events_page(&server, Some("0001-2"), &["0002-1"]);
+        let last = get_events_page(&server, Some("0002-1"), &[]);
+        let client = Client::new(&server.url("/"), DEFAULT_RPC_TIMEOUT).unwrap();
+        let res = client
+            .get_all_events(rpc::EventStart::Ledger(1), None, &[], &[], None)
+            .await
+            .unwrap();
+        let ids: Vec<_> = res.events.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, ["0001-1", "0001-2", "0002-1"]);
+        assert_eq!(res.latest_ledger, 10);
+        first.assert();
+        second.assert();
+        last.assert();
+
+        // Stops as soon as `max` events were returned
+        let res = client
+            .get_all_events(rpc::EventStart::Ledger(1), None, &[], &[], Some(2))
+            .await
+            .unwrap();
+        assert_eq!(res.events.len(), 2);
+        first.assert_hits(2);
+        second.assert_hits(1);
+    }
+
+    #[tokio::test]
+    async fn account_and_contract_compute_in_one_request() {
+        use soroban_env_host::xdr::{
+            AccountEntryExt, Limits, SequenceNumber, String32, Thresholds, VecM, WriteXdr,
+        };
+
+        let server = MockServer::start();
+        let account = stellar_strkey::ed25519::PublicKey([1; 32]);
+        let account_key = LedgerKey::Account(LedgerKeyAccount {
+            account_id: AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(account.0))),
+        });
+        let account_entry = AccountEntry {
+            account_id: AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(account.0))),
+            balance: 10_000_000_000,
+            seq_num: SequenceNumber(42),
+            num_sub_entries: 0,
+            inflation_dest: None,
+            flags: 0,
+            home_domain: String32::default(),
+            thresholds: Thresholds([1, 0, 0, 0]),
+            signers: VecM::default(),
+            ext: AccountEntryExt::V0,
+        };
+        let compute = ConfigSettingContractComputeV0 {
+            ledger_max_instructions: 500_000_000,
+            tx_max_instructions: 100_000_000,
+            fee_rate_per_instructions_increment: 25,
+            tx_memory_limit: 41_943_040,
+        };
+        let entries = [
+            (account_key, LedgerEntryData::Account(account_entry.clone())),
+            (
+                contract_compute_key(),
+                LedgerEntryData::ConfigSetting(ConfigSettingEntry::ContractComputeV0(
+                    compute.clone(),
+                )),
+            ),
+        ]
+        .map(|(key, entry)| {
+            json!({
+                "key": key.to_xdr_base64(Limits::none()).unwrap(),
+                "xdr": entry.to_xdr_base64(Limits::none()).unwrap(),
+                "lastModifiedLedgerSeq": 1
+            })
+        });
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .json_body_partial(r#"{ "method": "getLedgerEntries" }"#);
+            then.status(200).json_body(json!({
+                "jsonrpc": "2.0",
+                "id": 0,
+                "result": { "entries": entries, "latestLedger": 10 }
+            }));
+        });
+        let client = Client::new(&server.url("/"), DEFAULT_RPC_TIMEOUT).unwrap();
+        assert_eq!(
+            client
+                .get_account_and_contract_compute(&account)
+                .await
+                .unwrap(),
+            (account_entry, Some(compute))
+        );
+        mock.assert();
+    }
+}
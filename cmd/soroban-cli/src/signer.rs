@@ -1,3 +1,4 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
 use ed25519_dalek::ed25519::signature::Signer;
 use sha2::{Digest, Sha256};
 
@@ -5,11 +6,13 @@ use soroban_env_host::xdr::{
     self, AccountId, DecoratedSignature, Hash, HashIdPreimage, HashIdPreimageSorobanAuthorization,
     InvokeHostFunctionOp, Limits, Operation, OperationBody, PublicKey, ScAddress, ScMap, ScSymbol,
     ScVal, Signature, SignatureHint, SorobanAddressCredentials, SorobanAuthorizationEntry,
-    SorobanAuthorizedFunction, SorobanCredentials, Transaction, TransactionEnvelope,
-    TransactionSignaturePayload, TransactionSignaturePayloadTaggedTransaction,
+    SorobanAuthorizedFunction, SorobanAuthorizedInvocation, SorobanCredentials, Transaction,
+    TransactionEnvelope, TransactionSignaturePayload, TransactionSignaturePayloadTaggedTransaction,
     TransactionV1Envelope, Uint256, WriteXdr,
 };
 
+use crate::rpc;
+
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     #[error("Contract addresses are not supported to sign auth entries {address}")]
@@ -18,6 +21,8 @@ pub enum Error {
     Ed25519(#[from] ed25519_dalek::SignatureError),
     #[error("Missing signing key for account {address}")]
     MissingSignerForAddress { address: String },
+    #[error("only transaction v1 envelopes can be signed")]
+    OnlyTransactionV1Supported,
     #[error(transparent)]
     TryFromSlice(#[from] std::array::TryFromSliceError),
     #[error("User cancelled signing, perhaps need to add -y")]
@@ -26,6 +31,60 @@ pub enum Error {
     Xdr(#[from] xdr::Error),
 }
 
+/// A P-256 key registered with a smart wallet contract, which signs auth entries the way a
+/// WebAuthn authenticator (passkey) does
+#[derive(Clone, Debug)]
+pub struct Secp256r1Signer {
+    /// Smart wallet contract the key signs for
+    pub contract: [u8; 32],
+    /// ID the wallet stores the public key under
+    pub id: [u8; 32],
+    pub key: p256::ecdsa::SigningKey,
+    /// Relying party the signatures are made for, which the wallet may check
+    pub rp_id: String,
+}
+
+/// Signs the payloads of the auth entries of an ed25519 account
+pub trait AuthSigner {
+    fn public_key(&self) -> [u8; 32];
+    fn sign_payload(&self, payload: &[u8; 32]) -> Result<[u8; 64], Error>;
+}
+
+impl AuthSigner for ed25519_dalek::SigningKey {
+    fn public_key(&self) -> [u8; 32] {
+        self.verifying_key().to_bytes()
+    }
+
+    fn sign_payload(&self, payload: &[u8; 32]) -> Result<[u8; 64], Error> {
+        Ok(self.sign(payload).to_bytes())
+    }
+}
+
+/// Signatures over auth payloads made ahead of time by a key that isn't held locally, such as
+/// one on a Ledger device
+#[derive(Clone, Debug)]
+pub struct PresignedAuth {
+    pub public_key: [u8; 32],
+    /// Each payload with its signature
+    pub signatures: Vec<([u8; 32], [u8; 64])>,
+}
+
+impl AuthSigner for PresignedAuth {
+    fn public_key(&self) -> [u8; 32] {
+        self.public_key
+    }
+
+    fn sign_payload(&self, payload: &[u8; 32]) -> Result<[u8; 64], Error> {
+        self.signatures
+            .iter()
+            .find(|(signed, _)| signed == payload)
+            .map(|(_, signature)| *signature)
+            .ok_or_else(|| Error::MissingSignerForAddress {
+                address: stellar_strkey::ed25519::PublicKey(self.public_key).to_string(),
+            })
+    }
+}
+
 fn requires_auth(txn: &Transaction) -> Option<xdr::Operation> {
     let [op @ Operation {
         body: OperationBody::InvokeHostFunction(InvokeHostFunctionOp { auth, .. }),
@@ -42,11 +101,13 @@ fn requires_auth(txn: &Transaction) -> Option<xdr::Operation> {
 }
 
 // Use the given source_key and signers, to sign all SorobanAuthorizationEntry's in the given
-// transaction. If unable to sign, return an error.
+// transaction. Entries of smart wallet contracts are signed by the `contract_signers` registered
+// with them. If unable to sign, return an error.
 pub fn sign_soroban_authorizations(
     raw: &Transaction,
-    source_key: &ed25519_dalek::SigningKey,
+    source_key: &dyn AuthSigner,
     signers: &[ed25519_dalek::SigningKey],
+    contract_signers: &[Secp256r1Signer],
     signature_expiration_ledger: u32,
     network_passphrase: &str,
 ) -> Result<Option<Transaction>, Error> {
@@ -65,8 +126,7 @@ pub fn sign_soroban_authorizations(
 
     let network_id = Hash(Sha256::digest(network_passphrase.as_bytes()).into());
 
-    let verification_key = source_key.verifying_key();
-    let source_address = verification_key.as_bytes();
+    let source_address = source_key.public_key();
 
     let signed_auths = body
         .auth
@@ -90,19 +150,28 @@ pub fn sign_soroban_authorizations(
                 ScAddress::Account(AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(ref a)))) => a,
                 ScAddress::Contract(Hash(c)) => {
                     // This address is for a contract. This means we're using a custom
-                    // smart-contract account. Currently the CLI doesn't support that yet.
+                    // smart-contract account, which can only be signed for with a signer
+                    // registered with it.
+                    if let Some(signer) = contract_signers.iter().find(|s| s.contract == *c) {
+                        return sign_secp256r1_authorization_entry(
+                            raw_auth,
+                            signer,
+                            signature_expiration_ledger,
+                            &network_id,
+                        );
+                    }
                     return Err(Error::MissingSignerForAddress {
                         address: stellar_strkey::Strkey::Contract(stellar_strkey::Contract(*c))
                             .to_string(),
                     });
                 }
             };
-            let signer = if let Some(s) = signers
+            let signer: &dyn AuthSigner = if let Some(s) = signers
                 .iter()
                 .find(|s| needle == s.verifying_key().as_bytes())
             {
                 s
-            } else if needle == source_address {
+            } else if *needle == source_address {
                 // This is the source address, so we can sign it
                 source_key
             } else {
@@ -131,7 +200,7 @@ pub fn sign_soroban_authorizations(
 
 fn sign_soroban_authorization_entry(
     raw: &SorobanAuthorizationEntry,
-    signer: &ed25519_dalek::SigningKey,
+    signer: &dyn AuthSigner,
     signature_expiration_ledger: u32,
     network_id: &Hash,
 ) -> Result<SorobanAuthorizationEntry, Error> {
@@ -144,26 +213,20 @@ fn sign_soroban_authorization_entry(
         // Doesn't need special signing
         return Ok(auth);
     };
-    let SorobanAddressCredentials { nonce, .. } = credentials;
-
-    let preimage = HashIdPreimage::SorobanAuthorization(HashIdPreimageSorobanAuthorization {
-        network_id: network_id.clone(),
-        invocation: auth.root_invocation.clone(),
-        nonce: *nonce,
+    let (_, payload, _) = auth_payload(
+        credentials,
+        &auth.root_invocation,
         signature_expiration_ledger,
-    })
-    .to_xdr(Limits::none())?;
-
-    let payload = Sha256::digest(preimage);
-    let signature = signer.sign(&payload);
+        network_id,
+    )?;
+    let signature = signer.sign_payload(&payload)?;
 
     let map = ScMap::sorted_from(vec![
         (
             ScVal::Symbol(ScSymbol("public_key".try_into()?)),
             ScVal::Bytes(
                 signer
-                    .verifying_key()
-                    .to_bytes()
+                    .public_key()
                     .to_vec()
                     .try_into()
                     .map_err(Error::Xdr)?,
@@ -171,24 +234,262 @@ fn sign_soroban_authorization_entry(
         ),
         (
             ScVal::Symbol(ScSymbol("signature".try_into()?)),
-            ScVal::Bytes(
-                signature
-                    .to_bytes()
-                    .to_vec()
-                    .try_into()
-                    .map_err(Error::Xdr)?,
-            ),
+            ScVal::Bytes(signature.to_vec().try_into().map_err(Error::Xdr)?),
         ),
     ])
     .map_err(Error::Xdr)?;
+    // Keep signatures collected in earlier passes, as long as they signed the same payload
+    let mut signatures = match &credentials.signature {
+        ScVal::Vec(Some(existing))
+            if credentials.signature_expiration_ledger == signature_expiration_ledger =>
+        {
+            existing.to_vec()
+        }
+        _ => vec![],
+    };
+    signatures.push(ScVal::Map(Some(map)));
     credentials.signature = ScVal::Vec(Some(
-        vec![ScVal::Map(Some(map))].try_into().map_err(Error::Xdr)?,
+        dedupe_signatures(signatures)
+            .try_into()
+            .map_err(Error::Xdr)?,
     ));
     credentials.signature_expiration_ledger = signature_expiration_ledger;
     auth.credentials = SorobanCredentials::Address(credentials.clone());
     Ok(auth)
 }
 
+/// Sign an auth entry of a smart wallet with a WebAuthn assertion over its payload, as the
+/// `{id, authenticator_data, client_data_json, signature}` map such wallets take as signature.
+fn sign_secp256r1_authorization_entry(
+    raw: &SorobanAuthorizationEntry,
+    signer: &Secp256r1Signer,
+    signature_expiration_ledger: u32,
+    network_id: &Hash,
+) -> Result<SorobanAuthorizationEntry, Error> {
+    let mut auth = raw.clone();
+    let SorobanAuthorizationEntry {
+        credentials: SorobanCredentials::Address(ref mut credentials),
+        ..
+    } = auth
+    else {
+        // Doesn't need special signing
+        return Ok(auth);
+    };
+    let (_, payload, _) = auth_payload(
+        credentials,
+        &auth.root_invocation,
+        signature_expiration_ledger,
+        network_id,
+    )?;
+    // RP ID hash, then the user present and user verified flags, then a zero signature counter
+    let mut authenticator_data = Sha256::digest(signer.rp_id.as_bytes()).to_vec();
+    authenticator_data.extend([0x05, 0, 0, 0, 0]);
+    let client_data_json = format!(
+        r#"{{"type":"webauthn.get","challenge":"{}","origin":"https://{}"}}"#,
+        URL_SAFE_NO_PAD.encode(payload),
+        signer.rp_id
+    );
+    let mut message = authenticator_data.clone();
+    message.extend(Sha256::digest(client_data_json.as_bytes()));
+    let signature: p256::ecdsa::Signature =
+        p256::ecdsa::signature::Signer::sign(&signer.key, &message);
+    // The host only accepts signatures with a low S value
+    let signature = signature.normalize_s().unwrap_or(signature);
+
+    let bytes = |name: &str, bytes: Vec<u8>| -> Result<_, Error> {
+        Ok((
+            ScVal::Symbol(ScSymbol(name.try_into()?)),
+            ScVal::Bytes(bytes.try_into()?),
+        ))
+    };
+    let map = ScMap::sorted_from(vec![
+        bytes("authenticator_data", authenticator_data)?,
+        bytes("client_data_json", client_data_json.into_bytes())?,
+        bytes("id", signer.id.to_vec())?,
+        bytes("signature", signature.to_bytes().to_vec())?,
+    ])?;
+    credentials.signature = ScVal::Map(Some(map));
+    credentials.signature_expiration_ledger = signature_expiration_ledger;
+    auth.credentials = SorobanCredentials::Address(credentials.clone());
+    Ok(auth)
+}
+
+/// The payloads of the auth entries of `public_key` in the transaction's invocation, for
+/// signatures valid until `signature_expiration_ledger`, so a key that isn't held locally can
+/// sign them ahead of time
+pub fn auth_payloads_of(
+    tx: &Transaction,
+    public_key: &[u8; 32],
+    signature_expiration_ledger: u32,
+    network_passphrase: &str,
+) -> Result<Vec<[u8; 32]>, Error> {
+    let Some(Operation {
+        body: OperationBody::InvokeHostFunction(InvokeHostFunctionOp { auth, .. }),
+        ..
+    }) = requires_auth(tx)
+    else {
+        return Ok(vec![]);
+    };
+    let network_id = Hash(Sha256::digest(network_passphrase.as_bytes()).into());
+    auth.iter()
+        .filter_map(|entry| match &entry.credentials {
+            SorobanCredentials::Address(
+                credentials @ SorobanAddressCredentials {
+                    address:
+                        ScAddress::Account(AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(key)))),
+                    ..
+                },
+            ) if key == public_key => Some(
+                auth_payload(
+                    credentials,
+                    &entry.root_invocation,
+                    signature_expiration_ledger,
+                    &network_id,
+                )
+                .map(|(_, payload, _)| payload),
+            ),
+            _ => None,
+        })
+        .collect()
+}
+
+/// What a signer needs to sign an auth entry: its nonce, the hash to sign and the invocation being
+/// authorized
+pub type AuthPayload = (i64, [u8; 32], SorobanAuthorizedInvocation);
+
+/// Helpers for handing the auth entries of a simulation to a signer
+pub trait SimulateHostFunctionResultExt {
+    /// The payload of each auth entry with address credentials, for a signature valid until
+    /// `signature_expiration_ledger`. Entries using the source account's credentials need no
+    /// signature and are skipped.
+    fn auth_payloads(
+        &self,
+        network_passphrase: &str,
+        signature_expiration_ledger: u32,
+    ) -> Result<Vec<AuthPayload>, Error>;
+}
+
+impl SimulateHostFunctionResultExt for rpc::SimulateHostFunctionResult {
+    fn auth_payloads(
+        &self,
+        network_passphrase: &str,
+        signature_expiration_ledger: u32,
+    ) -> Result<Vec<AuthPayload>, Error> {
+        let network_id = Hash(Sha256::digest(network_passphrase.as_bytes()).into());
+        self.auth
+            .iter()
+            .filter_map(|entry| match &entry.credentials {
+                SorobanCredentials::Address(credentials) => Some(auth_payload(
+                    credentials,
+                    &entry.root_invocation,
+                    signature_expiration_ledger,
+                    &network_id,
+                )),
+                SorobanCredentials::SourceAccount => None,
+            })
+            .collect()
+    }
+}
+
+/// The payload of an auth entry for a signature valid until `signature_expiration_ledger`
+pub fn auth_payload(
+    credentials: &SorobanAddressCredentials,
+    root_invocation: &SorobanAuthorizedInvocation,
+    signature_expiration_ledger: u32,
+    network_id: &Hash,
+) -> Result<AuthPayload, Error> {
+    let preimage = HashIdPreimage::SorobanAuthorization(HashIdPreimageSorobanAuthorization {
+        network_id: network_id.clone(),
+        invocation: root_invocation.clone(),
+        nonce: credentials.nonce,
+        signature_expiration_ledger,
+    })
+    .to_xdr(Limits::none())?;
+    Ok((
+        credentials.nonce,
+        Sha256::digest(preimage).into(),
+        root_invocation.clone(),
+    ))
+}
+
+/// A signature in an auth entry's credentials, and whether it is valid for the entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthSignature {
+    pub public_key: Vec<u8>,
+    pub valid: bool,
+}
+
+/// Check each `{public_key, signature}` map in an auth entry's credentials against the payload
+/// its signers were meant to sign. Entries using the source account's credentials carry no
+/// signatures.
+pub fn verify_soroban_authorization_entry(
+    entry: &SorobanAuthorizationEntry,
+    network_passphrase: &str,
+) -> Result<Vec<AuthSignature>, Error> {
+    let SorobanCredentials::Address(credentials) = &entry.credentials else {
+        return Ok(vec![]);
+    };
+    let network_id = Hash(Sha256::digest(network_passphrase.as_bytes()).into());
+    let (_, payload, _) = auth_payload(
+        credentials,
+        &entry.root_invocation,
+        credentials.signature_expiration_ledger,
+        &network_id,
+    )?;
+    let signatures = match &credentials.signature {
+        ScVal::Vec(Some(signatures)) => signatures.to_vec(),
+        sig @ ScVal::Map(_) => vec![sig.clone()],
+        _ => vec![],
+    };
+    Ok(signatures
+        .iter()
+        .map(|sig| {
+            let field = |name: &str| match sig {
+                ScVal::Map(Some(map)) => map.iter().find_map(|e| match (&e.key, &e.val) {
+                    (ScVal::Symbol(s), ScVal::Bytes(b)) if s.as_vec() == name.as_bytes() => {
+                        Some(b.to_vec())
+                    }
+                    _ => None,
+                }),
+                _ => None,
+            };
+            let public_key = field("public_key").unwrap_or_default();
+            let valid = <[u8; 32]>::try_from(public_key.as_slice())
+                .ok()
+                .and_then(|key| ed25519_dalek::VerifyingKey::from_bytes(&key).ok())
+                .zip(
+                    field("signature")
+                        .and_then(|bytes| ed25519_dalek::Signature::from_slice(&bytes).ok()),
+                )
+                .is_some_and(|(key, sig)| key.verify_strict(&payload, &sig).is_ok());
+            AuthSignature { public_key, valid }
+        })
+        .collect())
+}
+
+/// Drop all but the last signature of each public key, and sort the rest by public key as account
+/// contracts expect. Signatures without a public key are kept as they are, ahead of the rest.
+fn dedupe_signatures(signatures: Vec<ScVal>) -> Vec<ScVal> {
+    let public_key = |sig: &ScVal| match sig {
+        ScVal::Map(Some(map)) => map
+            .iter()
+            .find(|e| matches!(&e.key, ScVal::Symbol(s) if s.as_vec() == b"public_key"))
+            .map(|e| e.val.clone()),
+        _ => None,
+    };
+    let mut without_key = Vec::new();
+    let mut by_key = std::collections::BTreeMap::new();
+    for sig in signatures {
+        if let Some(ScVal::Bytes(key)) = public_key(&sig) {
+            by_key.insert(key.to_vec(), sig);
+        } else {
+            without_key.push(sig);
+        }
+    }
+    without_key.extend(by_key.into_values());
+    without_key
+}
+
 pub fn sign_tx(
     key: &ed25519_dalek::SigningKey,
     tx: &Transaction,
@@ -208,10 +509,276 @@ pub fn sign_tx(
     }))
 }
 
+/// Append a signature from each of the given keys to a transaction envelope. New signatures are
+/// added in order of their public keys, skipping duplicate keys and keys whose signature the
+/// envelope already has.
+pub fn sign_tx_env(
+    keys: &[ed25519_dalek::SigningKey],
+    tx_env: TransactionEnvelope,
+    network_passphrase: &str,
+) -> Result<TransactionEnvelope, Error> {
+    let TransactionEnvelope::Tx(TransactionV1Envelope { tx, signatures }) = tx_env else {
+        return Err(Error::OnlyTransactionV1Supported);
+    };
+    let tx_hash = hash(&tx, network_passphrase)?;
+    let mut keys = keys.iter().collect::<Vec<_>>();
+    keys.sort_by_key(|key| key.verifying_key().to_bytes());
+    keys.dedup_by_key(|key| key.verifying_key().to_bytes());
+    let mut signatures = signatures.to_vec();
+    for key in keys {
+        let signature = DecoratedSignature {
+            hint: SignatureHint(key.verifying_key().to_bytes()[28..].try_into()?),
+            signature: Signature(key.sign(&tx_hash).to_bytes().try_into()?),
+        };
+        // Ed25519 signatures are deterministic, so signing again gives the same signature
+        if !signatures.contains(&signature) {
+            signatures.push(signature);
+        }
+    }
+    Ok(TransactionEnvelope::Tx(TransactionV1Envelope {
+        tx,
+        signatures: signatures.try_into()?,
+    }))
+}
+
 pub fn hash(tx: &Transaction, network_passphrase: &str) -> Result<[u8; 32], xdr::Error> {
+    payload_hash(
+        TransactionSignaturePayloadTaggedTransaction::Tx(tx.clone()),
+        network_passphrase,
+    )
+}
+
+/// The hash of the signature payload for a transaction or fee bump transaction, i.e. what
+/// signers sign.
+pub fn payload_hash(
+    tagged_transaction: TransactionSignaturePayloadTaggedTransaction,
+    network_passphrase: &str,
+) -> Result<[u8; 32], xdr::Error> {
     let signature_payload = TransactionSignaturePayload {
         network_id: Hash(Sha256::digest(network_passphrase).into()),
-        tagged_transaction: TransactionSignaturePayloadTaggedTransaction::Tx(tx.clone()),
+        tagged_transaction,
     };
     Ok(Sha256::digest(signature_payload.to_xdr(Limits::none())?).into())
 }
+
+#[cfg(test)]
+mod test {
+    use soroban_env_host::xdr::{
+        InvokeContractArgs, Memo, MuxedAccount, Preconditions, ScBytes, SequenceNumber,
+        TransactionExt, VecM,
+    };
+
+    use super::*;
+
+    fn unsigned_entry(address: &ed25519_dalek::SigningKey) -> SorobanAuthorizationEntry {
+        SorobanAuthorizationEntry {
+            credentials: SorobanCredentials::Address(SorobanAddressCredentials {
+                address: ScAddress::Account(AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(
+                    address.verifying_key().to_bytes(),
+                )))),
+                nonce: 1,
+                signature_expiration_ledger: 0,
+                signature: ScVal::Void,
+            }),
+            root_invocation: SorobanAuthorizedInvocation {
+                function: SorobanAuthorizedFunction::ContractFn(InvokeContractArgs {
+                    contract_address: ScAddress::Contract(Hash([0; 32])),
+                    function_name: ScSymbol("hello".try_into().unwrap()),
+                    args: VecM::default(),
+                }),
+                sub_invocations: VecM::default(),
+            },
+        }
+    }
+
+    fn public_keys(entry: &SorobanAuthorizationEntry) -> Vec<Vec<u8>> {
+        let SorobanCredentials::Address(SorobanAddressCredentials {
+            signature: ScVal::Vec(Some(signatures)),
+            ..
+        }) = &entry.credentials
+        else {
+            panic!("expected signatures");
+        };
+        signatures
+            .iter()
+            .map(|sig| {
+                let ScVal::Map(Some(map)) = sig else {
+                    panic!("expected a signature map");
+                };
+                let ScVal::Bytes(ScBytes(key)) = &map[0].val else {
+                    panic!("expected a public key");
+                };
+                key.to_vec()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn signatures_from_earlier_passes_are_kept() {
+        let alice = ed25519_dalek::SigningKey::from_bytes(&[1; 32]);
+        let bob = ed25519_dalek::SigningKey::from_bytes(&[2; 32]);
+        let network_id = Hash([0; 32]);
+        let entry = unsigned_entry(&alice);
+
+        let once = sign_soroban_authorization_entry(&entry, &alice, 100, &network_id).unwrap();
+        let twice = sign_soroban_authorization_entry(&once, &bob, 100, &network_id).unwrap();
+        let mut expected = vec![
+            alice.verifying_key().to_bytes().to_vec(),
+            bob.verifying_key().to_bytes().to_vec(),
+        ];
+        expected.sort();
+        assert_eq!(public_keys(&twice), expected);
+
+        // Signing again with the same key replaces its signature instead of adding another
+        let again = sign_soroban_authorization_entry(&twice, &alice, 100, &network_id).unwrap();
+        assert_eq!(public_keys(&again), expected);
+
+        // A different expiration changes the payload, so earlier signatures are dropped
+        let expired = sign_soroban_authorization_entry(&twice, &bob, 200, &network_id).unwrap();
+        assert_eq!(
+            public_keys(&expired),
+            vec![bob.verifying_key().to_bytes().to_vec()]
+        );
+    }
+
+    #[test]
+    fn signatures_without_a_public_key_are_kept() {
+        let sig = |key: u8| {
+            ScVal::Map(Some(
+                ScMap::sorted_from(vec![(
+                    ScVal::Symbol(ScSymbol("public_key".try_into().unwrap())),
+                    ScVal::Bytes(vec![key; 32].try_into().unwrap()),
+                )])
+                .unwrap(),
+            ))
+        };
+        let other = ScVal::U32(7);
+        assert_eq!(
+            dedupe_signatures(vec![sig(2), other.clone(), sig(1), sig(2)]),
+            vec![other, sig(1), sig(2)]
+        );
+    }
+
+    #[test]
+    fn signing_a_signed_envelope_again_adds_no_signature() {
+        let alice = ed25519_dalek::SigningKey::from_bytes(&[1; 32]);
+        let bob = ed25519_dalek::SigningKey::from_bytes(&[2; 32]);
+        let passphrase = "Standalone Network ; February 2017";
+        let tx_env = TransactionEnvelope::Tx(TransactionV1Envelope {
+            tx: Transaction {
+                source_account: MuxedAccount::Ed25519(Uint256(alice.verifying_key().to_bytes())),
+                fee: 100,
+                seq_num: SequenceNumber(1),
+                cond: Preconditions::None,
+                memo: Memo::None,
+                operations: VecM::default(),
+                ext: TransactionExt::V0,
+            },
+            signatures: VecM::default(),
+        });
+
+        let once = sign_tx_env(&[alice.clone()], tx_env, passphrase).unwrap();
+        let twice = sign_tx_env(&[alice, bob], once.clone(), passphrase).unwrap();
+        let (TransactionEnvelope::Tx(once), TransactionEnvelope::Tx(twice)) = (once, twice) else {
+            unreachable!()
+        };
+        assert_eq!(once.signatures.len(), 1);
+        assert_eq!(twice.signatures.len(), 2);
+        assert_eq!(twice.signatures[0], once.signatures[0]);
+    }
+
+    #[test]
+    fn secp256r1_signature_map() {
+        use p256::ecdsa::{signature::Verifier, VerifyingKey};
+
+        let signer = Secp256r1Signer {
+            contract: [7; 32],
+            id: [3; 32],
+            key: p256::ecdsa::SigningKey::from_slice(&[1; 32]).unwrap(),
+            rp_id: "example.com".to_string(),
+        };
+        let network_id = Hash([0; 32]);
+        let mut entry = unsigned_entry(&ed25519_dalek::SigningKey::from_bytes(&[1; 32]));
+        let SorobanCredentials::Address(ref mut credentials) = entry.credentials else {
+            unreachable!()
+        };
+        credentials.address = ScAddress::Contract(Hash(signer.contract));
+
+        let signed = sign_secp256r1_authorization_entry(&entry, &signer, 100, &network_id).unwrap();
+        let SorobanCredentials::Address(SorobanAddressCredentials {
+            signature: ScVal::Map(Some(map)),
+            signature_expiration_ledger: 100,
+            ..
+        }) = &signed.credentials
+        else {
+            panic!("expected a signature map");
+        };
+        let fields = map
+            .iter()
+            .map(|e| match (&e.key, &e.val) {
+                (ScVal::Symbol(name), ScVal::Bytes(bytes)) => {
+                    (name.to_utf8_string_lossy(), bytes.to_vec())
+                }
+                _ => panic!("expected symbol keys and bytes values"),
+            })
+            .collect::<Vec<_>>();
+        let names = fields.iter().map(|(n, _)| n.as_str()).collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            ["authenticator_data", "client_data_json", "id", "signature"]
+        );
+        let [(_, authenticator_data), (_, client_data_json), (_, id), (_, signature)] =
+            fields.as_slice()
+        else {
+            unreachable!()
+        };
+        assert_eq!(id, &[3; 32]);
+        assert_eq!(authenticator_data.len(), 37);
+        assert_eq!(authenticator_data[..32], Sha256::digest(b"example.com")[..]);
+
+        // The challenge is the auth entry's payload
+        let SorobanCredentials::Address(credentials) = &signed.credentials else {
+            unreachable!()
+        };
+        let (_, payload, _) =
+            auth_payload(credentials, &signed.root_invocation, 100, &network_id).unwrap();
+        let client_data: serde_json::Value = serde_json::from_slice(client_data_json).unwrap();
+        assert_eq!(client_data["challenge"], URL_SAFE_NO_PAD.encode(payload));
+        assert_eq!(client_data["origin"], "https://example.com");
+
+        let mut message = authenticator_data.clone();
+        message.extend(Sha256::digest(client_data_json));
+        let signature = p256::ecdsa::Signature::from_slice(signature).unwrap();
+        assert!(signature.normalize_s().is_none(), "S should already be low");
+        VerifyingKey::from(&signer.key)
+            .verify(&message, &signature)
+            .unwrap();
+    }
+
+    #[test]
+    fn auth_payloads_of_simulation() {
+        let alice = ed25519_dalek::SigningKey::from_bytes(&[1; 32]);
+        let entry = unsigned_entry(&alice);
+        let result = rpc::SimulateHostFunctionResult {
+            auth: vec![
+                entry.clone(),
+                SorobanAuthorizationEntry {
+                    credentials: SorobanCredentials::SourceAccount,
+                    ..entry.clone()
+                },
+            ],
+            xdr: ScVal::Void,
+        };
+        let payloads = result
+            .auth_payloads("Standalone Network ; February 2017", 100)
+            .unwrap();
+        assert_eq!(payloads.len(), 1);
+        let (nonce, payload_hash, root_invocation) = &payloads[0];
+        assert_eq!(*nonce, 1);
+        assert_eq!(
+            hex::encode(payload_hash),
+            "a1db81fbc1876dcdaec65c7277ad944076f914963fa937d0990551d5e5974184"
+        );
+        assert_eq!(root_invocation, &entry.root_invocation);
+    }
+}
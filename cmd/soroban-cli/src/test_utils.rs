@@ -0,0 +1,6 @@
+//! Helpers shared by unit tests
+
+/// A JSON-RPC response with `result`, as an RPC server mocked with httpmock sends it
+pub fn rpc_response(result: serde_json::Value) -> serde_json::Value {
+    serde_json::json!({ "jsonrpc": "2.0", "id": 0, "result": result })
+}